@@ -0,0 +1,89 @@
+use crate::json_output::print_json;
+use crate::render_worker::{JpegEncoderType, OutputFormat};
+use clap::ValueEnum;
+use serde::Serialize;
+
+/// What this build of `pdf` can do (`pdf capabilities`), so a frontend can
+/// hide options that would only fail.
+#[derive(Debug, Serialize)]
+pub struct Capabilities {
+    /// `--format` values this build can write
+    pub formats: Vec<String>,
+    /// `--encoder` values this build can use
+    pub encoders: Vec<String>,
+    pub features: Features,
+    /// The pdfium API the bindings were compiled against (the `pdfium_*`
+    /// Cargo feature), not the build of the library loaded at runtime
+    pub pdfium_version: &'static str,
+}
+
+/// Optional Cargo features compiled into this build.
+#[derive(Debug, Serialize)]
+pub struct Features {
+    /// libvips JPEG encoder (`--encoder vips`)
+    pub vips: bool,
+    /// AVIF output (`--format avif`)
+    pub avif: bool,
+    /// Lossy WebP; lossless WebP is always available
+    pub webp: bool,
+}
+
+impl Capabilities {
+    pub fn of_this_build() -> Self {
+        let features = Features {
+            vips: cfg!(feature = "vips"),
+            avif: cfg!(feature = "avif"),
+            webp: cfg!(feature = "webp"),
+        };
+        let formats = OutputFormat::value_variants()
+            .iter()
+            .filter(|format| **format != OutputFormat::Avif || features.avif)
+            .filter_map(value_name)
+            .collect();
+        let encoders = JpegEncoderType::value_variants()
+            .iter()
+            .filter(|encoder| **encoder != JpegEncoderType::Vips || features.vips)
+            .filter_map(value_name)
+            .collect();
+        Capabilities {
+            formats,
+            encoders,
+            features,
+            pdfium_version: "7350",
+        }
+    }
+}
+
+/// The command-line spelling of a clap value.
+fn value_name(value: &impl ValueEnum) -> Option<String> {
+    Some(value.to_possible_value()?.get_name().to_owned())
+}
+
+/// Print the capabilities of this build as JSON; pdfium is not loaded.
+pub fn run() {
+    print_json("capabilities", &Capabilities::of_this_build());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn capabilities_follow_the_compiled_features() {
+        let capabilities = Capabilities::of_this_build();
+
+        assert_eq!(&capabilities.formats[..4], ["jpeg", "png", "webp", "tiff"]);
+        assert_eq!(
+            capabilities.formats.iter().any(|format| format == "avif"),
+            cfg!(feature = "avif")
+        );
+        assert_eq!(
+            capabilities.encoders,
+            if cfg!(feature = "vips") {
+                vec!["image", "vips"]
+            } else {
+                vec!["image"]
+            }
+        );
+    }
+}
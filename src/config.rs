@@ -0,0 +1,250 @@
+use crate::error::Error;
+use clap::Command;
+use clap::builder::ArgPredicate;
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+
+pub const CONFIG_FILE_NAME: &str = "pdf.toml";
+
+/// Flags that pick the output size. A size from the config file is dropped
+/// when another one is given on the command line, instead of conflicting.
+const SIZE_FLAGS: [&str; 5] = ["target_width", "target_height", "dpi", "canvas", "widths"];
+
+/// `render` flag defaults read from `pdf.toml`.
+///
+/// Keys are the flag names with underscores (`target_width = 1280`); enum
+/// values are written as on the command line (`box = "bleed"`).
+#[derive(Debug, Default, Deserialize, PartialEq)]
+pub struct RenderDefaults {
+    pub workers: Option<u32>,
+    pub mode: Option<String>,
+    pub target_width: Option<u32>,
+    pub target_height: Option<u32>,
+    pub dpi: Option<u32>,
+    pub max_width: Option<u32>,
+    pub max_height: Option<u32>,
+    pub quality: Option<QualityValue>,
+    #[serde(rename = "box")]
+    pub box_type: Option<String>,
+    pub format: Option<String>,
+    pub encoder: Option<String>,
+    pub background: Option<String>,
+    pub grayscale: Option<bool>,
+    pub lossless: Option<bool>,
+    pub speed: Option<u8>,
+    pub name_template: Option<String>,
+    pub page_timeout: Option<u64>,
+    pub retries: Option<u32>,
+    pub worker_mem_limit: Option<u64>,
+}
+
+/// A `quality` value: a number, or a preset name such as `"high"`.
+#[derive(Debug, Deserialize, PartialEq)]
+#[serde(untagged)]
+pub enum QualityValue {
+    Number(f64),
+    Preset(String),
+}
+
+impl std::fmt::Display for QualityValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            QualityValue::Number(number) => write!(f, "{number}"),
+            QualityValue::Preset(name) => f.write_str(name),
+        }
+    }
+}
+
+impl RenderDefaults {
+    /// Parse `pdf.toml` contents, returning the defaults and any keys that
+    /// match no render flag.
+    pub fn parse(contents: &str) -> Result<(Self, Vec<String>), toml::de::Error> {
+        let table: toml::Table = contents.parse()?;
+        let known = Self::default().values().map(|(id, _)| id);
+        let unknown = table
+            .keys()
+            .filter(|key| !known.contains(&key.as_str()))
+            .cloned()
+            .collect();
+        Ok((table.try_into()?, unknown))
+    }
+
+    /// Values by clap arg id, as command-line strings.
+    fn values(&self) -> [(&'static str, Option<String>); 19] {
+        fn text(value: Option<impl ToString>) -> Option<String> {
+            value.map(|v| v.to_string())
+        }
+        [
+            ("workers", text(self.workers)),
+            ("mode", self.mode.clone()),
+            ("target_width", text(self.target_width)),
+            ("target_height", text(self.target_height)),
+            ("dpi", text(self.dpi)),
+            ("max_width", text(self.max_width)),
+            ("max_height", text(self.max_height)),
+            ("quality", text(self.quality.as_ref())),
+            ("box", self.box_type.clone()),
+            ("format", self.format.clone()),
+            ("encoder", self.encoder.clone()),
+            ("background", self.background.clone()),
+            ("grayscale", text(self.grayscale)),
+            ("lossless", text(self.lossless)),
+            ("speed", text(self.speed)),
+            ("name_template", self.name_template.clone()),
+            ("page_timeout", text(self.page_timeout)),
+            ("retries", text(self.retries)),
+            ("worker_mem_limit", text(self.worker_mem_limit)),
+        ]
+    }
+
+    /// Install the values as defaults of the `render` subcommand, so flags on
+    /// the command line still win and values go through the flags' own parsers.
+    pub fn apply(&self, command: Command) -> Command {
+        command.mut_subcommand("render", |mut render| {
+            for (id, value) in self.values() {
+                let Some(value) = value else { continue };
+                // clap's defaults are `&'static str`; this runs once per process.
+                let value: &'static str = value.leak();
+                render = render.mut_arg(id, |arg| {
+                    let arg = arg.default_value(value);
+                    if !SIZE_FLAGS.contains(&id) {
+                        return arg;
+                    }
+                    SIZE_FLAGS
+                        .iter()
+                        .filter(|&&other| other != id)
+                        .fold(arg, |arg, &other| {
+                            arg.default_value_if(other, ArgPredicate::IsPresent, None::<&str>)
+                        })
+                });
+            }
+            render
+        })
+    }
+}
+
+/// `pdf.toml` in the current directory, else in `$XDG_CONFIG_HOME`
+/// (`~/.config` when unset).
+pub fn find() -> Option<PathBuf> {
+    let config_home = std::env::var_os("XDG_CONFIG_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| Path::new(&home).join(".config")));
+    std::iter::once(PathBuf::from(CONFIG_FILE_NAME))
+        .chain(config_home.map(|dir| dir.join(CONFIG_FILE_NAME)))
+        .find(|path| path.is_file())
+}
+
+/// Load the first `pdf.toml` found, warning on stderr about unknown keys.
+pub fn load() -> Result<Option<RenderDefaults>, Error> {
+    let Some(path) = find() else {
+        return Ok(None);
+    };
+    let contents = std::fs::read_to_string(&path)?;
+    let (defaults, unknown) = RenderDefaults::parse(&contents)
+        .map_err(|e| Error::InvalidArgs(format!("{}: {e}", path.display())))?;
+    for key in unknown {
+        eprintln!("warning: {}: unknown key `{key}` ignored", path.display());
+    }
+    Ok(Some(defaults))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Arg;
+
+    fn render_command() -> Command {
+        let flag = |id: &'static str, long: &'static str| Arg::new(id).long(long);
+        Command::new("pdf").subcommand(
+            Command::new("render")
+                .arg(flag("target_width", "target-width"))
+                .arg(flag("target_height", "target-height"))
+                .arg(flag("dpi", "dpi"))
+                .arg(flag("canvas", "canvas"))
+                .arg(flag("quality", "quality").default_value("100"))
+                .arg(flag("workers", "workers")),
+        )
+    }
+
+    fn render_value(defaults: &RenderDefaults, args: &[&str], id: &str) -> Option<String> {
+        let matches = defaults
+            .apply(render_command())
+            .get_matches_from(["pdf", "render"].iter().chain(args));
+        let (_, render) = matches.subcommand().unwrap();
+        render.get_one::<String>(id).cloned()
+    }
+
+    #[test]
+    fn parse_reads_flags_and_reports_unknown_keys() {
+        let (defaults, unknown) = RenderDefaults::parse(
+            "target_width = 1280\nquality = 85\nbox = \"bleed\"\ncolour = \"red\"\n",
+        )
+        .unwrap();
+
+        assert_eq!(defaults.target_width, Some(1280));
+        assert_eq!(defaults.quality, Some(QualityValue::Number(85.0)));
+        assert_eq!(defaults.box_type.as_deref(), Some("bleed"));
+        assert_eq!(unknown, vec!["colour"]);
+    }
+
+    #[test]
+    fn quality_accepts_preset_names() {
+        let (defaults, _) = RenderDefaults::parse("quality = \"high\"").unwrap();
+
+        assert_eq!(defaults.quality, Some(QualityValue::Preset("high".into())));
+        assert_eq!(
+            render_value(&defaults, &[], "quality").as_deref(),
+            Some("high")
+        );
+    }
+
+    #[test]
+    fn parse_rejects_mistyped_values() {
+        assert!(RenderDefaults::parse("target_width = \"wide\"").is_err());
+        assert!(RenderDefaults::parse("target_width = ").is_err());
+    }
+
+    #[test]
+    fn command_line_overrides_config_values() {
+        let defaults = RenderDefaults {
+            quality: Some(QualityValue::Number(85.0)),
+            ..RenderDefaults::default()
+        };
+
+        assert_eq!(
+            render_value(&defaults, &[], "quality").as_deref(),
+            Some("85")
+        );
+        assert_eq!(
+            render_value(&defaults, &["--quality", "60"], "quality").as_deref(),
+            Some("60")
+        );
+        assert_eq!(render_value(&defaults, &[], "workers"), None);
+    }
+
+    #[test]
+    fn size_on_command_line_replaces_config_size() {
+        let defaults = RenderDefaults {
+            target_width: Some(1280),
+            ..RenderDefaults::default()
+        };
+
+        assert_eq!(
+            render_value(&defaults, &[], "target_width").as_deref(),
+            Some("1280")
+        );
+        assert_eq!(
+            render_value(&defaults, &["--dpi", "300"], "target_width"),
+            None
+        );
+        assert_eq!(
+            render_value(&defaults, &["--target-height", "900"], "target_width"),
+            None
+        );
+        assert_eq!(
+            render_value(&defaults, &["--canvas", "800x600"], "target_width"),
+            None
+        );
+    }
+}
@@ -0,0 +1,156 @@
+#[cfg(not(test))]
+use crate::error::Error;
+#[cfg(not(test))]
+use crate::json_output::print_json;
+#[cfg(not(test))]
+use crate::output_path::{check_output_file, create_parent_dir};
+#[cfg(not(test))]
+use crate::page_range::parse_page_range;
+#[cfg(not(test))]
+use crate::pdfium_init::{load_document, load_pdfium};
+#[cfg(not(test))]
+use crate::render_worker::{RenderOptions, RenderSize, render_page};
+use image::RgbImage;
+use image::imageops::overlay;
+#[cfg(not(test))]
+use serde::Serialize;
+#[cfg(not(test))]
+use std::path::Path;
+
+#[cfg(not(test))]
+#[derive(Serialize)]
+struct ContactSheetSummary {
+    pages: u32,
+    columns: u32,
+    rows: u32,
+    width_px: u32,
+    height_px: u32,
+    output_file: String,
+}
+
+/// Render the selected pages as `thumb_width` pixel wide thumbnails and tile
+/// them, `columns` to a row, into one image at `output_file`.
+///
+/// The format follows the file extension (`.jpg`, `.png`, ...). Thumbnails are
+/// rendered in memory with `render_page`; nothing else is written.
+#[cfg(not(test))]
+#[cfg_attr(coverage_nightly, coverage(off))]
+pub fn run(
+    pdf_path: &Path,
+    output_file: &Path,
+    pages: Option<&str>,
+    columns: u32,
+    thumb_width: u32,
+    password: Option<&str>,
+    quiet: bool,
+) -> Result<(), Error> {
+    if columns == 0 || thumb_width == 0 {
+        return Err(Error::InvalidArgs(
+            "--columns and --thumb-width must be greater than 0".into(),
+        ));
+    }
+
+    check_output_file(output_file)?;
+
+    let pdfium = load_pdfium()?;
+    let mut document = load_document(&pdfium, pdf_path, password)?;
+    let total_pages = document.pages().len() as u32;
+    let page_list = match pages {
+        Some(range_str) => parse_page_range(range_str, total_pages)?,
+        None => (1..=total_pages).collect(),
+    };
+    if page_list.is_empty() {
+        return Err(Error::PdfInvalid("PDF has no pages".into()));
+    }
+
+    let opts = RenderOptions {
+        size: RenderSize::TargetWidth(thumb_width),
+        ..RenderOptions::default()
+    };
+    let thumbs = page_list
+        .iter()
+        .map(|&page_num| {
+            let image = render_page(&mut document, page_num, &opts)?;
+            Ok(image.into_rgb8())
+        })
+        .collect::<Result<Vec<_>, Error>>()?;
+
+    let sheet = tile(&thumbs, columns);
+    create_parent_dir(output_file)?;
+    sheet
+        .save(output_file)
+        .map_err(|e| Error::Render(format!("{}: {e}", output_file.display())))?;
+
+    if quiet {
+        return Ok(());
+    }
+    let columns = columns.min(thumbs.len() as u32);
+    let summary = ContactSheetSummary {
+        pages: thumbs.len() as u32,
+        columns,
+        rows: (thumbs.len() as u32).div_ceil(columns),
+        width_px: sheet.width(),
+        height_px: sheet.height(),
+        output_file: output_file.display().to_string(),
+    };
+    print_json("contact-sheet", &summary);
+    Ok(())
+}
+
+/// Lay `thumbs` out left to right, top to bottom, on a white canvas.
+///
+/// Every cell is as wide as the widest thumbnail and as tall as the tallest,
+/// so mixed page sizes stay aligned; fewer thumbnails than `columns` narrows
+/// the sheet to one row of just those.
+pub fn tile(thumbs: &[RgbImage], columns: u32) -> RgbImage {
+    let columns = columns.min(thumbs.len() as u32).max(1);
+    let rows = (thumbs.len() as u32).div_ceil(columns);
+    let cell_width = thumbs.iter().map(RgbImage::width).max().unwrap_or(0);
+    let cell_height = thumbs.iter().map(RgbImage::height).max().unwrap_or(0);
+
+    let mut sheet = RgbImage::from_pixel(
+        columns * cell_width,
+        rows * cell_height,
+        image::Rgb([255, 255, 255]),
+    );
+    for (index, thumb) in (0u32..).zip(thumbs) {
+        let x = (index % columns) * cell_width;
+        let y = (index / columns) * cell_height;
+        overlay(&mut sheet, thumb, x.into(), y.into());
+    }
+    sheet
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn thumbnails_fill_rows_left_to_right() {
+        let thumbs: Vec<_> = [10, 80, 160]
+            .iter()
+            .map(|&shade| RgbImage::from_pixel(4, 6, image::Rgb([shade; 3])))
+            .collect();
+
+        let sheet = tile(&thumbs, 2);
+
+        assert_eq!(sheet.dimensions(), (8, 12));
+        assert_eq!(sheet.get_pixel(0, 0).0, [10; 3]);
+        assert_eq!(sheet.get_pixel(4, 0).0, [80; 3]);
+        assert_eq!(sheet.get_pixel(0, 6).0, [160; 3]);
+        // The missing fourth cell stays white.
+        assert_eq!(sheet.get_pixel(4, 6).0, [255; 3]);
+    }
+
+    #[test]
+    fn cells_fit_the_largest_thumbnail() {
+        let thumbs = vec![
+            RgbImage::from_pixel(4, 6, image::Rgb([0; 3])),
+            RgbImage::from_pixel(4, 3, image::Rgb([0; 3])),
+        ];
+
+        assert_eq!(tile(&thumbs, 4).dimensions(), (8, 6));
+        assert_eq!(tile(&thumbs, 1).dimensions(), (4, 12));
+        assert_eq!(tile(&[], 4).dimensions(), (0, 0));
+    }
+}
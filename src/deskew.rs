@@ -0,0 +1,166 @@
+use crate::render_worker::Background;
+use image::{DynamicImage, RgbaImage};
+use imageproc::geometric_transformations::{Interpolation, rotate_about_center};
+
+/// Largest skew looked for, in degrees either way. Scans are rarely off by
+/// more, and a wider search mistakes tables and diagrams for skewed text.
+pub const MAX_ANGLE: f32 = 5.0;
+/// Skew smaller than this, in degrees, is left alone.
+const MIN_CORRECTION: f32 = 0.1;
+/// Larger side the page is shrunk to before measuring, which bounds the work
+/// per page whatever the render size.
+const MEASURE_SIDE: u32 = 800;
+/// How far (0-255 per channel) a pixel must differ from the background to
+/// count as ink; higher than `--autocrop-threshold` so paper texture and
+/// JPEG noise stay out.
+const INK_THRESHOLD: u8 = 96;
+/// Pages with fewer ink pixels than this are too empty to measure.
+const MIN_INK: usize = 100;
+const COARSE_STEP: f32 = 0.5;
+const FINE_STEP: f32 = 0.05;
+
+/// Straighten `image` (`--deskew`): measure its skew with `skew_angle` and
+/// rotate it back about its center, filling the corners with `background`.
+/// The size is unchanged; pages that are straight enough are returned as is.
+pub fn deskew(image: DynamicImage, background: Background) -> DynamicImage {
+    let angle = skew_angle(&image, background);
+    if angle.abs() < MIN_CORRECTION {
+        return image;
+    }
+    // imageproc rotates clockwise.
+    DynamicImage::ImageRgba8(rotate_about_center(
+        &image.to_rgba8(),
+        -angle.to_radians(),
+        Interpolation::Bilinear,
+        background.pixel(),
+    ))
+}
+
+/// Degrees clockwise the lines of `image` run from horizontal, within
+/// `MAX_ANGLE`; `0.0` for pages with too little ink to tell.
+///
+/// Uses a projection profile: the ink pixels are projected onto the vertical
+/// axis at each candidate angle, and the angle whose row histogram is most
+/// sharply peaked (largest sum of squared counts) is the one that lines the
+/// text up. Candidates are tried every `COARSE_STEP` degrees, then every
+/// `FINE_STEP` around the best.
+pub fn skew_angle(image: &DynamicImage, background: Background) -> f32 {
+    let sample = if image.width().max(image.height()) > MEASURE_SIDE {
+        image.thumbnail(MEASURE_SIDE, MEASURE_SIDE).to_rgba8()
+    } else {
+        image.to_rgba8()
+    };
+    let ink = ink_points(&sample, background);
+    if ink.len() < MIN_INK {
+        return 0.0;
+    }
+
+    let best_of = |angles: &mut dyn Iterator<Item = f32>, start: f32| {
+        angles.fold((start, profile_score(&ink, start)), |best, angle| {
+            let score = profile_score(&ink, angle);
+            if score > best.1 { (angle, score) } else { best }
+        })
+    };
+    let steps = (MAX_ANGLE / COARSE_STEP).round() as i32;
+    let (coarse, _) = best_of(
+        &mut (-steps..=steps).map(|step| step as f32 * COARSE_STEP),
+        0.0,
+    );
+    let steps = (COARSE_STEP / FINE_STEP).round() as i32;
+    let (fine, _) = best_of(
+        &mut (-steps..=steps).map(|step| coarse + step as f32 * FINE_STEP),
+        coarse,
+    );
+    fine.clamp(-MAX_ANGLE, MAX_ANGLE)
+}
+
+/// Coordinates of the pixels differing from `background` by more than
+/// `INK_THRESHOLD`, in any channel or, on a transparent background, in alpha.
+fn ink_points(image: &RgbaImage, background: Background) -> Vec<(f32, f32)> {
+    let reference = [background.red, background.green, background.blue];
+    image
+        .enumerate_pixels()
+        .filter(|(_, _, pixel)| {
+            if background.is_transparent() {
+                pixel[3] > INK_THRESHOLD
+            } else {
+                (0..3).any(|channel| pixel[channel].abs_diff(reference[channel]) > INK_THRESHOLD)
+            }
+        })
+        .map(|(x, y, _)| (x as f32, y as f32))
+        .collect()
+}
+
+/// Sum of squared row counts of `ink` projected onto the vertical axis after
+/// turning it `angle` degrees counterclockwise.
+fn profile_score(ink: &[(f32, f32)], angle: f32) -> u64 {
+    let (sin, cos) = angle.to_radians().sin_cos();
+    let rows: Vec<i64> = ink
+        .iter()
+        .map(|&(x, y)| (y * cos - x * sin).round() as i64)
+        .collect();
+    let min = rows.iter().copied().min().unwrap_or(0);
+    let max = rows.iter().copied().max().unwrap_or(0);
+    let mut counts = vec![0u64; (max - min + 1) as usize];
+    for row in rows {
+        counts[(row - min) as usize] += 1;
+    }
+    counts.iter().map(|count| count * count).sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{GenericImageView, Rgba};
+
+    /// A white page with dark "text lines" tilted `angle` degrees clockwise.
+    fn lined_page(angle: f32) -> DynamicImage {
+        let mut page = RgbaImage::from_pixel(400, 300, Rgba([255; 4]));
+        for top in (40..260).step_by(20) {
+            for y in top..top + 6 {
+                for x in 40..360 {
+                    page.put_pixel(x, y, Rgba([0, 0, 0, 255]));
+                }
+            }
+        }
+        DynamicImage::ImageRgba8(rotate_about_center(
+            &page,
+            angle.to_radians(),
+            Interpolation::Bilinear,
+            Rgba([255; 4]),
+        ))
+    }
+
+    #[test]
+    fn measures_clockwise_and_counterclockwise_skew() {
+        for angle in [2.0, -3.0] {
+            let measured = skew_angle(&lined_page(angle), Background::WHITE);
+            assert!(
+                (measured - angle).abs() <= 0.2,
+                "measured {measured} for {angle}"
+            );
+        }
+        assert_eq!(skew_angle(&lined_page(0.0), Background::WHITE), 0.0);
+    }
+
+    #[test]
+    fn deskewed_page_measures_straighter() {
+        let skewed = lined_page(2.5);
+
+        let straightened = deskew(skewed.clone(), Background::WHITE);
+
+        assert_eq!(straightened.dimensions(), skewed.dimensions());
+        assert!(skew_angle(&straightened, Background::WHITE).abs() < MIN_CORRECTION);
+        // The uncovered corners take the background color.
+        assert_eq!(straightened.to_rgba8().get_pixel(0, 0).0, [255; 4]);
+    }
+
+    #[test]
+    fn blank_and_straight_pages_are_left_alone() {
+        let blank = DynamicImage::ImageRgba8(RgbaImage::from_pixel(50, 50, Rgba([255; 4])));
+        assert_eq!(skew_angle(&blank, Background::WHITE), 0.0);
+
+        let straight = lined_page(0.0);
+        assert_eq!(deskew(straight.clone(), Background::WHITE), straight);
+    }
+}
@@ -1,6 +1,7 @@
 use std::process::ExitCode;
 
 #[derive(Debug, thiserror::Error)]
+#[non_exhaustive]
 pub enum Error {
     #[error("{0}")]
     InvalidArgs(String),
@@ -14,18 +15,40 @@ pub enum Error {
     #[error("rendering error: {0}")]
     Render(String),
 
+    /// Some pages rendered and some failed; `Render` is for total failure
+    #[error("rendering error: {failed} errors during rendering ({succeeded} pages succeeded)")]
+    PartialRender { succeeded: u32, failed: u32 },
+
     #[error("I/O error: {0}")]
     Io(#[from] std::io::Error),
 }
 
 impl Error {
     pub fn exit_code(&self) -> ExitCode {
+        ExitCode::from(self.code())
+    }
+
+    /// The process exit status for this error.
+    pub fn code(&self) -> u8 {
+        match self {
+            Error::InvalidArgs(_) => 1,
+            Error::PdfInvalid(_) => 2,
+            Error::PdfiumNotFound(_) => 3,
+            Error::Render(_) => 4,
+            Error::Io(_) => 5,
+            Error::PartialRender { .. } => 6,
+        }
+    }
+
+    /// The variant as a stable snake_case name, for `--json-errors`.
+    pub fn kind(&self) -> &'static str {
         match self {
-            Error::InvalidArgs(_) => ExitCode::from(1),
-            Error::PdfInvalid(_) => ExitCode::from(2),
-            Error::PdfiumNotFound(_) => ExitCode::from(3),
-            Error::Render(_) => ExitCode::from(4),
-            Error::Io(_) => ExitCode::from(5),
+            Error::InvalidArgs(_) => "invalid_args",
+            Error::PdfInvalid(_) => "pdf_invalid",
+            Error::PdfiumNotFound(_) => "pdfium_not_found",
+            Error::Render(_) => "render",
+            Error::Io(_) => "io",
+            Error::PartialRender { .. } => "partial_render",
         }
     }
 }
@@ -56,5 +79,24 @@ mod tests {
             Error::Io(std::io::Error::from(std::io::ErrorKind::Other)).exit_code(),
             ExitCode::from(5)
         );
+        assert_eq!(
+            Error::PartialRender {
+                succeeded: 3,
+                failed: 1
+            }
+            .exit_code(),
+            ExitCode::from(6)
+        );
+    }
+
+    #[test]
+    fn kinds_name_the_variant() {
+        let error = Error::PdfiumNotFound("missing".into());
+        assert_eq!(error.kind(), "pdfium_not_found");
+        assert_eq!(error.code(), 3);
+        assert_eq!(
+            Error::Io(std::io::Error::from(std::io::ErrorKind::NotFound)).kind(),
+            "io"
+        );
     }
 }
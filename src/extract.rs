@@ -0,0 +1,134 @@
+#[cfg(not(test))]
+use crate::error::Error;
+#[cfg(not(test))]
+use crate::json_output::print_json;
+#[cfg(not(test))]
+use crate::output_path::{check_output_dir, create_output_dir};
+#[cfg(not(test))]
+use crate::page_range::parse_page_range;
+#[cfg(not(test))]
+use crate::pdfium_init::{load_document, load_pdfium};
+#[cfg(not(test))]
+use crate::render_worker::is_extractable_jpeg;
+#[cfg(not(test))]
+use pdfium_render::prelude::*;
+#[cfg(not(test))]
+use serde::Serialize;
+#[cfg(not(test))]
+use std::path::Path;
+
+#[cfg(not(test))]
+#[derive(Serialize)]
+struct ExtractSummary {
+    pages_scanned: u32,
+    images_raw: u32,
+    images_reencoded: u32,
+    output_dir: String,
+}
+
+/// Output filename for the `index`-th (1-based) image object on a page.
+pub fn image_filename(page_num: u32, index: u32, extension: &str) -> String {
+    format!("page-{page_num:04}-img-{index:02}.{extension}")
+}
+
+/// Save every image object on the selected pages.
+///
+/// Non-CMYK DCTDecode images are copied byte-for-byte as `.jpg` (the same test
+/// `render --extract-images` uses); everything else, including CMYK JPEGs, is
+/// decoded by pdfium and written as `.png`. Images nested in form XObjects are
+/// not visited.
+#[cfg(not(test))]
+#[cfg_attr(coverage_nightly, coverage(off))]
+pub fn run(
+    pdf_path: &Path,
+    output_dir: &Path,
+    pages: Option<&str>,
+    password: Option<&str>,
+    quiet: bool,
+) -> Result<(), Error> {
+    check_output_dir(output_dir)?;
+    let pdfium = load_pdfium()?;
+    let document = load_document(&pdfium, pdf_path, password)?;
+    let total_pages = document.pages().len() as u32;
+
+    let page_list = match pages {
+        Some(range_str) => parse_page_range(range_str, total_pages)?,
+        None => (1..=total_pages).collect(),
+    };
+    create_output_dir(output_dir)?;
+
+    let mut summary = ExtractSummary {
+        pages_scanned: 0,
+        images_raw: 0,
+        images_reencoded: 0,
+        output_dir: output_dir.display().to_string(),
+    };
+    for page_num in page_list {
+        let page = document
+            .pages()
+            .get((page_num - 1) as u16)
+            .map_err(|e| Error::PdfInvalid(format!("page {page_num}: {e}")))?;
+        extract_page_images(&page, page_num, output_dir, &mut summary)?;
+        summary.pages_scanned += 1;
+    }
+
+    if !quiet {
+        print_json("extract-images", &summary);
+    }
+    Ok(())
+}
+
+#[cfg(not(test))]
+#[cfg_attr(coverage_nightly, coverage(off))]
+fn extract_page_images(
+    page: &PdfPage,
+    page_num: u32,
+    output_dir: &Path,
+    summary: &mut ExtractSummary,
+) -> Result<(), Error> {
+    let mut index = 0;
+    for object in page.objects().iter() {
+        let Some(image_obj) = object.as_image_object() else {
+            continue;
+        };
+        index += 1;
+
+        if is_extractable_jpeg(image_obj) {
+            let data = image_obj.get_raw_image_data().map_err(|e| {
+                Error::Render(format!("page {page_num} image {index}: raw data: {e}"))
+            })?;
+            std::fs::write(
+                output_dir.join(image_filename(page_num, index, "jpg")),
+                data,
+            )?;
+            summary.images_raw += 1;
+        } else {
+            let image = image_obj.get_raw_image().map_err(|e| {
+                Error::Render(format!("page {page_num} image {index}: decode failed: {e}"))
+            })?;
+            image
+                .save_with_format(
+                    output_dir.join(image_filename(page_num, index, "png")),
+                    image::ImageFormat::Png,
+                )
+                .map_err(|e| {
+                    Error::Render(format!(
+                        "page {page_num} image {index}: PNG encode failed: {e}"
+                    ))
+                })?;
+            summary.images_reencoded += 1;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn image_filenames_number_page_and_image() {
+        assert_eq!(image_filename(7, 2, "jpg"), "page-0007-img-02.jpg");
+        assert_eq!(image_filename(12, 10, "png"), "page-0012-img-10.png");
+    }
+}
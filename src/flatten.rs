@@ -0,0 +1,90 @@
+#[cfg(not(test))]
+use crate::error::Error;
+#[cfg(not(test))]
+use crate::json_output::print_json;
+#[cfg(not(test))]
+use crate::output_path::{check_output_file, create_parent_dir};
+#[cfg(not(test))]
+use crate::page_range::parse_page_range;
+#[cfg(not(test))]
+use crate::pdfium_init::{load_document, load_pdfium};
+#[cfg(not(test))]
+use pdfium_render::prelude::*;
+#[cfg(not(test))]
+use serde::Serialize;
+#[cfg(not(test))]
+use std::path::Path;
+
+#[cfg(not(test))]
+#[derive(Serialize)]
+struct FlattenSummary {
+    pages: u32,
+    pages_flattened: u32,
+    output_file: String,
+}
+
+/// Bake the annotations and form fields of the selected pages into their
+/// content and write the whole document to `output_file`.
+///
+/// pdfium draws each annotation's appearance stream into the page and drops
+/// the annotation, so filled form values stay visible (and extractable as
+/// text) without an interactive form. The result is reopened to check that
+/// no page was lost.
+#[cfg(not(test))]
+#[cfg_attr(coverage_nightly, coverage(off))]
+pub fn run(
+    pdf_path: &Path,
+    output_file: &Path,
+    pages: Option<&str>,
+    password: Option<&str>,
+    quiet: bool,
+) -> Result<(), Error> {
+    check_output_file(output_file)?;
+    let pdfium = load_pdfium()?;
+    let document = load_document(&pdfium, pdf_path, password)?;
+    let total_pages = document.pages().len() as u32;
+    let page_list = match pages {
+        Some(range_str) => parse_page_range(range_str, total_pages)?,
+        None => (1..=total_pages).collect(),
+    };
+    if page_list.is_empty() {
+        return Err(Error::PdfInvalid("PDF has no pages".into()));
+    }
+
+    for &page_num in &page_list {
+        let page_error = |e: PdfiumError| Error::Render(format!("flatten page {page_num}: {e}"));
+        let mut page = document
+            .pages()
+            .get((page_num - 1) as PdfPageIndex)
+            .map_err(page_error)?;
+        page.flatten().map_err(page_error)?;
+    }
+
+    create_parent_dir(output_file)?;
+    document
+        .save_to_file(output_file)
+        .map_err(|e| Error::Render(format!("{}: {e}", output_file.display())))?;
+    drop(document);
+
+    // pdfium keeps the source's encryption when saving.
+    let written = pdfium
+        .load_pdf_from_file(output_file, password)
+        .map_err(|e| Error::Render(format!("{}: {e}", output_file.display())))?;
+    let written_pages = written.pages().len() as u32;
+    if written_pages != total_pages {
+        return Err(Error::Render(format!(
+            "{}: flattened PDF has {written_pages} pages, expected {total_pages}",
+            output_file.display()
+        )));
+    }
+
+    if !quiet {
+        let summary = FlattenSummary {
+            pages: total_pages,
+            pages_flattened: page_list.len() as u32,
+            output_file: output_file.display().to_string(),
+        };
+        print_json("flatten", &summary);
+    }
+    Ok(())
+}
@@ -0,0 +1,182 @@
+#[cfg(not(test))]
+use crate::error::Error;
+#[cfg(not(test))]
+use crate::json_output::print_json;
+#[cfg(not(test))]
+use crate::pdfium_init::{load_document, load_pdfium};
+#[cfg(not(test))]
+use pdfium_render::prelude::*;
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet};
+#[cfg(not(test))]
+use std::path::Path;
+
+/// A font used by the text on the document's pages (`info --fonts`).
+#[derive(Debug, PartialEq, Eq, Serialize)]
+pub struct FontInfo {
+    /// The `/BaseFont` name, including any subset prefix such as `ABCDEF+`
+    pub name: String,
+    pub embedded: bool,
+    /// Format of the embedded font program (`TrueType`, `OpenType`, `Type1`,
+    /// `Type1C` or `Type3`); null when the font is not embedded, as pdfium
+    /// does not expose the font dictionary's `/Subtype`
+    pub subtype: Option<&'static str>,
+    /// Pages with text in this font, ascending
+    pub pages: Vec<u32>,
+}
+
+/// A font's embedded subtype and the pages using it.
+type FontUse = (Option<&'static str>, BTreeSet<u32>);
+
+/// Fonts seen page by page, one entry per name and embedding.
+#[derive(Default)]
+pub struct FontList {
+    fonts: BTreeMap<(String, bool), FontUse>,
+}
+
+impl FontList {
+    /// Record that `page` uses the font; `subtype` is only called for a font
+    /// not seen before, since reading the font program is the slow part.
+    pub fn add(
+        &mut self,
+        page: u32,
+        name: String,
+        embedded: bool,
+        subtype: impl FnOnce() -> Option<&'static str>,
+    ) {
+        self.fonts
+            .entry((name, embedded))
+            .or_insert_with(|| (subtype(), BTreeSet::new()))
+            .1
+            .insert(page);
+    }
+
+    /// The fonts sorted by name, non-embedded before embedded.
+    pub fn into_fonts(self) -> Vec<FontInfo> {
+        self.fonts
+            .into_iter()
+            .map(|((name, embedded), (subtype, pages))| FontInfo {
+                name,
+                embedded,
+                subtype,
+                pages: pages.into_iter().collect(),
+            })
+            .collect()
+    }
+}
+
+/// Tell the font program format from the embedded data's signature. Type 3
+/// fonts are embedded as glyph procedures, so pdfium has no program for them.
+pub fn font_program_subtype(data: &[u8]) -> Option<&'static str> {
+    match data {
+        [] => Some("Type3"),
+        [b'O', b'T', b'T', b'O', ..] => Some("OpenType"),
+        [0, 1, 0, 0, ..] | [b't', b'r', b'u', b'e', ..] | [b't', b't', b'c', b'f', ..] => {
+            Some("TrueType")
+        }
+        // PostScript text, or PFB segments
+        [b'%', b'!', ..] | [0x80, 0x01, ..] => Some("Type1"),
+        // Bare CFF, header version 1
+        [1, 0, ..] => Some("Type1C"),
+        _ => None,
+    }
+}
+
+#[cfg(not(test))]
+#[derive(Serialize)]
+struct FontReport {
+    fonts: Vec<FontInfo>,
+}
+
+/// Print the fonts used by the text on every page (`info --fonts`). Every
+/// page's objects are loaded, so this is slower than plain `info`.
+#[cfg(not(test))]
+#[cfg_attr(coverage_nightly, coverage(off))]
+pub fn run_list(pdf_path: &Path, password: Option<&str>) -> Result<(), Error> {
+    let pdfium = load_pdfium()?;
+    let document = load_document(&pdfium, pdf_path, password)?;
+    let mut fonts = FontList::default();
+    for (index, page) in document.pages().iter().enumerate() {
+        for object in page.objects().iter() {
+            add_object_fonts(&mut fonts, index as u32 + 1, &object);
+        }
+    }
+    print_json(
+        "fonts",
+        &FontReport {
+            fonts: fonts.into_fonts(),
+        },
+    );
+    Ok(())
+}
+
+/// Record the font of a text object, or of the text inside a form XObject.
+#[cfg(not(test))]
+#[cfg_attr(coverage_nightly, coverage(off))]
+fn add_object_fonts(fonts: &mut FontList, page: u32, object: &PdfPageObject) {
+    match object {
+        PdfPageObject::Text(text) => {
+            let font = text.font();
+            let embedded = font.is_embedded().unwrap_or(false);
+            fonts.add(page, font.name(), embedded, || {
+                embedded
+                    .then(|| font_program_subtype(&font.data().unwrap_or_default()))
+                    .flatten()
+            });
+        }
+        PdfPageObject::XObjectForm(form) => {
+            for index in 0..form.len() {
+                if let Ok(nested) = form.get(index) {
+                    add_object_fonts(fonts, page, &nested);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fonts_are_deduplicated_with_their_pages() {
+        let mut fonts = FontList::default();
+        fonts.add(2, "Helvetica".into(), false, || None);
+        fonts.add(1, "ABCDEF+DejaVuSans".into(), true, || Some("TrueType"));
+        fonts.add(1, "Helvetica".into(), false, || unreachable!());
+        fonts.add(2, "Helvetica".into(), false, || unreachable!());
+
+        assert_eq!(
+            fonts.into_fonts(),
+            [
+                FontInfo {
+                    name: "ABCDEF+DejaVuSans".into(),
+                    embedded: true,
+                    subtype: Some("TrueType"),
+                    pages: vec![1],
+                },
+                FontInfo {
+                    name: "Helvetica".into(),
+                    embedded: false,
+                    subtype: None,
+                    pages: vec![1, 2],
+                },
+            ]
+        );
+        assert!(FontList::default().into_fonts().is_empty());
+    }
+
+    #[test]
+    fn font_programs_are_recognized_by_signature() {
+        assert_eq!(
+            font_program_subtype(&[0, 1, 0, 0, 0, 0x12]),
+            Some("TrueType")
+        );
+        assert_eq!(font_program_subtype(b"OTTO\0\x0a"), Some("OpenType"));
+        assert_eq!(font_program_subtype(b"%!PS-AdobeFont-1.0"), Some("Type1"));
+        assert_eq!(font_program_subtype(&[1, 0, 4, 2]), Some("Type1C"));
+        assert_eq!(font_program_subtype(&[]), Some("Type3"));
+        assert_eq!(font_program_subtype(b"wOFF"), None);
+    }
+}
@@ -0,0 +1,244 @@
+use crate::error::Error;
+use std::path::{Path, PathBuf};
+
+/// Profile bytes that fit in one JPEG APP2 segment, after the segment length,
+/// the `ICC_PROFILE\0` tag and the sequence number and count bytes.
+const JPEG_CHUNK_SIZE: usize = 65_535 - 2 - 14;
+const JPEG_ICC_TAG: &[u8; 12] = b"ICC_PROFILE\0";
+
+/// ICC profile embedded in JPEG and PNG output (`--icc` or `--srgb`).
+#[derive(Clone, Debug, PartialEq)]
+pub struct IccProfile {
+    /// The `--icc` file; `None` for the built-in sRGB profile
+    pub path: Option<PathBuf>,
+    pub data: Vec<u8>,
+}
+
+impl IccProfile {
+    /// Read an `--icc` profile, rejecting missing, empty and oversized files.
+    pub fn load(path: &Path) -> Result<Self, Error> {
+        let data = std::fs::read(path)
+            .map_err(|e| Error::InvalidArgs(format!("--icc {}: {e}", path.display())))?;
+        if data.is_empty() {
+            return Err(Error::InvalidArgs(format!(
+                "--icc {}: profile is empty",
+                path.display()
+            )));
+        }
+        if data.len() > JPEG_CHUNK_SIZE * 255 {
+            return Err(Error::InvalidArgs(format!(
+                "--icc {}: profile is larger than a JPEG can carry",
+                path.display()
+            )));
+        }
+        Ok(Self {
+            path: Some(path.to_path_buf()),
+            data,
+        })
+    }
+
+    /// The built-in sRGB profile (`--srgb`).
+    pub fn srgb() -> Self {
+        Self {
+            path: None,
+            data: srgb_profile(),
+        }
+    }
+}
+
+/// Insert `profile` as APP2 `ICC_PROFILE` segments into an encoded JPEG,
+/// after the SOI marker and the JFIF APP0 segment if there is one.
+pub fn embed_jpeg_icc(jpeg: &[u8], profile: &[u8]) -> Result<Vec<u8>, Error> {
+    if !jpeg.starts_with(&[0xFF, 0xD8]) {
+        return Err(Error::Render("ICC embedding: not a JPEG stream".into()));
+    }
+    let mut insert_at = 2;
+    if jpeg[2..].starts_with(&[0xFF, 0xE0]) && jpeg.len() >= 6 {
+        insert_at += 2 + u16::from_be_bytes([jpeg[4], jpeg[5]]) as usize;
+    }
+    let insert_at = insert_at.min(jpeg.len());
+
+    let chunks: Vec<_> = profile.chunks(JPEG_CHUNK_SIZE).collect();
+    let mut out = Vec::with_capacity(jpeg.len() + profile.len() + chunks.len() * 18);
+    out.extend_from_slice(&jpeg[..insert_at]);
+    for (index, chunk) in chunks.iter().enumerate() {
+        let length = (2 + JPEG_ICC_TAG.len() + 2 + chunk.len()) as u16;
+        out.extend_from_slice(&[0xFF, 0xE2]);
+        out.extend_from_slice(&length.to_be_bytes());
+        out.extend_from_slice(JPEG_ICC_TAG);
+        out.extend_from_slice(&[index as u8 + 1, chunks.len() as u8]);
+        out.extend_from_slice(chunk);
+    }
+    out.extend_from_slice(&jpeg[insert_at..]);
+    Ok(out)
+}
+
+/// A version 2 matrix/TRC display profile for sRGB: the D50-adapted sRGB
+/// primaries and the sRGB transfer curve sampled at 1024 points.
+pub fn srgb_profile() -> Vec<u8> {
+    const CURVE_POINTS: usize = 1024;
+
+    let curve: Vec<u16> = (0..CURVE_POINTS)
+        .map(|i| {
+            let encoded = i as f64 / (CURVE_POINTS - 1) as f64;
+            let linear = if encoded <= 0.04045 {
+                encoded / 12.92
+            } else {
+                ((encoded + 0.055) / 1.055).powf(2.4)
+            };
+            (linear * 65535.0).round() as u16
+        })
+        .collect();
+    let mut trc = b"curv\0\0\0\0".to_vec();
+    trc.extend_from_slice(&(CURVE_POINTS as u32).to_be_bytes());
+    for value in curve {
+        trc.extend_from_slice(&value.to_be_bytes());
+    }
+
+    let tags: [(&[u8; 4], Vec<u8>); 9] = [
+        (b"desc", text_description("sRGB")),
+        (b"cprt", text("No copyright, use freely")),
+        (b"wtpt", xyz([0.9642, 1.0, 0.8249])),
+        (b"rXYZ", xyz([0.4361, 0.2225, 0.0139])),
+        (b"gXYZ", xyz([0.3851, 0.7169, 0.0971])),
+        (b"bXYZ", xyz([0.1431, 0.0606, 0.7141])),
+        (b"rTRC", trc.clone()),
+        (b"gTRC", trc.clone()),
+        (b"bTRC", trc),
+    ];
+
+    let data_start = 128 + 4 + 12 * tags.len();
+    let mut table = (tags.len() as u32).to_be_bytes().to_vec();
+    let mut data = Vec::new();
+    for (signature, body) in &tags {
+        table.extend_from_slice(*signature);
+        table.extend_from_slice(&((data_start + data.len()) as u32).to_be_bytes());
+        table.extend_from_slice(&(body.len() as u32).to_be_bytes());
+        data.extend_from_slice(body);
+        // Tag data starts on four-byte boundaries.
+        data.resize(data.len().next_multiple_of(4), 0);
+    }
+
+    let size = 128 + table.len() + data.len();
+    let mut profile = Vec::with_capacity(size);
+    profile.extend_from_slice(&(size as u32).to_be_bytes());
+    profile.extend_from_slice(&[0; 4]); // preferred CMM
+    profile.extend_from_slice(&[2, 0x10, 0, 0]); // version 2.1
+    profile.extend_from_slice(b"mntrRGB XYZ ");
+    profile.extend_from_slice(&[0; 12]); // creation date
+    profile.extend_from_slice(b"acsp");
+    profile.extend_from_slice(&[0; 24]); // platform, flags, device, attributes
+    profile.extend_from_slice(&[0; 4]); // perceptual intent
+    profile.extend_from_slice(&xyz([0.9642, 1.0, 0.8249])[8..]); // D50 illuminant
+    profile.resize(128, 0); // creator and reserved bytes
+    profile.extend_from_slice(&table);
+    profile.extend_from_slice(&data);
+    profile
+}
+
+/// `XYZType` with one s15Fixed16 XYZ value.
+fn xyz(values: [f64; 3]) -> Vec<u8> {
+    let mut body = b"XYZ \0\0\0\0".to_vec();
+    for value in values {
+        body.extend_from_slice(&((value * 65536.0).round() as i32).to_be_bytes());
+    }
+    body
+}
+
+fn text(value: &str) -> Vec<u8> {
+    let mut body = b"text\0\0\0\0".to_vec();
+    body.extend_from_slice(value.as_bytes());
+    body.push(0);
+    body
+}
+
+/// Version 2 `textDescriptionType`: the ASCII name with empty Unicode and
+/// ScriptCode parts.
+fn text_description(value: &str) -> Vec<u8> {
+    let mut body = b"desc\0\0\0\0".to_vec();
+    body.extend_from_slice(&(value.len() as u32 + 1).to_be_bytes());
+    body.extend_from_slice(value.as_bytes());
+    body.push(0);
+    body.extend_from_slice(&[0; 8]); // Unicode language code and length
+    body.extend_from_slice(&[0; 3]); // ScriptCode code and length
+    body.extend_from_slice(&[0; 67]); // ScriptCode characters
+    body
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn be_u32(bytes: &[u8], at: usize) -> usize {
+        u32::from_be_bytes(bytes[at..at + 4].try_into().unwrap()) as usize
+    }
+
+    #[test]
+    fn srgb_profile_header_and_tags_are_consistent() {
+        let profile = srgb_profile();
+
+        assert_eq!(be_u32(&profile, 0), profile.len());
+        assert_eq!(&profile[12..24], b"mntrRGB XYZ ");
+        assert_eq!(&profile[36..40], b"acsp");
+        let tag_count = be_u32(&profile, 128);
+        assert_eq!(tag_count, 9);
+        for tag in 0..tag_count {
+            let entry = 132 + tag * 12;
+            let (offset, size) = (be_u32(&profile, entry + 4), be_u32(&profile, entry + 8));
+            assert_eq!(offset % 4, 0);
+            assert!(offset + size <= profile.len());
+        }
+    }
+
+    #[test]
+    fn srgb_curve_follows_the_srgb_transfer_function() {
+        let profile = srgb_profile();
+        let curve_offset = be_u32(&profile, 132 + 6 * 12 + 4);
+        let point = |index: usize| {
+            let at = curve_offset + 12 + index * 2;
+            u16::from_be_bytes([profile[at], profile[at + 1]])
+        };
+
+        assert_eq!(&profile[curve_offset..curve_offset + 4], b"curv");
+        assert_eq!(be_u32(&profile, curve_offset + 8), 1024);
+        assert_eq!(point(0), 0);
+        assert_eq!(point(1023), 65535);
+        // Mid grey (encoded 0.5) is about 21.4% linear light.
+        assert!((13_950..14_050).contains(&point(511)));
+    }
+
+    #[test]
+    fn embed_inserts_app2_after_jfif_header() {
+        let jfif = [0xFF, 0xE0, 0x00, 0x04, 0xAA, 0xBB];
+        let jpeg = [&[0xFF, 0xD8][..], &jfif, &[0xFF, 0xD9]].concat();
+
+        let embedded = embed_jpeg_icc(&jpeg, b"profile").unwrap();
+
+        assert_eq!(&embedded[..8], &jpeg[..8]);
+        assert_eq!(&embedded[8..10], &[0xFF, 0xE2]);
+        assert_eq!(&embedded[10..12], &(2u16 + 14 + 7).to_be_bytes());
+        assert_eq!(&embedded[12..24], b"ICC_PROFILE\0");
+        assert_eq!(&embedded[24..26], &[1, 1]);
+        assert_eq!(&embedded[26..33], b"profile");
+        assert_eq!(&embedded[33..], &[0xFF, 0xD9]);
+    }
+
+    #[test]
+    fn embed_splits_large_profiles_into_numbered_segments() {
+        let profile = vec![7u8; JPEG_CHUNK_SIZE * 2 + 10];
+
+        let embedded = embed_jpeg_icc(&[0xFF, 0xD8, 0xFF, 0xD9], &profile).unwrap();
+
+        let second = 2 + 4 + 14 + JPEG_CHUNK_SIZE;
+        assert_eq!(&embedded[2..4], &[0xFF, 0xE2]);
+        assert_eq!(&embedded[18..20], &[1, 3]);
+        assert_eq!(&embedded[second..second + 2], &[0xFF, 0xE2]);
+        assert_eq!(&embedded[second + 16..second + 18], &[2, 3]);
+        assert_eq!(embedded.len(), 4 + profile.len() + 3 * 18);
+    }
+
+    #[test]
+    fn embed_rejects_non_jpeg_data() {
+        assert!(embed_jpeg_icc(b"\x89PNG", b"profile").is_err());
+    }
+}
@@ -1,64 +1,590 @@
 #[cfg(not(test))]
 use crate::error::Error;
 #[cfg(not(test))]
-use crate::pdfium_init::load_pdfium;
+use crate::json_output::print_json;
 #[cfg(not(test))]
-use serde::Serialize;
+use crate::page_range::{divide_pages, parse_page_range};
+#[cfg(not(test))]
+use crate::pdfium_init::{SpooledStdin, forward_pdfium_path, is_stdin, load_document, load_pdfium};
+#[cfg(not(test))]
+use crate::render_worker::{is_single_jpeg_page, rotation_degrees};
+#[cfg(not(test))]
+use pdfium_render::prelude::*;
+#[cfg(not(test))]
+use serde::{Deserialize, Serialize};
 #[cfg(not(test))]
 use std::path::Path;
+#[cfg(not(test))]
+use std::process::{Child, Command, Stdio};
 
 #[cfg(not(test))]
 #[derive(Serialize)]
 pub struct PdfInfo {
     pub page_count: u32,
+    /// Header version such as `"1.7"`; null when pdfium reports none
+    pub pdf_version: Option<String>,
+    /// Size of the PDF file; null when it was read from stdin or via `read_info`
+    pub file_size_bytes: Option<u64>,
+    pub metadata: DocumentMetadata,
+    pub encryption: EncryptionInfo,
     pub pages: Vec<PageInfo>,
 }
 
+/// Standard security handler state; `permissions` is null for unencrypted documents.
+#[cfg(not(test))]
+#[derive(Serialize)]
+pub struct EncryptionInfo {
+    pub encrypted: bool,
+    /// The document was encrypted and opened with `--password`
+    pub opened_with_password: bool,
+    pub permissions: Option<Permissions>,
+}
+
+/// What the document's permission flags allow.
+#[cfg(not(test))]
+#[derive(Serialize)]
+pub struct Permissions {
+    pub print: bool,
+    pub copy: bool,
+    pub modify: bool,
+    pub annotate: bool,
+}
+
+/// Document information dictionary entries; missing entries serialize as null.
 #[cfg(not(test))]
 #[derive(Serialize)]
+pub struct DocumentMetadata {
+    pub title: Option<String>,
+    pub author: Option<String>,
+    pub subject: Option<String>,
+    pub keywords: Option<String>,
+    pub creator: Option<String>,
+    pub producer: Option<String>,
+    pub creation_date: Option<String>,
+    pub modification_date: Option<String>,
+}
+
+#[cfg(not(test))]
+#[derive(Serialize, Deserialize)]
 pub struct PageInfo {
     pub page: u32,
     pub width_pt: f32,
     pub height_pt: f32,
+    /// The page's `/Rotate`, degrees clockwise: 0, 90, 180 or 270. The
+    /// width and height are as displayed, after this rotation
+    pub rotation_deg: u32,
+    /// The page's label (`iii`, `A-1`); null when the document defines none
+    pub page_label: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub boxes: Option<PageBoxes>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub image_stats: Option<ImageStats>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub objects: Option<ObjectCounts>,
+}
+
+/// Optional per-page details, each costing extra pdfium calls per page.
+#[derive(Clone, Copy, Default)]
+pub struct PageDetails {
+    /// Report all five page boundary boxes (`--boxes`)
+    pub boxes: bool,
+    /// Report page object and image counts (`--image-stats`)
+    pub image_stats: bool,
+    /// Report annotation and per-type page object counts (`--objects`)
+    pub objects: bool,
+}
+
+/// Annotations and top-level page objects by type; objects nested in form
+/// XObjects are not counted.
+#[cfg(not(test))]
+#[derive(Serialize, Deserialize)]
+pub struct ObjectCounts {
+    pub annotation_count: u32,
+    pub image_count: u32,
+    pub path_count: u32,
+    pub text_count: u32,
+}
+
+/// Page object counts, to estimate how many pages `--extract-images` will copy.
+#[cfg(not(test))]
+#[derive(Serialize, Deserialize)]
+pub struct ImageStats {
+    pub objects: u32,
+    pub is_single_jpeg: bool,
+    pub image_count: u32,
+}
+
+/// All five page boundary boxes; undefined boxes serialize as null.
+#[cfg(not(test))]
+#[derive(Serialize, Deserialize)]
+pub struct PageBoxes {
+    pub media: Option<BoxBounds>,
+    pub crop: Option<BoxBounds>,
+    pub bleed: Option<BoxBounds>,
+    pub trim: Option<BoxBounds>,
+    pub art: Option<BoxBounds>,
+}
+
+/// First page size printed by `info --dimensions-only`.
+#[cfg(not(test))]
+#[derive(Serialize)]
+pub struct PageDimensions {
+    pub width_pt: f32,
+    pub height_pt: f32,
+}
+
+/// Box edges in points.
+#[cfg(not(test))]
+#[derive(Serialize, Deserialize)]
+pub struct BoxBounds {
+    pub left: f32,
+    pub bottom: f32,
+    pub right: f32,
+    pub top: f32,
 }
 
 #[cfg(not(test))]
 #[cfg_attr(coverage_nightly, coverage(off))]
-pub fn run(pdf_path: &Path, all_pages: bool) -> Result<(), Error> {
-    let pdfium = load_pdfium()?;
+pub fn run(
+    pdf_path: &Path,
+    all_pages: bool,
+    details: PageDetails,
+    workers: u32,
+    password: Option<&str>,
+) -> Result<(), Error> {
+    // Stdin is read into memory, except when workers need a path to open.
+    let multi_process = all_pages && workers > 1;
+    let spool = if multi_process && is_stdin(pdf_path) {
+        Some(SpooledStdin::create()?)
+    } else {
+        None
+    };
+    let pdf_path = spool.as_ref().map_or(pdf_path, SpooledStdin::path);
 
-    let document = pdfium
-        .load_pdf_from_file(pdf_path, None)
-        .map_err(|e| Error::PdfInvalid(format!("{}: {e}", pdf_path.display())))?;
+    let pdfium = load_pdfium()?;
+    let document = load_document(&pdfium, pdf_path, password)?;
 
     let page_count = document.pages().len() as u32;
+    let mut info = if multi_process && page_count > 1 {
+        PdfInfo {
+            page_count,
+            pdf_version: read_pdf_version(&document),
+            file_size_bytes: None,
+            metadata: read_metadata(&document),
+            encryption: read_encryption(&document),
+            pages: run_multi_process(pdf_path, page_count, workers, details, password)?,
+        }
+    } else {
+        read_info(&document, all_pages, details)?
+    };
+    info.encryption.opened_with_password = info.encryption.encrypted && password.is_some();
+    if !is_stdin(pdf_path) {
+        info.file_size_bytes = Some(std::fs::metadata(pdf_path)?.len());
+    }
+    print_json("info", &info);
+
+    Ok(())
+}
 
+/// Print only the first page's size (`info --dimensions-only`).
+///
+/// pdfium reads the size from the page dictionary without loading the page,
+/// and metadata, encryption, labels and the page count are skipped, so this is
+/// the cheapest call for scripts that only need the layout.
+#[cfg(not(test))]
+#[cfg_attr(coverage_nightly, coverage(off))]
+pub fn run_dimensions(pdf_path: &Path, password: Option<&str>) -> Result<(), Error> {
+    let pdfium = load_pdfium()?;
+    let document = load_document(&pdfium, pdf_path, password)?;
+    let size = document
+        .pages()
+        .page_size(0)
+        .map_err(|_| Error::PdfInvalid("PDF has no pages".into()))?;
+    let dimensions = PageDimensions {
+        width_pt: size.width().value,
+        height_pt: size.height().value,
+    };
+    print_json("dimensions", &dimensions);
+    Ok(())
+}
+
+/// Read page count, metadata and page dimensions from a loaded document.
+///
+/// Only the first page is described unless `all_pages` is set; `details`
+/// selects the optional `PageInfo` fields.
+#[cfg(not(test))]
+#[cfg_attr(coverage_nightly, coverage(off))]
+pub fn read_info(
+    document: &PdfDocument,
+    all_pages: bool,
+    details: PageDetails,
+) -> Result<PdfInfo, Error> {
     let pages = if all_pages {
         document
             .pages()
             .iter()
             .enumerate()
-            .map(|(i, page)| PageInfo {
-                page: i as u32 + 1,
-                width_pt: page.width().value,
-                height_pt: page.height().value,
-            })
+            .map(|(i, page)| page_info(i as u32 + 1, &page, details))
             .collect()
     } else {
-        // Just first page by default
+        // Just the first page by default: no other page is loaded
         let first = document
             .pages()
             .first()
             .map_err(|_| Error::PdfInvalid("PDF has no pages".into()))?;
-        vec![PageInfo {
-            page: 1,
-            width_pt: first.width().value,
-            height_pt: first.height().value,
-        }]
+        vec![page_info(1, &first, details)]
     };
 
-    let info = PdfInfo { page_count, pages };
-    println!("{}", serde_json::to_string_pretty(&info).unwrap());
+    Ok(PdfInfo {
+        page_count: document.pages().len() as u32,
+        pdf_version: read_pdf_version(document),
+        file_size_bytes: None,
+        metadata: read_metadata(document),
+        encryption: read_encryption(document),
+        pages,
+    })
+}
+
+/// Internal `info-worker` entry point: print `PageInfo` for `pages` as a JSON array.
+#[cfg(not(test))]
+#[cfg_attr(coverage_nightly, coverage(off))]
+pub fn run_worker(
+    pdf_path: &Path,
+    pages: &str,
+    details: PageDetails,
+    password: Option<&str>,
+) -> Result<(), Error> {
+    let pdfium = load_pdfium()?;
+    let document = load_document(&pdfium, pdf_path, password)?;
+    let page_list = parse_page_range(pages, document.pages().len() as u32)?;
+
+    let mut infos = Vec::with_capacity(page_list.len());
+    for page_num in page_list {
+        let page = document
+            .pages()
+            .get((page_num - 1) as u16)
+            .map_err(|e| Error::PdfInvalid(format!("page {page_num}: {e}")))?;
+        infos.push(page_info(page_num, &page, details));
+    }
+    println!("{}", serde_json::to_string(&infos).unwrap());
 
     Ok(())
 }
+
+/// Split page indices across `info-worker` subprocesses and merge their
+/// `PageInfo` arrays in page order.
+#[cfg(not(test))]
+#[cfg_attr(coverage_nightly, coverage(off))]
+fn run_multi_process(
+    pdf_path: &Path,
+    page_count: u32,
+    workers: u32,
+    details: PageDetails,
+    password: Option<&str>,
+) -> Result<Vec<PageInfo>, Error> {
+    let current_exe = std::env::current_exe()?;
+    let children: Vec<Child> = divide_pages(page_count, workers)
+        .iter()
+        .map(|&(start, end)| {
+            let mut cmd = Command::new(&current_exe);
+            forward_pdfium_path(&mut cmd);
+            cmd.arg("info-worker")
+                .arg(pdf_path)
+                .arg("--pages")
+                .arg(format!("{start}-{end}"));
+            if details.boxes {
+                cmd.arg("--boxes");
+            }
+            if details.image_stats {
+                cmd.arg("--image-stats");
+            }
+            if details.objects {
+                cmd.arg("--objects");
+            }
+            if let Some(password) = password {
+                cmd.arg("--password").arg(password);
+            }
+            cmd.stdout(Stdio::piped())
+                .stderr(Stdio::piped())
+                .spawn()
+                .map_err(Error::Io)
+        })
+        .collect::<Result<_, _>>()?;
+
+    // Chunks are in ascending page order, so concatenating keeps pages sorted.
+    let mut pages = Vec::with_capacity(page_count as usize);
+    for (i, child) in children.into_iter().enumerate() {
+        let output = child.wait_with_output()?;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(Error::PdfInvalid(format!(
+                "info worker {i}: exit {}: {stderr}",
+                output.status
+            )));
+        }
+        let chunk: Vec<PageInfo> = serde_json::from_slice(&output.stdout)
+            .map_err(|e| Error::PdfInvalid(format!("info worker {i}: invalid output: {e}")))?;
+        pages.extend(chunk);
+    }
+    Ok(pages)
+}
+
+#[cfg(not(test))]
+#[cfg_attr(coverage_nightly, coverage(off))]
+fn page_info(page_num: u32, page: &PdfPage, details: PageDetails) -> PageInfo {
+    PageInfo {
+        page: page_num,
+        width_pt: page.width().value,
+        height_pt: page.height().value,
+        rotation_deg: page.rotation().map_or(0, rotation_degrees),
+        page_label: page.label().map(str::to_string),
+        boxes: details.boxes.then(|| read_boxes(page)),
+        image_stats: details.image_stats.then(|| read_image_stats(page)),
+        objects: details.objects.then(|| read_object_counts(page)),
+    }
+}
+
+#[cfg(not(test))]
+#[cfg_attr(coverage_nightly, coverage(off))]
+fn read_object_counts(page: &PdfPage) -> ObjectCounts {
+    let mut counts = ObjectCounts {
+        annotation_count: page.annotations().len() as u32,
+        image_count: 0,
+        path_count: 0,
+        text_count: 0,
+    };
+    for object in page.objects().iter() {
+        match object.object_type() {
+            PdfPageObjectType::Image => counts.image_count += 1,
+            PdfPageObjectType::Path => counts.path_count += 1,
+            PdfPageObjectType::Text => counts.text_count += 1,
+            _ => {}
+        }
+    }
+    counts
+}
+
+#[cfg(not(test))]
+#[cfg_attr(coverage_nightly, coverage(off))]
+fn read_image_stats(page: &PdfPage) -> ImageStats {
+    let objects = page.objects();
+    ImageStats {
+        objects: objects.len() as u32,
+        is_single_jpeg: is_single_jpeg_page(page),
+        image_count: objects
+            .iter()
+            .filter(|object| object.as_image_object().is_some())
+            .count() as u32,
+    }
+}
+
+#[cfg(not(test))]
+#[cfg_attr(coverage_nightly, coverage(off))]
+fn read_boxes(page: &PdfPage) -> PageBoxes {
+    let boundaries = page.boundaries();
+    let bounds = |boundary: Result<PdfPageBoundaryBox, PdfiumError>| {
+        boundary.ok().map(|b| BoxBounds {
+            left: b.bounds.left().value,
+            bottom: b.bounds.bottom().value,
+            right: b.bounds.right().value,
+            top: b.bounds.top().value,
+        })
+    };
+
+    PageBoxes {
+        media: bounds(boundaries.media()),
+        crop: bounds(boundaries.crop()),
+        bleed: bounds(boundaries.bleed()),
+        trim: bounds(boundaries.trim()),
+        art: bounds(boundaries.art()),
+    }
+}
+
+#[cfg(not(test))]
+#[cfg_attr(coverage_nightly, coverage(off))]
+fn read_pdf_version(document: &PdfDocument) -> Option<String> {
+    let raw = match document.version() {
+        PdfDocumentVersion::Unset => return None,
+        PdfDocumentVersion::Pdf1_0 => 10,
+        PdfDocumentVersion::Pdf1_1 => 11,
+        PdfDocumentVersion::Pdf1_2 => 12,
+        PdfDocumentVersion::Pdf1_3 => 13,
+        PdfDocumentVersion::Pdf1_4 => 14,
+        PdfDocumentVersion::Pdf1_5 => 15,
+        PdfDocumentVersion::Pdf1_6 => 16,
+        PdfDocumentVersion::Pdf1_7 => 17,
+        PdfDocumentVersion::Pdf2_0 => 20,
+        PdfDocumentVersion::Other(raw) => raw,
+    };
+    format_pdf_version(raw)
+}
+
+/// Format pdfium's file version number (`17` for PDF 1.7) as `"1.7"`.
+fn format_pdf_version(raw: i32) -> Option<String> {
+    (raw > 0).then(|| format!("{}.{}", raw / 10, raw % 10))
+}
+
+#[cfg(not(test))]
+#[cfg_attr(coverage_nightly, coverage(off))]
+fn read_metadata(document: &PdfDocument) -> DocumentMetadata {
+    let metadata = document.metadata();
+    let text = |tag_type: PdfDocumentMetadataTagType| {
+        metadata
+            .get(tag_type)
+            .map(|tag| tag.value().to_string())
+            .filter(|value| !value.is_empty())
+    };
+    let date = |tag_type| text(tag_type).map(|raw| normalize_pdf_date(&raw));
+
+    DocumentMetadata {
+        title: text(PdfDocumentMetadataTagType::Title),
+        author: text(PdfDocumentMetadataTagType::Author),
+        subject: text(PdfDocumentMetadataTagType::Subject),
+        keywords: text(PdfDocumentMetadataTagType::Keywords),
+        creator: text(PdfDocumentMetadataTagType::Creator),
+        producer: text(PdfDocumentMetadataTagType::Producer),
+        creation_date: date(PdfDocumentMetadataTagType::CreationDate),
+        modification_date: raw_metadata_text(document, "ModDate")
+            .map(|raw| normalize_pdf_date(&raw)),
+    }
+}
+
+/// An info dictionary entry read by its key. pdfium-render's
+/// `ModificationDate` tag looks up a `/ModificationDate` key, not `/ModDate`.
+#[cfg(not(test))]
+#[cfg_attr(coverage_nightly, coverage(off))]
+fn raw_metadata_text(document: &PdfDocument, key: &str) -> Option<String> {
+    let bindings = document.bindings();
+    let handle = bindings.get_handle_from_document(document);
+    let length = bindings.FPDF_GetMetaText(handle, key, std::ptr::null_mut(), 0);
+    if length <= 2 {
+        return None;
+    }
+    let mut buffer = vec![0u16; length as usize / 2];
+    bindings.FPDF_GetMetaText(handle, key, buffer.as_mut_ptr().cast(), length);
+    buffer.pop();
+    Some(String::from_utf16_lossy(&buffer))
+}
+
+#[cfg(not(test))]
+#[cfg_attr(coverage_nightly, coverage(off))]
+fn read_encryption(document: &PdfDocument) -> EncryptionInfo {
+    let permissions = document.permissions();
+    let encrypted = !matches!(
+        permissions.security_handler_revision(),
+        Ok(PdfSecurityHandlerRevision::Unprotected) | Err(_)
+    );
+    let allowed = |flag: Result<bool, PdfiumError>| flag.unwrap_or(false);
+
+    EncryptionInfo {
+        encrypted,
+        opened_with_password: false,
+        permissions: encrypted.then(|| Permissions {
+            print: allowed(permissions.can_print_high_quality())
+                || allowed(permissions.can_print_only_low_quality()),
+            copy: allowed(permissions.can_extract_text_and_graphics()),
+            modify: allowed(permissions.can_modify_document_content()),
+            annotate: allowed(permissions.can_add_or_modify_text_annotations()),
+        }),
+    }
+}
+
+/// Convert a PDF date string to ISO-8601, passing it through unchanged if unparseable.
+fn normalize_pdf_date(raw: &str) -> String {
+    pdf_date_to_iso8601(raw).unwrap_or_else(|| raw.to_string())
+}
+
+/// Parse `D:YYYYMMDDHHmmSSOHH'mm'` (every field after the year optional).
+fn pdf_date_to_iso8601(raw: &str) -> Option<String> {
+    let trimmed = raw.trim();
+    let date = trimmed.strip_prefix("D:").unwrap_or(trimmed);
+    let digit_count = date.bytes().take_while(u8::is_ascii_digit).count();
+    if !(4..=14).contains(&digit_count) || digit_count % 2 != 0 {
+        return None;
+    }
+
+    let (digits, zone) = date.split_at(digit_count);
+    let field = |start: usize, default: u32| {
+        digits
+            .get(start..start + 2)
+            .map_or(Some(default), |f| f.parse().ok())
+    };
+    let year = &digits[..4];
+    let month = field(4, 1)?;
+    let day = field(6, 1)?;
+    let hour = field(8, 0)?;
+    let minute = field(10, 0)?;
+    let second = field(12, 0)?;
+    if !(1..=12).contains(&month)
+        || !(1..=31).contains(&day)
+        || hour > 23
+        || minute > 59
+        || second > 59
+    {
+        return None;
+    }
+
+    let offset = pdf_zone_offset(zone)?;
+    Some(format!(
+        "{year}-{month:02}-{day:02}T{hour:02}:{minute:02}:{second:02}{offset}"
+    ))
+}
+
+/// Convert the trailing `Z`, `+HH'mm'` or `-HH'mm'` zone of a PDF date to an ISO-8601 offset.
+fn pdf_zone_offset(zone: &str) -> Option<String> {
+    let Some(sign) = zone.chars().next() else {
+        return Some(String::new());
+    };
+    if sign == 'Z' {
+        return Some("Z".into());
+    }
+    if sign != '+' && sign != '-' {
+        return None;
+    }
+
+    let digits: String = zone[1..].chars().filter(|c| *c != '\'').collect();
+    if !matches!(digits.len(), 2 | 4) || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let minutes = digits.get(2..4).unwrap_or("00");
+    Some(format!("{sign}{}:{minutes}", &digits[..2]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pdf_versions_format_as_major_minor() {
+        assert_eq!(format_pdf_version(14).as_deref(), Some("1.4"));
+        assert_eq!(format_pdf_version(17).as_deref(), Some("1.7"));
+        assert_eq!(format_pdf_version(20).as_deref(), Some("2.0"));
+        assert_eq!(format_pdf_version(0), None);
+    }
+
+    #[test]
+    fn pdf_dates_normalize_to_iso8601() {
+        assert_eq!(
+            normalize_pdf_date("D:20230415103000+02'00'"),
+            "2023-04-15T10:30:00+02:00"
+        );
+        assert_eq!(
+            normalize_pdf_date("D:20230415103000-05'30"),
+            "2023-04-15T10:30:00-05:30"
+        );
+        assert_eq!(
+            normalize_pdf_date("D:20230415103000Z"),
+            "2023-04-15T10:30:00Z"
+        );
+        assert_eq!(normalize_pdf_date("D:2023"), "2023-01-01T00:00:00");
+        assert_eq!(normalize_pdf_date("20230415"), "2023-04-15T00:00:00");
+    }
+
+    #[test]
+    fn unparseable_pdf_dates_pass_through() {
+        assert_eq!(normalize_pdf_date("last tuesday"), "last tuesday");
+        assert_eq!(normalize_pdf_date("D:20231345"), "D:20231345");
+        assert_eq!(normalize_pdf_date("D:202304151"), "D:202304151");
+        assert_eq!(normalize_pdf_date("D:20230415+2"), "D:20230415+2");
+    }
+}
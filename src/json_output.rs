@@ -0,0 +1,142 @@
+use crate::error::Error;
+use serde::Serialize;
+use std::sync::OnceLock;
+
+/// Shape of the JSON documents the commands print (`--json-version`).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, clap::ValueEnum, Default)]
+pub enum JsonVersion {
+    /// The bare document, as printed before outputs were versioned
+    #[value(name = "0")]
+    Bare,
+    /// `{"schema": "pdf.<kind>.v1", "data": <document>}`
+    #[default]
+    #[value(name = "1")]
+    V1,
+}
+
+static JSON_VERSION: OnceLock<JsonVersion> = OnceLock::new();
+
+/// Print later documents in this shape; only the first call has an effect.
+pub fn set_json_version(version: JsonVersion) {
+    let _ = JSON_VERSION.set(version);
+}
+
+fn json_version() -> JsonVersion {
+    JSON_VERSION.get().copied().unwrap_or_default()
+}
+
+static JSON_ERRORS: OnceLock<bool> = OnceLock::new();
+
+/// Report errors as JSON (`--json-errors`); only the first call has an effect.
+pub fn set_json_errors(enabled: bool) {
+    let _ = JSON_ERRORS.set(enabled);
+}
+
+pub fn json_errors() -> bool {
+    JSON_ERRORS.get().copied().unwrap_or_default()
+}
+
+#[derive(Serialize)]
+struct ErrorReport<'a> {
+    error: ErrorDetails<'a>,
+}
+
+#[derive(Serialize)]
+struct ErrorDetails<'a> {
+    kind: &'a str,
+    message: String,
+    exit_code: u8,
+}
+
+/// `{"error": {"kind", "message", "exit_code"}}` on one line, printed on
+/// stderr with `--json-errors` instead of `error: <message>`.
+pub fn error_json(error: &Error) -> String {
+    serde_json::to_string(&ErrorReport {
+        error: ErrorDetails {
+            kind: error.kind(),
+            message: error.to_string(),
+            exit_code: error.code(),
+        },
+    })
+    .unwrap()
+}
+
+/// A document tagged with the schema describing it.
+#[derive(Serialize)]
+pub struct Envelope<'a, T: Serialize> {
+    pub schema: String,
+    pub data: &'a T,
+}
+
+/// Pretty JSON for `data`, a document of kind `kind` (`info`, `render`, ...),
+/// in the requested `--json-version` shape.
+pub fn to_json<T: Serialize>(kind: &str, data: &T, version: JsonVersion) -> String {
+    match version {
+        JsonVersion::Bare => serde_json::to_string_pretty(data),
+        JsonVersion::V1 => serde_json::to_string_pretty(&Envelope {
+            schema: format!("pdf.{kind}.v1"),
+            data,
+        }),
+    }
+    .unwrap()
+}
+
+/// Print a user-facing document on stdout; see [`to_json`].
+pub fn print_json<T: Serialize>(kind: &str, data: &T) {
+    println!("{}", to_json(kind, data, json_version()));
+}
+
+/// Write a user-facing document to `path`; see [`to_json`].
+pub fn write_json<T: Serialize>(
+    path: &std::path::Path,
+    kind: &str,
+    data: &T,
+) -> std::io::Result<()> {
+    std::fs::write(path, to_json(kind, data, json_version()) + "\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn v1_wraps_the_document_with_its_schema() {
+        let json: serde_json::Value =
+            serde_json::from_str(&to_json("info", &vec![1, 2], JsonVersion::V1)).unwrap();
+
+        assert_eq!(
+            json,
+            serde_json::json!({ "schema": "pdf.info.v1", "data": [1, 2] })
+        );
+    }
+
+    #[test]
+    fn bare_prints_the_document_alone() {
+        assert_eq!(
+            to_json("info", &vec![1, 2], JsonVersion::Bare),
+            "[\n  1,\n  2\n]"
+        );
+    }
+
+    #[test]
+    fn errors_report_kind_message_and_exit_code() {
+        let json: serde_json::Value =
+            serde_json::from_str(&error_json(&Error::Render("page 3: boom".into()))).unwrap();
+
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "error": {
+                    "kind": "render",
+                    "message": "rendering error: page 3: boom",
+                    "exit_code": 4
+                }
+            })
+        );
+    }
+
+    #[test]
+    fn latest_version_is_the_default() {
+        assert_eq!(JsonVersion::default(), JsonVersion::V1);
+    }
+}
@@ -0,0 +1,417 @@
+#[cfg(not(test))]
+use crate::error::Error;
+#[cfg(not(test))]
+use crate::json_output::print_json;
+#[cfg(not(test))]
+use crate::pdfium_init::{is_stdin, load_document, load_pdfium, password_error_message};
+#[cfg(not(test))]
+use pdfium_render::prelude::*;
+#[cfg(not(test))]
+use serde::Serialize;
+use std::collections::BTreeSet;
+#[cfg(not(test))]
+use std::ffi::{c_int, c_ulong, c_void};
+#[cfg(not(test))]
+use std::io::Read;
+#[cfg(not(test))]
+use std::path::Path;
+
+/// Optional content groups (layers) to hide or show before rendering
+/// (`--layers-off`, `--layers-on`), by their `/Name`.
+///
+/// pdfium has no API for a document's optional content configuration, so the
+/// toggles act on the page objects tagged with a layer (`/OC ... BDC`): hidden
+/// layers' objects are deleted, and shown layers' objects lose the tag so they
+/// draw whatever the PDF's default state. Objects nested in form XObjects and
+/// annotations keep their default visibility.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Layers {
+    pub off: Vec<String>,
+    pub on: Vec<String>,
+}
+
+/// What `Layers` does to one page object.
+#[derive(Debug, PartialEq, Eq)]
+enum ObjectAction {
+    Keep,
+    Remove,
+    /// Drop the marks at these indices, in ascending order
+    Unmark(Vec<usize>),
+}
+
+impl Layers {
+    pub fn is_empty(&self) -> bool {
+        self.off.is_empty() && self.on.is_empty()
+    }
+
+    /// The toggles as command-line arguments for worker processes.
+    pub fn to_args(&self) -> Vec<String> {
+        [("--layers-off", &self.off), ("--layers-on", &self.on)]
+            .into_iter()
+            .filter(|(_, names)| !names.is_empty())
+            .flat_map(|(arg, names)| [arg.to_string(), names.join(",")])
+            .collect()
+    }
+
+    /// Names given to `--layers-off` or `--layers-on` that are not in `available`.
+    pub fn unknown<'a>(&'a self, available: &BTreeSet<String>) -> Vec<&'a str> {
+        self.off
+            .iter()
+            .chain(&self.on)
+            .filter(|name| !available.contains(*name))
+            .map(String::as_str)
+            .collect()
+    }
+
+    /// Decide from the layer of each of an object's marks (`None` for marks
+    /// that are not `/OC`); hiding wins over showing.
+    fn action(&self, mark_layers: &[Option<String>]) -> ObjectAction {
+        let named = |names: &[String], layer: &Option<String>| {
+            layer.as_ref().is_some_and(|layer| names.contains(layer))
+        };
+        if mark_layers.iter().any(|layer| named(&self.off, layer)) {
+            return ObjectAction::Remove;
+        }
+        let shown: Vec<usize> = (0..mark_layers.len())
+            .filter(|&index| named(&self.on, &mark_layers[index]))
+            .collect();
+        if shown.is_empty() {
+            ObjectAction::Keep
+        } else {
+            ObjectAction::Unmark(shown)
+        }
+    }
+}
+
+#[cfg(not(test))]
+#[derive(Serialize)]
+struct LayerList {
+    layers: BTreeSet<String>,
+}
+
+/// Print the names of the layers used on the document's pages (`info --layers`).
+#[cfg(not(test))]
+#[cfg_attr(coverage_nightly, coverage(off))]
+pub fn run_list(pdf_path: &Path, password: Option<&str>) -> Result<(), Error> {
+    let pdfium = load_pdfium()?;
+    let layers = layer_names(&pdfium, pdf_path, password)?;
+    print_json("layers", &LayerList { layers });
+    Ok(())
+}
+
+/// Layer names tagging the objects of any page.
+#[cfg(not(test))]
+#[cfg_attr(coverage_nightly, coverage(off))]
+pub fn layer_names(
+    pdfium: &Pdfium,
+    pdf_path: &Path,
+    password: Option<&str>,
+) -> Result<BTreeSet<String>, Error> {
+    let document = RawDocument::open(pdfium, pdf_path, password)?;
+    let bindings = document.bindings;
+    let mut names = BTreeSet::new();
+    for page_index in 0..bindings.FPDF_GetPageCount(document.handle) {
+        let page = bindings.FPDF_LoadPage(document.handle, page_index);
+        if page.is_null() {
+            continue;
+        }
+        for index in 0..bindings.FPDFPage_CountObjects(page) {
+            let object = bindings.FPDFPage_GetObject(page, index);
+            names.extend(mark_layers(bindings, object).into_iter().flatten());
+        }
+        bindings.FPDF_ClosePage(page);
+    }
+    Ok(names)
+}
+
+/// Warn on stderr about `--layers-off`/`--layers-on` names no page uses.
+#[cfg(not(test))]
+#[cfg_attr(coverage_nightly, coverage(off))]
+pub fn warn_unknown_layers(
+    pdfium: &Pdfium,
+    pdf_path: &Path,
+    password: Option<&str>,
+    layers: &Layers,
+) -> Result<(), Error> {
+    if layers.is_empty() {
+        return Ok(());
+    }
+    let available = layer_names(pdfium, pdf_path, password)?;
+    for name in layers.unknown(&available) {
+        eprintln!(
+            "warning: {}: no layer named {name:?}; ignoring it",
+            pdf_path.display()
+        );
+    }
+    Ok(())
+}
+
+/// `load_document` with `layers` applied to every page. Only the loaded copy
+/// changes, never the file.
+#[cfg(not(test))]
+#[cfg_attr(coverage_nightly, coverage(off))]
+pub fn load_document_with_layers<'a>(
+    pdfium: &'a Pdfium,
+    pdf_path: &Path,
+    password: Option<&'a str>,
+    layers: &Layers,
+) -> Result<PdfDocument<'a>, Error> {
+    if layers.is_empty() {
+        return load_document(pdfium, pdf_path, password);
+    }
+    let document = RawDocument::open(pdfium, pdf_path, password)?;
+    let bindings = document.bindings;
+    for page_index in 0..bindings.FPDF_GetPageCount(document.handle) {
+        let page = bindings.FPDF_LoadPage(document.handle, page_index);
+        if page.is_null() {
+            continue;
+        }
+        let changed = apply_to_page(bindings, page, layers);
+        let regenerated = !changed || bindings.FPDFPage_GenerateContent(page) != 0;
+        bindings.FPDF_ClosePage(page);
+        if !regenerated {
+            return Err(Error::Render(format!(
+                "page {}: could not apply --layers-off/--layers-on",
+                page_index + 1
+            )));
+        }
+    }
+    let bytes = document.save()?;
+    drop(document);
+    // The copy is saved without encryption, so it needs no password.
+    pdfium
+        .load_pdf_from_byte_vec(bytes, None)
+        .map_err(|e| Error::PdfInvalid(format!("{}: {e}", pdf_path.display())))
+}
+
+/// Apply `layers` to the page's top-level objects; true if any changed.
+#[cfg(not(test))]
+#[cfg_attr(coverage_nightly, coverage(off))]
+fn apply_to_page(bindings: &dyn PdfiumLibraryBindings, page: FPDF_PAGE, layers: &Layers) -> bool {
+    let mut changed = false;
+    // Backwards, so removals don't shift the indices still to visit.
+    for index in (0..bindings.FPDFPage_CountObjects(page)).rev() {
+        let object = bindings.FPDFPage_GetObject(page, index);
+        match layers.action(&mark_layers(bindings, object)) {
+            ObjectAction::Keep => {}
+            ObjectAction::Remove => {
+                if bindings.FPDFPage_RemoveObject(page, object) != 0 {
+                    bindings.FPDFPageObj_Destroy(object);
+                    changed = true;
+                }
+            }
+            ObjectAction::Unmark(marks) => {
+                for mark_index in marks.into_iter().rev() {
+                    let mark = bindings.FPDFPageObj_GetMark(object, mark_index as c_ulong);
+                    bindings.FPDFPageObj_RemoveMark(object, mark);
+                }
+                changed = true;
+            }
+        }
+    }
+    changed
+}
+
+/// The layer name of each of the object's marks: the `/Name` of the
+/// optional content group an `/OC` mark refers to, `None` for other marks.
+#[cfg(not(test))]
+#[cfg_attr(coverage_nightly, coverage(off))]
+fn mark_layers(
+    bindings: &dyn PdfiumLibraryBindings,
+    object: FPDF_PAGEOBJECT,
+) -> Vec<Option<String>> {
+    (0..bindings.FPDFPageObj_CountMarks(object).max(0))
+        .map(|index| {
+            let mark = bindings.FPDFPageObj_GetMark(object, index as c_ulong);
+            let name = read_utf16(|buffer, length| {
+                bindings.FPDFPageObjMark_GetName(
+                    mark,
+                    buffer.as_mut_ptr().cast(),
+                    (buffer.len() * 2) as c_ulong,
+                    length,
+                )
+            });
+            if name.as_deref() != Some("OC") {
+                return None;
+            }
+            read_utf16(|buffer, length| {
+                bindings.FPDFPageObjMark_GetParamStringValue(
+                    mark,
+                    "Name",
+                    buffer.as_mut_ptr().cast(),
+                    (buffer.len() * 2) as c_ulong,
+                    length,
+                )
+            })
+        })
+        .collect()
+}
+
+/// Read a NUL-terminated UTF-16LE string from a pdfium getter that reports
+/// the byte length it needs, calling it once to size the buffer.
+#[cfg(not(test))]
+#[cfg_attr(coverage_nightly, coverage(off))]
+fn read_utf16(get: impl Fn(&mut [u16], &mut c_ulong) -> FPDF_BOOL) -> Option<String> {
+    let mut length: c_ulong = 0;
+    if get(&mut [], &mut length) == 0 || length < 2 {
+        return None;
+    }
+    let mut buffer = vec![0u16; length as usize / 2];
+    if get(&mut buffer, &mut length) == 0 {
+        return None;
+    }
+    buffer.pop();
+    Some(String::from_utf16_lossy(&buffer))
+}
+
+/// A document opened through pdfium's C API, for the optional content marks
+/// pdfium-render does not expose. Closed on drop.
+#[cfg(not(test))]
+struct RawDocument<'a> {
+    bindings: &'a dyn PdfiumLibraryBindings,
+    handle: FPDF_DOCUMENT,
+    /// pdfium reads from this buffer for as long as the document is open
+    _bytes: Vec<u8>,
+}
+
+/// `FPDF_GetLastError` after a wrong or missing password.
+#[cfg(not(test))]
+const FPDF_PASSWORD_ERROR: c_ulong = 4;
+/// `FPDF_SaveAsCopy` flag writing the document without its encryption.
+#[cfg(not(test))]
+const FPDF_REMOVE_SECURITY_FLAG: FPDF_DWORD = 3;
+
+#[cfg(not(test))]
+impl<'a> RawDocument<'a> {
+    #[cfg_attr(coverage_nightly, coverage(off))]
+    fn open(pdfium: &'a Pdfium, pdf_path: &Path, password: Option<&str>) -> Result<Self, Error> {
+        let bytes = if is_stdin(pdf_path) {
+            let mut bytes = Vec::new();
+            std::io::stdin().lock().read_to_end(&mut bytes)?;
+            bytes
+        } else {
+            std::fs::read(pdf_path)?
+        };
+        let bindings = pdfium.bindings();
+        let handle = bindings.FPDF_LoadMemDocument64(&bytes, password);
+        if handle.is_null() {
+            let message = if bindings.FPDF_GetLastError() == FPDF_PASSWORD_ERROR {
+                password_error_message(pdf_path, password.is_some())
+            } else {
+                format!("{}: pdfium could not load the document", pdf_path.display())
+            };
+            return Err(Error::PdfInvalid(message));
+        }
+        Ok(Self {
+            bindings,
+            handle,
+            _bytes: bytes,
+        })
+    }
+
+    /// The document as PDF bytes, including changes to its pages.
+    #[cfg_attr(coverage_nightly, coverage(off))]
+    fn save(&self) -> Result<Vec<u8>, Error> {
+        let mut writer = ByteWriter {
+            file_write: FPDF_FILEWRITE {
+                version: 1,
+                WriteBlock: Some(write_block),
+            },
+            bytes: Vec::new(),
+        };
+        let saved = self.bindings.FPDF_SaveAsCopy(
+            self.handle,
+            &mut writer.file_write,
+            FPDF_REMOVE_SECURITY_FLAG,
+        );
+        if saved == 0 {
+            return Err(Error::Render(
+                "could not apply --layers-off/--layers-on: saving the document failed".into(),
+            ));
+        }
+        Ok(writer.bytes)
+    }
+}
+
+#[cfg(not(test))]
+impl Drop for RawDocument<'_> {
+    fn drop(&mut self) {
+        self.bindings.FPDF_CloseDocument(self.handle);
+    }
+}
+
+/// `FPDF_FILEWRITE` collecting the written blocks; pdfium is handed a pointer
+/// to `file_write`, which `write_block` casts back to the whole struct.
+#[cfg(not(test))]
+#[repr(C)]
+struct ByteWriter {
+    file_write: FPDF_FILEWRITE,
+    bytes: Vec<u8>,
+}
+
+#[cfg(not(test))]
+#[cfg_attr(coverage_nightly, coverage(off))]
+unsafe extern "C" fn write_block(
+    file_write: *mut FPDF_FILEWRITE,
+    data: *const c_void,
+    size: c_ulong,
+) -> c_int {
+    // SAFETY: `file_write` is the first field of the `#[repr(C)]` `ByteWriter`
+    // passed to `FPDF_SaveAsCopy`, and pdfium passes `size` readable bytes.
+    let writer = unsafe { &mut *file_write.cast::<ByteWriter>() };
+    let data = unsafe { std::slice::from_raw_parts(data.cast::<u8>(), size as usize) };
+    writer.bytes.extend_from_slice(data);
+    1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn layers(off: &[&str], on: &[&str]) -> Layers {
+        Layers {
+            off: off.iter().map(|name| name.to_string()).collect(),
+            on: on.iter().map(|name| name.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn hidden_layers_remove_the_object() {
+        let layers = layers(&["Dimensions"], &["Notes"]);
+        let marks = [
+            None,
+            Some("Notes".to_string()),
+            Some("Dimensions".to_string()),
+        ];
+
+        assert_eq!(layers.action(&marks), ObjectAction::Remove);
+    }
+
+    #[test]
+    fn shown_layers_drop_only_their_marks() {
+        let layers = layers(&["Dimensions"], &["Notes"]);
+        let marks = [Some("Notes".to_string()), None, Some("Notes".to_string())];
+
+        assert_eq!(layers.action(&marks), ObjectAction::Unmark(vec![0, 2]));
+        assert_eq!(layers.action(&[None]), ObjectAction::Keep);
+        assert_eq!(layers.action(&[]), ObjectAction::Keep);
+    }
+
+    #[test]
+    fn unknown_names_are_reported_in_order() {
+        let layers = layers(&["Dimensions", "Ghost"], &["Notes", "Other"]);
+        let available = BTreeSet::from(["Dimensions".to_string(), "Notes".to_string()]);
+
+        assert_eq!(layers.unknown(&available), ["Ghost", "Other"]);
+    }
+
+    #[test]
+    fn layers_forward_only_what_is_set() {
+        assert!(Layers::default().to_args().is_empty());
+        assert_eq!(layers(&["A", "B"], &[]).to_args(), ["--layers-off", "A,B"]);
+        assert_eq!(
+            layers(&["A"], &["C"]).to_args(),
+            ["--layers-off", "A", "--layers-on", "C"]
+        );
+    }
+}
@@ -0,0 +1,84 @@
+#![cfg_attr(coverage_nightly, feature(coverage_attribute))]
+
+//! PDF rendering and info extraction using pdfium.
+//!
+//! The stable API is re-exported at the crate root:
+//!
+//! - [`render_pages`] renders a page list to image files in a directory, and
+//!   [`render_page`] renders one page of an already loaded document to an image.
+//! - [`RenderOptions`] holds the rendering settings (`RenderOptions::default()`
+//!   matches the CLI defaults).
+//! - [`read_info`] returns [`PdfInfo`] for a loaded document.
+//! - [`parse_page_range`] parses `--pages` style selections.
+//!
+//! The modules themselves back the `pdf` binary and may change between releases.
+//! [`pdfium_render`] is re-exported so callers use the same version as this crate.
+
+#[doc(hidden)]
+pub mod capabilities;
+#[doc(hidden)]
+pub mod config;
+#[doc(hidden)]
+pub mod contact_sheet;
+#[doc(hidden)]
+pub mod deskew;
+#[doc(hidden)]
+pub mod error;
+#[doc(hidden)]
+pub mod extract;
+#[doc(hidden)]
+pub mod flatten;
+#[doc(hidden)]
+pub mod fonts;
+#[doc(hidden)]
+pub mod icc;
+#[doc(hidden)]
+pub mod info;
+#[doc(hidden)]
+pub mod json_output;
+#[doc(hidden)]
+pub mod layers;
+#[doc(hidden)]
+pub mod name_template;
+#[doc(hidden)]
+pub mod outline;
+#[doc(hidden)]
+pub mod output_path;
+#[doc(hidden)]
+pub mod page_cache;
+#[doc(hidden)]
+pub mod page_range;
+#[doc(hidden)]
+pub mod pdfium_init;
+#[doc(hidden)]
+pub mod render;
+#[doc(hidden)]
+pub mod render_worker;
+#[doc(hidden)]
+pub mod search;
+#[doc(hidden)]
+pub mod subset;
+#[doc(hidden)]
+pub mod text;
+#[doc(hidden)]
+pub mod tiles;
+#[doc(hidden)]
+pub mod watermark;
+
+pub use error::Error;
+pub use icc::IccProfile;
+pub use info::PageDetails;
+#[cfg(not(test))]
+pub use info::{BoxBounds, DocumentMetadata, ImageStats, PageBoxes, PageInfo, PdfInfo, read_info};
+pub use layers::Layers;
+pub use name_template::NameTemplate;
+pub use page_range::parse_page_range;
+pub use pdfium_render;
+pub use render_worker::{
+    Annotations, Background, BoxType, JpegEncoderType, JpegSubsampling, OutputFormat, PageError,
+    PdfiumFlags, ProgressMode, Region, RenderOptions, RenderSize, ResampleFilter, Rotation,
+    WorkerResult,
+};
+#[cfg(not(test))]
+pub use render_worker::{render_page, render_pages};
+pub use watermark::Watermark;
@@ -1,25 +1,56 @@
 #![cfg_attr(coverage_nightly, feature(coverage_attribute))]
 
-mod error;
-mod info;
-mod page_range;
-mod pdfium_init;
-mod render;
-mod render_worker;
-
 #[cfg(not(test))]
-use clap::{Parser, Subcommand};
+use clap::{Args, CommandFactory, FromArgMatches, Parser, Subcommand};
+#[cfg(not(test))]
+use pdf::render::{ErrorFormat, WorkerMode};
 #[cfg(not(test))]
-use render_worker::{BoxType, JpegEncoderType, RenderOptions};
+use pdf::{
+    Annotations, Background, BoxType, IccProfile, JpegEncoderType, JpegSubsampling, Layers,
+    NameTemplate, OutputFormat, PageDetails, PdfiumFlags, ProgressMode, Region, RenderOptions,
+    RenderSize, ResampleFilter, Rotation, Watermark,
+};
+#[cfg(not(test))]
+use pdf::{
+    capabilities, config, contact_sheet, error, extract, flatten, fonts, info, json_output, layers,
+    name_template, outline, page_range, pdfium_init, render, render_worker, search, subset, text,
+    tiles, watermark,
+};
 #[cfg(not(test))]
 use std::path::PathBuf;
 #[cfg(not(test))]
 use std::process::ExitCode;
+#[cfg(not(test))]
+use std::time::Duration;
 
 #[cfg(not(test))]
 #[derive(Parser)]
 #[command(name = "pdf", about = "PDF rendering and info extraction using pdfium")]
 struct Cli {
+    /// pdfium library file, or the directory containing it; overrides
+    /// PDF_PDFIUM_PATH and the default search
+    #[arg(long, global = true, value_name = "DIR|FILE")]
+    pdfium_path: Option<PathBuf>,
+
+    /// Print no JSON summary and no progress (render, extract-images,
+    /// contact-sheet, tiles, subset, flatten); errors still go to stderr
+    #[arg(long, global = true)]
+    quiet: bool,
+
+    /// JSON output shape: 1 wraps each document as {"schema": "pdf.<kind>.v1",
+    /// "data": ...}; 0 prints the bare document
+    #[arg(long, global = true, value_enum, default_value = "1")]
+    json_version: json_output::JsonVersion,
+
+    /// Print errors on stderr as {"error": {"kind", "message", "exit_code"}}
+    /// instead of an `error: ...` line
+    #[arg(long, global = true)]
+    json_errors: bool,
+
+    /// Print the path of the pdfium library that was loaded on stderr
+    #[arg(long, global = true)]
+    verbose: bool,
+
     #[command(subcommand)]
     command: Commands,
 }
@@ -35,44 +66,318 @@ enum Commands {
         /// Include dimensions for all pages (default: first page only)
         #[arg(long)]
         all_pages: bool,
+
+        /// Include MediaBox, CropBox, BleedBox, TrimBox and ArtBox per page
+        #[arg(long)]
+        boxes: bool,
+
+        /// Include per-page object and image counts, and whether --extract-images
+        /// would copy the page's JPEG
+        #[arg(long)]
+        image_stats: bool,
+
+        /// Include per-page annotation, image, path and text object counts
+        #[arg(long)]
+        objects: bool,
+
+        /// Number of worker processes reading page dimensions (with --all-pages)
+        #[arg(long, default_value = "1")]
+        workers: u32,
+
+        /// Print only the first page's width and height, skipping metadata and
+        /// page loading
+        #[arg(long, conflicts_with_all = ["all_pages", "boxes", "image_stats", "objects"])]
+        dimensions_only: bool,
+
+        /// Print only the names of the optional content groups (layers) used
+        /// on the pages, for --layers-off and --layers-on
+        #[arg(
+            long,
+            conflicts_with_all = ["all_pages", "boxes", "image_stats", "objects", "dimensions_only"]
+        )]
+        layers: bool,
+
+        /// Print only the fonts used by the text on all pages, with whether each
+        /// is embedded and the pages using it; loads every page, so it is slower
+        #[arg(
+            long,
+            conflicts_with_all = [
+                "all_pages", "boxes", "image_stats", "objects", "dimensions_only", "layers"
+            ]
+        )]
+        fonts: bool,
+
+        /// Password for encrypted PDFs
+        #[arg(long)]
+        password: Option<String>,
     },
 
-    /// Render PDF pages to JPEG images
-    Render {
+    /// Internal: print page info for assigned pages as a JSON array
+    #[command(hide = true)]
+    InfoWorker {
+        pdf: PathBuf,
+
+        #[arg(long)]
+        pages: String,
+
+        #[arg(long)]
+        boxes: bool,
+
+        #[arg(long)]
+        image_stats: bool,
+
+        #[arg(long)]
+        objects: bool,
+
+        #[arg(long)]
+        password: Option<String>,
+    },
+
+    /// Output extracted page text as JSON
+    Text {
+        /// Path to the PDF file
+        pdf: PathBuf,
+
+        /// Page range to extract (e.g. "1-10", "3,5,7")
+        #[arg(long)]
+        pages: Option<String>,
+
+        /// Password for encrypted PDFs
+        #[arg(long)]
+        password: Option<String>,
+
+        /// Rebuild columns and spacing from character positions, like
+        /// pdftotext -layout (slower)
+        #[arg(long)]
+        layout: bool,
+
+        /// Print each page's character and word counts, and whether it has
+        /// any text, instead of the text itself (to find pages needing OCR)
+        #[arg(long, conflicts_with = "layout")]
+        stats: bool,
+    },
+
+    /// Find text and print each match's page and bounding box as JSON
+    Search {
+        /// Path to the PDF file
+        pdf: PathBuf,
+
+        /// Text to search for
+        query: String,
+
+        /// Page range to search (e.g. "1-10", "3,5,7")
+        #[arg(long)]
+        pages: Option<String>,
+
+        /// Match regardless of case
+        #[arg(long)]
+        ignore_case: bool,
+
+        /// Only match whole words
+        #[arg(long)]
+        whole_word: bool,
+
+        /// Password for encrypted PDFs
+        #[arg(long)]
+        password: Option<String>,
+    },
+
+    /// Output the bookmark tree as nested JSON
+    Outline {
+        /// Path to the PDF file
+        pdf: PathBuf,
+
+        /// Password for encrypted PDFs
+        #[arg(long)]
+        password: Option<String>,
+    },
+
+    /// Save every embedded image as page-NNNN-img-MM.jpg (raw JPEG) or .png
+    ExtractImages {
+        /// Path to the PDF file
+        pdf: PathBuf,
+
+        /// Output directory for extracted images
+        #[arg(short, long)]
+        output: PathBuf,
+
+        /// Page range to extract from (e.g. "1-10", "3,5,7")
+        #[arg(long)]
+        pages: Option<String>,
+
+        /// Password for encrypted PDFs
+        #[arg(long)]
+        password: Option<String>,
+    },
+
+    /// Tile page thumbnails into a single image for quick review
+    ContactSheet {
+        /// Path to the PDF file
+        pdf: PathBuf,
+
+        /// Output image; the format follows the extension (.jpg, .png, ...)
+        #[arg(short, long)]
+        output: PathBuf,
+
+        /// Page range to include (e.g. "1-10", "3,5,7")
+        #[arg(long)]
+        pages: Option<String>,
+
+        /// Thumbnails per row
+        #[arg(long, default_value = "4")]
+        columns: u32,
+
+        /// Width of each thumbnail in pixels
+        #[arg(long, default_value = "200")]
+        thumb_width: u32,
+
+        /// Password for encrypted PDFs
+        #[arg(long)]
+        password: Option<String>,
+    },
+
+    /// Slice pages into fixed-size JPEG tiles for deep-zoom viewers
+    Tiles {
         /// Path to the PDF file
         pdf: PathBuf,
 
-        /// Output directory for JPEG files
+        /// Output directory; tiles go to page-NNNN/<level>/<x>_<y>.jpg
         #[arg(short, long)]
         output: PathBuf,
 
-        /// Target width in pixels
-        #[arg(long, default_value = "2560")]
+        /// Page range to slice (e.g. "1-10", "3,5,7")
+        #[arg(long)]
+        pages: Option<String>,
+
+        /// Width and height of each tile in pixels
+        #[arg(long, default_value = "256")]
+        tile: u32,
+
+        /// Width in pixels each page is rendered at before slicing
+        #[arg(long)]
         target_width: u32,
 
-        /// JPEG quality (1-100)
-        #[arg(long, default_value = "100")]
-        quality: u8,
+        /// Password for encrypted PDFs
+        #[arg(long)]
+        password: Option<String>,
+    },
+
+    /// Copy selected pages into a new PDF, optionally rotated, without rasterizing
+    Subset {
+        /// Path to the PDF file
+        pdf: PathBuf,
+
+        /// Output PDF file
+        #[arg(short, long)]
+        output: PathBuf,
+
+        /// Page range to copy (e.g. "1-5,10")
+        #[arg(long)]
+        pages: Option<String>,
+
+        /// Turn every copied page clockwise, on top of its existing rotation
+        #[arg(long, value_enum, default_value = "0")]
+        rotate: Rotation,
+
+        /// Password for encrypted PDFs
+        #[arg(long)]
+        password: Option<String>,
+    },
+
+    /// Bake annotations and filled form fields into the page content
+    Flatten {
+        /// Path to the PDF file
+        pdf: PathBuf,
+
+        /// Output PDF file
+        #[arg(short, long)]
+        output: PathBuf,
+
+        /// Page range to flatten (e.g. "1-5,10"); other pages are copied as they are
+        #[arg(long)]
+        pages: Option<String>,
+
+        /// Password for encrypted PDFs
+        #[arg(long)]
+        password: Option<String>,
+    },
+
+    /// Print the output formats, encoders and optional features of this
+    /// build as JSON
+    Capabilities,
+
+    /// Render PDF pages to JPEG, PNG, WebP or TIFF images
+    Render {
+        /// Path to the PDF file; several PDFs each render into a subdirectory
+        /// of --output named after the file
+        #[arg(required = true)]
+        pdf: Vec<PathBuf>,
 
-        /// Page boundary box to use for rendering
-        #[arg(long, rename_all = "lower", value_enum, default_value = "crop")]
-        r#box: BoxType,
+        /// Output directory for rendered images
+        #[arg(
+            short,
+            long,
+            required_unless_present_any = ["stdout", "single_file", "estimate"]
+        )]
+        output: Option<PathBuf>,
+
+        /// Write every page as a frame of this multipage TIFF (requires
+        /// --format tiff; renders in a single process)
+        #[arg(
+            long,
+            value_name = "FILE",
+            conflicts_with_all = ["output", "stdout", "list_outputs", "repeat", "cache", "widths"]
+        )]
+        single_file: Option<PathBuf>,
+
+        /// Write the single selected page to stdout instead of a directory
+        #[arg(
+            long,
+            conflicts_with_all = [
+                "output", "extract_images", "page_timeout", "manifest", "list_outputs", "retries",
+                "worker_mem_limit", "max_inflight", "repeat", "cache", "widths"
+            ]
+        )]
+        stdout: bool,
 
         /// Page range to render (e.g. "1-10", "3,5,7")
         #[arg(long)]
         pages: Option<String>,
 
-        /// Number of worker processes
-        #[arg(long, default_value = "4")]
+        /// Read the page range from a file, one range per line; blank lines and
+        /// lines starting with # are skipped
+        #[arg(long, value_name = "FILE", conflicts_with = "pages")]
+        pages_from: Option<PathBuf>,
+
+        /// Number of worker processes, or "auto" (also 0) for one per CPU
+        #[arg(long, default_value = "4", value_parser = render::parse_workers)]
         workers: u32,
 
-        /// Extract raw JPEG from single-image pages instead of re-rendering
-        #[arg(long)]
-        extract_images: bool,
+        /// Run workers as subprocesses or as threads in this process
+        #[arg(long, value_enum, default_value = "process")]
+        mode: WorkerMode,
+
+        /// Failure reporting: stderr only, or also `failed_pages` in the JSON summary
+        #[arg(long, value_enum, default_value = "text")]
+        error_format: ErrorFormat,
+
+        /// Print only the JSON summary: no progress on stderr
+        #[arg(long, conflicts_with = "quiet")]
+        summary_only: bool,
+
+        /// Print the page count, each worker's pages and the output files as
+        /// JSON without rendering
+        #[arg(long, conflicts_with_all = ["stdout", "single_file"])]
+        dry_run: bool,
 
-        /// JPEG encoder backend
-        #[arg(long, value_enum, default_value = "image")]
-        encoder: JpegEncoderType,
+        /// Render three pages spread across the selection and print their
+        /// encoded sizes and the extrapolated total as JSON; nothing is
+        /// written to --output
+        #[arg(long, conflicts_with_all = ["stdout", "single_file", "dry_run", "widths"])]
+        estimate: bool,
+
+        #[command(flatten)]
+        render: RenderArgs,
     },
 
     /// Internal: render assigned pages in a single process
@@ -86,64 +391,716 @@ enum Commands {
         #[arg(long)]
         pages: String,
 
-        #[arg(long, default_value = "2560")]
-        target_width: u32,
+        /// Request position of the first assigned page (with --keep-order)
+        #[arg(long, default_value = "1")]
+        first_sequence: u32,
+
+        #[command(flatten)]
+        render: RenderArgs,
+    },
+}
 
-        #[arg(long, default_value = "100")]
-        quality: u8,
+/// Rendering flags shared by `render` and the hidden `render-worker`.
+#[cfg(not(test))]
+#[derive(Args)]
+struct RenderArgs {
+    /// Target width in pixels [default: page size in points, 1pt = 1px]
+    #[arg(long)]
+    target_width: Option<u32>,
 
-        #[arg(long, rename_all = "lower", value_enum, default_value = "crop")]
-        r#box: BoxType,
+    /// Target height in pixels; width follows each page's aspect ratio
+    #[arg(long)]
+    target_height: Option<u32>,
 
-        #[arg(long)]
-        extract_images: bool,
+    /// Render at a physical resolution, sizing each page from its width in points
+    #[arg(long)]
+    dpi: Option<u32>,
 
-        /// JPEG encoder backend
-        #[arg(long, value_enum, default_value = "image")]
-        encoder: JpegEncoderType,
-    },
+    /// Fit every page inside a WxH pixel canvas and pad it, centered, with
+    /// --background to exactly that size
+    #[arg(
+        long,
+        value_name = "WxH",
+        conflicts_with_all = ["target_width", "target_height", "dpi"]
+    )]
+    canvas: Option<String>,
+
+    /// Write each page at several widths in pixels (e.g. 480,1024,2560):
+    /// rendered once at the largest and scaled down for the rest, as
+    /// page-NNNN-W.jpg
+    #[arg(
+        long,
+        value_name = "W,...",
+        value_delimiter = ',',
+        conflicts_with_all = [
+            "target_width", "target_height", "dpi", "canvas", "align", "extract_images", "cache"
+        ]
+    )]
+    widths: Vec<u32>,
+
+    /// Scale pages down proportionally so no output is wider than this
+    #[arg(long)]
+    max_width: Option<u32>,
+
+    /// Scale pages down proportionally so no output is taller than this
+    #[arg(long)]
+    max_height: Option<u32>,
+
+    /// Render a page no larger than its native size (1pt = 1px) when the
+    /// requested size is bigger; larger pages still scale down to it
+    #[arg(long)]
+    no_upscale: bool,
+
+    /// Render at FACTOR times the output size (capped by pdfium's bitmap
+    /// limit), then downscale with --resample for sharper small text; costs CPU
+    #[arg(long, value_name = "FACTOR")]
+    supersample: Option<f32>,
+
+    /// Filter for the downscales of --widths and --supersample; only used
+    /// when one of them resizes a page
+    #[arg(long, value_enum, default_value = "lanczos")]
+    resample: ResampleFilter,
+
+    /// JPEG, lossy WebP or AVIF quality: 1-100, or low, medium, high or max
+    /// (mapped per format)
+    #[arg(long, default_value = "100")]
+    quality: String,
+
+    /// Page boundary box to use for rendering
+    #[arg(long, rename_all = "lower", value_enum, default_value = "crop")]
+    r#box: BoxType,
+
+    /// Extract raw JPEG from single-image pages instead of re-rendering
+    #[arg(long)]
+    extract_images: bool,
+
+    /// Decode extracted JPEGs and encode them with --quality, --subsampling
+    /// and --encoder, so they match re-rendered pages
+    #[arg(long, requires = "extract_images")]
+    reencode_extracted: bool,
+
+    /// Encode 8-bit grayscale JPEGs (extracted JPEGs may be color, so this
+    /// excludes --extract-images)
+    #[arg(long, conflicts_with = "extract_images")]
+    grayscale: bool,
+
+    /// JPEG encoder backend
+    #[arg(long, value_enum, default_value = "image")]
+    encoder: JpegEncoderType,
+
+    /// Write progressive JPEGs
+    #[arg(long)]
+    progressive: bool,
+
+    /// JPEG chroma subsampling [default: the encoder's own]
+    #[arg(long, value_enum)]
+    subsampling: Option<JpegSubsampling>,
+
+    /// Password for encrypted PDFs
+    #[arg(long)]
+    password: Option<String>,
+
+    /// Output image format
+    #[arg(long, value_enum, default_value = "jpeg")]
+    format: OutputFormat,
+
+    /// Encode lossless WebP instead of lossy at --quality (requires --format webp)
+    #[arg(long)]
+    lossless: bool,
+
+    /// AVIF encoder speed, 1 (slowest, smallest files) to 10 (fastest)
+    /// [default: 4] (requires --format avif)
+    #[arg(long, value_name = "1-10")]
+    speed: Option<u8>,
+
+    /// Embed this ICC profile in JPEG or PNG output
+    #[arg(long, value_name = "FILE", conflicts_with = "srgb")]
+    icc: Option<PathBuf>,
+
+    /// Embed a built-in sRGB ICC profile in JPEG or PNG output
+    #[arg(long)]
+    srgb: bool,
+
+    /// Strip EXIF, XMP and comment segments from JPEG output, including
+    /// extracted JPEGs, for byte-identical output across runs
+    #[arg(long)]
+    reproducible: bool,
+
+    /// With --rotate, store JPEGs unrotated and set the EXIF orientation so
+    /// viewers turn them instead
+    #[arg(long)]
+    exif_orientation: bool,
+
+    /// Render only text and vector graphics: image objects are removed from
+    /// the loaded page first (the file is not changed)
+    #[arg(long, conflicts_with = "extract_images")]
+    no_images: bool,
+
+    /// Hide these optional content groups (layers) by name, comma-separated;
+    /// `pdf info --layers` lists them. Unknown names only warn
+    #[arg(long, value_name = "NAMES", value_delimiter = ',')]
+    layers_off: Vec<String>,
+
+    /// Show these layers by name, comma-separated, even if the PDF hides them
+    /// by default
+    #[arg(long, value_name = "NAMES", value_delimiter = ',')]
+    layers_on: Vec<String>,
+
+    /// pdfium: anti-alias text for LCD subpixels
+    #[arg(long)]
+    lcd_text: bool,
+
+    /// pdfium: never use the platform's native text output (only Windows and
+    /// macOS builds of pdfium have one)
+    #[arg(long)]
+    no_native_text: bool,
+
+    /// pdfium: rasterize in grayscale, anti-aliasing included
+    #[arg(long)]
+    grayscale_aa: bool,
+
+    /// pdfium: halftone images when scaling them down
+    #[arg(long)]
+    force_halftone: bool,
+
+    /// pdfium: turn off text anti-aliasing
+    #[arg(long)]
+    no_smooth_text: bool,
+
+    /// pdfium: turn off image smoothing
+    #[arg(long)]
+    no_smooth_image: bool,
+
+    /// pdfium: turn off path (vector) anti-aliasing
+    #[arg(long)]
+    no_smooth_path: bool,
+
+    /// pdfium: draw annotations and form fields (on), or only the page
+    /// content (off), e.g. for redaction previews
+    #[arg(long, value_enum, default_value = "on")]
+    annotations: Annotations,
+
+    /// Draw this text diagonally across every rendered page (not applied to
+    /// pages copied by --extract-images)
+    #[arg(long, value_name = "TEXT")]
+    watermark_text: Option<String>,
+
+    /// Watermark opacity, 0 (invisible) to 1 (opaque)
+    #[arg(long, default_value_t = watermark::DEFAULT_OPACITY, requires = "watermark_text")]
+    watermark_opacity: f32,
+
+    /// Watermark angle in degrees, counterclockwise from horizontal
+    #[arg(
+        long,
+        default_value_t = watermark::DEFAULT_ANGLE,
+        allow_negative_numbers = true,
+        requires = "watermark_text"
+    )]
+    watermark_angle: f32,
+
+    /// TrueType or OpenType font for the watermark [default: bundled DejaVu Sans Bold]
+    #[arg(long, value_name = "FILE", requires = "watermark_text")]
+    watermark_font: Option<PathBuf>,
+
+    /// Keep transparency instead of compositing over white (requires --format png or webp);
+    /// same as --background transparent
+    #[arg(long, conflicts_with = "background")]
+    transparent: bool,
+
+    /// Page background: #RRGGBB, white, black or transparent (requires --format png or webp)
+    #[arg(long, default_value = "white")]
+    background: String,
+
+    /// Rotate rendered pages clockwise; --target-width applies to the rotated image
+    #[arg(long, value_enum, default_value = "0")]
+    rotate: Rotation,
+
+    /// Turn each page so its text reads upright, for scans whose text layer
+    /// runs sideways; pages without text keep their /Rotate orientation
+    #[arg(long)]
+    rotate_auto: bool,
+
+    /// Render only this rectangle of each page, in points from the top-left
+    /// corner; --target-width or --dpi then sizes the region
+    #[arg(long, value_name = "X,Y,W,H")]
+    region: Option<String>,
+
+    /// Pad the bottom and right edges with the background color so both
+    /// dimensions are multiples of N (e.g. 2 or 16 for video encoders)
+    #[arg(long, value_name = "N")]
+    align: Option<u32>,
+
+    /// Trim margins matching the --background color (white by default) from
+    /// rendered pages; pages copied by --extract-images are left as is
+    #[arg(long)]
+    autocrop: bool,
+
+    /// How far (0-255 per channel) a pixel may differ from the background and
+    /// still count as margin
+    #[arg(
+        long,
+        value_name = "N",
+        requires = "autocrop",
+        default_value_t = render_worker::DEFAULT_AUTOCROP_THRESHOLD
+    )]
+    autocrop_threshold: u8,
+
+    /// Measure how far each rendered page is rotated (up to 5 degrees, as in
+    /// a crooked scan) and turn it straight, filling the corners with the
+    /// --background color
+    #[arg(long)]
+    deskew: bool,
+
+    /// Render pages in the order given by --pages and prefix filenames with
+    /// their request position (NNNN-page-NNNN.jpg)
+    #[arg(long)]
+    keep_order: bool,
+
+    /// Render at most N of the selected pages: the first N in page order, or
+    /// in --pages order with --keep-order
+    #[arg(long, value_name = "N")]
+    limit: Option<u32>,
+
+    /// Output filename pattern, without extension: {n} page number, {n:0W}
+    /// zero-padded to W digits, {stem} PDF filename stem
+    #[arg(long, default_value = name_template::DEFAULT_NAME_TEMPLATE)]
+    name_template: String,
+
+    /// Name files by page label (page-iii.jpg) instead of page number, for
+    /// pages that have one; labels are sanitized for the filesystem
+    #[arg(long)]
+    label_names: bool,
+
+    /// After rendering, write a JSON list of {page, file, method, width_px,
+    /// height_px, bytes} for every output file
+    #[arg(long, value_name = "PATH")]
+    manifest: Option<PathBuf>,
+
+    /// Include `output_files`, the written file paths in page order, in the
+    /// JSON summary
+    #[arg(long)]
+    list_outputs: bool,
+
+    /// Abort before rendering if any output file already exists (default:
+    /// overwrite)
+    #[arg(long)]
+    no_overwrite: bool,
+
+    /// Give up on a page after this many seconds; the worker process is killed
+    /// and its remaining pages are reported as errors (process mode only)
+    #[arg(long, value_name = "SECS")]
+    page_timeout: Option<u64>,
+
+    /// Respawn a worker process that crashes or exits non-zero, re-rendering
+    /// its pages, up to this many times (process mode only)
+    #[arg(long, value_name = "N", default_value = "0")]
+    retries: u32,
+
+    /// Profiling: render the pages this many times in one process over the
+    /// same loaded document, overwriting the files, and report each
+    /// iteration's time as `iteration_secs`
+    #[arg(long, value_name = "N", default_value = "1", hide = true)]
+    repeat: u32,
+
+    /// Fail fast: stop at the first page error with exit code 4, killing the
+    /// remaining worker processes, instead of reporting all errors at the end
+    #[arg(long)]
+    strict: bool,
+
+    /// Cap each worker process's address space at this many MB, so a runaway
+    /// page fails its worker instead of exhausting memory (process mode only;
+    /// Unix only)
+    #[arg(long, value_name = "MB")]
+    worker_mem_limit: Option<u64>,
+
+    /// Run at most this many worker processes at once; the pages are still
+    /// split --workers ways, and each slice starts when a running one exits
+    /// (process mode only)
+    #[arg(long, value_name = "N")]
+    max_inflight: Option<u32>,
+
+    /// Keep rendered pages in this directory, keyed by a hash of the page's
+    /// content and the output settings; identical pages in later renders are
+    /// copied from it instead of rendered
+    #[arg(long, value_name = "DIR")]
+    cache: Option<PathBuf>,
+
+    /// Progress output on stderr: a status line, or one JSON object per page
+    #[arg(long, value_enum, default_value = "human")]
+    progress: ProgressMode,
+}
+
+#[cfg(not(test))]
+impl RenderArgs {
+    #[cfg_attr(coverage_nightly, coverage(off))]
+    fn into_options(self) -> Result<RenderOptions, error::Error> {
+        let mut extract_images = self.extract_images;
+        if extract_images && matches!(self.format, OutputFormat::Webp | OutputFormat::Avif) {
+            eprintln!(
+                "warning: --extract-images ignored with --format {} (embedded images are JPEG)",
+                self.format.extension()
+            );
+            extract_images = false;
+        }
+        if extract_images && self.autocrop {
+            eprintln!("warning: --autocrop is not applied to pages copied by --extract-images");
+        }
+        if extract_images && self.deskew {
+            eprintln!("warning: --deskew is not applied to pages copied by --extract-images");
+        }
+        if extract_images && self.watermark_text.is_some() {
+            eprintln!(
+                "warning: --watermark-text is not applied to pages copied by --extract-images"
+            );
+        }
+        let widths = render_worker::parse_widths(&self.widths)?;
+        let opts = RenderOptions {
+            size: match self.canvas.as_deref() {
+                Some(canvas) => RenderSize::parse_canvas(canvas)?,
+                None if !widths.is_empty() => RenderSize::from_widths(&widths),
+                None => RenderSize::from_args(self.target_width, self.target_height, self.dpi)?,
+            },
+            max_width: self.max_width,
+            max_height: self.max_height,
+            widths,
+            supersample: self.supersample,
+            resample: self.resample,
+            no_upscale: self.no_upscale,
+            quality: render_worker::parse_quality(&self.quality, self.format)?,
+            box_type: self.r#box,
+            extract_images,
+            reencode_extracted: self.reencode_extracted,
+            encoder: self.encoder,
+            progressive: self.progressive,
+            subsampling: self.subsampling,
+            password: self.password,
+            keep_order: self.keep_order,
+            limit: self.limit,
+            grayscale: self.grayscale,
+            format: self.format,
+            lossless: self.lossless,
+            speed: self.speed,
+            background: if self.transparent {
+                Background::TRANSPARENT
+            } else {
+                Background::parse(&self.background)?
+            },
+            rotation: self.rotate,
+            rotate_auto: self.rotate_auto,
+            region: self.region.as_deref().map(Region::parse).transpose()?,
+            align: self.align,
+            autocrop: self.autocrop.then_some(self.autocrop_threshold),
+            deskew: self.deskew,
+            icc_profile: match (self.icc, self.srgb) {
+                (Some(path), _) => Some(IccProfile::load(&path)?),
+                (None, true) => Some(IccProfile::srgb()),
+                (None, false) => None,
+            },
+            reproducible: self.reproducible,
+            exif_orientation: self.exif_orientation,
+            no_images: self.no_images,
+            layers: Layers {
+                off: self.layers_off,
+                on: self.layers_on,
+            },
+            pdfium_flags: PdfiumFlags {
+                lcd_text: self.lcd_text,
+                no_native_text: self.no_native_text,
+                grayscale_aa: self.grayscale_aa,
+                force_halftone: self.force_halftone,
+                no_smooth_text: self.no_smooth_text,
+                no_smooth_image: self.no_smooth_image,
+                no_smooth_path: self.no_smooth_path,
+                annotations: self.annotations,
+            },
+            watermark: self
+                .watermark_text
+                .map(|text| {
+                    Watermark::new(
+                        &text,
+                        self.watermark_opacity,
+                        self.watermark_angle,
+                        self.watermark_font.as_deref(),
+                    )
+                })
+                .transpose()?,
+            name_template: NameTemplate::parse(&self.name_template)?,
+            label_names: self.label_names,
+            overwrite: !self.no_overwrite,
+            manifest: self.manifest,
+            list_outputs: self.list_outputs,
+            progress: self.progress,
+            quiet: false,
+            page_timeout: self.page_timeout.map(Duration::from_secs),
+            retries: self.retries,
+            strict: self.strict,
+            repeat: self.repeat,
+            worker_mem_limit: self.worker_mem_limit,
+            max_inflight: self.max_inflight,
+            cache: self.cache,
+        };
+        opts.validate()?;
+        Ok(opts)
+    }
 }
 
 #[cfg(not(test))]
 #[cfg_attr(coverage_nightly, coverage(off))]
 fn main() -> ExitCode {
-    command_result_to_exit_code(dispatch(Cli::parse()))
+    command_result_to_exit_code(parse_cli().and_then(dispatch))
+}
+
+/// Parse the command line, with `pdf.toml` supplying `render` defaults.
+///
+/// The file is only read for `render`, so workers and other commands never
+/// repeat its warnings.
+#[cfg(not(test))]
+#[cfg_attr(coverage_nightly, coverage(off))]
+fn parse_cli() -> Result<Cli, error::Error> {
+    let command = Cli::command();
+    let matches = command.clone().get_matches();
+    let cli = Cli::from_arg_matches(&matches).unwrap_or_else(|e| e.exit());
+    // Before reading pdf.toml, so its errors are reported as JSON too.
+    json_output::set_json_errors(cli.json_errors);
+    if matches.subcommand_name() == Some("render")
+        && let Some(defaults) = config::load()?
+    {
+        let matches = defaults.apply(command).get_matches();
+        return Ok(Cli::from_arg_matches(&matches).unwrap_or_else(|e| e.exit()));
+    }
+    Ok(cli)
 }
 
 #[cfg(not(test))]
 #[cfg_attr(coverage_nightly, coverage(off))]
 fn dispatch(cli: Cli) -> Result<(), error::Error> {
+    if let Some(path) = cli.pdfium_path {
+        pdfium_init::set_pdfium_path(path);
+    }
+    pdfium_init::set_verbose(cli.verbose);
+    json_output::set_json_version(cli.json_version);
+
+    let quiet = cli.quiet;
     match cli.command {
-        Commands::Info { pdf, all_pages } => info::run(&pdf, all_pages),
-        command @ Commands::Render { .. } => run_render_command(command),
+        Commands::Info {
+            pdf,
+            all_pages,
+            boxes,
+            image_stats,
+            objects,
+            workers,
+            dimensions_only,
+            layers: list_layers,
+            fonts: list_fonts,
+            password,
+        } => {
+            if dimensions_only {
+                return info::run_dimensions(&pdf, password.as_deref());
+            }
+            if list_layers {
+                return layers::run_list(&pdf, password.as_deref());
+            }
+            if list_fonts {
+                return fonts::run_list(&pdf, password.as_deref());
+            }
+            let details = PageDetails {
+                boxes,
+                image_stats,
+                objects,
+            };
+            info::run(&pdf, all_pages, details, workers, password.as_deref())
+        }
+        Commands::InfoWorker {
+            pdf,
+            pages,
+            boxes,
+            image_stats,
+            objects,
+            password,
+        } => {
+            let details = PageDetails {
+                boxes,
+                image_stats,
+                objects,
+            };
+            info::run_worker(&pdf, &pages, details, password.as_deref())
+        }
+        Commands::Text {
+            pdf,
+            pages,
+            password,
+            layout,
+            stats,
+        } => text::run(&pdf, pages.as_deref(), password.as_deref(), layout, stats),
+        Commands::Search {
+            pdf,
+            query,
+            pages,
+            ignore_case,
+            whole_word,
+            password,
+        } => search::run(
+            &pdf,
+            &query,
+            pages.as_deref(),
+            ignore_case,
+            whole_word,
+            password.as_deref(),
+        ),
+        Commands::Outline { pdf, password } => outline::run(&pdf, password.as_deref()),
+        Commands::ExtractImages {
+            pdf,
+            output,
+            pages,
+            password,
+        } => extract::run(&pdf, &output, pages.as_deref(), password.as_deref(), quiet),
+        Commands::ContactSheet {
+            pdf,
+            output,
+            pages,
+            columns,
+            thumb_width,
+            password,
+        } => contact_sheet::run(
+            &pdf,
+            &output,
+            pages.as_deref(),
+            columns,
+            thumb_width,
+            password.as_deref(),
+            quiet,
+        ),
+        Commands::Tiles {
+            pdf,
+            output,
+            pages,
+            tile,
+            target_width,
+            password,
+        } => tiles::run(
+            &pdf,
+            &output,
+            pages.as_deref(),
+            tile,
+            target_width,
+            password.as_deref(),
+            quiet,
+        ),
+        Commands::Subset {
+            pdf,
+            output,
+            pages,
+            rotate,
+            password,
+        } => subset::run(
+            &pdf,
+            &output,
+            pages.as_deref(),
+            rotate,
+            password.as_deref(),
+            quiet,
+        ),
+        Commands::Flatten {
+            pdf,
+            output,
+            pages,
+            password,
+        } => flatten::run(&pdf, &output, pages.as_deref(), password.as_deref(), quiet),
+        Commands::Capabilities => {
+            capabilities::run();
+            Ok(())
+        }
+        command @ Commands::Render { .. } => run_render_command(command, quiet),
         command @ Commands::RenderWorker { .. } => run_render_worker_command(command),
     }
 }
 
 #[cfg(not(test))]
 #[cfg_attr(coverage_nightly, coverage(off))]
-fn run_render_command(command: Commands) -> Result<(), error::Error> {
+fn run_render_command(command: Commands, quiet: bool) -> Result<(), error::Error> {
     let Commands::Render {
         pdf,
         output,
-        target_width,
-        quality,
-        r#box,
+        stdout,
+        single_file,
         pages,
+        pages_from,
         workers,
-        extract_images,
-        encoder,
+        mode,
+        error_format,
+        summary_only,
+        dry_run,
+        estimate,
+        render,
     } = command
     else {
         unreachable!("render command handler called with non-render command");
     };
+    let pages = match pages_from {
+        Some(path) => Some(page_range::read_pages_file(&path)?),
+        None => pages,
+    };
+    let workers =
+        render::workers_override(workers, std::env::var(render::WORKERS_ENV).ok().as_deref())?;
+
+    let mut opts = render.into_options()?;
+    if quiet || summary_only {
+        opts.progress = ProgressMode::None;
+    }
+    opts.quiet = quiet;
+    let pdf = match &pdf[..] {
+        [pdf] => pdf.clone(),
+        pdfs => {
+            if stdout || single_file.is_some() || dry_run || estimate {
+                return Err(error::Error::InvalidArgs(
+                    "--stdout, --single-file, --dry-run and --estimate take a single PDF".into(),
+                ));
+            }
+            let Some(output) = output else {
+                unreachable!("clap requires --output unless --stdout or --single-file is set");
+            };
+            return render::run_batch(
+                pdfs,
+                &output,
+                pages.as_deref(),
+                workers,
+                mode,
+                error_format,
+                opts,
+            );
+        }
+    };
+    if stdout {
+        return render::run_to_stdout(&pdf, pages.as_deref(), opts);
+    }
+    if estimate {
+        return render::estimate(&pdf, pages.as_deref(), opts);
+    }
+    if let Some(single_file) = single_file {
+        return render::run_single_file(
+            &pdf,
+            &single_file,
+            pages.as_deref(),
+            workers,
+            error_format,
+            opts,
+        );
+    }
+    let Some(output) = output else {
+        unreachable!("clap requires --output unless --stdout or --single-file is set");
+    };
+    if dry_run {
+        return render::dry_run(&pdf, &output, pages.as_deref(), workers, mode, opts);
+    }
 
     render::run(
         &pdf,
         &output,
         pages.as_deref(),
         workers,
-        render_options(target_width, quality, r#box, extract_images, encoder),
+        mode,
+        error_format,
+        opts,
     )
 }
 
@@ -154,11 +1111,8 @@ fn run_render_worker_command(command: Commands) -> Result<(), error::Error> {
         pdf,
         output,
         pages,
-        target_width,
-        quality,
-        r#box,
-        extract_images,
-        encoder,
+        first_sequence,
+        render,
     } = command
     else {
         unreachable!("render-worker command handler called with non-worker command");
@@ -168,35 +1122,22 @@ fn run_render_worker_command(command: Commands) -> Result<(), error::Error> {
         &pdf,
         &output,
         &pages,
-        render_options(target_width, quality, r#box, extract_images, encoder),
+        first_sequence,
+        render.into_options()?,
     )
 }
 
-#[cfg(not(test))]
-#[cfg_attr(coverage_nightly, coverage(off))]
-fn render_options(
-    target_width: u32,
-    quality: u8,
-    box_type: BoxType,
-    extract_images: bool,
-    encoder: JpegEncoderType,
-) -> RenderOptions {
-    RenderOptions {
-        target_width,
-        quality,
-        box_type,
-        extract_images,
-        encoder,
-    }
-}
-
 #[cfg(not(test))]
 #[cfg_attr(coverage_nightly, coverage(off))]
 fn command_result_to_exit_code(result: Result<(), error::Error>) -> ExitCode {
     match result {
         Ok(()) => ExitCode::SUCCESS,
         Err(e) => {
-            eprintln!("error: {e}");
+            if json_output::json_errors() {
+                eprintln!("{}", json_output::error_json(&e));
+            } else {
+                eprintln!("error: {e}");
+            }
             e.exit_code()
         }
     }
@@ -208,19 +1149,32 @@ fn run_worker(
     pdf: &std::path::Path,
     output: &std::path::Path,
     pages: &str,
+    first_sequence: u32,
     opts: RenderOptions,
 ) -> Result<(), error::Error> {
     // Worker needs to know max page count for range parsing; open PDF to check
     let pdfium = pdfium_init::load_pdfium()?;
-    let document = pdfium
-        .load_pdf_from_file(pdf, None)
-        .map_err(|e| error::Error::PdfInvalid(format!("{}: {e}", pdf.display())))?;
+    let document = pdfium_init::load_document(&pdfium, pdf, opts.password.as_deref())?;
     let max_page = document.pages().len() as u32;
     drop(document);
     drop(pdfium);
 
-    let page_list = page_range::parse_page_range(pages, max_page)?;
-    let result = render_worker::render_pages(pdf, output, &page_list, &opts)?;
+    let page_list = if opts.keep_order {
+        page_range::parse_page_sequence(pages, max_page)?
+    } else {
+        page_range::parse_page_range(pages, max_page)?
+    };
+    let result = match opts.page_timeout {
+        Some(timeout) => render_worker::render_pages_with_watchdog(
+            pdf,
+            output,
+            &page_list,
+            first_sequence,
+            &opts,
+            timeout,
+        )?,
+        None => render_worker::render_pages(pdf, output, &page_list, first_sequence, &opts)?,
+    };
 
     // Output result as JSON on stdout for parent to collect
     println!("{}", serde_json::to_string(&result).unwrap());
@@ -0,0 +1,212 @@
+use crate::error::Error;
+
+/// Template used when `--name-template` is not given (`page-0007`).
+pub const DEFAULT_NAME_TEMPLATE: &str = "page-{n:04}";
+
+/// Parsed `--name-template` pattern for output filenames, without extension.
+///
+/// Supports `{n}` (page number), `{n:0W}` (page number zero-padded to `W`
+/// digits) and `{stem}` (the PDF filename without extension). The pattern is
+/// validated once up front so every page, extracted or rendered, gets a name
+/// from the same template.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct NameTemplate {
+    pattern: String,
+    parts: Vec<Part>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Part {
+    Literal(String),
+    Page { width: usize },
+    Stem,
+}
+
+impl NameTemplate {
+    pub fn parse(pattern: &str) -> Result<Self, Error> {
+        let invalid = |reason: &str| {
+            Error::InvalidArgs(format!("invalid --name-template {pattern:?}: {reason}"))
+        };
+
+        let mut parts = Vec::new();
+        let mut rest = pattern;
+        while let Some(open) = rest.find(['{', '}']) {
+            if rest[open..].starts_with('}') {
+                return Err(invalid("unmatched '}'"));
+            }
+            if open > 0 {
+                parts.push(Part::Literal(rest[..open].to_string()));
+            }
+            let Some(close) = rest[open..].find('}') else {
+                return Err(invalid("unclosed '{'"));
+            };
+            parts.push(parse_token(&rest[open + 1..open + close]).ok_or_else(|| {
+                invalid(&format!(
+                    "unknown placeholder {{{}}} (expected {{n}}, {{n:0W}} or {{stem}})",
+                    &rest[open + 1..open + close]
+                ))
+            })?);
+            rest = &rest[open + close + 1..];
+        }
+        if !rest.is_empty() {
+            parts.push(Part::Literal(rest.to_string()));
+        }
+
+        if !parts.iter().any(|part| matches!(part, Part::Page { .. })) {
+            return Err(invalid("must contain {n} so pages get distinct names"));
+        }
+        if pattern.contains(['/', '\\']) {
+            return Err(invalid("must not contain path separators"));
+        }
+
+        Ok(NameTemplate {
+            pattern: pattern.to_string(),
+            parts,
+        })
+    }
+
+    /// The pattern as given, for forwarding to worker processes.
+    pub fn as_str(&self) -> &str {
+        &self.pattern
+    }
+
+    /// Expand the template for one page.
+    pub fn render(&self, page_num: u32, stem: &str) -> String {
+        let mut name = String::new();
+        for part in &self.parts {
+            match part {
+                Part::Literal(text) => name.push_str(text),
+                Part::Page { width } => name.push_str(&format!("{page_num:0width$}")),
+                Part::Stem => name.push_str(stem),
+            }
+        }
+        name
+    }
+
+    /// Expand the template with a page label in place of the page number
+    /// (`--label-names`); numeric labels are still zero-padded.
+    pub fn render_label(&self, label: &str, stem: &str) -> String {
+        let numeric = label.bytes().all(|b| b.is_ascii_digit());
+        let mut name = String::new();
+        for part in &self.parts {
+            match part {
+                Part::Literal(text) => name.push_str(text),
+                Part::Page { width } if numeric => name.push_str(&format!("{label:0>width$}")),
+                Part::Page { .. } => name.push_str(label),
+                Part::Stem => name.push_str(stem),
+            }
+        }
+        name
+    }
+}
+
+/// Make a page label safe to use in a filename.
+///
+/// Characters other than ASCII letters, digits, `-`, `_` and `.` become `_`,
+/// and leading dots are dropped so a label can't name a hidden file. Returns
+/// `None` when nothing usable is left, so the caller falls back to the number.
+pub fn sanitize_label(label: &str) -> Option<String> {
+    let safe: String = label
+        .trim()
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    let safe = safe.trim_start_matches('.');
+    (!safe.is_empty()).then(|| safe.to_string())
+}
+
+impl Default for NameTemplate {
+    fn default() -> Self {
+        NameTemplate::parse(DEFAULT_NAME_TEMPLATE).expect("default template is valid")
+    }
+}
+
+/// Resolve the text between braces to a placeholder.
+fn parse_token(token: &str) -> Option<Part> {
+    match token {
+        "n" => Some(Part::Page { width: 0 }),
+        "stem" => Some(Part::Stem),
+        _ => {
+            let width = token.strip_prefix("n:0")?;
+            if width.is_empty() || !width.bytes().all(|b| b.is_ascii_digit()) {
+                return None;
+            }
+            width.parse().ok().map(|width| Part::Page { width })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_template_pads_to_four_digits() {
+        assert_eq!(NameTemplate::default().render(7, "doc"), "page-0007");
+    }
+
+    #[test]
+    fn page_number_padding() {
+        let template = NameTemplate::parse("invoice_{n:04}").unwrap();
+        assert_eq!(template.render(7, "doc"), "invoice_0007");
+        assert_eq!(template.render(12345, "doc"), "invoice_12345");
+
+        let template = NameTemplate::parse("p{n:02}").unwrap();
+        assert_eq!(template.render(3, "doc"), "p03");
+
+        let template = NameTemplate::parse("p{n}").unwrap();
+        assert_eq!(template.render(3, "doc"), "p3");
+    }
+
+    #[test]
+    fn stem_substitution() {
+        let template = NameTemplate::parse("{stem}-{n:03}").unwrap();
+        assert_eq!(template.render(9, "report"), "report-009");
+        assert_eq!(template.as_str(), "{stem}-{n:03}");
+    }
+
+    #[test]
+    fn labels_replace_page_number() {
+        let template = NameTemplate::default();
+        assert_eq!(template.render_label("iii", "doc"), "page-iii");
+        assert_eq!(template.render_label("7", "doc"), "page-0007");
+
+        let template = NameTemplate::parse("{stem}_{n}").unwrap();
+        assert_eq!(template.render_label("A-1", "doc"), "doc_A-1");
+    }
+
+    #[test]
+    fn labels_are_sanitized_for_filenames() {
+        assert_eq!(sanitize_label("iv").as_deref(), Some("iv"));
+        assert_eq!(sanitize_label(" A-1 ").as_deref(), Some("A-1"));
+        assert_eq!(sanitize_label("a/b\\c:d").as_deref(), Some("a_b_c_d"));
+        assert_eq!(sanitize_label("Annex \u{e9}").as_deref(), Some("Annex__"));
+        assert_eq!(sanitize_label("..").as_deref(), None);
+        assert_eq!(sanitize_label("   ").as_deref(), None);
+    }
+
+    #[test]
+    fn rejects_bad_tokens() {
+        for pattern in [
+            "page-{x}",
+            "page-{n:4}",
+            "page-{n:0}",
+            "page-{n:0a}",
+            "page-{n",
+            "page-n}",
+            "{stem}",
+            "dir/{n}",
+        ] {
+            assert!(
+                matches!(NameTemplate::parse(pattern), Err(Error::InvalidArgs(_))),
+                "{pattern} should be rejected"
+            );
+        }
+    }
+}
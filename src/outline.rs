@@ -0,0 +1,61 @@
+#[cfg(not(test))]
+use crate::error::Error;
+#[cfg(not(test))]
+use crate::json_output::print_json;
+#[cfg(not(test))]
+use crate::pdfium_init::{load_document, load_pdfium};
+#[cfg(not(test))]
+use pdfium_render::prelude::*;
+#[cfg(not(test))]
+use serde::Serialize;
+#[cfg(not(test))]
+use std::path::Path;
+
+/// Bookmarks nested deeper than this are dropped, so a malformed outline
+/// whose children loop back to an ancestor can't recurse forever.
+#[cfg(not(test))]
+const MAX_DEPTH: usize = 64;
+
+#[cfg(not(test))]
+#[derive(Serialize)]
+pub struct OutlineItem {
+    pub title: String,
+    /// 1-based target page; `None` unless the bookmark jumps to a page of
+    /// this document
+    pub page: Option<u32>,
+    pub children: Vec<OutlineItem>,
+}
+
+/// Print the bookmark tree as nested `{title, page, children}` JSON.
+///
+/// Documents without an outline print an empty array.
+#[cfg(not(test))]
+#[cfg_attr(coverage_nightly, coverage(off))]
+pub fn run(pdf_path: &Path, password: Option<&str>) -> Result<(), Error> {
+    let pdfium = load_pdfium()?;
+    let document = load_document(&pdfium, pdf_path, password)?;
+    let outline = read_siblings(document.bookmarks().root(), 0);
+    print_json("outline", &outline);
+    Ok(())
+}
+
+/// `first` and its following siblings, each with its children.
+#[cfg(not(test))]
+#[cfg_attr(coverage_nightly, coverage(off))]
+fn read_siblings(first: Option<PdfBookmark>, depth: usize) -> Vec<OutlineItem> {
+    if depth >= MAX_DEPTH {
+        return Vec::new();
+    }
+    std::iter::successors(first, PdfBookmark::next_sibling)
+        .map(|bookmark| OutlineItem {
+            title: bookmark.title().unwrap_or_default(),
+            // pdfium falls back to a GoTo action's destination when the
+            // bookmark has no /Dest of its own.
+            page: bookmark
+                .destination()
+                .and_then(|dest| dest.page_index().ok())
+                .map(|index| u32::from(index) + 1),
+            children: read_siblings(bookmark.first_child(), depth + 1),
+        })
+        .collect()
+}
@@ -0,0 +1,77 @@
+use crate::error::Error;
+use std::path::Path;
+
+/// Check an output directory (`render -o`, `extract-images -o`, `tiles -o`)
+/// before any work is done: it may not exist yet, but neither it nor the
+/// nearest existing ancestor may be a file, which `create_dir_all` only
+/// reports as an errno.
+pub fn check_output_dir(path: &Path) -> Result<(), Error> {
+    match nearest_existing(path) {
+        Some(existing) if !existing.is_dir() => {
+            let message = if existing == path {
+                format!(
+                    "{}: output path is a file, expected a directory",
+                    path.display()
+                )
+            } else {
+                format!(
+                    "{}: output path is inside {}, which is a file",
+                    path.display(),
+                    existing.display()
+                )
+            };
+            Err(Error::InvalidArgs(message))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Check an output file (`--single-file`, `contact-sheet -o`, `subset -o`,
+/// `flatten -o`): an existing file is replaced, but a directory is rejected,
+/// as is a parent path that runs through a file.
+pub fn check_output_file(path: &Path) -> Result<(), Error> {
+    if path.is_dir() {
+        return Err(Error::InvalidArgs(format!(
+            "{}: output path is a directory, expected a file",
+            path.display()
+        )));
+    }
+    match path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        Some(dir) => check_output_dir(dir),
+        None => Ok(()),
+    }
+}
+
+/// Create an output directory and check that files can be written to it, so
+/// a read-only directory fails once, naming it, instead of once per page.
+pub fn create_output_dir(path: &Path) -> Result<(), Error> {
+    let named = |e: std::io::Error, problem: &str| {
+        Error::Io(std::io::Error::new(
+            e.kind(),
+            format!("{}: {problem}: {e}", path.display()),
+        ))
+    };
+    std::fs::create_dir_all(path).map_err(|e| named(e, "cannot create output directory"))?;
+    let probe = path.join(format!(".pdf-write-check-{}", std::process::id()));
+    std::fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(&probe)
+        .map_err(|e| named(e, "output directory is not writable"))?;
+    std::fs::remove_file(&probe).map_err(|e| named(e, "output directory is not writable"))
+}
+
+/// Create the directory an output file goes in, if the path names one.
+pub fn create_parent_dir(path: &Path) -> Result<(), Error> {
+    if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        std::fs::create_dir_all(dir)?;
+    }
+    Ok(())
+}
+
+/// `path` or its closest ancestor that exists.
+fn nearest_existing(path: &Path) -> Option<&Path> {
+    path.ancestors()
+        .filter(|ancestor| !ancestor.as_os_str().is_empty())
+        .find(|ancestor| ancestor.exists())
+}
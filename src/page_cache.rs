@@ -0,0 +1,301 @@
+#[cfg(not(test))]
+use crate::render_worker::HashingWriter;
+use crate::render_worker::RenderOptions;
+#[cfg(not(test))]
+use pdfium_render::prelude::*;
+use sha2::{Digest, Sha256};
+use std::fmt::Debug;
+#[cfg(not(test))]
+use std::fs::File;
+#[cfg(not(test))]
+use std::io::BufWriter;
+use std::path::{Path, PathBuf};
+#[cfg(not(test))]
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Rendered pages kept in a `--cache` directory, each named after a SHA-256
+/// of the page's content and of the settings that shape its output, so an
+/// identical page is copied instead of rendered again.
+pub struct PageCache {
+    dir: PathBuf,
+    /// `render_settings` digest, computed once per worker
+    settings: String,
+    extension: &'static str,
+}
+
+impl PageCache {
+    pub fn new(dir: &Path, opts: &RenderOptions) -> Self {
+        PageCache {
+            dir: dir.to_path_buf(),
+            settings: render_settings(opts),
+            extension: opts.format.extension(),
+        }
+    }
+
+    /// The cache file for a page whose `page_content_hash` is `content`.
+    pub fn entry(&self, content: &str) -> PathBuf {
+        let mut hasher = Sha256::new();
+        hasher.update(&self.settings);
+        hasher.update(content);
+        self.dir
+            .join(format!("{:x}.{}", hasher.finalize(), self.extension))
+    }
+}
+
+/// Hex SHA-256 of every option that changes a rendered page's bytes, and of
+/// this crate's version, since encoders change between releases. Options that
+/// only affect scheduling, naming or reporting are left out, so changing them
+/// keeps the cache valid.
+pub fn render_settings(opts: &RenderOptions) -> String {
+    // No `..`: a new option has to be sorted into one group or the other.
+    let RenderOptions {
+        size,
+        max_width,
+        max_height,
+        widths,
+        supersample,
+        resample,
+        no_upscale,
+        quality,
+        box_type,
+        encoder,
+        progressive,
+        subsampling,
+        grayscale,
+        format,
+        lossless,
+        speed,
+        background,
+        rotation,
+        rotate_auto,
+        region,
+        align,
+        autocrop,
+        deskew,
+        icc_profile,
+        watermark,
+        exif_orientation,
+        no_images,
+        pdfium_flags,
+        layers,
+        reproducible,
+        extract_images: _,
+        reencode_extracted: _,
+        password: _,
+        keep_order: _,
+        limit: _,
+        name_template: _,
+        label_names: _,
+        overwrite: _,
+        manifest: _,
+        list_outputs: _,
+        progress: _,
+        quiet: _,
+        page_timeout: _,
+        retries: _,
+        strict: _,
+        repeat: _,
+        worker_mem_limit: _,
+        max_inflight: _,
+        cache: _,
+    } = opts;
+
+    let mut hasher = Sha256::new();
+    feed(&mut hasher, env!("CARGO_PKG_VERSION"));
+    feed(
+        &mut hasher,
+        (
+            size,
+            max_width,
+            max_height,
+            widths,
+            supersample,
+            resample,
+            no_upscale,
+            quality,
+        ),
+    );
+    feed(
+        &mut hasher,
+        (
+            box_type,
+            encoder,
+            progressive,
+            subsampling,
+            grayscale,
+            format,
+        ),
+    );
+    feed(
+        &mut hasher,
+        (lossless, speed, background, rotation, rotate_auto),
+    );
+    feed(
+        &mut hasher,
+        (region, align, autocrop, deskew, icc_profile, watermark),
+    );
+    feed(
+        &mut hasher,
+        (
+            exif_orientation,
+            no_images,
+            pdfium_flags,
+            layers,
+            reproducible,
+        ),
+    );
+    format!("{:x}", hasher.finalize())
+}
+
+/// Hex SHA-256 of the page as pdfium sees it: its size, rotation and crop
+/// box; every object's type, transform, bounds and colors; text with its font
+/// and size, path segments, raw image data and the objects inside form
+/// XObjects; and each annotation's type, bounds and contents.
+///
+/// Embedded fonts are identified by name, not by their data.
+#[cfg(not(test))]
+#[cfg_attr(coverage_nightly, coverage(off))]
+pub fn page_content_hash(page: &PdfPage) -> String {
+    let mut hasher = Sha256::new();
+    feed(
+        &mut hasher,
+        (
+            page.width(),
+            page.height(),
+            page.rotation().ok(),
+            page.boundaries().crop().ok().map(|crop| crop.bounds),
+        ),
+    );
+    for object in page.objects().iter() {
+        hash_object(&mut hasher, &object);
+    }
+    for annotation in page.annotations().iter() {
+        feed(
+            &mut hasher,
+            (
+                annotation.annotation_type(),
+                annotation.bounds().ok(),
+                annotation.contents(),
+            ),
+        );
+    }
+    format!("{:x}", hasher.finalize())
+}
+
+#[cfg(not(test))]
+#[cfg_attr(coverage_nightly, coverage(off))]
+fn hash_object(hasher: &mut Sha256, object: &PdfPageObject) {
+    feed(
+        hasher,
+        (
+            object.object_type(),
+            object.matrix().ok(),
+            object.bounds().ok(),
+            object.fill_color().ok(),
+            object.stroke_color().ok(),
+            object.stroke_width().ok(),
+        ),
+    );
+    if let Some(text) = object.as_text_object() {
+        feed(
+            hasher,
+            (
+                text.text(),
+                text.font().name(),
+                text.scaled_font_size(),
+                text.render_mode(),
+            ),
+        );
+    } else if let Some(path) = object.as_path_object() {
+        for segment in path.segments().iter() {
+            feed(hasher, (segment.segment_type(), segment.point()));
+        }
+    } else if let Some(image) = object.as_image_object() {
+        hasher.update(image.get_raw_image_data().unwrap_or_default());
+    } else if let Some(form) = object.as_x_object_form_object() {
+        for child in form.iter() {
+            hash_object(hasher, &child);
+        }
+    }
+}
+
+fn feed(hasher: &mut Sha256, value: impl Debug) {
+    hasher.update(format!("{value:?}\n"));
+}
+
+/// Copy the cached `entry` to `path`, returning the copy's hex SHA-256.
+///
+/// A missing entry fails before `path` is touched.
+#[cfg(not(test))]
+#[cfg_attr(coverage_nightly, coverage(off))]
+pub fn copy_cached(entry: &Path, path: &Path) -> std::io::Result<String> {
+    let mut cached = File::open(entry)?;
+    let mut writer = HashingWriter::new(BufWriter::new(File::create(path)?));
+    std::io::copy(&mut cached, &mut writer)?;
+    writer.finish()
+}
+
+/// Store the file rendered at `path` as `entry`. It is copied to a temporary
+/// name first and renamed, so other workers never see a partial entry.
+#[cfg(not(test))]
+#[cfg_attr(coverage_nightly, coverage(off))]
+pub fn store(path: &Path, entry: &Path) -> std::io::Result<()> {
+    static NEXT_TEMP: AtomicU64 = AtomicU64::new(0);
+
+    let temp = entry.with_extension(format!(
+        "{}-{}.tmp",
+        std::process::id(),
+        NEXT_TEMP.fetch_add(1, Ordering::Relaxed)
+    ));
+    std::fs::copy(path, &temp)?;
+    std::fs::rename(&temp, entry).inspect_err(|_| {
+        let _ = std::fs::remove_file(&temp);
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::render_worker::OutputFormat;
+
+    #[test]
+    fn output_settings_change_the_entry() {
+        let base = PageCache::new(Path::new("cache"), &RenderOptions::default());
+        let entry = base.entry("content");
+
+        assert_eq!(entry, base.entry("content"));
+        assert_ne!(entry, base.entry("other content"));
+        assert_eq!(entry.parent(), Some(Path::new("cache")));
+        assert_eq!(entry.extension().unwrap(), "jpg");
+
+        let lower_quality = RenderOptions {
+            quality: 80,
+            ..RenderOptions::default()
+        };
+        let png = RenderOptions {
+            format: OutputFormat::Png,
+            ..RenderOptions::default()
+        };
+        for opts in [lower_quality, png] {
+            assert_ne!(
+                PageCache::new(Path::new("cache"), &opts).entry("content"),
+                entry
+            );
+        }
+    }
+
+    #[test]
+    fn scheduling_options_keep_the_entry() {
+        let base = render_settings(&RenderOptions::default());
+        let scheduled = RenderOptions {
+            strict: true,
+            retries: 2,
+            keep_order: true,
+            overwrite: false,
+            max_inflight: Some(1),
+            ..RenderOptions::default()
+        };
+
+        assert_eq!(render_settings(&scheduled), base);
+    }
+}
@@ -1,4 +1,5 @@
 use crate::error::Error;
+use std::path::Path;
 
 /// Parse a page range string like "1-10", "3,5,7", "1-5,8,10-12" into a sorted Vec of 1-based page numbers.
 fn parse_page_number(s: &str) -> Result<u32, Error> {
@@ -7,6 +8,30 @@ fn parse_page_number(s: &str) -> Result<u32, Error> {
         .map_err(|_| Error::InvalidArgs(format!("invalid page number: {s}")))
 }
 
+/// A page number, or `~N` counting back from the last page (`~1` is the last).
+///
+/// `~` rather than a minus sign, which already separates ranges.
+fn parse_page_ref(s: &str, max_page: u32) -> Result<u32, Error> {
+    let Some(offset_str) = s.trim().strip_prefix('~') else {
+        return parse_page_number(s);
+    };
+    let offset: u32 = offset_str
+        .trim()
+        .parse()
+        .map_err(|_| Error::InvalidArgs(format!("invalid page number: {s}")))?;
+    if offset == 0 {
+        return Err(Error::InvalidArgs(
+            "~0 is not a page: ~1 is the last page".into(),
+        ));
+    }
+    match max_page.checked_sub(offset - 1) {
+        Some(page) if page > 0 => Ok(page),
+        _ => Err(Error::InvalidArgs(format!(
+            "~{offset} is before page 1 (page count {max_page})"
+        ))),
+    }
+}
+
 fn validate_page(page: u32, max_page: u32) -> Result<(), Error> {
     if page == 0 {
         return Err(Error::InvalidArgs("page numbers are 1-based".into()));
@@ -20,34 +45,159 @@ fn validate_page(page: u32, max_page: u32) -> Result<(), Error> {
 }
 
 pub fn parse_page_range(input: &str, max_page: u32) -> Result<Vec<u32>, Error> {
-    let mut pages = Vec::new();
-
-    for part in input.split(',') {
-        let part = part.trim();
-        if let Some((start_str, end_str)) = part.split_once('-') {
-            let start = parse_page_number(start_str)?;
-            let end = parse_page_number(end_str)?;
-            validate_page(start, max_page)?;
-            validate_page(end, max_page)?;
-            if start > end {
-                return Err(Error::InvalidArgs(format!(
-                    "invalid range: {start} > {end}"
-                )));
-            }
-            pages.extend(start..=end);
-        } else {
-            let page = parse_page_number(part)?;
-            validate_page(page, max_page)?;
-            pages.push(page);
+    // Mark pages in a document-sized mask so overlapping or repeated parts can
+    // never allocate more than `max_page` entries.
+    let mut selected = vec![false; max_page as usize];
+    for span in parse_spans(input, max_page)? {
+        for page in span.pages() {
+            selected[(page - 1) as usize] = true;
         }
     }
 
-    pages.sort_unstable();
+    Ok((1..=max_page)
+        .filter(|&page| selected[(page - 1) as usize])
+        .collect())
+}
+
+/// Like `parse_page_range`, but keep the pages in the order they were requested.
+///
+/// Only adjacent duplicates are removed, so "5,1,3,1" stays `[5, 1, 3, 1]`.
+/// The expanded sequence may not be longer than the document.
+pub fn parse_page_sequence(input: &str, max_page: u32) -> Result<Vec<u32>, Error> {
+    let spans = parse_spans(input, max_page)?;
+    let total: u64 = spans.iter().map(|span| span.len()).sum();
+    if total > u64::from(max_page) {
+        return Err(Error::InvalidArgs(format!(
+            "page selection expands to {total} pages, more than page count {max_page}"
+        )));
+    }
+
+    let mut pages = Vec::with_capacity(total as usize);
+    for span in spans {
+        pages.extend(span.pages());
+    }
     pages.dedup();
     Ok(pages)
 }
 
+/// Inclusive page span, taking every `step`-th page (2 for `odd`/`even`).
+#[derive(Clone, Copy)]
+struct Span {
+    start: u32,
+    end: u32,
+    step: u32,
+}
+
+impl Span {
+    fn contiguous(start: u32, end: u32) -> Self {
+        Span {
+            start,
+            end,
+            step: 1,
+        }
+    }
+
+    fn pages(self) -> impl Iterator<Item = u32> {
+        (self.start..=self.end).step_by(self.step as usize)
+    }
+
+    fn len(self) -> u64 {
+        if self.end < self.start {
+            return 0;
+        }
+        u64::from((self.end - self.start) / self.step) + 1
+    }
+}
+
+/// Parse and validate every comma-separated part before anything is expanded.
+fn parse_spans(input: &str, max_page: u32) -> Result<Vec<Span>, Error> {
+    input
+        .split(',')
+        .map(|part| parse_part(part.trim(), max_page))
+        .collect()
+}
+
+/// Resolve one comma-separated part ("7", "1-5", "5-", "-3", "-", "~2-~1",
+/// "odd") to a span.
+///
+/// A missing start means page 1 and a missing end means the last page.
+/// `odd`/`even` select that subset of the whole document (empty if none exist).
+fn parse_part(part: &str, max_page: u32) -> Result<Span, Error> {
+    match part {
+        "odd" => {
+            return Ok(Span {
+                start: 1,
+                end: max_page,
+                step: 2,
+            });
+        }
+        "even" => {
+            return Ok(Span {
+                start: 2,
+                end: max_page,
+                step: 2,
+            });
+        }
+        _ => {}
+    }
+
+    if let Some(page) = parse_last(part, max_page)? {
+        validate_page(page, max_page)?;
+        return Ok(Span::contiguous(page, page));
+    }
+
+    let Some((start_str, end_str)) = part.split_once('-') else {
+        let page = parse_page_ref(part, max_page)?;
+        validate_page(page, max_page)?;
+        return Ok(Span::contiguous(page, page));
+    };
+
+    let start = parse_bound(start_str, 1, max_page)?;
+    let end = parse_bound(end_str, max_page, max_page)?;
+    validate_page(start, max_page)?;
+    validate_page(end, max_page)?;
+    if start > end {
+        return Err(Error::InvalidArgs(format!(
+            "invalid range: {start} > {end}"
+        )));
+    }
+    Ok(Span::contiguous(start, end))
+}
+
+/// Resolve the standalone `last` and `last-N` forms; `None` for anything else.
+fn parse_last(part: &str, max_page: u32) -> Result<Option<u32>, Error> {
+    if part == "last" {
+        return Ok(Some(max_page));
+    }
+    let Some(offset_str) = part.strip_prefix("last-") else {
+        return Ok(None);
+    };
+
+    let offset: u32 = offset_str
+        .trim()
+        .parse()
+        .map_err(|_| Error::InvalidArgs(format!("invalid page expression: {part}")))?;
+    match max_page.checked_sub(offset) {
+        Some(page) if page > 0 => Ok(Some(page)),
+        _ => Err(Error::InvalidArgs(format!(
+            "{part} is before page 1 (page count {max_page})"
+        ))),
+    }
+}
+
+fn parse_bound(s: &str, default: u32, max_page: u32) -> Result<u32, Error> {
+    match s.trim() {
+        "" => Ok(default),
+        "last" => Ok(max_page),
+        _ => parse_page_ref(s, max_page),
+    }
+}
+
 /// Divide pages into roughly equal chunks for worker distribution.
+///
+/// Chunks are 1-based index ranges into the page list, not page numbers, so an
+/// unsorted `--keep-order` list is split by request position and each worker
+/// receives a contiguous slice of the requested sequence.
 pub fn divide_pages(total_pages: u32, num_workers: u32) -> Vec<(u32, u32)> {
     if total_pages == 0 || num_workers == 0 {
         return Vec::new();
@@ -70,6 +220,29 @@ pub fn divide_pages(total_pages: u32, num_workers: u32) -> Vec<(u32, u32)> {
     ranges
 }
 
+/// Read a `--pages-from` file: one selection per line, joined with commas for
+/// `parse_page_range`. Blank lines and lines starting with `#` are skipped.
+pub fn read_pages_file(path: &Path) -> Result<String, Error> {
+    let text = std::fs::read_to_string(path)
+        .map_err(|e| Error::InvalidArgs(format!("--pages-from {}: {e}", path.display())))?;
+    let pages = join_page_lines(&text);
+    if pages.is_empty() {
+        return Err(Error::InvalidArgs(format!(
+            "--pages-from {}: no pages listed",
+            path.display()
+        )));
+    }
+    Ok(pages)
+}
+
+fn join_page_lines(text: &str) -> String {
+    text.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -110,6 +283,153 @@ mod tests {
         assert!(parse_page_range("0", 10).is_err());
     }
 
+    #[test]
+    fn parse_open_ended_to_last_page() {
+        assert_eq!(parse_page_range("5-", 7).unwrap(), vec![5, 6, 7]);
+    }
+
+    #[test]
+    fn parse_open_start_from_first_page() {
+        assert_eq!(parse_page_range("-3", 7).unwrap(), vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn parse_bare_dash_means_all_pages() {
+        assert_eq!(parse_page_range("-", 4).unwrap(), vec![1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn parse_open_ended_still_validates_bounds() {
+        assert!(parse_page_range("11-", 10).is_err());
+        assert!(parse_page_range("-11", 10).is_err());
+        assert!(parse_page_range("0-", 10).is_err());
+    }
+
+    #[test]
+    fn parse_last_keyword() {
+        assert_eq!(parse_page_range("last", 12).unwrap(), vec![12]);
+    }
+
+    #[test]
+    fn parse_first_and_last() {
+        assert_eq!(parse_page_range("1,last", 12).unwrap(), vec![1, 12]);
+    }
+
+    #[test]
+    fn parse_last_minus_offset() {
+        assert_eq!(parse_page_range("last-1", 12).unwrap(), vec![11]);
+        assert_eq!(parse_page_range("10-last", 12).unwrap(), vec![10, 11, 12]);
+    }
+
+    #[test]
+    fn parse_malformed_last_arithmetic() {
+        assert!(matches!(
+            parse_page_range("last-x", 12),
+            Err(Error::InvalidArgs(_))
+        ));
+        assert!(matches!(
+            parse_page_range("last-5-last", 12),
+            Err(Error::InvalidArgs(_))
+        ));
+        assert!(matches!(
+            parse_page_range("last-12", 12),
+            Err(Error::InvalidArgs(_))
+        ));
+    }
+
+    #[test]
+    fn parse_from_end() {
+        assert_eq!(parse_page_range("~1", 10).unwrap(), vec![10]);
+        assert_eq!(parse_page_range("~10", 10).unwrap(), vec![1]);
+        assert_eq!(parse_page_range("~3-~1", 10).unwrap(), vec![8, 9, 10]);
+        assert_eq!(parse_page_range("~3-", 10).unwrap(), vec![8, 9, 10]);
+        assert_eq!(parse_page_range("2-~8", 10).unwrap(), vec![2, 3]);
+        assert_eq!(parse_page_range("1,~1", 10).unwrap(), vec![1, 10]);
+        assert_eq!(parse_page_sequence("~1,~2", 10).unwrap(), vec![10, 9]);
+    }
+
+    #[test]
+    fn parse_from_end_stays_within_document() {
+        for input in ["~0", "~11", "~x", "~", "~1-~3", "~11-~1"] {
+            assert!(
+                matches!(parse_page_range(input, 10), Err(Error::InvalidArgs(_))),
+                "{input}"
+            );
+        }
+    }
+
+    #[test]
+    fn parse_sorts_by_default() {
+        assert_eq!(parse_page_range("5,1,3", 10).unwrap(), vec![1, 3, 5]);
+    }
+
+    #[test]
+    fn parse_sequence_keeps_requested_order() {
+        assert_eq!(parse_page_sequence("5,1,3", 10).unwrap(), vec![5, 1, 3]);
+        assert_eq!(
+            parse_page_sequence("3-4,4-5,1", 10).unwrap(),
+            vec![3, 4, 5, 1]
+        );
+        assert_eq!(parse_page_sequence("2,1,2", 10).unwrap(), vec![2, 1, 2]);
+    }
+
+    #[test]
+    fn parse_huge_range_fails_without_expanding() {
+        let start = std::time::Instant::now();
+        assert!(matches!(
+            parse_page_range("1-4000000000", 10),
+            Err(Error::InvalidArgs(_))
+        ));
+        assert!(matches!(
+            parse_page_sequence("1-4000000000", 10),
+            Err(Error::InvalidArgs(_))
+        ));
+        assert!(matches!(
+            parse_page_range("1-99999999999", 10),
+            Err(Error::InvalidArgs(_))
+        ));
+        assert!(start.elapsed() < std::time::Duration::from_millis(100));
+    }
+
+    #[test]
+    fn parse_repeated_ranges_stay_within_page_count() {
+        let input = vec!["-"; 1000].join(",");
+        assert_eq!(parse_page_range(&input, 3).unwrap(), vec![1, 2, 3]);
+        assert!(matches!(
+            parse_page_sequence(&input, 3),
+            Err(Error::InvalidArgs(_))
+        ));
+    }
+
+    #[test]
+    fn parse_odd_pages() {
+        assert_eq!(parse_page_range("odd", 7).unwrap(), vec![1, 3, 5, 7]);
+    }
+
+    #[test]
+    fn parse_even_pages() {
+        assert_eq!(parse_page_range("even", 7).unwrap(), vec![2, 4, 6]);
+    }
+
+    #[test]
+    fn parse_odd_even_compose_and_stay_in_bounds() {
+        assert_eq!(parse_page_range("even,1", 7).unwrap(), vec![1, 2, 4, 6]);
+        assert_eq!(parse_page_range("even", 1).unwrap(), Vec::<u32>::new());
+        assert_eq!(parse_page_sequence("even", 7).unwrap(), vec![2, 4, 6]);
+    }
+
+    #[test]
+    fn page_lines_skip_comments_and_blank_lines() {
+        let text = "# chapter 1\n1-3\n\n  7 \n#10\n~2-\n";
+
+        assert_eq!(join_page_lines(text), "1-3,7,~2-");
+        assert_eq!(
+            parse_page_range(&join_page_lines(text), 12).unwrap(),
+            vec![1, 2, 3, 7, 11, 12]
+        );
+        assert_eq!(join_page_lines("# nothing\n\n"), "");
+    }
+
     #[test]
     fn divide_evenly() {
         assert_eq!(divide_pages(12, 4), vec![(1, 3), (4, 6), (7, 9), (10, 12)]);
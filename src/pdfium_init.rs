@@ -2,59 +2,265 @@
 use crate::error::Error;
 #[cfg(not(test))]
 use pdfium_render::prelude::*;
+#[cfg(not(test))]
+use std::io::Read;
+use std::path::Path;
+use std::path::PathBuf;
+#[cfg(not(test))]
+use std::process::Command;
+use std::sync::OnceLock;
+#[cfg(not(test))]
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// `pdf` argument meaning "read the PDF from stdin".
+pub const STDIN_PATH: &str = "-";
+
+pub fn is_stdin(path: &Path) -> bool {
+    path == Path::new(STDIN_PATH)
+}
+
+/// Environment variable naming the pdfium library file or its directory.
+pub const PDFIUM_PATH_ENV: &str = "PDF_PDFIUM_PATH";
+
+static PDFIUM_PATH: OnceLock<PathBuf> = OnceLock::new();
+
+/// Use this library file or directory instead of searching (`--pdfium-path`).
+///
+/// Takes precedence over `PDF_PDFIUM_PATH`; only the first call has an effect.
+pub fn set_pdfium_path(path: PathBuf) {
+    let _ = PDFIUM_PATH.set(path);
+}
+
+/// The `--pdfium-path` value, falling back to `PDF_PDFIUM_PATH`.
+fn explicit_pdfium_path() -> Option<PathBuf> {
+    PDFIUM_PATH
+        .get()
+        .cloned()
+        .or_else(|| std::env::var_os(PDFIUM_PATH_ENV).map(PathBuf::from))
+}
+
+/// Pass `--pdfium-path` on to a worker subprocess through `PDF_PDFIUM_PATH`.
+#[cfg(not(test))]
+pub fn forward_pdfium_path(command: &mut Command) {
+    if let Some(path) = PDFIUM_PATH.get() {
+        command.env(PDFIUM_PATH_ENV, path);
+    }
+}
+
+#[cfg(not(test))]
+static VERBOSE: AtomicBool = AtomicBool::new(false);
+
+/// Report on stderr which pdfium library was loaded (`--verbose`).
+#[cfg(not(test))]
+pub fn set_verbose(verbose: bool) {
+    VERBOSE.store(verbose, Ordering::Relaxed);
+}
 
 /// Initialize pdfium by searching for the library in standard locations.
 ///
 /// Search order:
-/// 1. PDFIUM_LIBRARY_PATH, when set
-/// 2. Next to the current executable
-/// 3. System library paths (LD_LIBRARY_PATH, /usr/lib, etc.)
+/// 1. `--pdfium-path` or PDF_PDFIUM_PATH, when set (no fallback if it fails)
+/// 2. PDFIUM_LIBRARY_PATH, when set
+/// 3. Next to the current executable
+/// 4. System library paths (LD_LIBRARY_PATH, /usr/lib, etc.)
+///
+/// When nothing loads, the error lists every location tried.
 #[cfg(not(test))]
 #[cfg_attr(coverage_nightly, coverage(off))]
 pub fn load_pdfium() -> Result<Pdfium, Error> {
-    if let Some(bindings) = load_from_env()? {
-        return Ok(Pdfium::new(bindings));
+    let (bindings, source) = bind_pdfium()?;
+    // Once per process: commands and worker threads may load pdfium again.
+    if VERBOSE.swap(false, Ordering::Relaxed) {
+        eprintln!("pdfium: loaded {source}");
+    }
+    Ok(Pdfium::new(bindings))
+}
+
+/// Bind the first library found in `load_pdfium`'s search order, with a
+/// description of where it came from.
+#[cfg(not(test))]
+#[cfg_attr(coverage_nightly, coverage(off))]
+fn bind_pdfium() -> Result<(Box<dyn PdfiumLibraryBindings>, String), Error> {
+    if let Some(path) = explicit_pdfium_path() {
+        let library_path = if path.is_dir() {
+            Pdfium::pdfium_platform_library_name_at_path(&path)
+        } else {
+            path
+        };
+        return bind_library(&library_path);
     }
 
-    if let Some(bindings) = load_from_exe_dir()? {
-        return Ok(Pdfium::new(bindings));
+    if let Some(path) = std::env::var_os("PDFIUM_LIBRARY_PATH") {
+        return bind_library(Path::new(&path));
     }
 
-    Pdfium::bind_to_system_library()
-        .map(Pdfium::new)
-        .map_err(|e| Error::PdfiumNotFound(e.to_string()))
+    let mut tried = Vec::new();
+    if let Some(exe_dir) = std::env::current_exe()
+        .ok()
+        .and_then(|path| path.parent().map(Path::to_path_buf))
+    {
+        let library_path = Pdfium::pdfium_platform_library_name_at_path(&exe_dir);
+        if library_path.exists() {
+            return bind_library(&library_path);
+        }
+        tried.push(format!("{} (not found)", library_path.display()));
+    }
+
+    let system = format!(
+        "system library {}",
+        Pdfium::pdfium_platform_library_name().to_string_lossy()
+    );
+    match Pdfium::bind_to_system_library() {
+        Ok(bindings) => Ok((bindings, system)),
+        Err(e) => {
+            tried.push(format!("{system} ({e})"));
+            Err(Error::PdfiumNotFound(not_found_message(&tried)))
+        }
+    }
 }
 
+/// Bind to the library file at `path`; failing to load it is final.
 #[cfg(not(test))]
 #[cfg_attr(coverage_nightly, coverage(off))]
-fn load_from_env() -> Result<Option<Box<dyn PdfiumLibraryBindings>>, Error> {
-    let Ok(path) = std::env::var("PDFIUM_LIBRARY_PATH") else {
-        return Ok(None);
-    };
+fn bind_library(path: &Path) -> Result<(Box<dyn PdfiumLibraryBindings>, String), Error> {
+    Pdfium::bind_to_library(path)
+        .map(|bindings| (bindings, path.display().to_string()))
+        .map_err(|e| Error::PdfiumNotFound(format!("failed to load {}: {e}", path.display())))
+}
 
-    Pdfium::bind_to_library(&path)
-        .map(Some)
-        .map_err(|e| Error::PdfiumNotFound(format!("failed to load {path}: {e}")))
+/// The error for a search that found nothing, naming each location tried.
+pub fn not_found_message(tried: &[String]) -> String {
+    format!(
+        "tried {}; set --pdfium-path or {PDFIUM_PATH_ENV} to the library file or its directory",
+        tried.join(", ")
+    )
 }
 
+/// Open a PDF document, turning pdfium's password failure into a `--password` hint.
+///
+/// A path of `-` reads the whole of stdin into memory, so it can only be opened
+/// once per process; use `SpooledStdin` when the document is opened again.
 #[cfg(not(test))]
 #[cfg_attr(coverage_nightly, coverage(off))]
-fn load_from_exe_dir() -> Result<Option<Box<dyn PdfiumLibraryBindings>>, Error> {
-    let Some(exe_dir) = std::env::current_exe()
-        .ok()
-        .and_then(|path| path.parent().map(|dir| dir.to_path_buf()))
-    else {
-        return Ok(None);
+pub fn load_document<'a>(
+    pdfium: &'a Pdfium,
+    path: &Path,
+    password: Option<&'a str>,
+) -> Result<PdfDocument<'a>, Error> {
+    let loaded = if is_stdin(path) {
+        let mut bytes = Vec::new();
+        std::io::stdin().lock().read_to_end(&mut bytes)?;
+        pdfium.load_pdf_from_byte_vec(bytes, password)
+    } else {
+        pdfium.load_pdf_from_file(path, password)
     };
+    loaded.map_err(|e| match e {
+        PdfiumError::PdfiumLibraryInternalError(PdfiumInternalError::PasswordError) => {
+            Error::PdfInvalid(password_error_message(path, password.is_some()))
+        }
+        e => Error::PdfInvalid(format!("{}: {e}", path.display())),
+    })
+}
+
+/// Stdin buffered to a temporary `stdin.pdf`, for consumers that need a real
+/// path: worker subprocesses, or code that opens the document more than once.
+/// The file and its directory are removed on drop.
+#[cfg(not(test))]
+pub struct SpooledStdin {
+    dir: PathBuf,
+    path: PathBuf,
+}
 
-    let library_path = Pdfium::pdfium_platform_library_name_at_path(exe_dir.as_path());
-    if !library_path.exists() {
-        return Ok(None);
+#[cfg(not(test))]
+impl SpooledStdin {
+    #[cfg_attr(coverage_nightly, coverage(off))]
+    pub fn create() -> Result<Self, Error> {
+        let nanos = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_nanos());
+        let dir = std::env::temp_dir().join(format!("pdf-stdin-{}-{nanos}", std::process::id()));
+        std::fs::create_dir(&dir)?;
+        let spool = SpooledStdin {
+            path: dir.join("stdin.pdf"),
+            dir,
+        };
+
+        let mut file = std::fs::File::create(&spool.path)?;
+        std::io::copy(&mut std::io::stdin().lock(), &mut file)?;
+        Ok(spool)
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
     }
+}
 
-    Pdfium::bind_to_library(&library_path)
-        .map(Some)
-        .map_err(|e| {
-            Error::PdfiumNotFound(format!("failed to load {}: {e}", library_path.display()))
-        })
+#[cfg(not(test))]
+impl Drop for SpooledStdin {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.dir);
+    }
+}
+
+pub fn password_error_message(path: &Path, password_given: bool) -> String {
+    if password_given {
+        format!("{}: incorrect password", path.display())
+    } else {
+        format!(
+            "{}: PDF is encrypted; supply the password with --password",
+            path.display()
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dash_means_stdin() {
+        assert!(is_stdin(Path::new("-")));
+        assert!(!is_stdin(Path::new("./-")));
+        assert!(!is_stdin(Path::new("doc.pdf")));
+    }
+
+    #[test]
+    fn pdfium_path_flag_is_the_explicit_path() {
+        set_pdfium_path(PathBuf::from("/opt/pdfium/7350"));
+        set_pdfium_path(PathBuf::from("/ignored"));
+
+        assert_eq!(
+            explicit_pdfium_path(),
+            Some(PathBuf::from("/opt/pdfium/7350"))
+        );
+    }
+
+    #[test]
+    fn not_found_lists_every_location_tried() {
+        let tried = [
+            "/opt/pdf/libpdfium.so (not found)".to_string(),
+            "system library libpdfium.so (cannot open shared object file)".to_string(),
+        ];
+
+        assert_eq!(
+            not_found_message(&tried),
+            "tried /opt/pdf/libpdfium.so (not found), system library libpdfium.so \
+             (cannot open shared object file); set --pdfium-path or PDF_PDFIUM_PATH to the \
+             library file or its directory"
+        );
+    }
+
+    #[test]
+    fn password_errors_suggest_flag_only_when_missing() {
+        let path = Path::new("invoice.pdf");
+
+        assert_eq!(
+            password_error_message(path, false),
+            "invoice.pdf: PDF is encrypted; supply the password with --password"
+        );
+        assert_eq!(
+            password_error_message(path, true),
+            "invoice.pdf: incorrect password"
+        );
+    }
 }
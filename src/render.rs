@@ -1,12 +1,29 @@
 use crate::error::Error;
 #[cfg(not(test))]
+use crate::icc::IccProfile;
+use crate::json_output::print_json;
+#[cfg(not(test))]
+use crate::json_output::write_json;
+#[cfg(not(test))]
+use crate::layers::warn_unknown_layers;
+#[cfg(not(test))]
+use crate::output_path::{
+    check_output_dir, check_output_file, create_output_dir, create_parent_dir,
+};
 use crate::page_range::divide_pages;
 #[cfg(not(test))]
-use crate::pdfium_init::load_pdfium;
+use crate::page_range::{parse_page_range, parse_page_sequence};
+use crate::pdfium_init::is_stdin;
 #[cfg(not(test))]
-use crate::render_worker::{BoxType, JpegEncoderType, RenderOptions};
+use crate::pdfium_init::{SpooledStdin, forward_pdfium_path, load_document, load_pdfium};
+use crate::render_worker::{BoxType, ManifestEntry, PageError, WorkerResult};
+#[cfg(not(test))]
+use crate::render_worker::{
+    JpegEncoderType, OutputFormat, ProgressMode, RenderOptions, RenderSize, ResampleFilter,
+    output_filename, page_label, sized_output_path,
+};
 use serde::Serialize;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 #[cfg(not(test))]
 use std::process::Command;
 use std::time::Instant;
@@ -16,23 +33,234 @@ struct RenderSummary {
     pages_rendered: u32,
     #[serde(skip_serializing_if = "is_zero")]
     pages_extracted: u32,
+    /// Rendered pages copied from `--cache`, included in `pages_rendered`
+    #[serde(skip_serializing_if = "is_zero")]
+    pages_cached: u32,
+    /// Selected pages left out by `--limit`
+    #[serde(skip_serializing_if = "is_zero")]
+    pages_truncated: u32,
+    /// Failed pages plus whole-worker failures, in every error format
+    failed: u32,
     workers_used: u32,
     elapsed_secs: f64,
+    /// Rendered plus extracted pages per second of `elapsed_secs`, counting
+    /// every `--repeat` iteration
+    pages_per_second: f64,
+    /// Seconds each `--repeat` iteration took, pdfium and document loading
+    /// excluded
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    iteration_secs: Vec<f64>,
+    output_dir: String,
+    /// The multipage TIFF written with `--single-file`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    output_file: Option<String>,
+    /// Every written file, with `--list-outputs`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    output_files: Option<Vec<String>>,
+    /// Only with `--error-format json`, so text-mode output is unchanged
+    #[serde(skip_serializing_if = "Option::is_none")]
+    failed_pages: Option<Vec<PageError>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    worker_errors: Option<Vec<String>>,
+    /// Pages whose worker failed and was respawned with `--retries`
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    retried_pages: Vec<u32>,
+    /// Pages rendered at native size because `--no-upscale` would not
+    /// enlarge them to the requested size, ascending
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pages_not_upscaled: Vec<u32>,
+    /// Pages defining none of the boxes `--box` asks for, rendered with their
+    /// CropBox instead; a warning lists them
+    #[serde(skip_serializing_if = "is_zero")]
+    box_fallbacks: u32,
+    /// Per-file counts when several PDFs are rendered at once
+    #[serde(skip_serializing_if = "Option::is_none")]
+    files: Option<Vec<FileSummary>>,
+}
+
+/// One input's share of a multi-PDF `render` summary.
+#[derive(Serialize)]
+struct FileSummary {
+    pdf: String,
     output_dir: String,
+    pages_rendered: u32,
+    #[serde(skip_serializing_if = "is_zero")]
+    pages_extracted: u32,
+    failed: u32,
+    /// Only with `--error-format json`, like the top-level fields
+    #[serde(skip_serializing_if = "Option::is_none")]
+    failed_pages: Option<Vec<PageError>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    worker_errors: Option<Vec<String>>,
+    /// Why the file could not be rendered at all
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
 }
 
+/// What `--dry-run` prints instead of rendering.
+#[cfg(not(test))]
+#[derive(Serialize)]
+struct DryRunSummary {
+    page_count: u32,
+    #[serde(skip_serializing_if = "is_zero")]
+    pages_truncated: u32,
+    workers_used: u32,
+    /// Each worker's pages, as they would be passed to `render-worker --pages`
+    worker_pages: Vec<String>,
+    output_files: Vec<String>,
+}
+
+/// What `--estimate` prints instead of rendering every page.
+#[cfg(not(test))]
+#[derive(Serialize)]
+struct EstimateSummary {
+    page_count: u32,
+    /// The pages rendered to measure, in page order
+    sample_pages: Vec<u32>,
+    /// Encoded size of each sample page's output, in `sample_pages` order
+    sample_bytes: Vec<u64>,
+    estimated_bytes_per_page: u64,
+    estimated_total_bytes: u64,
+}
+
+/// How many pages `--estimate` renders.
+#[cfg(not(test))]
+const ESTIMATE_SAMPLES: usize = 3;
+
 fn is_zero(v: &u32) -> bool {
     *v == 0
 }
 
+/// How `render` parallelizes work across `--workers`.
+#[derive(Clone, Copy, PartialEq, Eq, clap::ValueEnum, Default)]
+pub enum WorkerMode {
+    /// Spawn `render-worker` subprocesses (crash isolation)
+    #[default]
+    Process,
+    /// Render on threads in this process, each with its own pdfium instance
+    Thread,
+}
+
+/// Parse `--workers`: a count, or `auto` (also `0`) for one worker per CPU
+/// reported by `std::thread::available_parallelism`.
+pub fn parse_workers(s: &str) -> Result<u32, String> {
+    let workers = match s {
+        "auto" => 0,
+        _ => s
+            .parse()
+            .map_err(|_| format!("expected a number or `auto`, got `{s}`"))?,
+    };
+    if workers > 0 {
+        return Ok(workers);
+    }
+    Ok(std::thread::available_parallelism().map_or(1, |cpus| cpus.get() as u32))
+}
+
+/// Environment variable that overrides `--workers` for `render`, e.g.
+/// `PDF_WORKERS=1` where the sandbox does not allow spawning processes.
+pub const WORKERS_ENV: &str = "PDF_WORKERS";
+
+/// The worker count to use: `PDF_WORKERS` (`env`) when set, parsed like
+/// `--workers`, otherwise `workers` from the command line.
+pub fn workers_override(workers: u32, env: Option<&str>) -> Result<u32, Error> {
+    match env {
+        Some(value) => {
+            parse_workers(value).map_err(|e| Error::InvalidArgs(format!("{WORKERS_ENV}: {e}")))
+        }
+        None => Ok(workers),
+    }
+}
+
+/// How `render` reports failed pages.
+#[derive(Clone, Copy, PartialEq, Eq, clap::ValueEnum, Default)]
+pub enum ErrorFormat {
+    /// Print failures on stderr only
+    #[default]
+    Text,
+    /// Also list them in the JSON summary as `failed_pages` and `worker_errors`
+    Json,
+}
+
+/// Pages rendered across all workers, plus what went wrong.
+///
+/// `failed_pages` come from the workers' own reports; `worker_errors` are
+/// failures of a whole worker (crash, bad exit) that can't be tied to a page.
+#[derive(Default)]
+struct RenderTotals {
+    pages_rendered: u32,
+    pages_extracted: u32,
+    pages_cached: u32,
+    failed_pages: Vec<PageError>,
+    worker_errors: Vec<String>,
+    /// Written files in request order: workers get contiguous slices of the
+    /// page list and are collected in order
+    outputs: Vec<ManifestEntry>,
+    retried_pages: Vec<u32>,
+    /// Pages `--no-upscale` kept at native size
+    not_upscaled: Vec<u32>,
+    /// Pages without the `--box` asked for, which kept their CropBox
+    box_fallbacks: Vec<u32>,
+    /// Each `--repeat` iteration's time; `outputs` and the counts are the
+    /// last iteration's
+    iteration_secs: Vec<f64>,
+}
+
+impl RenderTotals {
+    fn succeeded(&self) -> u32 {
+        self.pages_rendered + self.pages_extracted
+    }
+
+    fn failed(&self) -> u32 {
+        (self.failed_pages.len() + self.worker_errors.len()) as u32
+    }
+
+    fn add(&mut self, result: WorkerResult) {
+        self.pages_rendered += result.pages_rendered;
+        self.pages_extracted += result.pages_extracted;
+        self.pages_cached += result.pages_cached;
+        self.failed_pages.extend(result.errors);
+        self.outputs.extend(result.outputs);
+        self.not_upscaled.extend(result.not_upscaled);
+        self.box_fallbacks.extend(result.box_fallbacks);
+    }
+
+    /// Fold in another document's totals (`run_batch`).
+    #[cfg(not(test))]
+    fn merge(&mut self, other: RenderTotals) {
+        self.pages_rendered += other.pages_rendered;
+        self.pages_extracted += other.pages_extracted;
+        self.pages_cached += other.pages_cached;
+        self.failed_pages.extend(other.failed_pages);
+        self.outputs.extend(other.outputs);
+        self.not_upscaled.extend(other.not_upscaled);
+        self.box_fallbacks.extend(other.box_fallbacks);
+        self.worker_errors.extend(other.worker_errors);
+        self.retried_pages.extend(other.retried_pages);
+    }
+}
+
 struct RenderPlan {
     page_list: Vec<u32>,
+    /// Labels for `page_list`, read only with `--label-names`
+    labels: Vec<Option<String>>,
     effective_workers: u32,
+    /// Selected pages dropped by `--limit`
+    pages_truncated: u32,
+}
+
+/// The pages given to one worker process, kept so it can be respawned.
+struct WorkerAssignment {
+    worker: usize,
+    pages: Vec<u32>,
+    /// Request position of the first page (with `--keep-order`)
+    first_sequence: u32,
 }
 
 /// Orchestrate multi-process PDF rendering.
 ///
-/// Reads page count, divides work across workers, spawns `render-worker` subprocesses.
+/// Reads page count, divides work across workers, spawns `render-worker` subprocesses
+/// (or threads with `WorkerMode::Thread`). A `-` path is spooled to a temporary
+/// file first, since the document is opened for planning and again per worker.
 #[cfg(not(test))]
 #[cfg_attr(coverage_nightly, coverage(off))]
 pub fn run(
@@ -40,33 +268,564 @@ pub fn run(
     output_dir: &Path,
     pages: Option<&str>,
     num_workers: u32,
+    mode: WorkerMode,
+    error_format: ErrorFormat,
     opts: RenderOptions,
 ) -> Result<(), Error> {
+    check_worker_mode(mode, &opts)?;
     let start = Instant::now();
-    let plan = build_render_plan(pdf_path, pages, num_workers)?;
-    std::fs::create_dir_all(output_dir)?;
-
-    eprintln!(
-        "Rendering {} pages from {} with {} workers",
-        plan.page_list.len(),
-        pdf_path.display(),
-        plan.effective_workers
-    );
+    let (totals, plan) = render_document(pdf_path, output_dir, pages, num_workers, mode, &opts)?;
+
+    if !opts.quiet {
+        print_summary(
+            &totals,
+            &plan,
+            start,
+            output_dir,
+            None,
+            error_format,
+            opts.list_outputs
+                .then(|| output_files(&totals.outputs, opts.keep_order)),
+        );
+    }
+    if let Some(manifest) = &opts.manifest {
+        write_manifest(manifest, &totals.outputs)?;
+    }
+    check_errors(&totals, opts.strict)
+}
+
+/// Render one document into `output_dir` for `run` or `run_batch`, leaving
+/// the summary and error reporting to the caller.
+#[cfg(not(test))]
+#[cfg_attr(coverage_nightly, coverage(off))]
+fn render_document(
+    pdf_path: &Path,
+    output_dir: &Path,
+    pages: Option<&str>,
+    num_workers: u32,
+    mode: WorkerMode,
+    opts: &RenderOptions,
+) -> Result<(RenderTotals, RenderPlan), Error> {
+    check_output_dir(output_dir)?;
+    let spool = spool_if_stdin(pdf_path)?;
+    let input_path = spool.as_ref().map_or(pdf_path, SpooledStdin::path);
+    let plan = build_render_plan(input_path, pages, num_workers, opts)?;
+    // The page-timeout watchdog exits its process, retries respawn one and the
+    // memory limit is set on one, so each always runs in a worker.
+    let needs_worker =
+        opts.page_timeout.is_some() || opts.retries > 0 || opts.worker_mem_limit.is_some();
+    let single_process = plan.effective_workers <= 1 && !needs_worker;
+    if opts.repeat > 1 && !single_process {
+        return Err(Error::InvalidArgs(
+            "--repeat times a single process: use --workers 1, without --page-timeout, \
+             --retries or --worker-mem-limit"
+                .into(),
+        ));
+    }
+    if !opts.overwrite {
+        let conflicts = existing_outputs(input_path, output_dir, &plan, opts);
+        check_no_overwrite(&conflicts)?;
+    }
+    create_output_dir(output_dir)?;
+
+    if opts.progress == ProgressMode::Human {
+        eprintln!(
+            "Rendering {} pages from {} with {} workers",
+            plan.page_list.len(),
+            pdf_path.display(),
+            plan.effective_workers
+        );
+    }
 
-    let (rendered, extracted, errors) = if plan.effective_workers <= 1 {
-        run_single_process(pdf_path, output_dir, &plan.page_list, &opts)?
+    let totals = if single_process {
+        run_single_process(input_path, output_dir, &plan.page_list, opts)?
+    } else if mode == WorkerMode::Thread {
+        run_multi_thread(input_path, output_dir, &plan, opts)
     } else {
-        run_multi_process(pdf_path, output_dir, &plan, &opts)?
+        run_multi_process(input_path, output_dir, &plan, opts)?
     };
+    warn_box_fallbacks(pdf_path, opts.box_type, &totals.box_fallbacks);
+    Ok((totals, plan))
+}
 
-    print_summary(
-        rendered,
-        extracted,
-        plan.effective_workers,
-        start,
-        output_dir,
-    );
-    check_errors(errors)
+/// `run` for several PDFs, one after another: each renders with its own
+/// workers into a subdirectory of `output_dir` named after its file stem.
+///
+/// One summary covers every file, with per-file counts in `files`. A file
+/// that cannot be rendered at all is recorded there and the rest still run,
+/// unless `--strict` is set.
+#[cfg(not(test))]
+#[cfg_attr(coverage_nightly, coverage(off))]
+pub fn run_batch(
+    pdf_paths: &[PathBuf],
+    output_dir: &Path,
+    pages: Option<&str>,
+    num_workers: u32,
+    mode: WorkerMode,
+    error_format: ErrorFormat,
+    opts: RenderOptions,
+) -> Result<(), Error> {
+    check_worker_mode(mode, &opts)?;
+    if opts.manifest.is_some() {
+        return Err(Error::InvalidArgs(
+            "--manifest describes a single PDF; render the files one at a time".into(),
+        ));
+    }
+    let output_dirs = batch_output_dirs(pdf_paths, output_dir)?;
+    check_output_dir(output_dir)?;
+
+    let start = Instant::now();
+    let json_errors = error_format == ErrorFormat::Json;
+    let mut totals = RenderTotals::default();
+    let mut files = Vec::new();
+    let mut output_files_list = Vec::new();
+    let mut plan = RenderPlan {
+        page_list: Vec::new(),
+        labels: Vec::new(),
+        effective_workers: 0,
+        pages_truncated: 0,
+    };
+    for (pdf_path, file_dir) in pdf_paths.iter().zip(&output_dirs) {
+        let mut file = FileSummary {
+            pdf: pdf_path.display().to_string(),
+            output_dir: file_dir.display().to_string(),
+            pages_rendered: 0,
+            pages_extracted: 0,
+            failed: 0,
+            failed_pages: None,
+            worker_errors: None,
+            error: None,
+        };
+        match render_document(pdf_path, file_dir, pages, num_workers, mode, &opts) {
+            Ok((file_totals, file_plan)) => {
+                for err in &file_totals.failed_pages {
+                    eprintln!("error: {}: {err}", pdf_path.display());
+                }
+                for err in &file_totals.worker_errors {
+                    eprintln!("error: {}: {err}", pdf_path.display());
+                }
+                file.pages_rendered = file_totals.pages_rendered;
+                file.pages_extracted = file_totals.pages_extracted;
+                file.failed = file_totals.failed();
+                file.failed_pages = json_errors.then(|| file_totals.failed_pages.clone());
+                file.worker_errors = json_errors.then(|| file_totals.worker_errors.clone());
+                if opts.list_outputs {
+                    output_files_list.extend(output_files(&file_totals.outputs, opts.keep_order));
+                }
+                plan.effective_workers = plan.effective_workers.max(file_plan.effective_workers);
+                plan.pages_truncated += file_plan.pages_truncated;
+                totals.merge(file_totals);
+            }
+            Err(e) if opts.strict => return Err(e),
+            Err(e) => {
+                eprintln!("error: {}: {e}", pdf_path.display());
+                totals
+                    .worker_errors
+                    .push(format!("{}: {e}", pdf_path.display()));
+                file.failed = 1;
+                file.error = Some(e.to_string());
+            }
+        }
+        let failed = file.failed > 0;
+        files.push(file);
+        if opts.strict && failed {
+            break;
+        }
+    }
+
+    if !opts.quiet {
+        let mut summary = render_summary(
+            &totals,
+            &plan,
+            start,
+            output_dir,
+            None,
+            error_format,
+            opts.list_outputs.then_some(output_files_list),
+        );
+        // Page numbers are only meaningful per file.
+        summary.failed_pages = None;
+        summary.worker_errors = None;
+        summary.files = Some(files);
+        print_json("render", &summary);
+    }
+    render_outcome(totals.succeeded(), totals.failed(), opts.strict)
+}
+
+/// The subdirectory of `output_dir` each PDF renders into: its file stem, or
+/// `stdin` for `-`. Two inputs with the same stem are rejected.
+pub fn batch_output_dirs(pdf_paths: &[PathBuf], output_dir: &Path) -> Result<Vec<PathBuf>, Error> {
+    let mut dirs: Vec<PathBuf> = Vec::with_capacity(pdf_paths.len());
+    for (index, pdf_path) in pdf_paths.iter().enumerate() {
+        let stem = if is_stdin(pdf_path) {
+            "stdin".into()
+        } else {
+            pdf_path
+                .file_stem()
+                .map(|stem| stem.to_string_lossy())
+                .unwrap_or_default()
+        };
+        let dir = output_dir.join(&*stem);
+        if let Some(other) = dirs.iter().position(|existing| *existing == dir) {
+            return Err(Error::InvalidArgs(format!(
+                "{} and {} would both render into {}",
+                pdf_paths[other].display(),
+                pdf_paths[index].display(),
+                dir.display()
+            )));
+        }
+        dirs.push(dir);
+    }
+    Ok(dirs)
+}
+
+/// Plan a render like `run` and print the page count, each worker's pages and
+/// the output files as JSON, without rendering or creating `output_dir`.
+#[cfg(not(test))]
+#[cfg_attr(coverage_nightly, coverage(off))]
+pub fn dry_run(
+    pdf_path: &Path,
+    output_dir: &Path,
+    pages: Option<&str>,
+    num_workers: u32,
+    mode: WorkerMode,
+    opts: RenderOptions,
+) -> Result<(), Error> {
+    check_worker_mode(mode, &opts)?;
+    check_output_dir(output_dir)?;
+
+    let spool = spool_if_stdin(pdf_path)?;
+    let input_path = spool.as_ref().map_or(pdf_path, SpooledStdin::path);
+    let plan = build_render_plan(input_path, pages, num_workers, &opts)?;
+    let summary = DryRunSummary {
+        page_count: plan.page_list.len() as u32,
+        pages_truncated: plan.pages_truncated,
+        workers_used: plan.effective_workers,
+        worker_pages: worker_page_lists(&plan.page_list, plan.effective_workers),
+        output_files: planned_outputs(input_path, output_dir, &plan, &opts)
+            .iter()
+            .map(|path| path.display().to_string())
+            .collect(),
+    };
+    print_json("render-plan", &summary);
+    Ok(())
+}
+
+/// Render a sample of the selected pages with the chosen settings and print
+/// their encoded sizes and the total extrapolated to every selected page.
+///
+/// The samples go to a temporary directory that is removed afterwards, so
+/// nothing is written to `-o`.
+#[cfg(not(test))]
+#[cfg_attr(coverage_nightly, coverage(off))]
+pub fn estimate(
+    pdf_path: &Path,
+    pages: Option<&str>,
+    mut opts: RenderOptions,
+) -> Result<(), Error> {
+    opts.progress = ProgressMode::None;
+    let spool = spool_if_stdin(pdf_path)?;
+    let input_path = spool.as_ref().map_or(pdf_path, SpooledStdin::path);
+    let plan = build_render_plan(input_path, pages, 1, &opts)?;
+    let samples = sample_pages(&plan.page_list, ESTIMATE_SAMPLES);
+
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_nanos());
+    let scratch_dir =
+        std::env::temp_dir().join(format!("pdf-estimate-{}-{nanos}", std::process::id()));
+    std::fs::create_dir(&scratch_dir)?;
+    let result = crate::render_worker::render_pages(input_path, &scratch_dir, &samples, 1, &opts);
+    let _ = std::fs::remove_dir_all(&scratch_dir);
+    let result = result?;
+
+    for err in &result.errors {
+        eprintln!("error: {err}");
+    }
+    if result.outputs.is_empty() {
+        return Err(Error::Render("no sample page could be rendered".into()));
+    }
+    let sample_bytes: Vec<u64> = result.outputs.iter().map(|entry| entry.bytes).collect();
+    let page_count = plan.page_list.len() as u32;
+    let summary = EstimateSummary {
+        page_count,
+        sample_pages: result.outputs.iter().map(|entry| entry.page).collect(),
+        estimated_bytes_per_page: extrapolate(&sample_bytes, 1),
+        estimated_total_bytes: extrapolate(&sample_bytes, page_count),
+        sample_bytes,
+    };
+    print_json("render-estimate", &summary);
+    Ok(())
+}
+
+/// Up to `count` pages spread evenly over `page_list`, first and last
+/// included, so the same selection always samples the same pages.
+pub fn sample_pages(page_list: &[u32], count: usize) -> Vec<u32> {
+    if page_list.len() <= count {
+        return page_list.to_vec();
+    }
+    if count < 2 {
+        return page_list[..count].to_vec();
+    }
+    let last = page_list.len() - 1;
+    (0..count)
+        .map(|i| page_list[i * last / (count - 1)])
+        .collect()
+}
+
+/// The mean of `sample_bytes` scaled to `page_count` pages, rounded.
+pub fn extrapolate(sample_bytes: &[u64], page_count: u32) -> u64 {
+    if sample_bytes.is_empty() {
+        return 0;
+    }
+    let total: u64 = sample_bytes.iter().sum();
+    (total as f64 * f64::from(page_count) / sample_bytes.len() as f64).round() as u64
+}
+
+#[cfg(not(test))]
+fn check_worker_mode(mode: WorkerMode, opts: &RenderOptions) -> Result<(), Error> {
+    if opts.page_timeout.is_some() && mode == WorkerMode::Thread {
+        return Err(Error::InvalidArgs(
+            "--page-timeout requires --mode process: a stuck page can only be stopped by \
+             killing its worker process"
+                .into(),
+        ));
+    }
+    if opts.retries > 0 && mode == WorkerMode::Thread {
+        return Err(Error::InvalidArgs(
+            "--retries requires --mode process: only a worker process can be respawned".into(),
+        ));
+    }
+    if opts.worker_mem_limit.is_some() && mode == WorkerMode::Thread {
+        return Err(Error::InvalidArgs(
+            "--worker-mem-limit requires --mode process: threads share this process's memory"
+                .into(),
+        ));
+    }
+    if opts.max_inflight.is_some() && mode == WorkerMode::Thread {
+        return Err(Error::InvalidArgs(
+            "--max-inflight requires --mode process: it limits running worker processes".into(),
+        ));
+    }
+    #[cfg(not(unix))]
+    if opts.worker_mem_limit.is_some() {
+        eprintln!("warning: --worker-mem-limit is only enforced on Unix; ignoring it");
+    }
+    Ok(())
+}
+
+/// Render all selected pages into one multipage TIFF (`--single-file`).
+///
+/// Frames have to be appended to one file in page order, so this always runs
+/// in a single process; `num_workers` only decides whether to warn about that.
+#[cfg(not(test))]
+#[cfg_attr(coverage_nightly, coverage(off))]
+pub fn run_single_file(
+    pdf_path: &Path,
+    output_file: &Path,
+    pages: Option<&str>,
+    num_workers: u32,
+    error_format: ErrorFormat,
+    opts: RenderOptions,
+) -> Result<(), Error> {
+    if opts.format != OutputFormat::Tiff {
+        return Err(Error::InvalidArgs(
+            "--single-file writes a multipage TIFF and requires --format tiff".into(),
+        ));
+    }
+    if opts.page_timeout.is_some() {
+        return Err(Error::InvalidArgs(
+            "--page-timeout needs worker processes and cannot be combined with --single-file"
+                .into(),
+        ));
+    }
+    if opts.manifest.is_some() {
+        return Err(Error::InvalidArgs(
+            "--manifest lists per-page files and cannot be combined with --single-file".into(),
+        ));
+    }
+    if opts.retries > 0 {
+        return Err(Error::InvalidArgs(
+            "--retries needs worker processes and cannot be combined with --single-file".into(),
+        ));
+    }
+    if opts.worker_mem_limit.is_some() {
+        return Err(Error::InvalidArgs(
+            "--worker-mem-limit needs worker processes and cannot be combined with --single-file"
+                .into(),
+        ));
+    }
+    check_output_file(output_file)?;
+    if num_workers > 1 {
+        eprintln!(
+            "warning: --single-file renders in a single process; ignoring --workers {num_workers}"
+        );
+    }
+
+    let start = Instant::now();
+    let spool = spool_if_stdin(pdf_path)?;
+    let input_path = spool.as_ref().map_or(pdf_path, SpooledStdin::path);
+    let plan = build_render_plan(input_path, pages, 1, &opts)?;
+    if !opts.overwrite && output_file.exists() {
+        check_no_overwrite(&[output_file.to_path_buf()])?;
+    }
+    create_parent_dir(output_file)?;
+    let output_dir = output_file.parent().unwrap_or(Path::new(""));
+
+    if opts.progress == ProgressMode::Human {
+        eprintln!(
+            "Rendering {} pages from {} into {}",
+            plan.page_list.len(),
+            pdf_path.display(),
+            output_file.display()
+        );
+    }
+
+    let result = crate::render_worker::render_pages_to_tiff(
+        input_path,
+        &plan.page_list,
+        output_file,
+        &opts,
+    )?;
+    let mut totals = RenderTotals::default();
+    totals.add(result);
+    warn_box_fallbacks(pdf_path, opts.box_type, &totals.box_fallbacks);
+
+    if !opts.quiet {
+        print_summary(
+            &totals,
+            &plan,
+            start,
+            output_dir,
+            Some(output_file),
+            error_format,
+            None,
+        );
+    }
+    check_errors(&totals, opts.strict)
+}
+
+/// Render exactly one page to stdout, bypassing workers and the output directory.
+#[cfg(not(test))]
+#[cfg_attr(coverage_nightly, coverage(off))]
+pub fn run_to_stdout(
+    pdf_path: &Path,
+    pages: Option<&str>,
+    opts: RenderOptions,
+) -> Result<(), Error> {
+    let spool = spool_if_stdin(pdf_path)?;
+    let pdf_path = spool.as_ref().map_or(pdf_path, SpooledStdin::path);
+    let plan = build_render_plan(pdf_path, pages, 1, &opts)?;
+    let page_num = single_page(&plan.page_list)?;
+    crate::render_worker::render_page_to_stdout(pdf_path, page_num, &opts)
+}
+
+#[cfg(not(test))]
+#[cfg_attr(coverage_nightly, coverage(off))]
+fn spool_if_stdin(pdf_path: &Path) -> Result<Option<SpooledStdin>, Error> {
+    if is_stdin(pdf_path) {
+        SpooledStdin::create().map(Some)
+    } else {
+        Ok(None)
+    }
+}
+
+/// Output paths for `pages` that already exist in `output_dir`.
+#[cfg(not(test))]
+#[cfg_attr(coverage_nightly, coverage(off))]
+fn existing_outputs(
+    pdf_path: &Path,
+    output_dir: &Path,
+    plan: &RenderPlan,
+    opts: &RenderOptions,
+) -> Vec<PathBuf> {
+    planned_outputs(pdf_path, output_dir, plan, opts)
+        .into_iter()
+        .filter(|path| path.exists())
+        .collect()
+}
+
+/// Output path of every planned file, in request order; with `--widths`, one
+/// per width for each page.
+#[cfg(not(test))]
+#[cfg_attr(coverage_nightly, coverage(off))]
+fn planned_outputs(
+    pdf_path: &Path,
+    output_dir: &Path,
+    plan: &RenderPlan,
+    opts: &RenderOptions,
+) -> Vec<PathBuf> {
+    let stem = pdf_path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy())
+        .unwrap_or_default();
+    // Workers number `--keep-order` output by position in the full request.
+    (1..)
+        .zip(&plan.page_list)
+        .map(|(sequence, &page)| {
+            let label = plan.labels.get(sequence as usize - 1).cloned().flatten();
+            output_dir.join(output_filename(
+                &opts.name_template,
+                &stem,
+                page,
+                label.as_deref(),
+                opts.keep_order.then_some(sequence),
+                opts.format,
+            ))
+        })
+        .flat_map(|path| {
+            if opts.widths.is_empty() {
+                vec![path]
+            } else {
+                opts.widths
+                    .iter()
+                    .map(|&width| sized_output_path(&path, width))
+                    .collect()
+            }
+        })
+        .collect()
+}
+
+/// Write the `--manifest` JSON array, including for partially failed runs.
+#[cfg(not(test))]
+#[cfg_attr(coverage_nightly, coverage(off))]
+fn write_manifest(path: &Path, outputs: &[ManifestEntry]) -> Result<(), Error> {
+    write_json(path, "manifest", &outputs)?;
+    Ok(())
+}
+
+/// Refuse to start when `--no-overwrite` found existing output files.
+fn check_no_overwrite(conflicts: &[PathBuf]) -> Result<(), Error> {
+    const LISTED: usize = 3;
+    if conflicts.is_empty() {
+        return Ok(());
+    }
+    let mut listed: Vec<_> = conflicts
+        .iter()
+        .take(LISTED)
+        .map(|path| path.display().to_string())
+        .collect();
+    if conflicts.len() > LISTED {
+        listed.push(format!("and {} more", conflicts.len() - LISTED));
+    }
+    Err(Error::Io(std::io::Error::new(
+        std::io::ErrorKind::AlreadyExists,
+        format!(
+            "--no-overwrite: {} output files already exist: {}",
+            conflicts.len(),
+            listed.join(", ")
+        ),
+    )))
+}
+
+fn single_page(pages: &[u32]) -> Result<u32, Error> {
+    match pages {
+        [page] => Ok(*page),
+        _ => Err(Error::InvalidArgs(format!(
+            "--stdout requires exactly one page, got {}",
+            pages.len()
+        ))),
+    }
 }
 
 #[cfg(not(test))]
@@ -75,29 +834,51 @@ fn build_render_plan(
     pdf_path: &Path,
     pages: Option<&str>,
     num_workers: u32,
+    opts: &RenderOptions,
 ) -> Result<RenderPlan, Error> {
     let pdfium = load_pdfium()?;
-    let document = pdfium
-        .load_pdf_from_file(pdf_path, None)
-        .map_err(|e| Error::PdfInvalid(format!("{}: {e}", pdf_path.display())))?;
+    let document = load_document(&pdfium, pdf_path, opts.password.as_deref())?;
     let total_pages = document.pages().len() as u32;
+    warn_unknown_layers(&pdfium, pdf_path, opts.password.as_deref(), &opts.layers)?;
 
     if total_pages == 0 {
         return Err(Error::PdfInvalid("PDF has no pages".into()));
     }
 
-    let page_list = match pages {
-        Some(range_str) => crate::page_range::parse_page_range(range_str, total_pages)?,
+    let mut page_list = match pages {
+        Some(range_str) if opts.keep_order => parse_page_sequence(range_str, total_pages)?,
+        Some(range_str) => parse_page_range(range_str, total_pages)?,
         None => (1..=total_pages).collect(),
     };
+    let pages_truncated = apply_limit(&mut page_list, opts.limit);
 
+    let labels = if opts.label_names {
+        page_list
+            .iter()
+            .map(|&page| page_label(&document, page))
+            .collect()
+    } else {
+        Vec::new()
+    };
     let effective_workers = num_workers.min(page_list.len() as u32);
     Ok(RenderPlan {
         page_list,
+        labels,
         effective_workers,
+        pages_truncated,
     })
 }
 
+/// Keep the first `limit` pages of `page_list`, returning how many were dropped.
+fn apply_limit(page_list: &mut Vec<u32>, limit: Option<u32>) -> u32 {
+    let Some(limit) = limit else {
+        return 0;
+    };
+    let dropped = page_list.len().saturating_sub(limit as usize);
+    page_list.truncate(limit as usize);
+    dropped as u32
+}
+
 #[cfg(not(test))]
 #[cfg_attr(coverage_nightly, coverage(off))]
 fn run_single_process(
@@ -105,9 +886,14 @@ fn run_single_process(
     output_dir: &Path,
     pages: &[u32],
     opts: &RenderOptions,
-) -> Result<(u32, u32, Vec<String>), Error> {
-    let result = crate::render_worker::render_pages(pdf_path, output_dir, pages, opts)?;
-    Ok((result.pages_rendered, result.pages_extracted, result.errors))
+) -> Result<RenderTotals, Error> {
+    let mut result = crate::render_worker::render_pages(pdf_path, output_dir, pages, 1, opts)?;
+    let mut totals = RenderTotals {
+        iteration_secs: std::mem::take(&mut result.iteration_secs),
+        ..RenderTotals::default()
+    };
+    totals.add(result);
+    Ok(totals)
 }
 
 #[cfg(not(test))]
@@ -117,60 +903,341 @@ fn run_multi_process(
     output_dir: &Path,
     plan: &RenderPlan,
     opts: &RenderOptions,
-) -> Result<(u32, u32, Vec<String>), Error> {
-    let ranges = divide_pages(plan.page_list.len() as u32, plan.effective_workers);
+) -> Result<RenderTotals, Error> {
     let current_exe = std::env::current_exe()?;
 
-    let children: Vec<_> = ranges
-        .iter()
-        .map(|&(start, end)| {
-            let worker_pages = &plan.page_list[(start as usize - 1)..=(end as usize - 1)];
-            let pages_str = format_page_list(worker_pages);
-            spawn_worker(&current_exe, pdf_path, output_dir, &pages_str, opts)
-        })
-        .collect::<Result<Vec<_>, _>>()?;
+    let assignments = worker_chunks(&plan.page_list, plan.effective_workers)
+        .into_iter()
+        .enumerate()
+        .map(|(worker, (first_sequence, pages))| WorkerAssignment {
+            worker,
+            pages: pages.to_vec(),
+            first_sequence,
+        })
+        .collect();
+
+    let max_inflight = opts.max_inflight.map_or(usize::MAX, |n| n as usize);
+    let mut any_started = false;
+    let mut spawn_error = None;
+    let totals = run_with_retries(assignments, opts.retries, |batch| {
+        // A worker that cannot start (e.g. under a tiny --worker-mem-limit)
+        // fails like one that crashed, rather than aborting the whole render.
+        collect_worker_results(batch.len(), max_inflight, opts.strict, |index| {
+            let assignment = &batch[index];
+            let child = spawn_worker(
+                &current_exe,
+                pdf_path,
+                output_dir,
+                &format_page_list(&assignment.pages),
+                assignment.first_sequence,
+                opts,
+            )
+            .map_err(|e| format!("failed to start: {e}"));
+            match &child {
+                Ok(_) => any_started = true,
+                Err(e) => {
+                    spawn_error.get_or_insert_with(|| e.clone());
+                }
+            }
+            child
+        })
+    })?;
+
+    // No process could be started at all, as in sandboxes that forbid
+    // fork/exec: render here instead. Under --worker-mem-limit a worker that
+    // cannot start is the limit at work, so it stays a failure.
+    if let Some(e) = spawn_error
+        && !any_started
+        && opts.worker_mem_limit.is_none()
+    {
+        eprintln!("warning: worker processes {e}; rendering in this process instead");
+        return run_single_process(pdf_path, output_dir, &plan.page_list, opts);
+    }
+    Ok(totals)
+}
+
+/// Run `assignments` through `run_batch`, respawning failed workers for the
+/// same pages up to `retries` times.
+///
+/// `run_batch` returns one result per assignment, in order: the worker's
+/// report, or why it failed. A failed attempt's partial report is discarded,
+/// since its pages are all rendered again.
+fn run_with_retries(
+    assignments: Vec<WorkerAssignment>,
+    retries: u32,
+    mut run_batch: impl FnMut(&[WorkerAssignment]) -> Result<Vec<Result<WorkerOutput, String>>, Error>,
+) -> Result<RenderTotals, Error> {
+    let mut totals = RenderTotals::default();
+    let mut pending = assignments;
+
+    for attempt in 0..=retries {
+        let results = run_batch(&pending)?;
+        let mut failed = Vec::new();
+        for (assignment, result) in pending.into_iter().zip(results) {
+            match result {
+                Ok(result) => totals.add(result.into()),
+                Err(e) if attempt < retries => {
+                    eprintln!(
+                        "warning: worker {}: {e}; retrying pages {}",
+                        assignment.worker,
+                        format_page_list(&assignment.pages)
+                    );
+                    failed.push(assignment);
+                }
+                Err(e) => totals
+                    .worker_errors
+                    .push(format!("worker {}: {e}", assignment.worker)),
+            }
+        }
+
+        // Later attempts retry a subset of the first retry's pages.
+        if attempt == 0 {
+            for assignment in &failed {
+                totals.retried_pages.extend(&assignment.pages);
+            }
+        }
+        if failed.is_empty() {
+            break;
+        }
+        pending = failed;
+    }
+
+    Ok(totals)
+}
+
+/// Render on scoped threads, one per worker chunk.
+///
+/// pdfium objects are not `Send`, so every thread loads its own pdfium and
+/// document via `render_pages`; results are merged directly without JSON.
+#[cfg(not(test))]
+#[cfg_attr(coverage_nightly, coverage(off))]
+fn run_multi_thread(
+    pdf_path: &Path,
+    output_dir: &Path,
+    plan: &RenderPlan,
+    opts: &RenderOptions,
+) -> RenderTotals {
+    std::thread::scope(|scope| {
+        let handles: Vec<_> = worker_chunks(&plan.page_list, plan.effective_workers)
+            .into_iter()
+            .map(|(start, worker_pages)| {
+                scope.spawn(move || {
+                    crate::render_worker::render_pages(
+                        pdf_path,
+                        output_dir,
+                        worker_pages,
+                        start,
+                        opts,
+                    )
+                })
+            })
+            .collect();
+
+        let mut totals = RenderTotals::default();
+        for (i, handle) in handles.into_iter().enumerate() {
+            match handle.join() {
+                Ok(Ok(result)) => totals.add(result),
+                Ok(Err(e)) => totals.worker_errors.push(format!("worker {i}: {e}")),
+                Err(_) => totals
+                    .worker_errors
+                    .push(format!("worker {i}: thread panicked")),
+            }
+        }
+        totals
+    })
+}
+
+/// Spawn one worker per assignment, at most `max_inflight` at a time, and
+/// collect their reports in assignment order. Workers are polled, so a slot
+/// is refilled as soon as any worker exits. With `strict`, the first failure
+/// kills the running workers and the rest are never started.
+#[cfg(not(test))]
+#[cfg_attr(coverage_nightly, coverage(off))]
+fn collect_worker_results(
+    count: usize,
+    max_inflight: usize,
+    strict: bool,
+    mut spawn: impl FnMut(usize) -> Result<std::process::Child, String>,
+) -> Result<Vec<Result<WorkerOutput, String>>, Error> {
+    const POLL: std::time::Duration = std::time::Duration::from_millis(20);
+
+    let mut results: Vec<Option<Result<WorkerOutput, String>>> = (0..count).map(|_| None).collect();
+    let mut running = Vec::new();
+    let mut next = 0;
+    let mut failed = false;
+
+    loop {
+        while !failed && next < count && running.len() < max_inflight {
+            match spawn(next) {
+                Ok(child) => running.push((next, RunningWorker::new(child))),
+                Err(e) => {
+                    results[next] = Some(Err(e));
+                    failed = strict;
+                }
+            }
+            next += 1;
+        }
+        if running.is_empty() && (failed || next == count) {
+            break;
+        }
+        if failed {
+            for (index, mut worker) in running.drain(..) {
+                let _ = worker.child.kill();
+                let _ = worker.child.wait();
+                results[index] = Some(Err("stopped after another worker failed (--strict)".into()));
+            }
+            continue;
+        }
+
+        let mut still_running = Vec::new();
+        for (index, mut worker) in running.drain(..) {
+            match worker.child.try_wait()? {
+                Some(status) => {
+                    let (stdout, stderr) = worker.output();
+                    let result = worker_result(status, &stdout, &stderr);
+                    failed |=
+                        strict && !result.as_ref().is_ok_and(|result| result.errors.is_empty());
+                    results[index] = Some(result);
+                }
+                None => still_running.push((index, worker)),
+            }
+        }
+        // A freed slot is refilled straight away.
+        let refill = next < count && still_running.len() < max_inflight;
+        running = still_running;
+        if !running.is_empty() && !failed && !refill {
+            std::thread::sleep(POLL);
+        }
+    }
+
+    for result in &mut results[next..] {
+        *result = Some(Err(
+            "not started after another worker failed (--strict)".into()
+        ));
+    }
+    Ok(results.into_iter().map(Option::unwrap).collect())
+}
+
+/// A worker process whose pipes are drained on threads, so it never blocks
+/// on a full pipe while the parent polls it.
+#[cfg(not(test))]
+struct RunningWorker {
+    child: std::process::Child,
+    stdout: Option<std::thread::JoinHandle<Vec<u8>>>,
+    stderr: Option<std::thread::JoinHandle<Vec<u8>>>,
+}
+
+#[cfg(not(test))]
+#[cfg_attr(coverage_nightly, coverage(off))]
+impl RunningWorker {
+    fn new(mut child: std::process::Child) -> Self {
+        fn drain(
+            pipe: Option<impl std::io::Read + Send + 'static>,
+        ) -> Option<std::thread::JoinHandle<Vec<u8>>> {
+            pipe.map(|mut pipe| {
+                std::thread::spawn(move || {
+                    let mut bytes = Vec::new();
+                    let _ = pipe.read_to_end(&mut bytes);
+                    bytes
+                })
+            })
+        }
+        let stdout = drain(child.stdout.take());
+        let stderr = drain(child.stderr.take());
+        Self {
+            child,
+            stdout,
+            stderr,
+        }
+    }
+
+    /// Everything the exited worker wrote to stdout and stderr.
+    fn output(&mut self) -> (Vec<u8>, Vec<u8>) {
+        let join = |handle: Option<std::thread::JoinHandle<Vec<u8>>>| {
+            handle
+                .and_then(|handle| handle.join().ok())
+                .unwrap_or_default()
+        };
+        (join(self.stdout.take()), join(self.stderr.take()))
+    }
+}
+
+/// A finished worker's report, or why it failed.
+#[cfg(not(test))]
+fn worker_result(
+    status: std::process::ExitStatus,
+    stdout: &[u8],
+    stderr: &[u8],
+) -> Result<WorkerOutput, String> {
+    let result = serde_json::from_str::<WorkerOutput>(&String::from_utf8_lossy(stdout));
+    // A worker stopped by the page-timeout watchdog exits non-zero but has
+    // already reported its pages; only unexplained failures are worker errors.
+    let reported = result
+        .as_ref()
+        .is_ok_and(|result| !result.errors.is_empty());
 
-    collect_worker_results(children)
+    if !status.success() && !reported {
+        let stderr = String::from_utf8_lossy(stderr);
+        Err(format!("exit {status}: {stderr}"))
+    } else {
+        Ok(result.unwrap_or_default())
+    }
 }
 
+/// Name the pages that lacked the box `--box` asked for and kept their
+/// CropBox, once per document rather than once per page.
 #[cfg(not(test))]
-#[cfg_attr(coverage_nightly, coverage(off))]
-fn collect_worker_results(
-    children: Vec<std::process::Child>,
-) -> Result<(u32, u32, Vec<String>), Error> {
-    let mut total_rendered = 0u32;
-    let mut total_extracted = 0u32;
-    let mut all_errors = Vec::new();
-
-    for (i, child) in children.into_iter().enumerate() {
-        let output = child.wait_with_output()?;
-        if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            all_errors.push(format!("worker {i}: exit {}: {stderr}", output.status));
-        }
+fn warn_box_fallbacks(pdf_path: &Path, box_type: BoxType, pages: &[u32]) {
+    if let Some(message) = box_fallback_message(box_type, pages) {
+        eprintln!("warning: {}: {message}", pdf_path.display());
+    }
+}
 
-        let stdout = String::from_utf8_lossy(&output.stdout);
-        if let Ok(result) = serde_json::from_str::<WorkerOutput>(&stdout) {
-            total_rendered += result.pages_rendered;
-            total_extracted += result.pages_extracted;
-            all_errors.extend(result.errors);
-        }
+/// The `warn_box_fallbacks` message for `pages`, in any order; `None` when
+/// every page had its box.
+fn box_fallback_message(box_type: BoxType, pages: &[u32]) -> Option<String> {
+    let missing = match box_type {
+        BoxType::Crop => return None,
+        BoxType::Bleed => "BleedBox",
+        BoxType::Auto => "TrimBox or BleedBox",
+    };
+    if pages.is_empty() {
+        return None;
     }
+    let mut pages = pages.to_vec();
+    pages.sort_unstable();
+    let noun = if pages.len() == 1 { "page" } else { "pages" };
+    Some(format!(
+        "no {missing} on {noun} {}; rendered the CropBox instead",
+        format_page_list(&pages)
+    ))
+}
 
-    Ok((total_rendered, total_extracted, all_errors))
+/// Print every failure and turn them into `PartialRender` when at least one
+/// page made it, `Render` when none did or `strict` is set.
+fn check_errors(totals: &RenderTotals, strict: bool) -> Result<(), Error> {
+    for err in &totals.failed_pages {
+        eprintln!("error: {err}");
+    }
+    for err in &totals.worker_errors {
+        eprintln!("error: {err}");
+    }
+    render_outcome(totals.succeeded(), totals.failed(), strict)
 }
 
-fn check_errors(errors: Vec<String>) -> Result<(), Error> {
-    if errors.is_empty() {
+/// `PartialRender` when some pages made it, `Render` when none did or
+/// `strict` is set; `Ok` without failures.
+fn render_outcome(succeeded: u32, failed: u32, strict: bool) -> Result<(), Error> {
+    if failed == 0 {
         return Ok(());
     }
-    for err in &errors {
-        eprintln!("error: {err}");
+    match succeeded {
+        _ if strict => Err(Error::Render(
+            "stopped at the first error (--strict)".into(),
+        )),
+        0 => Err(Error::Render(format!("{failed} errors during rendering"))),
+        succeeded => Err(Error::PartialRender { succeeded, failed }),
     }
-    Err(Error::Render(format!(
-        "{} errors during rendering",
-        errors.len()
-    )))
 }
 
 #[cfg(not(test))]
@@ -180,11 +1247,13 @@ fn spawn_worker(
     pdf_path: &Path,
     output_dir: &Path,
     pages: &str,
+    first_sequence: u32,
     opts: &RenderOptions,
 ) -> Result<std::process::Child, Error> {
     let box_str = match opts.box_type {
         BoxType::Crop => "crop",
         BoxType::Bleed => "bleed",
+        BoxType::Auto => "auto",
     };
 
     let encoder_str = match opts.encoder {
@@ -192,32 +1261,261 @@ fn spawn_worker(
         JpegEncoderType::Vips => "vips",
     };
 
+    let format_str = match opts.format {
+        OutputFormat::Jpeg => "jpeg",
+        OutputFormat::Png => "png",
+        OutputFormat::Webp => "webp",
+        OutputFormat::Tiff => "tiff",
+        OutputFormat::Avif => "avif",
+    };
+
     let mut cmd = Command::new(exe);
+    forward_pdfium_path(&mut cmd);
+    if let Some(megabytes) = opts.worker_mem_limit {
+        limit_address_space(&mut cmd, megabytes);
+    }
     cmd.arg("render-worker")
         .arg(pdf_path)
         .arg("-o")
         .arg(output_dir)
         .arg("--pages")
         .arg(pages)
-        .arg("--target-width")
-        .arg(opts.target_width.to_string())
         .arg("--quality")
         .arg(opts.quality.to_string())
         .arg("--box")
         .arg(box_str)
         .arg("--encoder")
-        .arg(encoder_str);
+        .arg(encoder_str)
+        .arg("--format")
+        .arg(format_str)
+        .arg("--background")
+        .arg(opts.background.to_arg())
+        .arg("--rotate")
+        .arg(opts.rotation.degrees().to_string())
+        .arg("--name-template")
+        .arg(opts.name_template.as_str());
+
+    match opts.progress {
+        ProgressMode::Human => {}
+        ProgressMode::Json => {
+            cmd.arg("--progress").arg("json");
+        }
+        ProgressMode::None => {
+            cmd.arg("--progress").arg("none");
+        }
+    }
+
+    // The worker takes its size from --widths, which conflicts with the others.
+    match opts.size {
+        _ if !opts.widths.is_empty() => {
+            let widths: Vec<String> = opts.widths.iter().map(u32::to_string).collect();
+            cmd.arg("--widths").arg(widths.join(","))
+        }
+        RenderSize::TargetWidth(width) => cmd.arg("--target-width").arg(width.to_string()),
+        RenderSize::TargetHeight(height) => cmd.arg("--target-height").arg(height.to_string()),
+        RenderSize::Dpi(dpi) => cmd.arg("--dpi").arg(dpi.to_string()),
+        RenderSize::Canvas { width, height } => {
+            cmd.arg("--canvas").arg(format!("{width}x{height}"))
+        }
+    };
 
     if opts.extract_images {
         cmd.arg("--extract-images");
     }
 
+    if opts.rotate_auto {
+        cmd.arg("--rotate-auto");
+    }
+
+    if opts.reencode_extracted {
+        cmd.arg("--reencode-extracted");
+    }
+
+    if opts.grayscale {
+        cmd.arg("--grayscale");
+    }
+
+    if opts.reproducible {
+        cmd.arg("--reproducible");
+    }
+
+    if opts.exif_orientation {
+        cmd.arg("--exif-orientation");
+    }
+
+    if opts.no_images {
+        cmd.arg("--no-images");
+    }
+
+    if opts.strict {
+        cmd.arg("--strict");
+    }
+
+    cmd.args(opts.pdfium_flags.to_args());
+    cmd.args(opts.layers.to_args());
+
+    if opts.progressive {
+        cmd.arg("--progressive");
+    }
+
+    if let Some(subsampling) = opts.subsampling {
+        cmd.arg("--subsampling").arg(subsampling.to_arg());
+    }
+
+    if opts.lossless {
+        cmd.arg("--lossless");
+    }
+
+    if let Some(speed) = opts.speed {
+        cmd.arg("--speed").arg(speed.to_string());
+    }
+
+    // Workers read the `--icc` file again rather than receive its bytes.
+    match &opts.icc_profile {
+        Some(IccProfile {
+            path: Some(path), ..
+        }) => {
+            cmd.arg("--icc").arg(path);
+        }
+        Some(_) => {
+            cmd.arg("--srgb");
+        }
+        None => {}
+    }
+
+    if let Some(watermark) = &opts.watermark {
+        cmd.arg("--watermark-text")
+            .arg(&watermark.text)
+            .arg("--watermark-opacity")
+            .arg(watermark.opacity.to_string())
+            .arg(format!("--watermark-angle={}", watermark.angle));
+        if let Some(font) = &watermark.font_path {
+            cmd.arg("--watermark-font").arg(font);
+        }
+    }
+
+    if let Some(max_width) = opts.max_width {
+        cmd.arg("--max-width").arg(max_width.to_string());
+    }
+
+    if let Some(max_height) = opts.max_height {
+        cmd.arg("--max-height").arg(max_height.to_string());
+    }
+
+    if opts.no_upscale {
+        cmd.arg("--no-upscale");
+    }
+
+    if let Some(factor) = opts.supersample {
+        cmd.arg("--supersample").arg(factor.to_string());
+    }
+
+    if opts.resample != ResampleFilter::default() {
+        cmd.arg("--resample").arg(opts.resample.to_arg());
+    }
+
+    if let Some(region) = opts.region {
+        cmd.arg("--region").arg(region.to_arg());
+    }
+
+    if let Some(align) = opts.align {
+        cmd.arg("--align").arg(align.to_string());
+    }
+
+    if let Some(threshold) = opts.autocrop {
+        cmd.arg("--autocrop")
+            .arg("--autocrop-threshold")
+            .arg(threshold.to_string());
+    }
+
+    if opts.deskew {
+        cmd.arg("--deskew");
+    }
+
+    if let Some(timeout) = opts.page_timeout {
+        cmd.arg("--page-timeout").arg(timeout.as_secs().to_string());
+    }
+
+    if opts.label_names {
+        cmd.arg("--label-names");
+    }
+
+    if opts.keep_order {
+        cmd.arg("--keep-order")
+            .arg("--first-sequence")
+            .arg(first_sequence.to_string());
+    }
+
+    if let Some(password) = &opts.password {
+        cmd.arg("--password").arg(password);
+    }
+
+    if let Some(cache) = &opts.cache {
+        cmd.arg("--cache").arg(cache);
+    }
+
+    // JSON progress lines pass straight through to our stderr; anything else
+    // is captured and only shown if the worker fails.
+    let stderr = match opts.progress {
+        ProgressMode::Human | ProgressMode::None => std::process::Stdio::piped(),
+        ProgressMode::Json => std::process::Stdio::inherit(),
+    };
     cmd.stdout(std::process::Stdio::piped())
-        .stderr(std::process::Stdio::piped())
+        .stderr(stderr)
         .spawn()
         .map_err(Error::Io)
 }
 
+/// Set `RLIMIT_AS` in the worker between fork and exec, so allocations past
+/// `megabytes` fail inside the worker instead of growing until the OOM killer
+/// picks a victim.
+#[cfg(all(unix, not(test)))]
+#[cfg_attr(coverage_nightly, coverage(off))]
+fn limit_address_space(cmd: &mut Command, megabytes: u64) {
+    use std::os::unix::process::CommandExt;
+
+    let bytes = megabytes.saturating_mul(1024 * 1024) as libc::rlim_t;
+    let limit = libc::rlimit {
+        rlim_cur: bytes,
+        rlim_max: bytes,
+    };
+    // SAFETY: setrlimit is async-signal-safe and the closure allocates nothing.
+    unsafe {
+        cmd.pre_exec(move || {
+            if libc::setrlimit(libc::RLIMIT_AS, &limit) == 0 {
+                Ok(())
+            } else {
+                Err(std::io::Error::last_os_error())
+            }
+        });
+    }
+}
+
+/// Without `setrlimit` there is nothing to apply; `check_worker_mode` has
+/// already warned.
+#[cfg(all(not(unix), not(test)))]
+fn limit_address_space(_cmd: &mut Command, _megabytes: u64) {}
+
+/// Split `pages` into `workers` contiguous slices, each with the 1-based
+/// position of its first page in `pages` (the `--keep-order` sequence number).
+///
+/// `divide_pages` ranges are positions in the list, never page numbers, so a
+/// sparse or unsorted selection splits the same way as `1..=N`.
+fn worker_chunks(pages: &[u32], workers: u32) -> Vec<(u32, &[u32])> {
+    divide_pages(pages.len() as u32, workers)
+        .into_iter()
+        .map(|(start, end)| (start, &pages[(start as usize - 1)..end as usize]))
+        .collect()
+}
+
+/// The `--pages` argument for each of `workers` contiguous slices of `pages`.
+fn worker_page_lists(pages: &[u32], workers: u32) -> Vec<String> {
+    worker_chunks(pages, workers)
+        .into_iter()
+        .map(|(_, chunk)| format_page_list(chunk))
+        .collect()
+}
+
 fn format_page_list(pages: &[u32]) -> String {
     if pages.is_empty() {
         return String::new();
@@ -250,30 +1548,114 @@ fn push_range(parts: &mut Vec<String>, start: u32, end: u32) {
 }
 
 fn print_summary(
-    pages_rendered: u32,
-    pages_extracted: u32,
-    workers: u32,
+    totals: &RenderTotals,
+    plan: &RenderPlan,
     start: Instant,
     output_dir: &Path,
+    output_file: Option<&Path>,
+    error_format: ErrorFormat,
+    output_files: Option<Vec<String>>,
 ) {
+    let summary = render_summary(
+        totals,
+        plan,
+        start,
+        output_dir,
+        output_file,
+        error_format,
+        output_files,
+    );
+    print_json("render", &summary);
+}
+
+fn render_summary(
+    totals: &RenderTotals,
+    plan: &RenderPlan,
+    start: Instant,
+    output_dir: &Path,
+    output_file: Option<&Path>,
+    error_format: ErrorFormat,
+    output_files: Option<Vec<String>>,
+) -> RenderSummary {
     let elapsed = start.elapsed().as_secs_f64();
-    let summary = RenderSummary {
-        pages_rendered,
-        pages_extracted,
-        workers_used: workers,
+    let json_errors = error_format == ErrorFormat::Json;
+    RenderSummary {
+        pages_rendered: totals.pages_rendered,
+        pages_extracted: totals.pages_extracted,
+        pages_cached: totals.pages_cached,
+        pages_truncated: plan.pages_truncated,
+        failed: totals.failed(),
+        workers_used: plan.effective_workers,
         elapsed_secs: (elapsed * 100.0).round() / 100.0,
+        pages_per_second: pages_per_second(
+            totals.succeeded() * totals.iteration_secs.len().max(1) as u32,
+            elapsed,
+        ),
+        iteration_secs: totals.iteration_secs.clone(),
         output_dir: output_dir.display().to_string(),
-    };
-    println!("{}", serde_json::to_string_pretty(&summary).unwrap());
+        output_file: output_file.map(|path| path.display().to_string()),
+        output_files,
+        failed_pages: json_errors.then(|| totals.failed_pages.clone()),
+        worker_errors: json_errors.then(|| totals.worker_errors.clone()),
+        retried_pages: totals.retried_pages.clone(),
+        pages_not_upscaled: {
+            let mut pages = totals.not_upscaled.clone();
+            pages.sort_unstable();
+            pages
+        },
+        box_fallbacks: totals.box_fallbacks.len() as u32,
+        files: None,
+    }
+}
+
+/// The written files for `--list-outputs`: sorted by page, or left in
+/// request order with `--keep-order` (workers' results are merged in order).
+fn output_files(outputs: &[ManifestEntry], keep_order: bool) -> Vec<String> {
+    let mut entries: Vec<&ManifestEntry> = outputs.iter().collect();
+    if !keep_order {
+        entries.sort_by_key(|entry| entry.page);
+    }
+    entries.iter().map(|entry| entry.file.clone()).collect()
+}
+
+/// Throughput for the summary, rounded to two decimals like `elapsed_secs`.
+fn pages_per_second(pages: u32, elapsed_secs: f64) -> f64 {
+    if elapsed_secs <= 0.0 {
+        return 0.0;
+    }
+    (pages as f64 / elapsed_secs * 100.0).round() / 100.0
 }
 
-#[derive(serde::Deserialize)]
+#[derive(serde::Deserialize, Default)]
 struct WorkerOutput {
     pages_rendered: u32,
     #[serde(default)]
     pages_extracted: u32,
     #[serde(default)]
-    errors: Vec<String>,
+    pages_cached: u32,
+    #[serde(default)]
+    errors: Vec<PageError>,
+    #[serde(default)]
+    outputs: Vec<ManifestEntry>,
+    #[serde(default)]
+    not_upscaled: Vec<u32>,
+    #[serde(default)]
+    box_fallbacks: Vec<u32>,
+}
+
+impl From<WorkerOutput> for WorkerResult {
+    fn from(output: WorkerOutput) -> Self {
+        WorkerResult {
+            pages_rendered: output.pages_rendered,
+            pages_extracted: output.pages_extracted,
+            pages_cached: output.pages_cached,
+            errors: output.errors,
+            outputs: output.outputs,
+            not_upscaled: output.not_upscaled,
+            box_fallbacks: output.box_fallbacks,
+            ..WorkerResult::default()
+        }
+    }
 }
 
 #[cfg(test)]
@@ -287,31 +1669,149 @@ mod tests {
         assert!(!is_zero(&1));
     }
 
+    #[test]
+    fn batch_files_render_into_their_stem() {
+        let pdfs = [
+            PathBuf::from("scans/a.pdf"),
+            PathBuf::from("b.PDF"),
+            PathBuf::from("-"),
+        ];
+
+        assert_eq!(
+            batch_output_dirs(&pdfs, Path::new("out")).unwrap(),
+            [
+                PathBuf::from("out/a"),
+                PathBuf::from("out/b"),
+                PathBuf::from("out/stdin")
+            ]
+        );
+
+        let clash = [
+            PathBuf::from("2023/report.pdf"),
+            PathBuf::from("2024/report.pdf"),
+        ];
+        assert!(matches!(
+            batch_output_dirs(&clash, Path::new("out")),
+            Err(Error::InvalidArgs(message)) if message.contains("out/report")
+        ));
+    }
+
     #[test]
     fn check_errors_reports_combined_worker_errors() {
-        assert!(check_errors(Vec::new()).is_ok());
+        assert!(check_errors(&RenderTotals::default(), false).is_ok());
 
-        let err = check_errors(vec![
-            "page 1 failed".to_string(),
-            "page 2 failed".to_string(),
-        ])
-        .unwrap_err();
+        let totals = RenderTotals {
+            failed_pages: vec![PageError::new(1, "failed")],
+            worker_errors: vec!["worker 1: thread panicked".to_string()],
+            ..RenderTotals::default()
+        };
+        let err = check_errors(&totals, false).unwrap_err();
 
         assert_eq!(
             err.to_string(),
             "rendering error: 2 errors during rendering"
         );
+        assert_eq!(err.exit_code(), std::process::ExitCode::from(4));
+    }
+
+    #[test]
+    fn check_errors_distinguishes_partial_failure() {
+        let totals = RenderTotals {
+            pages_rendered: 2,
+            pages_extracted: 1,
+            failed_pages: vec![PageError::new(4, "failed")],
+            ..RenderTotals::default()
+        };
+        let err = check_errors(&totals, false).unwrap_err();
+
+        assert!(matches!(
+            err,
+            Error::PartialRender {
+                succeeded: 3,
+                failed: 1
+            }
+        ));
+        assert_eq!(err.exit_code(), std::process::ExitCode::from(6));
+        assert_eq!(
+            err.to_string(),
+            "rendering error: 1 errors during rendering (3 pages succeeded)"
+        );
+    }
+
+    #[test]
+    fn check_errors_with_strict_fails_the_whole_render() {
+        let totals = RenderTotals {
+            pages_rendered: 2,
+            pages_extracted: 1,
+            failed_pages: vec![PageError::new(4, "failed")],
+            ..RenderTotals::default()
+        };
+        let err = check_errors(&totals, true).unwrap_err();
+
+        assert!(matches!(err, Error::Render(_)));
+        assert_eq!(
+            err.to_string(),
+            "rendering error: stopped at the first error (--strict)"
+        );
+        assert_eq!(err.exit_code(), std::process::ExitCode::from(4));
     }
 
     #[test]
     fn render_plan_keeps_pages_and_worker_count() {
         let plan = RenderPlan {
             page_list: vec![1, 3, 5],
+            labels: Vec::new(),
             effective_workers: 2,
+            pages_truncated: 0,
         };
 
         assert_eq!(plan.page_list, vec![1, 3, 5]);
+        assert!(plan.labels.is_empty());
         assert_eq!(plan.effective_workers, 2);
+        assert_eq!(plan.pages_truncated, 0);
+    }
+
+    #[test]
+    fn worker_mode_defaults_to_process() {
+        assert!(matches!(WorkerMode::default(), WorkerMode::Process));
+        assert!(matches!(WorkerMode::Thread, WorkerMode::Thread));
+    }
+
+    #[test]
+    fn no_overwrite_lists_first_conflicts() {
+        assert!(check_no_overwrite(&[]).is_ok());
+
+        let conflicts: Vec<_> = (1..=5)
+            .map(|n| PathBuf::from(format!("out/page-000{n}.jpg")))
+            .collect();
+        let err = check_no_overwrite(&conflicts).unwrap_err();
+
+        assert!(matches!(&err, Error::Io(e) if e.kind() == std::io::ErrorKind::AlreadyExists));
+        assert_eq!(
+            err.to_string(),
+            "I/O error: --no-overwrite: 5 output files already exist: out/page-0001.jpg, \
+             out/page-0002.jpg, out/page-0003.jpg, and 2 more"
+        );
+    }
+
+    #[test]
+    fn stdout_requires_exactly_one_page() {
+        assert_eq!(single_page(&[3]).unwrap(), 3);
+        assert!(matches!(single_page(&[]), Err(Error::InvalidArgs(_))));
+        assert!(matches!(single_page(&[1, 2]), Err(Error::InvalidArgs(_))));
+    }
+
+    #[test]
+    fn box_fallbacks_are_named_once_in_page_order() {
+        assert_eq!(box_fallback_message(BoxType::Bleed, &[]), None);
+        assert_eq!(
+            box_fallback_message(BoxType::Bleed, &[4]).unwrap(),
+            "no BleedBox on page 4; rendered the CropBox instead"
+        );
+        assert_eq!(
+            box_fallback_message(BoxType::Auto, &[3, 1, 2, 7]).unwrap(),
+            "no TrimBox or BleedBox on pages 1-3,7; rendered the CropBox instead"
+        );
     }
 
     #[test]
@@ -321,6 +1821,55 @@ mod tests {
         assert_eq!(format_page_list(&[1, 2, 3, 5, 7, 8]), "1-3,5,7-8");
     }
 
+    #[test]
+    fn workers_auto_uses_available_parallelism() {
+        let cpus = std::thread::available_parallelism().unwrap().get() as u32;
+        assert_eq!(parse_workers("auto"), Ok(cpus));
+        assert_eq!(parse_workers("0"), Ok(cpus));
+        assert_eq!(parse_workers("3"), Ok(3));
+        assert!(parse_workers("many").is_err());
+        assert!(parse_workers("-1").is_err());
+    }
+
+    #[test]
+    fn workers_env_overrides_the_command_line() {
+        assert_eq!(workers_override(4, None).unwrap(), 4);
+        assert_eq!(workers_override(4, Some("1")).unwrap(), 1);
+        assert_eq!(workers_override(1, Some("3")).unwrap(), 3);
+        let err = workers_override(4, Some("none")).unwrap_err();
+        assert!(err.to_string().contains("PDF_WORKERS"), "{err}");
+    }
+
+    #[test]
+    fn sparse_page_lists_split_by_position() {
+        let pages = [1, 5, 9, 20];
+        assert_eq!(
+            worker_chunks(&pages, 2),
+            vec![(1, &pages[..2]), (3, &pages[2..])]
+        );
+        assert_eq!(worker_page_lists(&pages, 2), vec!["1,5", "9,20"]);
+
+        for workers in 1..=6 {
+            let chunks = worker_chunks(&pages, workers);
+            assert_eq!(chunks.len(), workers.min(4) as usize);
+            let rejoined: Vec<u32> = chunks.iter().flat_map(|(_, c)| c.iter().copied()).collect();
+            assert_eq!(rejoined, pages);
+            let mut position = 1;
+            for (first_sequence, chunk) in chunks {
+                assert_eq!(first_sequence, position);
+                position += chunk.len() as u32;
+            }
+        }
+    }
+
+    #[test]
+    fn worker_page_lists_split_the_request_in_order() {
+        let pages: Vec<u32> = (1..=10).collect();
+        assert_eq!(worker_page_lists(&pages, 3), vec!["1-4", "5-7", "8-10"]);
+        assert_eq!(worker_page_lists(&[9, 2, 4, 5], 2), vec!["9,2", "4-5"]);
+        assert!(worker_page_lists(&[], 4).is_empty());
+    }
+
     #[test]
     fn worker_output_defaults_missing_optional_fields() {
         let output: WorkerOutput = serde_json::from_str(r#"{"pages_rendered":3}"#).unwrap();
@@ -328,16 +1877,231 @@ mod tests {
         assert_eq!(output.pages_rendered, 3);
         assert_eq!(output.pages_extracted, 0);
         assert!(output.errors.is_empty());
+        assert!(output.outputs.is_empty());
+    }
+
+    #[test]
+    fn worker_output_parses_structured_page_errors() {
+        let output: WorkerOutput = serde_json::from_str(
+            r#"{"pages_rendered":1,"errors":[{"page":4,"reason":"bad page"}]}"#,
+        )
+        .unwrap();
+
+        assert_eq!(output.errors, vec![PageError::new(4, "bad page")]);
+    }
+
+    #[test]
+    fn json_error_format_adds_failures_to_summary() {
+        let totals = RenderTotals {
+            pages_rendered: 1,
+            failed_pages: vec![PageError::new(2, "bad page")],
+            ..RenderTotals::default()
+        };
+        let summary = RenderSummary {
+            pages_rendered: totals.pages_rendered,
+            pages_extracted: 0,
+            pages_cached: 0,
+            pages_truncated: 0,
+            failed: totals.failed(),
+            workers_used: 1,
+            elapsed_secs: 0.0,
+            pages_per_second: 0.0,
+            iteration_secs: Vec::new(),
+            output_dir: "/tmp/out".into(),
+            output_file: None,
+            output_files: None,
+            failed_pages: Some(totals.failed_pages.clone()),
+            worker_errors: Some(Vec::new()),
+            retried_pages: Vec::new(),
+            pages_not_upscaled: Vec::new(),
+            box_fallbacks: 0,
+            files: None,
+        };
+
+        let json = serde_json::to_value(&summary).unwrap();
+
+        assert_eq!(json["failed"], 1);
+        assert_eq!(json["failed_pages"][0]["page"], 2);
+        assert_eq!(json["failed_pages"][0]["reason"], "bad page");
+        assert_eq!(json["worker_errors"], serde_json::json!([]));
+    }
+
+    fn assignments() -> Vec<WorkerAssignment> {
+        vec![
+            WorkerAssignment {
+                worker: 0,
+                pages: vec![1, 2],
+                first_sequence: 1,
+            },
+            WorkerAssignment {
+                worker: 1,
+                pages: vec![3, 4],
+                first_sequence: 3,
+            },
+        ]
+    }
+
+    fn rendered(pages: &[u32]) -> WorkerOutput {
+        WorkerOutput {
+            pages_rendered: pages.len() as u32,
+            ..WorkerOutput::default()
+        }
+    }
+
+    #[test]
+    fn worker_failing_once_is_retried_for_its_pages() {
+        let mut batches = Vec::new();
+        let totals = run_with_retries(assignments(), 2, |batch| {
+            batches.push(
+                batch
+                    .iter()
+                    .map(|a| (a.first_sequence, a.pages.clone()))
+                    .collect::<Vec<_>>(),
+            );
+            Ok(batch
+                .iter()
+                .map(|a| {
+                    if a.worker == 1 && batches.len() == 1 {
+                        Err("exit status: 137: out of memory".to_string())
+                    } else {
+                        Ok(rendered(&a.pages))
+                    }
+                })
+                .collect())
+        })
+        .unwrap();
+
+        assert_eq!(
+            batches,
+            vec![
+                vec![(1, vec![1, 2]), (3, vec![3, 4])],
+                vec![(3, vec![3, 4])]
+            ]
+        );
+        assert_eq!(totals.pages_rendered, 4);
+        assert_eq!(totals.retried_pages, vec![3, 4]);
+        assert!(totals.worker_errors.is_empty());
+    }
+
+    #[test]
+    fn worker_failing_every_attempt_is_a_worker_error() {
+        let mut attempts = 0;
+        let totals = run_with_retries(assignments(), 1, |batch| {
+            attempts += 1;
+            Ok(batch
+                .iter()
+                .map(|a| match a.worker {
+                    0 => Err("exit status: 1: crashed".to_string()),
+                    _ => Ok(rendered(&a.pages)),
+                })
+                .collect())
+        })
+        .unwrap();
+
+        assert_eq!(attempts, 2);
+        assert_eq!(totals.pages_rendered, 2);
+        assert_eq!(totals.retried_pages, vec![1, 2]);
+        assert_eq!(
+            totals.worker_errors,
+            vec!["worker 0: exit status: 1: crashed"]
+        );
+    }
+
+    #[test]
+    fn failures_without_retries_are_reported_immediately() {
+        let totals = run_with_retries(assignments(), 0, |batch| {
+            Ok(batch
+                .iter()
+                .map(|_| Err("exit status: 1: ".to_string()))
+                .collect())
+        })
+        .unwrap();
+
+        assert!(totals.retried_pages.is_empty());
+        assert_eq!(totals.worker_errors.len(), 2);
+    }
+
+    #[test]
+    fn limit_keeps_the_first_pages_in_order() {
+        let mut pages = vec![9, 3, 7, 1];
+        assert_eq!(apply_limit(&mut pages, Some(2)), 2);
+        assert_eq!(pages, [9, 3]);
+
+        assert_eq!(apply_limit(&mut pages, Some(5)), 0);
+        assert_eq!(apply_limit(&mut pages, None), 0);
+        assert_eq!(pages, [9, 3]);
+    }
+
+    #[test]
+    fn samples_spread_over_the_selection() {
+        let pages: Vec<u32> = (1..=10).collect();
+
+        assert_eq!(sample_pages(&pages, 3), [1, 5, 10]);
+        assert_eq!(sample_pages(&[2, 4, 6, 8], 3), [2, 4, 8]);
+        assert_eq!(sample_pages(&[7, 9], 3), [7, 9]);
+        assert_eq!(sample_pages(&pages, 1), [1]);
+    }
+
+    #[test]
+    fn extrapolate_scales_the_mean_sample() {
+        assert_eq!(extrapolate(&[100, 200, 300], 10), 2000);
+        assert_eq!(extrapolate(&[100, 101], 1), 101);
+        assert_eq!(extrapolate(&[], 10), 0);
+    }
+
+    #[test]
+    fn pages_per_second_divides_by_elapsed_time() {
+        assert_eq!(pages_per_second(50, 6.5), 7.69);
+        assert_eq!(pages_per_second(3, 0.0), 0.0);
+        assert_eq!(pages_per_second(0, 2.0), 0.0);
+    }
+
+    #[test]
+    fn output_files_sort_by_page_unless_keeping_order() {
+        let entry = |page: u32| ManifestEntry {
+            page,
+            file: format!("out/page-{page}.jpg"),
+            method: crate::render_worker::PageStatus::Rendered,
+            width_pt: 10.0,
+            height_pt: 10.0,
+            width_px: 10,
+            height_px: 10,
+            bytes: 100,
+            sha256: String::new(),
+        };
+        let outputs = [entry(5), entry(1), entry(3)];
+
+        assert_eq!(
+            output_files(&outputs, false),
+            ["out/page-1.jpg", "out/page-3.jpg", "out/page-5.jpg"]
+        );
+        assert_eq!(
+            output_files(&outputs, true),
+            ["out/page-5.jpg", "out/page-1.jpg", "out/page-3.jpg"]
+        );
     }
 
     #[test]
     fn print_summary_serializes_without_error() {
+        let totals = RenderTotals {
+            pages_rendered: 3,
+            pages_extracted: 1,
+            ..RenderTotals::default()
+        };
+        let plan = RenderPlan {
+            page_list: vec![1, 2, 3, 4],
+            labels: Vec::new(),
+            effective_workers: 2,
+            pages_truncated: 0,
+        };
         print_summary(
-            3,
-            1,
-            2,
+            &totals,
+            &plan,
             Instant::now() - Duration::from_millis(1234),
             Path::new("/tmp/out"),
+            Some(Path::new("/tmp/out/book.tiff")),
+            ErrorFormat::Json,
+            Some(vec!["/tmp/out/page-0001.jpg".into()]),
         );
     }
 }
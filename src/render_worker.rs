@@ -1,26 +1,53 @@
 #[cfg(not(test))]
+use crate::deskew::deskew;
 use crate::error::Error;
+use crate::icc::IccProfile;
 #[cfg(not(test))]
-use crate::pdfium_init::load_pdfium;
+use crate::icc::embed_jpeg_icc;
+use crate::layers::Layers;
+#[cfg(not(test))]
+use crate::layers::load_document_with_layers;
+use crate::name_template::{NameTemplate, sanitize_label};
 #[cfg(not(test))]
+use crate::page_cache::{PageCache, copy_cached, page_content_hash};
+#[cfg(not(test))]
+use crate::pdfium_init::load_pdfium;
+use crate::watermark::Watermark;
+#[cfg(feature = "avif")]
+use image::codecs::avif::AvifEncoder;
 use image::codecs::jpeg::JpegEncoder;
+use image::codecs::png::PngEncoder;
+use image::codecs::tiff::TiffEncoder;
+use image::codecs::webp::WebPEncoder;
+use image::imageops::FilterType;
+use image::{DynamicImage, ImageEncoder};
 #[cfg(not(test))]
 use pdfium_render::prelude::*;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 #[cfg(not(test))]
 use std::fs::File;
 #[cfg(not(test))]
 use std::io::BufWriter;
+use std::io::{Cursor, Seek, Write};
+use std::path::{Path, PathBuf};
 #[cfg(not(test))]
-use std::path::Path;
+use std::sync::{Mutex, MutexGuard, PoisonError};
+use std::time::Duration;
+#[cfg(not(test))]
+use std::time::Instant;
 
-#[derive(Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, clap::ValueEnum)]
 pub enum BoxType {
+    /// The CropBox, or the MediaBox where none is set
     Crop,
+    /// The BleedBox, falling back to `crop`
     Bleed,
+    /// The first of TrimBox and BleedBox the page defines, else `crop`
+    Auto,
 }
 
-#[derive(Clone, Copy, PartialEq, Eq, clap::ValueEnum, Default)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, clap::ValueEnum, Default)]
 pub enum JpegEncoderType {
     /// Rust `image` crate (default)
     #[default]
@@ -29,430 +56,4046 @@ pub enum JpegEncoderType {
     Vips,
 }
 
-#[derive(Serialize)]
-pub struct WorkerResult {
-    pub pages_rendered: u32,
-    pub pages_extracted: u32,
-    pub errors: Vec<String>,
+/// Chroma subsampling for JPEG output (`--subsampling`).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, clap::ValueEnum)]
+pub enum JpegSubsampling {
+    /// Full-resolution chroma
+    #[value(name = "444")]
+    Full,
+    /// Chroma halved horizontally (not available with --encoder vips)
+    #[value(name = "422")]
+    Half,
+    /// Chroma halved in both directions; smallest files
+    #[value(name = "420")]
+    Quarter,
 }
 
-/// Rendering options shared between single-process and multi-process modes.
-pub struct RenderOptions {
-    pub target_width: u32,
-    pub quality: u8,
-    pub box_type: BoxType,
-    pub extract_images: bool,
-    pub encoder: JpegEncoderType,
+impl JpegSubsampling {
+    /// The `--subsampling` token, for forwarding to worker processes.
+    pub fn to_arg(self) -> &'static str {
+        match self {
+            JpegSubsampling::Full => "444",
+            JpegSubsampling::Half => "422",
+            JpegSubsampling::Quarter => "420",
+        }
+    }
 }
 
-/// Render a range of pages from a PDF to JPEG files.
-///
-/// Pages are 1-based. Each page produces `page-NNNN.jpg` in `output_dir`.
-/// When `extract_images` is true, pages containing a single JPEG image are
-/// extracted directly without re-encoding.
-#[cfg(not(test))]
-#[cfg_attr(coverage_nightly, coverage(off))]
-pub fn render_pages(
-    pdf_path: &Path,
-    output_dir: &Path,
-    pages: &[u32],
-    opts: &RenderOptions,
-) -> Result<WorkerResult, Error> {
-    let pdfium = load_pdfium()?;
-    let mut document = pdfium
-        .load_pdf_from_file(pdf_path, None)
-        .map_err(|e| Error::PdfInvalid(format!("{}: {e}", pdf_path.display())))?;
-    let render_config = PdfRenderConfig::new().set_target_width(opts.target_width as i32);
-
-    let mut result = WorkerResult {
-        pages_rendered: 0,
-        pages_extracted: 0,
-        errors: Vec::new(),
-    };
-    for &page_num in pages {
-        process_page(
-            &mut document,
-            &render_config,
-            output_dir,
-            page_num,
-            opts,
-            &mut result,
-        );
-    }
-    if result.pages_rendered + result.pages_extracted > 0 {
-        eprintln!();
-    }
-    Ok(result)
+/// Filter for scaling a rendered page down (`--resample`): the smaller
+/// `--widths` and the `--supersample` downscale. Other sizes are rasterized
+/// directly by pdfium, so no filter applies to them.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, clap::ValueEnum, Default)]
+pub enum ResampleFilter {
+    /// Nearest neighbour; fastest, blocky
+    Nearest,
+    /// Bilinear
+    Triangle,
+    /// Catmull-Rom bicubic
+    Catmull,
+    /// Lanczos3; sharpest, slowest (default)
+    #[default]
+    Lanczos,
 }
 
-#[cfg(not(test))]
-#[cfg_attr(coverage_nightly, coverage(off))]
-fn process_page(
-    document: &mut PdfDocument,
-    render_config: &PdfRenderConfig,
-    output_dir: &Path,
-    page_num: u32,
-    opts: &RenderOptions,
-    result: &mut WorkerResult,
-) {
-    let page_index = (page_num - 1) as u16;
-    if opts.box_type == BoxType::Bleed {
-        apply_bleed_box(document, page_index);
-    }
-    let page = match document.pages().get(page_index) {
-        Ok(page) => page,
-        Err(e) => {
-            result.errors.push(format!("page {page_num}: {e}"));
-            return;
+impl ResampleFilter {
+    /// The `--resample` token, for forwarding to worker processes.
+    pub fn to_arg(self) -> &'static str {
+        match self {
+            ResampleFilter::Nearest => "nearest",
+            ResampleFilter::Triangle => "triangle",
+            ResampleFilter::Catmull => "catmull",
+            ResampleFilter::Lanczos => "lanczos",
         }
-    };
-
-    if opts.extract_images
-        && let Some(Ok(())) = try_extract_jpeg(&page, output_dir, page_num)
-    {
-        result.pages_extracted += 1;
-        eprint!("\rExtracted page {page_num}");
-        return;
     }
 
-    match render_page_to_jpeg(
-        &page,
-        render_config,
-        output_dir,
-        page_num,
-        opts.quality,
-        opts.encoder,
-    ) {
-        Ok(()) => {
-            result.pages_rendered += 1;
-            eprint!("\rRendered page {page_num}");
-        }
-        Err(e) => {
-            result.errors.push(format!("page {page_num}: {e}"));
+    fn filter_type(self) -> FilterType {
+        match self {
+            ResampleFilter::Nearest => FilterType::Nearest,
+            ResampleFilter::Triangle => FilterType::Triangle,
+            ResampleFilter::Catmull => FilterType::CatmullRom,
+            ResampleFilter::Lanczos => FilterType::Lanczos3,
         }
     }
 }
 
-#[cfg(not(test))]
-#[cfg_attr(coverage_nightly, coverage(off))]
-fn apply_bleed_box(document: &mut PdfDocument, page_index: u16) {
-    let Ok(mut page) = document.pages().get(page_index) else {
-        return;
-    };
-
-    let bleed_rect = match page.boundaries().bleed() {
-        Ok(b) => b.bounds,
-        Err(_) => return, // No BleedBox defined, use default CropBox
-    };
-
-    // Override CropBox with BleedBox bounds
-    let _ = page
-        .boundaries_mut()
-        .set(PdfPageBoundaryBoxType::Crop, bleed_rect);
+/// Image format written for rendered pages.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, clap::ValueEnum, Default)]
+pub enum OutputFormat {
+    #[default]
+    Jpeg,
+    Png,
+    /// Lossy with --quality (requires --features webp), or --lossless
+    Webp,
+    /// Uncompressed; one file per page, or all pages with --single-file
+    Tiff,
+    /// Lossy with --quality and --speed (requires --features avif)
+    Avif,
 }
 
-/// Try to extract a raw JPEG from a page that contains a single image object.
-///
-/// Returns `None` if the page is not a single-image page or the image is not
-/// stored as JPEG (DCTDecode filter). Returns `Some(Ok(()))` on successful
-/// extraction, `Some(Err(..))` on I/O failure.
-#[cfg(not(test))]
-#[cfg_attr(coverage_nightly, coverage(off))]
-fn try_extract_jpeg(page: &PdfPage, output_dir: &Path, page_num: u32) -> Option<Result<(), Error>> {
-    let objects = page.objects();
-    if objects.len() != 1 {
-        return None;
+impl OutputFormat {
+    pub fn extension(self) -> &'static str {
+        match self {
+            OutputFormat::Jpeg => "jpg",
+            OutputFormat::Png => "png",
+            OutputFormat::Webp => "webp",
+            OutputFormat::Tiff => "tiff",
+            OutputFormat::Avif => "avif",
+        }
     }
 
-    let obj = objects.get(0).ok()?;
-    let image_obj = obj.as_image_object()?;
-
-    if !is_extractable_jpeg(image_obj) {
-        return None;
-    }
-    // PDFs can embed a full two-page spread and use CropBox to show one half.
-    // Skip extraction if the image aspect ratio doesn't match the page.
-    if !image_matches_page_aspect(image_obj, page) {
-        return None;
+    /// Whether the format can store an alpha channel.
+    pub fn supports_alpha(self) -> bool {
+        matches!(
+            self,
+            OutputFormat::Png | OutputFormat::Webp | OutputFormat::Tiff | OutputFormat::Avif
+        )
     }
-
-    Some(write_raw_jpeg(image_obj, output_dir, page_num))
 }
 
-/// Check if the embedded image's aspect ratio roughly matches the page's.
-///
-/// A spread image (landscape) embedded in a portrait page means the page is
-/// cropping to show only part of the image — raw extraction would be wrong.
-#[cfg(not(test))]
-#[cfg_attr(coverage_nightly, coverage(off))]
-fn image_matches_page_aspect(image_obj: &PdfPageImageObject, page: &PdfPage) -> bool {
-    let (Ok(img_w), Ok(img_h)) = (image_obj.width(), image_obj.height()) else {
-        return true;
-    };
-    let (pw, ph) = (page.width().value as f64, page.height().value as f64);
-    aspect_ratios_match(img_w as f64, img_h as f64, pw, ph)
+/// `--autocrop-threshold` when not given: enough to absorb JPEG noise and
+/// anti-aliasing in scanned margins.
+pub const DEFAULT_AUTOCROP_THRESHOLD: u8 = 16;
+
+/// `--speed` when not given: `image`'s own default for AVIF.
+pub const DEFAULT_AVIF_SPEED: u8 = 4;
+
+/// Named `--quality` levels, mapped onto each format's own scale.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, clap::ValueEnum)]
+pub enum QualityPreset {
+    Low,
+    Medium,
+    High,
+    Max,
 }
 
-/// Returns true if two rectangles have similar aspect ratios (within 10%).
-fn aspect_ratios_match(w1: f64, h1: f64, w2: f64, h2: f64) -> bool {
-    if w1 == 0.0 || h1 == 0.0 || w2 == 0.0 || h2 == 0.0 {
-        return true;
+impl QualityPreset {
+    /// The numeric quality this preset stands for with `format`.
+    pub fn quality(self, format: OutputFormat) -> u8 {
+        match (format, self) {
+            // AVIF holds up better than JPEG at the same number.
+            (OutputFormat::Avif, QualityPreset::Low) => 40,
+            (OutputFormat::Avif, QualityPreset::Medium) => 55,
+            (OutputFormat::Avif, QualityPreset::High) => 70,
+            (OutputFormat::Avif, QualityPreset::Max) => 90,
+            (_, QualityPreset::Low) => 50,
+            (_, QualityPreset::Medium) => 75,
+            (_, QualityPreset::High) => 90,
+            (_, QualityPreset::Max) => 100,
+        }
     }
-    let ratio_diff = ((w1 / h1) - (w2 / h2)).abs() / (w2 / h2);
-    ratio_diff < 0.1
 }
 
-#[cfg(not(test))]
-#[cfg_attr(coverage_nightly, coverage(off))]
-fn is_extractable_jpeg(image_obj: &PdfPageImageObject) -> bool {
-    let filters = image_obj.filters();
-    if filters.len() != 1 {
-        return false;
-    }
-    if !matches!(filters.get(0).ok(), Some(f) if f.name() == "DCTDecode") {
-        return false;
+/// Parse `--quality`: a number from 1 to 100 (decimals are rounded, as the
+/// encoders take whole numbers) or a `QualityPreset` name resolved for `format`.
+pub fn parse_quality(value: &str, format: OutputFormat) -> Result<u8, Error> {
+    if let Ok(preset) = <QualityPreset as clap::ValueEnum>::from_str(value, true) {
+        return Ok(preset.quality(format));
     }
-    // CMYK JPEGs extracted raw get their colors inverted when decoded as RGB.
-    // Detect CMYK via raw JPEG SOF marker (covers DeviceCMYK and ICC-based CMYK).
-    let data = match image_obj.get_raw_image_data() {
-        Ok(d) => d,
-        Err(_) => return false,
-    };
-    if jpeg_is_cmyk(&data) {
-        return false;
+    match value.parse::<f64>() {
+        Ok(number) if (1.0..=100.0).contains(&number) => Ok(number.round() as u8),
+        _ => Err(Error::InvalidArgs(format!(
+            "--quality must be 1-100 or low, medium, high or max, got `{value}`"
+        ))),
     }
-    true
 }
 
-/// Check if raw JPEG data is CMYK by reading the SOF marker's component count.
-/// CMYK JPEGs have 4 components; RGB has 3, grayscale has 1.
-fn jpeg_is_cmyk(data: &[u8]) -> bool {
-    // Scan for SOF0 (0xFFC0) or SOF2 (0xFFC2) marker
-    let mut i = 0;
-    while i + 1 < data.len() {
-        if data[i] != 0xFF {
-            i += 1;
-            continue;
+/// Clockwise rotation applied to rendered pages.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, clap::ValueEnum, Default)]
+pub enum Rotation {
+    #[default]
+    #[value(name = "0")]
+    None,
+    #[value(name = "90")]
+    Degrees90,
+    #[value(name = "180")]
+    Degrees180,
+    #[value(name = "270")]
+    Degrees270,
+}
+
+impl Rotation {
+    pub fn degrees(self) -> u32 {
+        match self {
+            Rotation::None => 0,
+            Rotation::Degrees90 => 90,
+            Rotation::Degrees180 => 180,
+            Rotation::Degrees270 => 270,
         }
-        let marker = data[i + 1];
-        // SOF0 = 0xC0, SOF1 = 0xC1, SOF2 = 0xC2
-        if matches!(marker, 0xC0..=0xC2) {
-            // SOF layout: FF Cn [length:2] [precision:1] [height:2] [width:2] [components:1]
-            if i + 9 < data.len() {
-                let num_components = data[i + 9];
-                return num_components == 4;
-            }
-            return false;
+    }
+
+    /// Whether the output image swaps the page's width and height.
+    pub fn is_quarter_turn(self) -> bool {
+        matches!(self, Rotation::Degrees90 | Rotation::Degrees270)
+    }
+
+    /// The quarter turn nearest to `degrees`, in any range.
+    fn nearest(degrees: f32) -> Self {
+        match (degrees / 90.0).round().rem_euclid(4.0) as u8 {
+            1 => Rotation::Degrees90,
+            2 => Rotation::Degrees180,
+            3 => Rotation::Degrees270,
+            _ => Rotation::None,
         }
-        // Skip non-SOF markers (read length and advance)
-        if marker == 0xD8 || marker == 0xD9 || marker == 0x00 {
-            i += 2;
-        } else if i + 3 < data.len() {
-            let len = u16::from_be_bytes([data[i + 2], data[i + 3]]) as usize;
-            i += 2 + len;
-        } else {
-            break;
+    }
+
+    /// The EXIF Orientation value telling viewers to turn an unrotated image
+    /// by this much clockwise.
+    pub fn exif_orientation(self) -> u16 {
+        match self {
+            Rotation::None => 1,
+            Rotation::Degrees90 => 6,
+            Rotation::Degrees180 => 3,
+            Rotation::Degrees270 => 8,
         }
     }
-    false
 }
 
-#[cfg(not(test))]
-#[cfg_attr(coverage_nightly, coverage(off))]
-fn write_raw_jpeg(
-    image_obj: &PdfPageImageObject,
-    output_dir: &Path,
-    page_num: u32,
-) -> Result<(), Error> {
-    let data = image_obj
-        .get_raw_image_data()
-        .map_err(|e| Error::Render(format!("extract image data: {e}")))?;
-    if data.is_empty() {
-        return Err(Error::Render("empty image data".into()));
+/// The clockwise turn that levels a page's text (`--rotate-auto`), from its
+/// characters' angles as pdfium reports them (clockwise, so text running up
+/// the page is at 270) and its `/Rotate`.
+///
+/// pdfium displays the page turned by `/Rotate` already, so each character
+/// leans clockwise by its angle plus that; the quarter turn most characters
+/// lean by is undone. Pages without text, and ties with upright text, are not
+/// turned.
+pub fn level_rotation(char_angles: impl IntoIterator<Item = f32>, page_rotate: u32) -> Rotation {
+    let mut counts = [0usize; 4];
+    for angle in char_angles {
+        counts[Rotation::nearest(-(angle + page_rotate as f32)).degrees() as usize / 90] += 1;
     }
+    let (turns, _) = counts
+        .iter()
+        .enumerate()
+        .max_by_key(|&(turns, &count)| (count, turns == 0))
+        .unwrap();
+    Rotation::nearest(turns as f32 * 90.0)
+}
 
-    let filename = format!("page-{page_num:04}.jpg");
-    let path = output_dir.join(filename);
+/// Color the page is cleared to before rendering (`--background`).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Background {
+    pub red: u8,
+    pub green: u8,
+    pub blue: u8,
+    pub alpha: u8,
+}
 
-    // Validate with libjpeg-turbo (same decoder as libvips). If it fails,
-    // the `image` crate can still decode it — re-encode to produce a clean
-    // JPEG that vips will accept.
-    if turbojpeg::decompress(&data, turbojpeg::PixelFormat::RGB).is_err() {
-        let img = image::load_from_memory_with_format(&data, image::ImageFormat::Jpeg)
-            .map_err(|e| Error::Render(format!("JPEG decode failed: {e}")))?;
-        let file = File::create(&path)?;
-        let writer = BufWriter::new(file);
-        img.into_rgb8()
-            .write_with_encoder(JpegEncoder::new_with_quality(writer, 100))
-            .map_err(|e| Error::Render(format!("JPEG re-encode failed: {e}")))?;
-        eprintln!("  (re-encoded corrupt JPEG for page {page_num})");
-        return Ok(());
+impl Background {
+    pub const WHITE: Background = Background::opaque(255, 255, 255);
+    pub const BLACK: Background = Background::opaque(0, 0, 0);
+    /// Fully transparent; requires PNG output to keep the alpha channel.
+    pub const TRANSPARENT: Background = Background {
+        red: 255,
+        green: 255,
+        blue: 255,
+        alpha: 0,
+    };
+
+    const fn opaque(red: u8, green: u8, blue: u8) -> Self {
+        Background {
+            red,
+            green,
+            blue,
+            alpha: 255,
+        }
     }
 
-    std::fs::write(&path, &data)?;
-    Ok(())
-}
+    /// Parse `#RRGGBB`, `white`, `black` or `transparent`.
+    pub fn parse(s: &str) -> Result<Self, Error> {
+        match s.to_ascii_lowercase().as_str() {
+            "white" => return Ok(Background::WHITE),
+            "black" => return Ok(Background::BLACK),
+            "transparent" => return Ok(Background::TRANSPARENT),
+            _ => {}
+        }
 
-#[cfg(not(test))]
-#[cfg_attr(coverage_nightly, coverage(off))]
-fn render_page_to_jpeg(
-    page: &PdfPage,
-    config: &PdfRenderConfig,
-    output_dir: &Path,
-    page_num: u32,
-    quality: u8,
-    encoder_type: JpegEncoderType,
-) -> Result<(), Error> {
-    let bitmap = page
-        .render_with_config(config)
-        .map_err(|e| Error::Render(format!("render failed: {e}")))?;
+        let invalid = || {
+            Error::InvalidArgs(format!(
+                "invalid --background {s:?}: expected #RRGGBB, white, black or transparent"
+            ))
+        };
+        let hex = s.strip_prefix('#').ok_or_else(invalid)?;
+        if hex.len() != 6 || !hex.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return Err(invalid());
+        }
+        let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).map_err(|_| invalid());
+        Ok(Background::opaque(channel(0)?, channel(2)?, channel(4)?))
+    }
 
-    let image = bitmap.as_image().into_rgb8();
+    pub fn is_transparent(self) -> bool {
+        self.alpha < 255
+    }
 
-    let filename = format!("page-{page_num:04}.jpg");
-    let path = output_dir.join(filename);
+    /// The fill used for padding (`--align`, `--canvas`).
+    pub fn pixel(self) -> image::Rgba<u8> {
+        image::Rgba([self.red, self.green, self.blue, self.alpha])
+    }
 
-    match encoder_type {
-        JpegEncoderType::Image => encode_jpeg_image(&image, &path, quality),
-        JpegEncoderType::Vips => encode_jpeg_vips(&image, &path, quality),
+    /// Round-trippable form for forwarding to worker processes.
+    pub fn to_arg(self) -> String {
+        if self == Background::TRANSPARENT {
+            return "transparent".into();
+        }
+        format!("#{:02x}{:02x}{:02x}", self.red, self.green, self.blue)
     }
 }
 
-#[cfg(not(test))]
-#[cfg_attr(coverage_nightly, coverage(off))]
-fn encode_jpeg_image(image: &image::RgbImage, path: &Path, quality: u8) -> Result<(), Error> {
-    let file = File::create(path)?;
-    let writer = BufWriter::new(file);
-    let encoder = JpegEncoder::new_with_quality(writer, quality);
-    image
-        .write_with_encoder(encoder)
-        .map_err(|e| Error::Render(format!("JPEG encode failed: {e}")))?;
-    Ok(())
+/// Whether pdfium draws annotations and form fields (`--annotations`).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, clap::ValueEnum, Default)]
+pub enum Annotations {
+    /// Draw annotation appearances and form field values (default)
+    #[default]
+    On,
+    /// Page content only, e.g. for redaction previews
+    Off,
 }
 
-#[cfg(all(feature = "vips", not(test)))]
-#[cfg_attr(coverage_nightly, coverage(off))]
-fn encode_jpeg_vips(image: &image::RgbImage, path: &Path, quality: u8) -> Result<(), Error> {
-    let (width, height) = image.dimensions();
-    let raw = image.as_raw();
-
-    let vips_image = libvips::VipsImage::new_from_memory(
-        raw,
-        width as i32,
-        height as i32,
-        3,
-        libvips::ops::BandFormat::Uchar,
-    )
-    .map_err(|e| Error::Render(format!("vips from memory: {e}")))?;
+/// pdfium rendering flags (`--lcd-text`, `--no-native-text`, ...), all off by
+/// default, with annotations drawn.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PdfiumFlags {
+    /// Subpixel (LCD) text anti-aliasing; pdfium only applies it to bitmaps
+    /// without alpha, so it has no effect on a transparent `--background`
+    pub lcd_text: bool,
+    /// Never use the platform's native text output (only Windows and macOS
+    /// builds of pdfium have one)
+    pub no_native_text: bool,
+    /// Rasterize in grayscale inside pdfium, anti-aliasing included
+    pub grayscale_aa: bool,
+    /// Halftone when scaling images down
+    pub force_halftone: bool,
+    pub no_smooth_text: bool,
+    pub no_smooth_image: bool,
+    pub no_smooth_path: bool,
+    pub annotations: Annotations,
+}
 
-    let path_str = path
-        .to_str()
-        .ok_or_else(|| Error::Render("non-UTF8 path".into()))?;
-    libvips::ops::jpegsave_with_opts(
-        &vips_image,
-        path_str,
-        &libvips::ops::JpegsaveOptions {
-            q: quality as i32,
-            ..Default::default()
-        },
-    )
-    .map_err(|e| Error::Render(format!("vips jpegsave: {e}")))?;
+impl PdfiumFlags {
+    /// The flags that are set, as command-line arguments for worker processes.
+    pub fn to_args(self) -> Vec<&'static str> {
+        [
+            (self.lcd_text, "--lcd-text"),
+            (self.no_native_text, "--no-native-text"),
+            (self.grayscale_aa, "--grayscale-aa"),
+            (self.force_halftone, "--force-halftone"),
+            (self.no_smooth_text, "--no-smooth-text"),
+            (self.no_smooth_image, "--no-smooth-image"),
+            (self.no_smooth_path, "--no-smooth-path"),
+            (self.annotations == Annotations::Off, "--annotations=off"),
+        ]
+        .into_iter()
+        .filter_map(|(set, arg)| set.then_some(arg))
+        .collect()
+    }
 
-    Ok(())
+    /// Set the flags on `config`; unset flags keep pdfium-render's defaults.
+    #[cfg(not(test))]
+    fn apply(self, config: PdfRenderConfig) -> PdfRenderConfig {
+        config
+            .use_lcd_text_rendering(self.lcd_text)
+            .disable_native_text_rendering(self.no_native_text)
+            .use_grayscale_rendering(self.grayscale_aa)
+            .force_half_tone(self.force_halftone)
+            .set_text_smoothing(!self.no_smooth_text)
+            .set_image_smoothing(!self.no_smooth_image)
+            .set_path_smoothing(!self.no_smooth_path)
+            .render_annotations(self.annotations == Annotations::On)
+            .render_form_data(self.annotations == Annotations::On)
+    }
 }
 
-#[cfg(all(not(feature = "vips"), not(test)))]
-#[cfg_attr(coverage_nightly, coverage(off))]
-fn encode_jpeg_vips(_image: &image::RgbImage, _path: &Path, _quality: u8) -> Result<(), Error> {
-    Err(Error::InvalidArgs(
-        "--encoder vips requires building with --features vips".into(),
-    ))
+/// Rectangle of the page to render (`--region`), in points from the top-left corner.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Region {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+impl Region {
+    /// Parse `x,y,w,h` in PDF points.
+    pub fn parse(s: &str) -> Result<Self, Error> {
+        let invalid =
+            |reason: &str| Error::InvalidArgs(format!("invalid --region {s:?}: {reason}"));
+        let values = s
+            .split(',')
+            .map(|part| part.trim().parse::<f32>())
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|_| invalid("expected x,y,w,h in points"))?;
+        let [x, y, width, height] = values[..] else {
+            return Err(invalid("expected x,y,w,h in points"));
+        };
+        if !values.iter().all(|v| v.is_finite()) {
+            return Err(invalid("expected x,y,w,h in points"));
+        }
+        if x < 0.0 || y < 0.0 {
+            return Err(invalid("x and y must be non-negative"));
+        }
+        if width <= 0.0 || height <= 0.0 {
+            return Err(invalid("width and height must be positive"));
+        }
+        Ok(Region {
+            x,
+            y,
+            width,
+            height,
+        })
+    }
 
-    #[test]
-    fn aspect_match_same_ratio() {
-        assert!(aspect_ratios_match(2560.0, 3937.0, 477.0, 733.5));
+    /// Round-trippable form for forwarding to worker processes.
+    pub fn to_arg(self) -> String {
+        format!("{},{},{},{}", self.x, self.y, self.width, self.height)
     }
 
-    #[test]
-    fn aspect_match_slightly_different() {
-        // 5% difference — within tolerance
-        assert!(aspect_ratios_match(2560.0, 3937.0, 480.0, 733.5));
+    /// Error unless the region fits on a `page_width` x `page_height` point page.
+    pub fn check_within(self, page_width: f32, page_height: f32) -> Result<(), Error> {
+        // Allow for rounding in the page size pdfium reports.
+        const TOLERANCE: f32 = 0.01;
+        if self.x + self.width > page_width + TOLERANCE
+            || self.y + self.height > page_height + TOLERANCE
+        {
+            return Err(Error::InvalidArgs(format!(
+                "--region {} extends past the {page_width}x{page_height}pt page",
+                self.to_arg()
+            )));
+        }
+        Ok(())
     }
 
-    #[test]
-    fn aspect_mismatch_spread_on_portrait_page() {
-        // Spread image (8284x6400 landscape) on portrait page (477x733.5)
-        assert!(!aspect_ratios_match(8284.0, 6400.0, 477.0, 733.5));
+    /// Pixel rectangle `(left, top, width, height)` at `pixels_per_point`,
+    /// clamped to an `image_width` x `image_height` bitmap.
+    pub fn pixel_rect(
+        self,
+        pixels_per_point: f32,
+        image_width: u32,
+        image_height: u32,
+    ) -> (u32, u32, u32, u32) {
+        let to_px = |pt: f32| (pt * pixels_per_point).round() as u32;
+        let left = to_px(self.x).min(image_width.saturating_sub(1));
+        let top = to_px(self.y).min(image_height.saturating_sub(1));
+        let width = to_px(self.width).max(1).min(image_width - left);
+        let height = to_px(self.height).max(1).min(image_height - top);
+        (left, top, width, height)
     }
+}
 
-    #[test]
+/// How per-page progress is reported on stderr.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, clap::ValueEnum, Default)]
+pub enum ProgressMode {
+    /// Overwrite a single "Rendered page N" status line
+    #[default]
+    Human,
+    /// One JSON object per completed page, flushed per line
+    Json,
+    /// No per-page progress (`--quiet`, `--summary-only`)
+    None,
+}
+
+/// Outcome of one page, as reported in progress events and the manifest.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PageStatus {
+    Rendered,
+    Extracted,
+    Failed,
+}
+
+#[derive(Serialize)]
+struct ProgressEvent {
+    event: &'static str,
+    page: u32,
+    status: PageStatus,
+}
+
+/// JSON line for a completed page, e.g. `{"event":"page","page":7,"status":"rendered"}`.
+pub fn progress_json(page_num: u32, status: PageStatus) -> String {
+    let event = ProgressEvent {
+        event: "page",
+        page: page_num,
+        status,
+    };
+    serde_json::to_string(&event).unwrap()
+}
+
+/// Report a completed page on stderr.
+///
+/// JSON lines are written with a single call so lines from concurrent workers
+/// sharing the terminal or pipe do not interleave.
+#[cfg(not(test))]
+fn report_page(progress: ProgressMode, page_num: u32, status: PageStatus) {
+    let mut stderr = std::io::stderr().lock();
+    match (progress, status) {
+        (ProgressMode::Json, _) => {
+            let _ = stderr.write_all(format!("{}\n", progress_json(page_num, status)).as_bytes());
+        }
+        (ProgressMode::Human, PageStatus::Rendered) => {
+            let _ = write!(stderr, "\rRendered page {page_num}");
+        }
+        (ProgressMode::Human, PageStatus::Extracted) => {
+            let _ = write!(stderr, "\rExtracted page {page_num}");
+        }
+        // Failures are collected in `WorkerResult::errors` and reported at the end.
+        (ProgressMode::Human, PageStatus::Failed) | (ProgressMode::None, _) => {}
+    }
+    let _ = stderr.flush();
+}
+
+/// How the output pixel size of each page is chosen.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RenderSize {
+    /// Fixed pixel width for every page.
+    TargetWidth(u32),
+    /// Fixed pixel height for every page; width follows the aspect ratio.
+    TargetHeight(u32),
+    /// Physical resolution; pixel width is derived from each page's width in points.
+    Dpi(u32),
+    /// Fit each page inside `width` x `height` pixels, keeping its aspect
+    /// ratio; `render_page_image` pads the rest with the background.
+    Canvas { width: u32, height: u32 },
+}
+
+impl RenderSize {
+    /// The page's own size in points, one pixel per point. Used when no size is given.
+    pub const NATIVE: RenderSize = RenderSize::Dpi(72);
+
+    /// Resolve the mutually exclusive `--target-width` / `--target-height` /
+    /// `--dpi` arguments.
+    pub fn from_args(
+        target_width: Option<u32>,
+        target_height: Option<u32>,
+        dpi: Option<u32>,
+    ) -> Result<Self, Error> {
+        match (target_width, target_height, dpi) {
+            (Some(_), Some(_), _) => Err(Error::InvalidArgs(
+                "--target-width and --target-height are mutually exclusive".into(),
+            )),
+            (Some(_), _, Some(_)) | (_, Some(_), Some(_)) => Err(Error::InvalidArgs(
+                "--dpi cannot be combined with --target-width or --target-height".into(),
+            )),
+            (_, _, Some(0)) => Err(Error::InvalidArgs("--dpi must be greater than 0".into())),
+            (Some(0), _, _) => Err(Error::InvalidArgs(
+                "--target-width must be greater than 0".into(),
+            )),
+            (_, Some(0), _) => Err(Error::InvalidArgs(
+                "--target-height must be greater than 0".into(),
+            )),
+            (Some(width), None, None) => Ok(RenderSize::TargetWidth(width)),
+            (None, Some(height), None) => Ok(RenderSize::TargetHeight(height)),
+            (None, None, Some(dpi)) => Ok(RenderSize::Dpi(dpi)),
+            (None, None, None) => Ok(RenderSize::NATIVE),
+        }
+    }
+
+    /// The size for `--widths`: the largest of `widths`, which `parse_widths`
+    /// puts first.
+    pub fn from_widths(widths: &[u32]) -> Self {
+        RenderSize::TargetWidth(widths[0])
+    }
+
+    /// Parse `--canvas WxH`, in pixels.
+    pub fn parse_canvas(s: &str) -> Result<Self, Error> {
+        let invalid =
+            || Error::InvalidArgs(format!("invalid --canvas {s:?}: expected WxH in pixels"));
+        let (width, height) = s.split_once('x').ok_or_else(invalid)?;
+        let width: u32 = width.trim().parse().map_err(|_| invalid())?;
+        let height: u32 = height.trim().parse().map_err(|_| invalid())?;
+        if width == 0 || height == 0 {
+            return Err(Error::InvalidArgs(format!(
+                "invalid --canvas {s:?}: width and height must be greater than 0"
+            )));
+        }
+        Ok(RenderSize::Canvas { width, height })
+    }
+
+    /// Pixel width for a page of `width_pt` x `height_pt` points (1pt = 1/72 inch).
+    pub fn pixel_width(self, width_pt: f32, height_pt: f32) -> u32 {
+        match self {
+            RenderSize::TargetWidth(width) => width,
+            RenderSize::TargetHeight(height) => {
+                (width_pt as f64 / height_pt as f64 * height as f64).round() as u32
+            }
+            RenderSize::Dpi(dpi) => (width_pt as f64 / 72.0 * dpi as f64).round() as u32,
+            // Rounded down when the height is the limit, so rounding the
+            // height from the width cannot overflow the canvas.
+            RenderSize::Canvas { width, height } => {
+                let fitted = (width_pt as f64 / height_pt as f64 * height as f64).floor() as u32;
+                fitted.clamp(1, width)
+            }
+        }
+    }
+}
+
+/// Factor (at most 1) that shrinks a `width` x `height` pixel image to fit
+/// within `--max-width` / `--max-height`, keeping its aspect ratio.
+pub fn max_size_scale(
+    width: f64,
+    height: f64,
+    max_width: Option<u32>,
+    max_height: Option<u32>,
+) -> f64 {
+    let fit = |size: f64, max: Option<u32>| match max {
+        Some(max) if size > max as f64 => max as f64 / size,
+        _ => 1.0,
+    };
+    fit(width, max_width).min(fit(height, max_height))
+}
+
+/// Factor (at most 1) that keeps a `width_px` wide output within
+/// `native_width_pt`, the page's size at one pixel per point
+/// (`--no-upscale`); 1.0 without the option.
+pub fn no_upscale_scale(width_px: f64, native_width_pt: f32, no_upscale: bool) -> f64 {
+    if !no_upscale {
+        return 1.0;
+    }
+    (native_width_pt as f64 / width_px).min(1.0)
+}
+
+/// Largest `--supersample` factor.
+pub const MAX_SUPERSAMPLE: f32 = 8.0;
+
+/// The factor to render a `width_px` x `height_px` output at for
+/// `--supersample`: `requested`, lowered so pdfium's bitmap stays within
+/// `MAX_BITMAP_PIXELS`, and 1.0 (no supersampling) without the option.
+pub fn supersample_factor(width_px: f64, height_px: f64, requested: Option<f32>) -> f64 {
+    let Some(requested) = requested else {
+        return 1.0;
+    };
+    let fits = (MAX_BITMAP_PIXELS as f64 / (width_px * height_px)).sqrt();
+    (requested as f64).min(fits).max(1.0)
+}
+
+/// Largest bitmap pdfium is asked for. Its buffer size is an `int`, so four
+/// bytes per pixel have to stay under 2 GiB.
+pub const MAX_BITMAP_PIXELS: u64 = i32::MAX as u64 / 4;
+
+/// Reject pages whose bitmap would be empty or too large to allocate, before
+/// pdfium fails on them with a less specific error.
+pub fn check_bitmap_size(width_px: f64, height_px: f64) -> Result<(), Error> {
+    // Written so NaN from a degenerate page size fails too.
+    if !(width_px >= 1.0 && height_px >= 1.0) {
+        return Err(Error::PdfInvalid("zero-size page".into()));
+    }
+    let (width, height) = (width_px.round(), height_px.round());
+    if width * height > MAX_BITMAP_PIXELS as f64 {
+        return Err(Error::Render(format!(
+            "page would render at {width}x{height} pixels, over the {MAX_BITMAP_PIXELS} pixel limit"
+        )));
+    }
+    Ok(())
+}
+
+/// A page that failed to render, as reported in `WorkerResult::errors`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PageError {
+    pub page: u32,
+    #[serde(rename = "reason")]
+    pub message: String,
+}
+
+impl PageError {
+    pub fn new(page: u32, message: impl Into<String>) -> Self {
+        Self {
+            page,
+            message: message.into(),
+        }
+    }
+}
+
+impl std::fmt::Display for PageError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "page {}: {}", self.page, self.message)
+    }
+}
+
+/// A written output file, as listed in the `--manifest`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ManifestEntry {
+    pub page: u32,
+    pub file: String,
+    /// `rendered` or `extracted`
+    pub method: PageStatus,
+    /// Page size in points, as `info` reports it
+    pub width_pt: f32,
+    pub height_pt: f32,
+    /// Size of the written image
+    pub width_px: u32,
+    pub height_px: u32,
+    pub bytes: u64,
+    /// Hex SHA-256 of the written file, hashed as it was written
+    pub sha256: String,
+}
+
+#[derive(Serialize, Default)]
+pub struct WorkerResult {
+    pub pages_rendered: u32,
+    pub pages_extracted: u32,
+    /// Rendered pages copied from `--cache`; also counted in `pages_rendered`
+    #[serde(default)]
+    pub pages_cached: u32,
+    pub errors: Vec<PageError>,
+    /// One entry per written file, in the order the pages were processed
+    pub outputs: Vec<ManifestEntry>,
+    /// Seconds each `--repeat` iteration took; empty without `--repeat`
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub iteration_secs: Vec<f64>,
+    /// Pages rendered at native size instead of the requested larger size
+    /// (`--no-upscale`)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub not_upscaled: Vec<u32>,
+    /// Pages defining none of the boxes `--box` asks for, rendered with their
+    /// CropBox instead
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub box_fallbacks: Vec<u32>,
+}
+
+impl WorkerResult {
+    pub fn merge(&mut self, other: WorkerResult) {
+        self.pages_rendered += other.pages_rendered;
+        self.pages_extracted += other.pages_extracted;
+        self.pages_cached += other.pages_cached;
+        self.errors.extend(other.errors);
+        self.outputs.extend(other.outputs);
+        self.not_upscaled.extend(other.not_upscaled);
+        self.box_fallbacks.extend(other.box_fallbacks);
+    }
+}
+
+/// Rendering options shared between single-process and multi-process modes.
+pub struct RenderOptions {
+    pub size: RenderSize,
+    /// Caps applied after `size`; larger pages are scaled down to fit
+    pub max_width: Option<u32>,
+    pub max_height: Option<u32>,
+    /// Write each page at every one of these widths, largest first, rendering
+    /// it once at the first (`--widths`, see `parse_widths`); `size` is then
+    /// that width. Empty for one file per page. `render_pages` only, and not
+    /// combined with `extract_images`, `cache` or `align`
+    pub widths: Vec<u32>,
+    /// Render at this multiple of the output size, then downscale with
+    /// `resample` (`--supersample`)
+    pub supersample: Option<f32>,
+    /// Filter for the `widths` and `supersample` downscales; unused when
+    /// neither resizes the page
+    pub resample: ResampleFilter,
+    /// Never render a page larger than its native size, one pixel per point
+    /// (`--no-upscale`); clamped pages are listed in `WorkerResult::not_upscaled`
+    pub no_upscale: bool,
+    pub quality: u8,
+    pub box_type: BoxType,
+    pub extract_images: bool,
+    /// Decode extracted JPEGs and encode them with `quality`, `subsampling`
+    /// and `encoder` like rendered pages, instead of copying their bytes
+    pub reencode_extracted: bool,
+    pub encoder: JpegEncoderType,
+    /// Progressive instead of baseline JPEG
+    pub progressive: bool,
+    /// Chroma subsampling; `None` keeps the encoder's default
+    pub subsampling: Option<JpegSubsampling>,
+    pub password: Option<String>,
+    pub keep_order: bool,
+    /// Render only the first this many selected pages; only read by
+    /// `render::run` when planning
+    pub limit: Option<u32>,
+    pub grayscale: bool,
+    pub format: OutputFormat,
+    /// Lossless WebP instead of lossy at `quality` (WebP only)
+    pub lossless: bool,
+    /// AVIF encoder speed, 1 (smallest files) to 10 (fastest); `None` keeps
+    /// `DEFAULT_AVIF_SPEED` (AVIF only)
+    pub speed: Option<u8>,
+    pub background: Background,
+    pub rotation: Rotation,
+    /// Turn each page so its text reads upright (`--rotate-auto`); pages
+    /// without text keep the orientation their `/Rotate` gives them
+    pub rotate_auto: bool,
+    /// Render only this part of each page; `size` then applies to the region
+    pub region: Option<Region>,
+    /// Pad rendered images with `background` up to multiples of this many pixels
+    pub align: Option<u32>,
+    /// Trim margins that match `background` within this per-channel
+    /// tolerance (`--autocrop`); rendered pages only
+    pub autocrop: Option<u8>,
+    /// Measure each rendered page's skew and rotate it straight, filling the
+    /// corners with `background` (`--deskew`); rendered pages only
+    pub deskew: bool,
+    /// Embedded in JPEG and PNG output
+    pub icc_profile: Option<IccProfile>,
+    /// Drawn over every rendered page; extracted JPEGs are copied as is
+    pub watermark: Option<Watermark>,
+    /// Store JPEGs unrotated and record `rotation` as EXIF orientation
+    /// instead of turning the pixels
+    pub exif_orientation: bool,
+    /// Remove image objects before rendering, leaving text and vectors
+    pub no_images: bool,
+    /// pdfium rendering toggles; the default is pdfium's own default config
+    pub pdfium_flags: PdfiumFlags,
+    /// Layers hidden or shown before rendering (`--layers-off`, `--layers-on`)
+    pub layers: Layers,
+    /// Strip EXIF, XMP, IPTC and comment segments from JPEG output, including
+    /// extracted JPEGs, so output bytes depend only on the page and settings
+    pub reproducible: bool,
+    pub name_template: NameTemplate,
+    /// Name files by page label instead of number, where the page has one
+    pub label_names: bool,
+    /// Replace existing output files; when false `render::run` refuses to start
+    pub overwrite: bool,
+    /// Where `render::run` writes the JSON list of `WorkerResult::outputs`
+    pub manifest: Option<PathBuf>,
+    /// List the written files in the summary as `output_files`; only read by
+    /// `render::run`
+    pub list_outputs: bool,
+    pub progress: ProgressMode,
+    /// Skip the JSON summary on stdout (`--quiet`); only read by `render::run`
+    pub quiet: bool,
+    /// Per-page time limit; only enforced by `render_pages_with_watchdog`.
+    pub page_timeout: Option<Duration>,
+    /// Times `render::run` respawns a worker process that exits unsuccessfully
+    pub retries: u32,
+    /// Stop at the first failed page: `render_pages` returns `Error::Render`
+    /// and `render::run` stops the remaining workers
+    pub strict: bool,
+    /// Render the pages this many times over the same loaded document, for
+    /// profiling (`--repeat`); each iteration overwrites the previous files
+    pub repeat: u32,
+    /// Address-space limit in MB for each worker process; only read by
+    /// `render::run`, and only enforced on Unix
+    pub worker_mem_limit: Option<u64>,
+    /// Most worker processes running at once; the rest of the `--workers`
+    /// slices wait for a free slot. Only read by `render::run`
+    pub max_inflight: Option<u32>,
+    /// Directory of rendered pages keyed by page content and settings
+    /// (`--cache`); a page found there is copied instead of rendered
+    pub cache: Option<PathBuf>,
+}
+
+impl Default for RenderOptions {
+    /// The CLI defaults: native-size (1pt = 1px) JPEG at quality 100 on a white background.
+    fn default() -> Self {
+        RenderOptions {
+            size: RenderSize::NATIVE,
+            max_width: None,
+            max_height: None,
+            widths: Vec::new(),
+            supersample: None,
+            resample: ResampleFilter::Lanczos,
+            no_upscale: false,
+            quality: 100,
+            box_type: BoxType::Crop,
+            extract_images: false,
+            reencode_extracted: false,
+            encoder: JpegEncoderType::Image,
+            progressive: false,
+            subsampling: None,
+            password: None,
+            keep_order: false,
+            limit: None,
+            grayscale: false,
+            format: OutputFormat::Jpeg,
+            lossless: false,
+            speed: None,
+            background: Background::WHITE,
+            rotation: Rotation::None,
+            rotate_auto: false,
+            region: None,
+            align: None,
+            autocrop: None,
+            deskew: false,
+            icc_profile: None,
+            watermark: None,
+            exif_orientation: false,
+            no_images: false,
+            pdfium_flags: PdfiumFlags::default(),
+            layers: Layers::default(),
+            reproducible: false,
+            name_template: NameTemplate::default(),
+            label_names: false,
+            overwrite: true,
+            manifest: None,
+            list_outputs: false,
+            progress: ProgressMode::Human,
+            quiet: false,
+            page_timeout: None,
+            retries: 0,
+            strict: false,
+            repeat: 1,
+            worker_mem_limit: None,
+            max_inflight: None,
+            cache: None,
+        }
+    }
+}
+
+impl RenderOptions {
+    /// Reject flag combinations the output format cannot represent.
+    pub fn validate(&self) -> Result<(), Error> {
+        if self.background.is_transparent() && !self.format.supports_alpha() {
+            return Err(Error::InvalidArgs(
+                "a transparent background requires --format png, webp or avif (JPEG has no alpha channel)"
+                    .into(),
+            ));
+        }
+        if self.lossless && self.format != OutputFormat::Webp {
+            return Err(Error::InvalidArgs(
+                "--lossless only applies to --format webp".into(),
+            ));
+        }
+        if self.format == OutputFormat::Webp && !self.lossless && !cfg!(feature = "webp") {
+            return Err(Error::InvalidArgs(
+                "lossy --format webp requires building with --features webp; use --lossless".into(),
+            ));
+        }
+        if self.format == OutputFormat::Avif && !cfg!(feature = "avif") {
+            return Err(Error::InvalidArgs(
+                "--format avif requires building with --features avif".into(),
+            ));
+        }
+        if self.speed.is_some() && self.format != OutputFormat::Avif {
+            return Err(Error::InvalidArgs(
+                "--speed only applies to --format avif".into(),
+            ));
+        }
+        if self.speed.is_some_and(|speed| !(1..=10).contains(&speed)) {
+            return Err(Error::InvalidArgs(
+                "--speed must be between 1 and 10".into(),
+            ));
+        }
+        if self.extract_images && self.format != OutputFormat::Jpeg {
+            return Err(Error::InvalidArgs(
+                "--extract-images copies raw JPEG data and requires --format jpeg".into(),
+            ));
+        }
+        if (self.progressive || self.subsampling.is_some()) && self.format != OutputFormat::Jpeg {
+            return Err(Error::InvalidArgs(
+                "--progressive and --subsampling require --format jpeg".into(),
+            ));
+        }
+        if self.subsampling == Some(JpegSubsampling::Half) && self.encoder == JpegEncoderType::Vips
+        {
+            return Err(Error::InvalidArgs(
+                "--subsampling 422 is not supported by --encoder vips; use 444 or 420".into(),
+            ));
+        }
+        if self
+            .supersample
+            .is_some_and(|factor| !(1.0..=MAX_SUPERSAMPLE).contains(&factor))
+        {
+            return Err(Error::InvalidArgs(format!(
+                "--supersample must be between 1 and {MAX_SUPERSAMPLE}"
+            )));
+        }
+        if self.max_width == Some(0) || self.max_height == Some(0) {
+            return Err(Error::InvalidArgs(
+                "--max-width and --max-height must be greater than 0".into(),
+            ));
+        }
+        if self.page_timeout == Some(Duration::ZERO) {
+            return Err(Error::InvalidArgs(
+                "--page-timeout must be greater than 0".into(),
+            ));
+        }
+        if self.worker_mem_limit == Some(0) {
+            return Err(Error::InvalidArgs(
+                "--worker-mem-limit must be greater than 0".into(),
+            ));
+        }
+        if self.max_inflight == Some(0) {
+            return Err(Error::InvalidArgs(
+                "--max-inflight must be greater than 0".into(),
+            ));
+        }
+        if self.strict && self.retries > 0 {
+            return Err(Error::InvalidArgs(
+                "--strict stops at the first failure and cannot be combined with --retries".into(),
+            ));
+        }
+        if self.limit == Some(0) {
+            return Err(Error::InvalidArgs("--limit must be greater than 0".into()));
+        }
+        if self.repeat == 0 {
+            return Err(Error::InvalidArgs("--repeat must be greater than 0".into()));
+        }
+        if let Some(name) = self
+            .layers
+            .off
+            .iter()
+            .find(|name| self.layers.on.contains(name))
+        {
+            return Err(Error::InvalidArgs(format!(
+                "layer {name:?} is given to both --layers-off and --layers-on"
+            )));
+        }
+        if self.extract_images && self.rotation != Rotation::None {
+            return Err(Error::InvalidArgs(
+                "--extract-images copies raw JPEG data and cannot be combined with --rotate".into(),
+            ));
+        }
+        if self.region.is_some() && self.extract_images {
+            return Err(Error::InvalidArgs(
+                "--extract-images copies whole pages and cannot be combined with --region".into(),
+            ));
+        }
+        if self.align == Some(0) {
+            return Err(Error::InvalidArgs("--align must be greater than 0".into()));
+        }
+        if let RenderSize::Canvas { .. } = self.size {
+            let conflict = if self.align.is_some() {
+                Some("--align")
+            } else if self.extract_images {
+                Some("--extract-images")
+            } else if self.exif_orientation {
+                Some("--exif-orientation")
+            } else {
+                None
+            };
+            if let Some(conflict) = conflict {
+                return Err(Error::InvalidArgs(format!(
+                    "--canvas sets the exact output size and cannot be combined with {conflict}"
+                )));
+            }
+        }
+        if self.align.is_some() && self.extract_images {
+            return Err(Error::InvalidArgs(
+                "--extract-images copies raw JPEG data and cannot be combined with --align".into(),
+            ));
+        }
+        if self.region.is_some() && self.rotation != Rotation::None {
+            return Err(Error::InvalidArgs(
+                "--region cannot be combined with --rotate".into(),
+            ));
+        }
+        if self.rotate_auto {
+            let conflict = if self.rotation != Rotation::None {
+                Some("--rotate")
+            } else if self.exif_orientation {
+                Some("--exif-orientation")
+            } else if self.region.is_some() {
+                Some("--region")
+            } else if self.extract_images {
+                Some("--extract-images")
+            } else {
+                None
+            };
+            if let Some(conflict) = conflict {
+                return Err(Error::InvalidArgs(format!(
+                    "--rotate-auto picks each page's rotation and cannot be combined with \
+                     {conflict}"
+                )));
+            }
+        }
+        if self.icc_profile.is_some()
+            && !matches!(self.format, OutputFormat::Jpeg | OutputFormat::Png)
+        {
+            return Err(Error::InvalidArgs(
+                "--icc and --srgb require --format jpeg or png".into(),
+            ));
+        }
+        if self.no_images && self.extract_images {
+            return Err(Error::InvalidArgs(
+                "--no-images renders pages without their images and cannot be combined with \
+                 --extract-images"
+                    .into(),
+            ));
+        }
+        if self.exif_orientation && self.format != OutputFormat::Jpeg {
+            return Err(Error::InvalidArgs(
+                "--exif-orientation requires --format jpeg".into(),
+            ));
+        }
+        if self.exif_orientation && self.align.is_some() {
+            return Err(Error::InvalidArgs(
+                "--exif-orientation cannot be combined with --align: padding would land on \
+                 the wrong edges once viewers turn the image"
+                    .into(),
+            ));
+        }
+        if self.icc_profile.is_some() && self.extract_images {
+            return Err(Error::InvalidArgs(
+                "--extract-images copies raw JPEG data and cannot be combined with --icc or --srgb"
+                    .into(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// The rotation turned into the pixels: none when `exif_orientation`
+    /// leaves it to viewers.
+    pub fn baked_rotation(&self) -> Rotation {
+        if self.exif_orientation {
+            Rotation::None
+        } else {
+            self.rotation
+        }
+    }
+}
+
+/// Check `--widths` and order them largest first, dropping repeats.
+pub fn parse_widths(widths: &[u32]) -> Result<Vec<u32>, Error> {
+    if widths.contains(&0) {
+        return Err(Error::InvalidArgs(
+            "--widths must all be greater than 0".into(),
+        ));
+    }
+    let mut widths = widths.to_vec();
+    widths.sort_unstable_by(|a, b| b.cmp(a));
+    widths.dedup();
+    Ok(widths)
+}
+
+/// `path` with `-<width>` before its extension, naming one of the `--widths`
+/// files (`page-0001-480.jpg`).
+pub fn sized_output_path(path: &Path, width: u32) -> PathBuf {
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+    let name = match path.extension() {
+        Some(extension) => format!("{stem}-{width}.{}", extension.to_string_lossy()),
+        None => format!("{stem}-{width}"),
+    };
+    path.with_file_name(name)
+}
+
+/// Size of a `width` x `height` image scaled down to `target_width`, keeping
+/// its aspect ratio; images no wider than that keep their size.
+pub fn scaled_size(width: u32, height: u32, target_width: u32) -> (u32, u32) {
+    if target_width >= width {
+        return (width, height);
+    }
+    let scaled_height = (height as f64 * target_width as f64 / width as f64).round() as u32;
+    (target_width, scaled_height.max(1))
+}
+
+/// Output filename for a page, expanded from the `--name-template`.
+///
+/// `stem` is the PDF filename without extension. With `--label-names`,
+/// `label` is the page's label and replaces the number once sanitized. With
+/// `--keep-order`, `sequence` is the page's 1-based position in the request
+/// and prefixes the name so files sort in request order.
+pub fn output_filename(
+    template: &NameTemplate,
+    stem: &str,
+    page_num: u32,
+    label: Option<&str>,
+    sequence: Option<u32>,
+    format: OutputFormat,
+) -> String {
+    let name = match label.and_then(sanitize_label) {
+        Some(label) => template.render_label(&label, stem),
+        None => template.render(page_num, stem),
+    };
+    let extension = format.extension();
+    match sequence {
+        Some(sequence) => format!("{sequence:04}-{name}.{extension}"),
+        None => format!("{name}.{extension}"),
+    }
+}
+
+/// Render a range of pages from a PDF to image files.
+///
+/// Pages are 1-based. Each page produces `page-NNNN.jpg` (or `.png`, or a
+/// `--name-template` name) in `output_dir`
+/// (see `output_filename`); `first_sequence` is the request position of
+/// `pages[0]` and only matters with `keep_order`.
+/// When `extract_images` is true, pages containing a single JPEG image are
+/// extracted directly without re-encoding.
+///
+/// `opts.page_timeout` is ignored here; see `render_pages_with_watchdog`.
+#[cfg(not(test))]
+#[cfg_attr(coverage_nightly, coverage(off))]
+pub fn render_pages(
+    pdf_path: &Path,
+    output_dir: &Path,
+    pages: &[u32],
+    first_sequence: u32,
+    opts: &RenderOptions,
+) -> Result<WorkerResult, Error> {
+    let progress = Mutex::new(PageProgress::default());
+    render_pages_tracked(pdf_path, output_dir, pages, first_sequence, opts, &progress)?;
+    Ok(take_result(progress))
+}
+
+/// `render_pages` for a `render-worker` process, enforcing `timeout` per page.
+///
+/// pdfium calls can't be interrupted and its objects aren't `Send`, so a page
+/// that overruns can't be abandoned in-process. Instead a watchdog thread
+/// prints the partial `WorkerResult`, with `page N: timeout` and every
+/// unstarted page recorded as errors, and exits the process. The parent
+/// collects that output like any failed worker's.
+#[cfg(not(test))]
+#[cfg_attr(coverage_nightly, coverage(off))]
+pub fn render_pages_with_watchdog(
+    pdf_path: &Path,
+    output_dir: &Path,
+    pages: &[u32],
+    first_sequence: u32,
+    opts: &RenderOptions,
+    timeout: Duration,
+) -> Result<WorkerResult, Error> {
+    let progress = Mutex::new(PageProgress::default());
+    std::thread::scope(|scope| {
+        scope.spawn(|| watch_page_timeout(&progress, pages, timeout));
+        let outcome =
+            render_pages_tracked(pdf_path, output_dir, pages, first_sequence, opts, &progress);
+        lock(&progress).done = true;
+        outcome
+    })?;
+    Ok(take_result(progress))
+}
+
+/// Worker state shared between the render loop and the page-timeout watchdog.
+#[cfg(not(test))]
+#[derive(Default)]
+struct PageProgress {
+    result: WorkerResult,
+    /// Index into the page list of the page being processed, and its start time.
+    current: Option<(usize, Instant)>,
+    done: bool,
+}
+
+#[cfg(not(test))]
+fn lock(progress: &Mutex<PageProgress>) -> MutexGuard<'_, PageProgress> {
+    progress.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+#[cfg(not(test))]
+fn take_result(progress: Mutex<PageProgress>) -> WorkerResult {
+    progress
+        .into_inner()
+        .unwrap_or_else(PoisonError::into_inner)
+        .result
+}
+
+#[cfg(not(test))]
+#[cfg_attr(coverage_nightly, coverage(off))]
+fn watch_page_timeout(progress: &Mutex<PageProgress>, pages: &[u32], timeout: Duration) {
+    let tick = (timeout / 10).clamp(Duration::from_millis(10), Duration::from_millis(200));
+    loop {
+        std::thread::sleep(tick);
+        let mut state = lock(progress);
+        if state.done {
+            return;
+        }
+        let Some((index, started)) = state.current else {
+            continue;
+        };
+        if started.elapsed() < timeout {
+            continue;
+        }
+
+        // Keep the lock so the render loop can't touch the result again.
+        let mut result = std::mem::take(&mut state.result);
+        result.errors.extend(timeout_errors(pages, index, timeout));
+        println!("{}", serde_json::to_string(&result).unwrap());
+        let _ = std::io::stdout().flush();
+        // Same exit code as `Error::Render`.
+        std::process::exit(4);
+    }
+}
+
+/// Errors for a worker stopped because `pages[index]` exceeded the timeout.
+pub fn timeout_errors(pages: &[u32], index: usize, timeout: Duration) -> Vec<PageError> {
+    let timed_out = pages[index];
+    let mut errors = vec![PageError::new(
+        timed_out,
+        format!("timeout after {}s", timeout.as_secs()),
+    )];
+    errors.extend(pages[index + 1..].iter().map(|&page| {
+        PageError::new(
+            page,
+            format!("not rendered, worker stopped after page {timed_out} timed out"),
+        )
+    }));
+    errors
+}
+
+#[cfg(not(test))]
+#[cfg_attr(coverage_nightly, coverage(off))]
+fn render_pages_tracked(
+    pdf_path: &Path,
+    output_dir: &Path,
+    pages: &[u32],
+    first_sequence: u32,
+    opts: &RenderOptions,
+    progress: &Mutex<PageProgress>,
+) -> Result<(), Error> {
+    let pdfium = load_pdfium()?;
+    let mut document =
+        load_document_with_layers(&pdfium, pdf_path, opts.password.as_deref(), &opts.layers)?;
+
+    let stem = pdf_path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy())
+        .unwrap_or_default();
+    let cache = match &opts.cache {
+        Some(dir) => {
+            std::fs::create_dir_all(dir)?;
+            Some(PageCache::new(dir, opts))
+        }
+        None => None,
+    };
+    let mut strict_failure = None;
+    let mut iteration_secs = Vec::new();
+    for iteration in 0..opts.repeat.max(1) {
+        if iteration > 0 {
+            // Each iteration overwrites the previous one's files, so only the
+            // last one's report is kept.
+            lock(progress).result = WorkerResult::default();
+        }
+        let started = Instant::now();
+        for (index, (sequence, &page_num)) in (first_sequence..).zip(pages).enumerate() {
+            let label = opts
+                .label_names
+                .then(|| page_label(&document, page_num))
+                .flatten();
+            let filename = output_filename(
+                &opts.name_template,
+                &stem,
+                page_num,
+                label.as_deref(),
+                opts.keep_order.then_some(sequence),
+                opts.format,
+            );
+            lock(progress).current = Some((index, Instant::now()));
+            let mut page_result = WorkerResult::default();
+            process_page(
+                &mut document,
+                &output_dir.join(filename),
+                page_num,
+                opts,
+                cache.as_ref(),
+                &mut page_result,
+            );
+            if opts.strict {
+                strict_failure = page_result.errors.first().map(PageError::to_string);
+            }
+            let mut state = lock(progress);
+            state.result.merge(page_result);
+            state.current = None;
+            if strict_failure.is_some() {
+                break;
+            }
+        }
+        iteration_secs.push((started.elapsed().as_secs_f64() * 1000.0).round() / 1000.0);
+        if strict_failure.is_some() {
+            break;
+        }
+    }
+
+    let mut state = lock(progress);
+    if opts.repeat > 1 {
+        state.result.iteration_secs = iteration_secs;
+    }
+    if opts.progress == ProgressMode::Human
+        && state.result.pages_rendered + state.result.pages_extracted > 0
+    {
+        eprintln!();
+    }
+    match strict_failure {
+        Some(failure) => Err(Error::Render(failure)),
+        None => Ok(()),
+    }
+}
+
+/// The page's label (`iii`, `A-1`), if the document defines page labels.
+#[cfg(not(test))]
+#[cfg_attr(coverage_nightly, coverage(off))]
+pub fn page_label(document: &PdfDocument, page_num: u32) -> Option<String> {
+    let page = document.pages().get((page_num - 1) as u16).ok()?;
+    page.label().map(str::to_string)
+}
+
+#[cfg(not(test))]
+#[cfg_attr(coverage_nightly, coverage(off))]
+fn process_page(
+    document: &mut PdfDocument,
+    path: &Path,
+    page_num: u32,
+    opts: &RenderOptions,
+    cache: Option<&PageCache>,
+    result: &mut WorkerResult,
+) {
+    let page_index = (page_num - 1) as u16;
+    if apply_box(document, page_index, opts.box_type) {
+        result.box_fallbacks.push(page_num);
+    }
+    let mut page = match document.pages().get(page_index) {
+        Ok(page) => page,
+        Err(e) => {
+            result.errors.push(PageError::new(page_num, e.to_string()));
+            report_page(opts.progress, page_num, PageStatus::Failed);
+            return;
+        }
+    };
+    if opts.no_images {
+        remove_image_objects(&mut page);
+    }
+
+    if opts.extract_images
+        && let Some(Ok(sha256)) = try_extract_jpeg(&page, path, page_num, opts)
+    {
+        result.pages_extracted += 1;
+        let dimensions = image::image_dimensions(path).unwrap_or_default();
+        result.outputs.push(manifest_entry(
+            page_num,
+            path,
+            PageStatus::Extracted,
+            page_size(&page),
+            dimensions,
+            sha256,
+        ));
+        report_page(opts.progress, page_num, PageStatus::Extracted);
+        return;
+    }
+
+    let cache_entry = cache.map(|cache| cache.entry(&page_content_hash(&page)));
+    if let Some(entry) = &cache_entry
+        && let Ok(sha256) = copy_cached(entry, path)
+    {
+        result.pages_rendered += 1;
+        result.pages_cached += 1;
+        if page_render_config(&page, opts).is_ok_and(|(_, setup)| setup.not_upscaled) {
+            result.not_upscaled.push(page_num);
+        }
+        let dimensions = image::image_dimensions(path).unwrap_or_default();
+        result.outputs.push(manifest_entry(
+            page_num,
+            path,
+            PageStatus::Rendered,
+            page_size(&page),
+            dimensions,
+            sha256,
+        ));
+        report_page(opts.progress, page_num, PageStatus::Rendered);
+        return;
+    }
+
+    match page_render_config(&page, opts).and_then(|(config, setup)| {
+        let written = if opts.widths.is_empty() {
+            render_page_to_file(&page, &config, setup, path, opts)
+                .map(|(dimensions, sha256)| vec![(path.to_path_buf(), dimensions, sha256)])
+        } else {
+            render_page_to_widths(&page, &config, setup, path, opts)
+        };
+        written.map(|written| (written, setup.not_upscaled))
+    }) {
+        Ok((written, not_upscaled)) => {
+            result.pages_rendered += 1;
+            if not_upscaled {
+                result.not_upscaled.push(page_num);
+            }
+            if let Some(entry) = &cache_entry
+                && let Err(e) = crate::page_cache::store(path, entry)
+            {
+                eprintln!("warning: page {page_num}: not cached: {e}");
+            }
+            for (file, dimensions, sha256) in written {
+                result.outputs.push(manifest_entry(
+                    page_num,
+                    &file,
+                    PageStatus::Rendered,
+                    page_size(&page),
+                    dimensions,
+                    sha256,
+                ));
+            }
+            report_page(opts.progress, page_num, PageStatus::Rendered);
+        }
+        Err(e) => {
+            result.errors.push(PageError::new(page_num, e.to_string()));
+            report_page(opts.progress, page_num, PageStatus::Failed);
+        }
+    }
+}
+
+/// The page's width and height in points.
+#[cfg(not(test))]
+#[cfg_attr(coverage_nightly, coverage(off))]
+fn page_size(page: &PdfPage) -> (f32, f32) {
+    (page.width().value, page.height().value)
+}
+
+#[cfg(not(test))]
+#[cfg_attr(coverage_nightly, coverage(off))]
+fn manifest_entry(
+    page: u32,
+    path: &Path,
+    method: PageStatus,
+    (width_pt, height_pt): (f32, f32),
+    (width_px, height_px): (u32, u32),
+    sha256: String,
+) -> ManifestEntry {
+    ManifestEntry {
+        page,
+        file: path.display().to_string(),
+        method,
+        width_pt,
+        height_pt,
+        width_px,
+        height_px,
+        bytes: std::fs::metadata(path).map_or(0, |metadata| metadata.len()),
+        sha256,
+    }
+}
+
+/// Render `pages` in order as the frames of one multipage TIFF at `path`.
+///
+/// Used by `render --single-file`. Frames are appended as each page renders,
+/// so only one page is held in memory; failed pages are left out and
+/// reported in the result.
+#[cfg(not(test))]
+#[cfg_attr(coverage_nightly, coverage(off))]
+pub fn render_pages_to_tiff(
+    pdf_path: &Path,
+    pages: &[u32],
+    path: &Path,
+    opts: &RenderOptions,
+) -> Result<WorkerResult, Error> {
+    let pdfium = load_pdfium()?;
+    let mut document =
+        load_document_with_layers(&pdfium, pdf_path, opts.password.as_deref(), &opts.layers)?;
+    let mut encoder = tiff::encoder::TiffEncoder::new(BufWriter::new(File::create(path)?))
+        .map_err(|e| Error::Render(format!("TIFF encode failed: {e}")))?;
+
+    let mut result = WorkerResult::default();
+    for &page_num in pages {
+        // `render_page` applies the box again, to the same effect.
+        if apply_box(&mut document, (page_num - 1) as u16, opts.box_type) {
+            result.box_fallbacks.push(page_num);
+        }
+        match render_page(&mut document, page_num, opts)
+            .and_then(|image| write_tiff_frame(&mut encoder, &image))
+        {
+            Ok(()) => {
+                result.pages_rendered += 1;
+                report_page(opts.progress, page_num, PageStatus::Rendered);
+            }
+            Err(e) => {
+                report_page(opts.progress, page_num, PageStatus::Failed);
+                if opts.strict {
+                    return Err(Error::Render(format!("page {page_num}: {e}")));
+                }
+                result.errors.push(PageError::new(page_num, e.to_string()));
+            }
+        }
+    }
+    if opts.progress == ProgressMode::Human && result.pages_rendered > 0 {
+        eprintln!();
+    }
+    Ok(result)
+}
+
+/// Override the page's CropBox with the first box `box_type` prefers that the
+/// page defines. Pages defining none of them keep their CropBox, which pdfium
+/// already falls back to the MediaBox; for those this returns `true`.
+#[cfg(not(test))]
+#[cfg_attr(coverage_nightly, coverage(off))]
+fn apply_box(document: &mut PdfDocument, page_index: u16, box_type: BoxType) -> bool {
+    let preferred: &[PdfPageBoundaryBoxType] = match box_type {
+        BoxType::Crop => return false,
+        BoxType::Bleed => &[PdfPageBoundaryBoxType::Bleed],
+        BoxType::Auto => &[PdfPageBoundaryBoxType::Trim, PdfPageBoundaryBoxType::Bleed],
+    };
+    // A page that fails to load is reported when it is rendered.
+    let Ok(mut page) = document.pages().get(page_index) else {
+        return false;
+    };
+
+    let Some(rect) = preferred
+        .iter()
+        .find_map(|&boundary| page.boundaries().get(boundary).ok())
+        .map(|boundary| boundary.bounds)
+    else {
+        return true;
+    };
+    let _ = page
+        .boundaries_mut()
+        .set(PdfPageBoundaryBoxType::Crop, rect);
+    false
+}
+
+/// Delete the page's image objects (`--no-images`) so only text and vector
+/// content render. Only the loaded document changes, never the file; images
+/// nested in form XObjects are left in place.
+///
+/// Goes through the raw bindings, like `layers::apply_to_page`: pdfium-render
+/// destroys a removed `PdfPageObject` twice when it drops, which crashes.
+#[cfg(not(test))]
+#[cfg_attr(coverage_nightly, coverage(off))]
+fn remove_image_objects(page: &mut PdfPage) {
+    let bindings = page.bindings();
+    let handle = bindings.get_handle_from_page(page);
+    // Backwards, so removals don't shift the indices still to visit.
+    for index in (0..bindings.FPDFPage_CountObjects(handle)).rev() {
+        let object = bindings.FPDFPage_GetObject(handle, index);
+        if bindings.FPDFPageObj_GetType(object) == FPDF_PAGEOBJ_IMAGE as i32
+            && bindings.FPDFPage_RemoveObject(handle, object) != 0
+        {
+            bindings.FPDFPageObj_Destroy(object);
+        }
+    }
+}
+
+/// Whether `--extract-images` would copy this page's JPEG instead of rendering it.
+///
+/// True for pages holding exactly one object: a non-CMYK DCTDecode image whose
+/// aspect ratio matches the page.
+#[cfg(not(test))]
+#[cfg_attr(coverage_nightly, coverage(off))]
+pub fn is_single_jpeg_page(page: &PdfPage) -> bool {
+    let objects = page.objects();
+    if objects.len() != 1 {
+        return false;
+    }
+    let Ok(obj) = objects.get(0) else {
+        return false;
+    };
+    let Some(image_obj) = obj.as_image_object() else {
+        return false;
+    };
+
+    // PDFs can embed a full two-page spread and use CropBox to show one half.
+    // Skip extraction if the image aspect ratio doesn't match the page.
+    is_extractable_jpeg(image_obj) && image_matches_page_aspect(image_obj, page)
+}
+
+/// Try to extract a raw JPEG from a page that contains a single image object.
+///
+/// Returns `None` if `is_single_jpeg_page` rejects the page. Returns
+/// `Some(Ok(sha256))` on successful extraction, `Some(Err(..))` on I/O failure.
+#[cfg(not(test))]
+#[cfg_attr(coverage_nightly, coverage(off))]
+fn try_extract_jpeg(
+    page: &PdfPage,
+    path: &Path,
+    page_num: u32,
+    opts: &RenderOptions,
+) -> Option<Result<String, Error>> {
+    if !is_single_jpeg_page(page) {
+        return None;
+    }
+
+    let obj = page.objects().get(0).ok()?;
+    let image_obj = obj.as_image_object()?;
+    // The image fills the page, so its resolution is its width over the page's.
+    let dpi = image_obj
+        .width()
+        .map_or(72.0, |width| width as f32 / page.width().value * 72.0);
+    Some(write_raw_jpeg(image_obj, path, page_num, dpi, opts))
+}
+
+/// Check if the embedded image's aspect ratio roughly matches the page's.
+///
+/// A spread image (landscape) embedded in a portrait page means the page is
+/// cropping to show only part of the image — raw extraction would be wrong.
+#[cfg(not(test))]
+#[cfg_attr(coverage_nightly, coverage(off))]
+fn image_matches_page_aspect(image_obj: &PdfPageImageObject, page: &PdfPage) -> bool {
+    let (Ok(img_w), Ok(img_h)) = (image_obj.width(), image_obj.height()) else {
+        return true;
+    };
+    let (pw, ph) = (page.width().value as f64, page.height().value as f64);
+    aspect_ratios_match(img_w as f64, img_h as f64, pw, ph)
+}
+
+/// Returns true if two rectangles have similar aspect ratios (within 10%).
+fn aspect_ratios_match(w1: f64, h1: f64, w2: f64, h2: f64) -> bool {
+    if w1 == 0.0 || h1 == 0.0 || w2 == 0.0 || h2 == 0.0 {
+        return true;
+    }
+    let ratio_diff = ((w1 / h1) - (w2 / h2)).abs() / (w2 / h2);
+    ratio_diff < 0.1
+}
+
+#[cfg(not(test))]
+#[cfg_attr(coverage_nightly, coverage(off))]
+pub(crate) fn is_extractable_jpeg(image_obj: &PdfPageImageObject) -> bool {
+    let filters = image_obj.filters();
+    if filters.len() != 1 {
+        return false;
+    }
+    if !matches!(filters.get(0).ok(), Some(f) if f.name() == "DCTDecode") {
+        return false;
+    }
+    // CMYK JPEGs extracted raw get their colors inverted when decoded as RGB.
+    // Detect CMYK via raw JPEG SOF marker (covers DeviceCMYK and ICC-based CMYK).
+    let data = match image_obj.get_raw_image_data() {
+        Ok(d) => d,
+        Err(_) => return false,
+    };
+    if jpeg_is_cmyk(&data) {
+        return false;
+    }
+    true
+}
+
+/// Check if raw JPEG data is CMYK by reading the SOF marker's component count.
+/// CMYK JPEGs have 4 components; RGB has 3, grayscale has 1.
+fn jpeg_is_cmyk(data: &[u8]) -> bool {
+    jpeg_component_count(data) == Some(4)
+}
+
+/// Read the component count from the first SOF marker, if one is present.
+fn jpeg_component_count(data: &[u8]) -> Option<u8> {
+    // Scan for SOF0 (0xFFC0) or SOF2 (0xFFC2) marker
+    let mut i = 0;
+    while i + 1 < data.len() {
+        if data[i] != 0xFF {
+            i += 1;
+            continue;
+        }
+        let marker = data[i + 1];
+        // SOF0 = 0xC0, SOF1 = 0xC1, SOF2 = 0xC2
+        if matches!(marker, 0xC0..=0xC2) {
+            // SOF layout: FF Cn [length:2] [precision:1] [height:2] [width:2] [components:1]
+            return data.get(i + 9).copied();
+        }
+        // Skip non-SOF markers (read length and advance)
+        if marker == 0xD8 || marker == 0xD9 || marker == 0x00 {
+            i += 2;
+        } else if i + 3 < data.len() {
+            let len = u16::from_be_bytes([data[i + 2], data[i + 3]]) as usize;
+            i += 2 + len;
+        } else {
+            break;
+        }
+    }
+    None
+}
+
+/// Copy the image's JPEG bytes to `path`, or re-encode them at `dpi` with
+/// `--reencode-extracted`. Returns the hex SHA-256 of what was written.
+#[cfg(not(test))]
+#[cfg_attr(coverage_nightly, coverage(off))]
+fn write_raw_jpeg(
+    image_obj: &PdfPageImageObject,
+    path: &Path,
+    page_num: u32,
+    dpi: f32,
+    opts: &RenderOptions,
+) -> Result<String, Error> {
+    let data = image_obj
+        .get_raw_image_data()
+        .map_err(|e| Error::Render(format!("extract image data: {e}")))?;
+    if data.is_empty() {
+        return Err(Error::Render("empty image data".into()));
+    }
+
+    // Validate with libjpeg-turbo (same decoder as libvips). If it fails,
+    // the `image` crate can still decode it — re-encode to produce a clean
+    // JPEG that vips will accept.
+    if turbojpeg::decompress(&data, turbojpeg::PixelFormat::RGB).is_err() {
+        let img = image::load_from_memory_with_format(&data, image::ImageFormat::Jpeg)
+            .map_err(|e| Error::Render(format!("JPEG decode failed: {e}")))?;
+        let mut writer = HashingWriter::new(BufWriter::new(File::create(path)?));
+        img.into_rgb8()
+            .write_with_encoder(JpegEncoder::new_with_quality(&mut writer, 100))
+            .map_err(|e| Error::Render(format!("JPEG re-encode failed: {e}")))?;
+        if opts.progress == ProgressMode::Human {
+            eprintln!("  (re-encoded corrupt JPEG for page {page_num})");
+        }
+        return Ok(writer.finish()?);
+    }
+
+    if opts.reencode_extracted {
+        let image = image::load_from_memory_with_format(&data, image::ImageFormat::Jpeg)
+            .map_err(|e| Error::Render(format!("JPEG decode failed: {e}")))?;
+        // Grayscale JPEGs stay single-component, as rendered --grayscale pages are.
+        let image = match image {
+            DynamicImage::ImageLuma8(_) => image,
+            other => DynamicImage::ImageRgb8(other.into_rgb8()),
+        };
+        let mut writer = HashingWriter::new(BufWriter::new(File::create(path)?));
+        encode_page(&image, dpi, &mut writer, opts)?;
+        return Ok(writer.finish()?);
+    }
+    let mut writer = HashingWriter::new(BufWriter::new(File::create(path)?));
+    if opts.reproducible {
+        writer.write_all(&strip_jpeg_metadata(&data))?;
+    } else {
+        writer.write_all(&data)?;
+    }
+    Ok(writer.finish()?)
+}
+
+/// Drop the APP1 (EXIF, XMP), APP13 (IPTC) and comment segments from a JPEG,
+/// where creation tools leave timestamps. Other segments, including ICC
+/// profiles and Adobe color transforms, are kept; the scan data is untouched.
+///
+/// Input that doesn't parse as a JPEG header is returned unchanged.
+pub fn strip_jpeg_metadata(jpeg: &[u8]) -> Vec<u8> {
+    const APP1: u8 = 0xE1;
+    const APP13: u8 = 0xED;
+    const COM: u8 = 0xFE;
+    const SOS: u8 = 0xDA;
+
+    if !jpeg.starts_with(&[0xFF, 0xD8]) {
+        return jpeg.to_vec();
+    }
+    let mut out = jpeg[..2].to_vec();
+    let mut pos = 2;
+    while pos + 4 <= jpeg.len() {
+        if jpeg[pos] != 0xFF {
+            return jpeg.to_vec();
+        }
+        let marker = jpeg[pos + 1];
+        if marker == 0xFF {
+            // Fill byte before a marker
+            pos += 1;
+            continue;
+        }
+        if marker == SOS {
+            break;
+        }
+        let end = pos + 2 + u16::from_be_bytes([jpeg[pos + 2], jpeg[pos + 3]]) as usize;
+        if end > jpeg.len() {
+            return jpeg.to_vec();
+        }
+        if !matches!(marker, APP1 | APP13 | COM) {
+            out.extend_from_slice(&jpeg[pos..end]);
+        }
+        pos = end;
+    }
+    out.extend_from_slice(&jpeg[pos.min(jpeg.len())..]);
+    out
+}
+
+/// End of the SOI marker and the JFIF APP0 segment after it, if any: where
+/// other APPn segments go.
+fn jfif_end(jpeg: &[u8]) -> usize {
+    if jpeg.len() >= 6 && jpeg[2..].starts_with(&[0xFF, 0xE0]) {
+        (4 + u16::from_be_bytes([jpeg[4], jpeg[5]]) as usize).min(jpeg.len())
+    } else {
+        2
+    }
+}
+
+/// Record `dpi` in the JFIF APP0 density fields (units: dots per inch),
+/// adding a JFIF header when the encoder wrote none.
+///
+/// Input that isn't a JPEG stream is returned unchanged.
+pub fn set_jfif_density(jpeg: &[u8], dpi: u16) -> Vec<u8> {
+    const JFIF_TAG: &[u8; 5] = b"JFIF\0";
+
+    if !jpeg.starts_with(&[0xFF, 0xD8]) {
+        return jpeg.to_vec();
+    }
+    let mut out = jpeg.to_vec();
+    // FFE0, length, "JFIF\0", version, then units at 13 and densities at 14..18.
+    if jfif_end(jpeg) >= 20 && &jpeg[6..11] == JFIF_TAG {
+        out[13] = 1;
+        out[14..16].copy_from_slice(&dpi.to_be_bytes());
+        out[16..18].copy_from_slice(&dpi.to_be_bytes());
+        return out;
+    }
+    let mut app0 = vec![0xFF, 0xE0, 0x00, 0x10];
+    app0.extend_from_slice(JFIF_TAG);
+    app0.extend_from_slice(&[1, 1, 1]);
+    app0.extend_from_slice(&dpi.to_be_bytes());
+    app0.extend_from_slice(&dpi.to_be_bytes());
+    app0.extend_from_slice(&[0, 0]);
+    out.splice(2..2, app0);
+    out
+}
+
+/// Insert an EXIF APP1 segment holding only the Orientation tag after the
+/// JFIF header.
+pub fn embed_exif_orientation(jpeg: &[u8], orientation: u16) -> Vec<u8> {
+    // Big-endian TIFF header, then IFD0 with one SHORT entry and no next IFD.
+    let mut exif = b"Exif\0\0MM\0\x2A\0\0\0\x08\0\x01\x01\x12\0\x03\0\0\0\x01".to_vec();
+    exif.extend_from_slice(&orientation.to_be_bytes());
+    exif.extend_from_slice(&[0, 0, 0, 0, 0, 0]);
+
+    let insert_at = jfif_end(jpeg);
+    let mut out = Vec::with_capacity(jpeg.len() + exif.len() + 4);
+    out.extend_from_slice(&jpeg[..insert_at]);
+    out.extend_from_slice(&[0xFF, 0xE1]);
+    out.extend_from_slice(&(exif.len() as u16 + 2).to_be_bytes());
+    out.extend_from_slice(&exif);
+    out.extend_from_slice(&jpeg[insert_at..]);
+    out
+}
+
+/// A writer that hashes everything passed through it, so output files get
+/// their checksum without being read back.
+pub struct HashingWriter<W: Write> {
+    inner: W,
+    hasher: Sha256,
+}
+
+impl<W: Write> HashingWriter<W> {
+    pub fn new(inner: W) -> Self {
+        Self {
+            inner,
+            hasher: Sha256::new(),
+        }
+    }
+
+    /// Flush the inner writer and return the hex SHA-256 of the bytes written.
+    pub fn finish(mut self) -> std::io::Result<String> {
+        self.inner.flush()?;
+        Ok(format!("{:x}", self.hasher.finalize()))
+    }
+}
+
+impl<W: Write> Write for HashingWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let written = self.inner.write(buf)?;
+        self.hasher.update(&buf[..written]);
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// Render and encode one page to `path`, returning the image's size and the
+/// file's hex SHA-256.
+#[cfg(not(test))]
+#[cfg_attr(coverage_nightly, coverage(off))]
+fn render_page_to_file(
+    page: &PdfPage,
+    config: &PdfRenderConfig,
+    setup: PageSetup,
+    path: &Path,
+    opts: &RenderOptions,
+) -> Result<((u32, u32), String), Error> {
+    let (image, dpi) = render_page_image(page, config, setup, opts)?;
+    let mut writer = HashingWriter::new(BufWriter::new(File::create(path)?));
+    encode_page(&image, dpi, &mut writer, opts)?;
+    Ok(((image.width(), image.height()), writer.finish()?))
+}
+
+/// A written file's path, pixel size and hex SHA-256.
+#[cfg(not(test))]
+type WrittenFile = (PathBuf, (u32, u32), String);
+
+/// Render one page at the largest of `opts.widths` and write it, then each
+/// smaller width scaled down from that image, to `sized_output_path`. Returns
+/// every file's path, size and hex SHA-256, largest first.
+///
+/// A width the rendered image does not reach (`--max-width`, `--no-upscale`,
+/// `--autocrop`) is written at the rendered size.
+#[cfg(not(test))]
+#[cfg_attr(coverage_nightly, coverage(off))]
+fn render_page_to_widths(
+    page: &PdfPage,
+    config: &PdfRenderConfig,
+    setup: PageSetup,
+    path: &Path,
+    opts: &RenderOptions,
+) -> Result<Vec<WrittenFile>, Error> {
+    let (image, dpi) = render_page_image(page, config, setup, opts)?;
+    let mut written = Vec::with_capacity(opts.widths.len());
+    for &width in &opts.widths {
+        let (scaled_width, scaled_height) = scaled_size(image.width(), image.height(), width);
+        let scaled;
+        let output = if scaled_width == image.width() {
+            &image
+        } else {
+            scaled = image.resize_exact(scaled_width, scaled_height, opts.resample.filter_type());
+            &scaled
+        };
+        let file = sized_output_path(path, width);
+        let mut writer = HashingWriter::new(BufWriter::new(File::create(&file)?));
+        let output_dpi = dpi * scaled_width as f32 / image.width() as f32;
+        encode_page(output, output_dpi, &mut writer, opts)?;
+        written.push((file, (scaled_width, scaled_height), writer.finish()?));
+    }
+    Ok(written)
+}
+
+/// Render a single page and write the encoded image to stdout.
+///
+/// Used by `render --stdout`; nothing is written to disk and no progress is
+/// printed, so stdout carries only the image bytes.
+#[cfg(not(test))]
+#[cfg_attr(coverage_nightly, coverage(off))]
+pub fn render_page_to_stdout(
+    pdf_path: &Path,
+    page_num: u32,
+    opts: &RenderOptions,
+) -> Result<(), Error> {
+    let pdfium = load_pdfium()?;
+    let mut document =
+        load_document_with_layers(&pdfium, pdf_path, opts.password.as_deref(), &opts.layers)?;
+    let (image, dpi) = render_page_with_dpi(&mut document, page_num, opts)?;
+    let mut stdout = std::io::stdout().lock();
+    encode_page(&image, dpi, &mut stdout, opts)?;
+    stdout.flush()?;
+    Ok(())
+}
+
+/// Render one 1-based page of an already loaded document to an image.
+///
+/// Applies sizing, rotation, background, watermark, grayscale and `--box` from
+/// `opts`; output format and filename options are ignored. `BoxType::Bleed`
+/// and `BoxType::Auto` replace the page's crop box in `document`.
+///
+/// ```no_run
+/// use pdf::pdfium_render::prelude::*;
+/// use pdf::{RenderOptions, RenderSize, render_page};
+///
+/// let pdfium = Pdfium::new(Pdfium::bind_to_system_library()?);
+/// let mut document = pdfium.load_pdf_from_file("comic.pdf", None)?;
+/// let opts = RenderOptions {
+///     size: RenderSize::TargetWidth(1280),
+///     ..RenderOptions::default()
+/// };
+///
+/// let image = render_page(&mut document, 1, &opts)?;
+/// image.save("page-1.jpg")?;
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+#[cfg(not(test))]
+#[cfg_attr(coverage_nightly, coverage(off))]
+pub fn render_page(
+    document: &mut PdfDocument,
+    page_num: u32,
+    opts: &RenderOptions,
+) -> Result<DynamicImage, Error> {
+    render_page_with_dpi(document, page_num, opts).map(|(image, _)| image)
+}
+
+/// `render_page`, also returning the resolution the page was rendered at.
+#[cfg(not(test))]
+#[cfg_attr(coverage_nightly, coverage(off))]
+fn render_page_with_dpi(
+    document: &mut PdfDocument,
+    page_num: u32,
+    opts: &RenderOptions,
+) -> Result<(DynamicImage, f32), Error> {
+    let page_index = (page_num - 1) as u16;
+    apply_box(document, page_index, opts.box_type);
+    let mut page = document
+        .pages()
+        .get(page_index)
+        .map_err(|e| Error::Render(format!("page {page_num}: {e}")))?;
+    if opts.no_images {
+        remove_image_objects(&mut page);
+    }
+
+    let (config, setup) = page_render_config(&page, opts)?;
+    render_page_image(&page, &config, setup, opts)
+}
+
+/// What `page_render_config` chose for a page, for `render_page_image`.
+#[cfg(not(test))]
+#[derive(Clone, Copy)]
+struct PageSetup {
+    /// The `--supersample` factor the page renders at (1.0 without it), which
+    /// `render_page_image` scales back down by
+    supersample: f64,
+    /// The rotation turned into the pixels
+    baked_rotation: Rotation,
+    /// `--no-upscale` kept the page below the requested size
+    not_upscaled: bool,
+}
+
+/// The clockwise turn of `page` as displayed: `--rotate`, or with
+/// `--rotate-auto` the turn that levels its text.
+#[cfg(not(test))]
+#[cfg_attr(coverage_nightly, coverage(off))]
+fn page_rotation(page: &PdfPage, opts: &RenderOptions) -> Rotation {
+    if !opts.rotate_auto {
+        return opts.rotation;
+    }
+    let angles: Vec<f32> = page
+        .text()
+        .map(|text| {
+            text.chars()
+                .iter()
+                .filter_map(|text_char| text_char.angle_degrees().ok())
+                .collect()
+        })
+        .unwrap_or_default();
+    level_rotation(angles, page.rotation().map_or(0, rotation_degrees))
+}
+
+/// The render settings for `page`, and the supersampling and rotation they
+/// use.
+#[cfg(not(test))]
+#[cfg_attr(coverage_nightly, coverage(off))]
+fn page_render_config(
+    page: &PdfPage,
+    opts: &RenderOptions,
+) -> Result<(PdfRenderConfig, PageSetup), Error> {
+    // Built per page: with --dpi the pixel width depends on each page's size.
+    // The width applies to the rotated output, so a quarter turn sizes from the
+    // page height and the constraint itself is not rotated. --max-width,
+    // --max-height and --no-upscale shrink the computed size here, before any
+    // bitmap exists.
+    if !(page.width().value > 0.0 && page.height().value > 0.0) {
+        return Err(Error::PdfInvalid("zero-size page".into()));
+    }
+    // Sizes apply to the image as displayed, which with --exif-orientation is
+    // the stored image turned by the viewer.
+    let rotation = page_rotation(page, opts);
+    let baked_rotation = if opts.exif_orientation {
+        Rotation::None
+    } else {
+        rotation
+    };
+    let (output_width_pt, output_height_pt) = if rotation.is_quarter_turn() {
+        (page.height().value, page.width().value)
+    } else {
+        (page.width().value, page.height().value)
+    };
+    let mut config = PdfRenderConfig::new();
+    let supersample;
+    let not_upscaled;
+    if let Some(region) = opts.region {
+        // Scale the whole page so the region gets the requested size, and
+        // only rasterize the region; `render_page_image` crops to it.
+        let region_width_px = opts.size.pixel_width(region.width, region.height) as f64;
+        let region_height_px = region_width_px * (region.height / region.width) as f64;
+        let max_scale = max_size_scale(
+            region_width_px,
+            region_height_px,
+            opts.max_width,
+            opts.max_height,
+        );
+        let upscale_cap = no_upscale_scale(region_width_px, region.width, opts.no_upscale);
+        not_upscaled = upscale_cap < max_scale;
+        let scale = max_scale.min(upscale_cap);
+        let mut pixels_per_point = (region_width_px * scale) as f32 / region.width;
+        // pdfium allocates the whole page and clips; that is what has to fit.
+        supersample = supersample_factor(
+            (page.width().value * pixels_per_point) as f64,
+            (page.height().value * pixels_per_point) as f64,
+            opts.supersample,
+        );
+        pixels_per_point *= supersample as f32;
+        check_bitmap_size(
+            (page.width().value * pixels_per_point) as f64,
+            (page.height().value * pixels_per_point) as f64,
+        )?;
+        let (left, top, width, height) = region.pixel_rect(pixels_per_point, u32::MAX, u32::MAX);
+        config = config
+            .set_target_width((page.width().value * pixels_per_point).round() as i32)
+            .clip(
+                left as i32,
+                top as i32,
+                (left + width) as i32,
+                (top + height) as i32,
+            );
+    } else {
+        let width_px = opts.size.pixel_width(output_width_pt, output_height_pt) as f64;
+        let height_px = width_px * (output_height_pt / output_width_pt) as f64;
+        let max_scale = max_size_scale(width_px, height_px, opts.max_width, opts.max_height);
+        let upscale_cap = no_upscale_scale(width_px, output_width_pt, opts.no_upscale);
+        not_upscaled = upscale_cap < max_scale;
+        let max_scale = max_scale.min(upscale_cap);
+        supersample = supersample_factor(
+            width_px * max_scale,
+            height_px * max_scale,
+            opts.supersample,
+        );
+        let scale = max_scale * supersample;
+        check_bitmap_size(width_px * scale, height_px * scale)?;
+        // pdfium-render scales from the unrotated page, whose height is the
+        // displayed width after a quarter turn, baked in or left to EXIF.
+        config = match (opts.size, rotation.is_quarter_turn()) {
+            (RenderSize::TargetHeight(height), false) => {
+                config.set_target_height((height as f64 * scale).round() as i32)
+            }
+            (RenderSize::TargetHeight(height), true) => {
+                config.set_target_width((height as f64 * scale).round() as i32)
+            }
+            (_, false) => config.set_target_width((width_px * scale).round() as i32),
+            (_, true) => config.set_target_height((width_px * scale).round() as i32),
+        }
+        .rotate(render_rotation(baked_rotation), true);
+    }
+    let background = opts.background;
+    let config = opts
+        .pdfium_flags
+        .apply(config)
+        .set_clear_color(PdfColor::new(
+            background.red,
+            background.green,
+            background.blue,
+            background.alpha,
+        ));
+    let config = if opts.pdfium_flags.lcd_text && !background.is_transparent() {
+        config.set_format(PdfBitmapFormat::BGRx)
+    } else {
+        config
+    };
+    Ok((
+        config,
+        PageSetup {
+            supersample,
+            baked_rotation,
+            not_upscaled,
+        },
+    ))
+}
+
+/// Degrees clockwise of a page's `/Rotate`, as pdfium reports it.
+#[cfg(not(test))]
+pub fn rotation_degrees(rotation: PdfPageRenderRotation) -> u32 {
+    match rotation {
+        PdfPageRenderRotation::None => 0,
+        PdfPageRenderRotation::Degrees90 => 90,
+        PdfPageRenderRotation::Degrees180 => 180,
+        PdfPageRenderRotation::Degrees270 => 270,
+    }
+}
+
+/// pdfium's equivalent of a `--rotate` value.
+#[cfg(not(test))]
+pub fn render_rotation(rotation: Rotation) -> PdfPageRenderRotation {
+    match rotation {
+        Rotation::None => PdfPageRenderRotation::None,
+        Rotation::Degrees90 => PdfPageRenderRotation::Degrees90,
+        Rotation::Degrees180 => PdfPageRenderRotation::Degrees180,
+        Rotation::Degrees270 => PdfPageRenderRotation::Degrees270,
+    }
+}
+
+#[cfg(not(test))]
+#[cfg_attr(coverage_nightly, coverage(off))]
+/// The rendered page and its resolution in pixels per inch, taken before
+/// `--align` padding.
+fn render_page_image(
+    page: &PdfPage,
+    config: &PdfRenderConfig,
+    setup: PageSetup,
+    opts: &RenderOptions,
+) -> Result<(DynamicImage, f32), Error> {
+    if let Some(region) = opts.region {
+        region.check_within(page.width().value, page.height().value)?;
+    }
+    let bitmap = page
+        .render_with_config(config)
+        .map_err(|e| Error::Render(format!("render failed: {e}")))?;
+    let mut image = bitmap.as_image();
+    if let Some(region) = opts.region {
+        let pixels_per_point = image.width() as f32 / page.width().value;
+        let (left, top, width, height) =
+            region.pixel_rect(pixels_per_point, image.width(), image.height());
+        image = image.crop_imm(left, top, width, height);
+    }
+    if setup.supersample > 1.0 {
+        let width = ((image.width() as f64 / setup.supersample).round() as u32).max(1);
+        let height = ((image.height() as f64 / setup.supersample).round() as u32).max(1);
+        image = image.resize_exact(width, height, opts.resample.filter_type());
+    }
+    let stored_width_pt = match opts.region {
+        Some(region) => region.width,
+        None if setup.baked_rotation.is_quarter_turn() => page.height().value,
+        None => page.width().value,
+    };
+    let dpi = image.width() as f32 / stored_width_pt * 72.0;
+    // Before --autocrop, so the filled corners are trimmed with the margins.
+    if opts.deskew {
+        image = deskew(image, opts.background);
+    }
+    if let Some(threshold) = opts.autocrop {
+        image = autocrop(image, opts.background, threshold);
+    }
+    if let Some(watermark) = &opts.watermark {
+        image = watermark.apply(image);
+    }
+    if let RenderSize::Canvas { width, height } = opts.size {
+        image = pad_to_canvas(image, width, height, opts.background);
+    }
+    if let Some(align) = opts.align {
+        image = pad_to_multiple(image, align, opts.background);
+    }
+    let image = match (opts.grayscale, opts.background.is_transparent()) {
+        (true, true) => DynamicImage::ImageLumaA8(image.into_luma_alpha8()),
+        (true, false) => DynamicImage::ImageLuma8(image.into_luma8()),
+        (false, true) => DynamicImage::ImageRgba8(image.into_rgba8()),
+        (false, false) => DynamicImage::ImageRgb8(image.into_rgb8()),
+    };
+    Ok((image, dpi))
+}
+
+/// Crop `image` to the bounding box of its content (`--autocrop`); blank
+/// images are returned as is.
+pub fn autocrop(image: DynamicImage, background: Background, threshold: u8) -> DynamicImage {
+    match content_bounds(&image.to_rgba8(), background, threshold) {
+        Some((left, top, width, height)) if (width, height) != (image.width(), image.height()) => {
+            image.crop_imm(left, top, width, height)
+        }
+        _ => image,
+    }
+}
+
+/// `(left, top, width, height)` of the pixels that differ from `background`
+/// by more than `threshold` in any channel, or in alpha for a transparent
+/// background; `None` when there are none.
+pub fn content_bounds(
+    image: &image::RgbaImage,
+    background: Background,
+    threshold: u8,
+) -> Option<(u32, u32, u32, u32)> {
+    let reference = [background.red, background.green, background.blue];
+    let is_content = |pixel: &image::Rgba<u8>| {
+        if background.is_transparent() {
+            pixel[3] > threshold
+        } else {
+            (0..3).any(|channel| pixel[channel].abs_diff(reference[channel]) > threshold)
+        }
+    };
+    let mut bounds: Option<(u32, u32, u32, u32)> = None;
+    for (x, y, pixel) in image.enumerate_pixels() {
+        if !is_content(pixel) {
+            continue;
+        }
+        bounds = Some(match bounds {
+            None => (x, y, x, y),
+            Some((left, top, right, bottom)) => {
+                (left.min(x), top.min(y), right.max(x), bottom.max(y))
+            }
+        });
+    }
+    bounds.map(|(left, top, right, bottom)| (left, top, right - left + 1, bottom - top + 1))
+}
+
+/// Pad `image` on the right and bottom with `background` so both dimensions
+/// are multiples of `align` (`--align`); aligned images are returned as is.
+pub fn pad_to_multiple(image: DynamicImage, align: u32, background: Background) -> DynamicImage {
+    let width = image.width().next_multiple_of(align);
+    let height = image.height().next_multiple_of(align);
+    if width == image.width() && height == image.height() {
+        return image;
+    }
+    let mut canvas = image::RgbaImage::from_pixel(width, height, background.pixel());
+    image::imageops::replace(&mut canvas, &image.to_rgba8(), 0, 0);
+    DynamicImage::ImageRgba8(canvas)
+}
+
+/// Center `image` on a `width` x `height` canvas of `background`
+/// (`--canvas`); an image of exactly that size is returned as is.
+pub fn pad_to_canvas(
+    image: DynamicImage,
+    width: u32,
+    height: u32,
+    background: Background,
+) -> DynamicImage {
+    if width == image.width() && height == image.height() {
+        return image;
+    }
+    let mut canvas = image::RgbaImage::from_pixel(width, height, background.pixel());
+    let left = (i64::from(width) - i64::from(image.width())) / 2;
+    let top = (i64::from(height) - i64::from(image.height())) / 2;
+    image::imageops::replace(&mut canvas, &image.to_rgba8(), left, top);
+    DynamicImage::ImageRgba8(canvas)
+}
+
+#[cfg(not(test))]
+#[cfg_attr(coverage_nightly, coverage(off))]
+/// Encode `image` in `opts.format`; JPEGs record `dpi` as their density.
+fn encode_page(
+    image: &DynamicImage,
+    dpi: f32,
+    writer: &mut dyn Write,
+    opts: &RenderOptions,
+) -> Result<(), Error> {
+    match opts.format {
+        // Every JPEG encoder gets the same treatment: metadata is stripped,
+        // then density, profile and orientation are spliced in after encoding.
+        OutputFormat::Jpeg => {
+            let mut jpeg = Vec::new();
+            encode_jpeg(image, &mut jpeg, opts)?;
+            if opts.reproducible {
+                jpeg = strip_jpeg_metadata(&jpeg);
+            }
+            jpeg = set_jfif_density(&jpeg, dpi.round().clamp(1.0, u16::MAX as f32) as u16);
+            if let Some(profile) = &opts.icc_profile {
+                jpeg = embed_jpeg_icc(&jpeg, &profile.data)?;
+            }
+            if opts.exif_orientation && opts.rotation != Rotation::None {
+                jpeg = embed_exif_orientation(&jpeg, opts.rotation.exif_orientation());
+            }
+            writer.write_all(&jpeg)?;
+            Ok(())
+        }
+        OutputFormat::Png => encode_png(image, writer, opts.icc_profile.as_ref()),
+        OutputFormat::Webp if opts.lossless => encode_webp_lossless(image, writer),
+        OutputFormat::Webp => encode_webp_lossy(image, writer, opts.quality),
+        OutputFormat::Tiff => encode_tiff(image, writer),
+        OutputFormat::Avif => encode_avif(
+            image,
+            writer,
+            opts.quality,
+            opts.speed.unwrap_or(DEFAULT_AVIF_SPEED),
+        ),
+    }
+}
+
+/// Encode as PNG, keeping whatever channels (including alpha) the image has,
+/// with `icc_profile` in an iCCP chunk.
+fn encode_png(
+    image: &DynamicImage,
+    writer: &mut dyn Write,
+    icc_profile: Option<&IccProfile>,
+) -> Result<(), Error> {
+    let mut encoder = PngEncoder::new(writer);
+    if let Some(profile) = icc_profile {
+        encoder
+            .set_icc_profile(profile.data.clone())
+            .map_err(|e| Error::Render(format!("PNG ICC profile: {e}")))?;
+    }
+    image
+        .write_with_encoder(encoder)
+        .map_err(|e| Error::Render(format!("PNG encode failed: {e}")))
+}
+
+/// Encode a single-page TIFF. The encoder needs to seek, so the file is built
+/// in memory first (pages may go to stdout).
+fn encode_tiff(image: &DynamicImage, writer: &mut dyn Write) -> Result<(), Error> {
+    let mut buffer = Cursor::new(Vec::new());
+    image
+        .write_with_encoder(TiffEncoder::new(&mut buffer))
+        .map_err(|e| Error::Render(format!("TIFF encode failed: {e}")))?;
+    writer.write_all(buffer.get_ref())?;
+    Ok(())
+}
+
+/// Append `image` to a multipage TIFF as its next frame.
+pub fn write_tiff_frame<W: Write + Seek>(
+    encoder: &mut tiff::encoder::TiffEncoder<W>,
+    image: &DynamicImage,
+) -> Result<(), Error> {
+    use tiff::encoder::colortype;
+
+    let (width, height) = (image.width(), image.height());
+    let written = match image {
+        DynamicImage::ImageLuma8(gray) => {
+            encoder.write_image::<colortype::Gray8>(width, height, gray.as_raw())
+        }
+        DynamicImage::ImageRgb8(rgb) => {
+            encoder.write_image::<colortype::RGB8>(width, height, rgb.as_raw())
+        }
+        other => encoder.write_image::<colortype::RGBA8>(width, height, other.to_rgba8().as_raw()),
+    };
+    written.map_err(|e| Error::Render(format!("TIFF encode failed: {e}")))
+}
+
+/// Encode lossless WebP with the `image` crate, which has no lossy encoder.
+fn encode_webp_lossless(image: &DynamicImage, writer: &mut dyn Write) -> Result<(), Error> {
+    image
+        .write_with_encoder(WebPEncoder::new_lossless(writer))
+        .map_err(|e| Error::Render(format!("WebP encode failed: {e}")))
+}
+
+/// Encode lossy WebP at `quality` (0-100) with libwebp.
+#[cfg(all(feature = "webp", not(test)))]
+#[cfg_attr(coverage_nightly, coverage(off))]
+fn encode_webp_lossy(
+    image: &DynamicImage,
+    writer: &mut dyn Write,
+    quality: u8,
+) -> Result<(), Error> {
+    // libwebp takes RGB or RGBA only; grayscale is expanded.
+    let webp = if image.color().has_alpha() {
+        let rgba = image.to_rgba8();
+        webp::Encoder::from_rgba(&rgba, rgba.width(), rgba.height()).encode(quality as f32)
+    } else {
+        let rgb = image.to_rgb8();
+        webp::Encoder::from_rgb(&rgb, rgb.width(), rgb.height()).encode(quality as f32)
+    };
+    writer.write_all(&webp)?;
+    Ok(())
+}
+
+#[cfg(all(not(feature = "webp"), not(test)))]
+#[cfg_attr(coverage_nightly, coverage(off))]
+fn encode_webp_lossy(
+    _image: &DynamicImage,
+    _writer: &mut dyn Write,
+    _quality: u8,
+) -> Result<(), Error> {
+    Err(Error::InvalidArgs(
+        "lossy --format webp requires building with --features webp".into(),
+    ))
+}
+
+/// Encode AVIF with the `image` crate's ravif encoder; `quality` (1-100) maps
+/// directly to AVIF quality.
+#[cfg(feature = "avif")]
+fn encode_avif(
+    image: &DynamicImage,
+    writer: &mut dyn Write,
+    quality: u8,
+    speed: u8,
+) -> Result<(), Error> {
+    image
+        .write_with_encoder(AvifEncoder::new_with_speed_quality(writer, speed, quality))
+        .map_err(|e| Error::Render(format!("AVIF encode failed: {e}")))
+}
+
+#[cfg(all(not(feature = "avif"), not(test)))]
+#[cfg_attr(coverage_nightly, coverage(off))]
+fn encode_avif(
+    _image: &DynamicImage,
+    _writer: &mut dyn Write,
+    _quality: u8,
+    _speed: u8,
+) -> Result<(), Error> {
+    Err(Error::InvalidArgs(
+        "--format avif requires building with --features avif".into(),
+    ))
+}
+
+#[cfg(not(test))]
+#[cfg_attr(coverage_nightly, coverage(off))]
+fn encode_jpeg(
+    image: &DynamicImage,
+    writer: &mut dyn Write,
+    opts: &RenderOptions,
+) -> Result<(), Error> {
+    match (opts.encoder, opts.subsampling) {
+        // The image crate only writes baseline JPEG; jpeg-encoder writes SOF2.
+        (JpegEncoderType::Image, subsampling) if opts.progressive => encode_jpeg_progressive(
+            image,
+            writer,
+            opts.quality,
+            subsampling.unwrap_or(JpegSubsampling::Full),
+        ),
+        // The image crate always writes 4:4:4; libjpeg-turbo handles the rest.
+        (JpegEncoderType::Image, Some(subsampling)) if subsampling != JpegSubsampling::Full => {
+            encode_jpeg_turbo(image, writer, opts.quality, subsampling)
+        }
+        (JpegEncoderType::Image, _) => encode_jpeg_image(image, writer, opts.quality),
+        (JpegEncoderType::Vips, subsampling) => {
+            encode_jpeg_vips(image, writer, opts.quality, opts.progressive, subsampling)
+        }
+    }
+}
+
+/// Encode an RGB8 or Luma8 image; grayscale input yields a single-component JPEG.
+fn encode_jpeg_image(
+    image: &DynamicImage,
+    writer: &mut dyn Write,
+    quality: u8,
+) -> Result<(), Error> {
+    let encoder = JpegEncoder::new_with_quality(writer, quality);
+    image
+        .write_with_encoder(encoder)
+        .map_err(|e| Error::Render(format!("JPEG encode failed: {e}")))?;
+    Ok(())
+}
+
+/// Encode an RGB8 or Luma8 image with libjpeg-turbo at the given chroma
+/// subsampling; grayscale input has no chroma and ignores it.
+fn encode_jpeg_turbo(
+    image: &DynamicImage,
+    writer: &mut dyn Write,
+    quality: u8,
+    subsampling: JpegSubsampling,
+) -> Result<(), Error> {
+    let rgb;
+    let (pixels, channels, format, subsamp) = match image {
+        DynamicImage::ImageLuma8(gray) => (
+            gray.as_raw(),
+            1,
+            turbojpeg::PixelFormat::GRAY,
+            turbojpeg::Subsamp::Gray,
+        ),
+        other => {
+            rgb = other.to_rgb8();
+            let subsamp = match subsampling {
+                JpegSubsampling::Full => turbojpeg::Subsamp::None,
+                JpegSubsampling::Half => turbojpeg::Subsamp::Sub2x1,
+                JpegSubsampling::Quarter => turbojpeg::Subsamp::Sub2x2,
+            };
+            (rgb.as_raw(), 3, turbojpeg::PixelFormat::RGB, subsamp)
+        }
+    };
+    let width = image.width() as usize;
+    let turbo_image = turbojpeg::Image {
+        pixels: pixels.as_slice(),
+        width,
+        pitch: width * channels,
+        height: image.height() as usize,
+        format,
+    };
+    let jpeg = turbojpeg::compress(turbo_image, quality as i32, subsamp)
+        .map_err(|e| Error::Render(format!("JPEG encode failed: {e}")))?;
+    writer.write_all(&jpeg)?;
+    Ok(())
+}
+
+/// Encode an RGB8 or Luma8 image as progressive JPEG at the given chroma
+/// subsampling; grayscale input has no chroma and ignores it.
+fn encode_jpeg_progressive(
+    image: &DynamicImage,
+    writer: &mut dyn Write,
+    quality: u8,
+    subsampling: JpegSubsampling,
+) -> Result<(), Error> {
+    let (Ok(width), Ok(height)) = (u16::try_from(image.width()), u16::try_from(image.height()))
+    else {
+        return Err(Error::Render(format!(
+            "JPEG encode failed: {}x{} exceeds the 65535 pixel JPEG limit",
+            image.width(),
+            image.height()
+        )));
+    };
+    let rgb;
+    let (pixels, color_type) = match image {
+        DynamicImage::ImageLuma8(gray) => (gray.as_raw(), jpeg_encoder::ColorType::Luma),
+        other => {
+            rgb = other.to_rgb8();
+            (rgb.as_raw(), jpeg_encoder::ColorType::Rgb)
+        }
+    };
+    let mut encoder = jpeg_encoder::Encoder::new(writer, quality);
+    encoder.set_progressive(true);
+    encoder.set_sampling_factor(match subsampling {
+        JpegSubsampling::Full => jpeg_encoder::SamplingFactor::R_4_4_4,
+        JpegSubsampling::Half => jpeg_encoder::SamplingFactor::R_4_2_2,
+        JpegSubsampling::Quarter => jpeg_encoder::SamplingFactor::R_4_2_0,
+    });
+    encoder
+        .encode(pixels, width, height, color_type)
+        .map_err(|e| Error::Render(format!("JPEG encode failed: {e}")))
+}
+
+#[cfg(all(feature = "vips", not(test)))]
+#[cfg_attr(coverage_nightly, coverage(off))]
+fn encode_jpeg_vips(
+    image: &DynamicImage,
+    writer: &mut dyn Write,
+    quality: u8,
+    progressive: bool,
+    subsampling: Option<JpegSubsampling>,
+) -> Result<(), Error> {
+    // One band for grayscale: vips interprets 1-band uchar as B_W and writes a
+    // single-component JPEG.
+    let vips_image = libvips::VipsImage::new_from_memory(
+        image.as_bytes(),
+        image.width() as i32,
+        image.height() as i32,
+        image.color().channel_count() as i32,
+        libvips::ops::BandFormat::Uchar,
+    )
+    .map_err(|e| Error::Render(format!("vips from memory: {e}")))?;
+
+    let jpeg = libvips::ops::jpegsave_buffer_with_opts(
+        &vips_image,
+        &libvips::ops::JpegsaveBufferOptions {
+            q: quality as i32,
+            interlace: progressive,
+            subsample_mode: match subsampling {
+                None => libvips::ops::ForeignSubsample::Auto,
+                Some(JpegSubsampling::Full) => libvips::ops::ForeignSubsample::Off,
+                // `validate` rejects 4:2:2, which vips cannot write.
+                Some(JpegSubsampling::Half | JpegSubsampling::Quarter) => {
+                    libvips::ops::ForeignSubsample::On
+                }
+            },
+            ..Default::default()
+        },
+    )
+    .map_err(|e| Error::Render(format!("vips jpegsave: {e}")))?;
+
+    writer.write_all(&jpeg)?;
+    Ok(())
+}
+
+#[cfg(all(not(feature = "vips"), not(test)))]
+#[cfg_attr(coverage_nightly, coverage(off))]
+fn encode_jpeg_vips(
+    _image: &DynamicImage,
+    _writer: &mut dyn Write,
+    _quality: u8,
+    _progressive: bool,
+    _subsampling: Option<JpegSubsampling>,
+) -> Result<(), Error> {
+    Err(Error::InvalidArgs(
+        "--encoder vips requires building with --features vips".into(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn quality_presets_map_per_format() {
+        assert_eq!(parse_quality("high", OutputFormat::Jpeg).unwrap(), 90);
+        assert_eq!(parse_quality("HIGH", OutputFormat::Webp).unwrap(), 90);
+        assert_eq!(parse_quality("high", OutputFormat::Avif).unwrap(), 70);
+        assert_eq!(parse_quality("max", OutputFormat::Jpeg).unwrap(), 100);
+        assert_eq!(parse_quality("low", OutputFormat::Jpeg).unwrap(), 50);
+    }
+
+    #[test]
+    fn numeric_quality_is_rounded_and_range_checked() {
+        assert_eq!(parse_quality("85", OutputFormat::Jpeg).unwrap(), 85);
+        assert_eq!(parse_quality("92.5", OutputFormat::Jpeg).unwrap(), 93);
+        for bad in ["0", "101", "-5", "NaN", "best", ""] {
+            assert!(
+                matches!(
+                    parse_quality(bad, OutputFormat::Jpeg),
+                    Err(Error::InvalidArgs(_))
+                ),
+                "{bad}"
+            );
+        }
+    }
+
+    #[test]
+    fn aspect_match_same_ratio() {
+        assert!(aspect_ratios_match(2560.0, 3937.0, 477.0, 733.5));
+    }
+
+    #[test]
+    fn aspect_match_slightly_different() {
+        // 5% difference — within tolerance
+        assert!(aspect_ratios_match(2560.0, 3937.0, 480.0, 733.5));
+    }
+
+    #[test]
+    fn aspect_mismatch_spread_on_portrait_page() {
+        // Spread image (8284x6400 landscape) on portrait page (477x733.5)
+        assert!(!aspect_ratios_match(8284.0, 6400.0, 477.0, 733.5));
+    }
+
+    #[test]
     fn aspect_mismatch_landscape_image_on_portrait_page() {
         // Landscape image on portrait page — image wider than page expects
         assert!(!aspect_ratios_match(6400.0, 3937.0, 477.0, 733.5));
     }
 
     #[test]
-    fn aspect_match_zero_dimensions_allow_extraction() {
-        assert!(aspect_ratios_match(0.0, 100.0, 477.0, 733.5));
-        assert!(aspect_ratios_match(100.0, 0.0, 477.0, 733.5));
-        assert!(aspect_ratios_match(100.0, 100.0, 0.0, 733.5));
+    fn aspect_match_zero_dimensions_allow_extraction() {
+        assert!(aspect_ratios_match(0.0, 100.0, 477.0, 733.5));
+        assert!(aspect_ratios_match(100.0, 0.0, 477.0, 733.5));
+        assert!(aspect_ratios_match(100.0, 100.0, 0.0, 733.5));
+    }
+
+    #[test]
+    fn jpeg_cmyk_detection_uses_sof_component_count() {
+        let rgb = [
+            0xFF, 0xD8, 0xFF, 0xC0, 0x00, 0x0B, 0x08, 0x00, 0x10, 0x00, 0x10, 0x03,
+        ];
+        let cmyk = [
+            0xFF, 0xD8, 0xFF, 0xC2, 0x00, 0x0B, 0x08, 0x00, 0x10, 0x00, 0x10, 0x04,
+        ];
+
+        assert!(!jpeg_is_cmyk(&rgb));
+        assert!(jpeg_is_cmyk(&cmyk));
+        assert!(!jpeg_is_cmyk(&[0xFF, 0xD8, 0xFF]));
+    }
+
+    #[test]
+    fn render_options_types_have_expected_defaults() {
+        assert!(matches!(JpegEncoderType::default(), JpegEncoderType::Image));
+        assert!(matches!(BoxType::Crop, BoxType::Crop));
+        assert!(matches!(BoxType::Bleed, BoxType::Bleed));
+    }
+
+    #[test]
+    fn worker_result_serializes_expected_fields() {
+        let result = WorkerResult {
+            pages_rendered: 2,
+            pages_extracted: 1,
+            pages_cached: 0,
+            errors: vec![PageError::new(3, "failed")],
+            outputs: Vec::new(),
+            iteration_secs: Vec::new(),
+            not_upscaled: Vec::new(),
+            box_fallbacks: Vec::new(),
+        };
+
+        let json = serde_json::to_value(result).unwrap();
+
+        assert_eq!(json["pages_rendered"], 2);
+        assert_eq!(json["pages_extracted"], 1);
+        assert_eq!(json["errors"][0]["page"], 3);
+        assert_eq!(json["errors"][0]["reason"], "failed");
+        assert!(json.get("iteration_secs").is_none());
+        assert!(json.get("not_upscaled").is_none());
+        assert!(json.get("box_fallbacks").is_none());
+    }
+
+    #[test]
+    fn page_error_round_trips_and_displays_page() {
+        let error = PageError::new(7, "render failed");
+        let json = serde_json::to_string(&error).unwrap();
+
+        assert_eq!(json, r#"{"page":7,"reason":"render failed"}"#);
+        assert_eq!(serde_json::from_str::<PageError>(&json).unwrap(), error);
+        assert_eq!(error.to_string(), "page 7: render failed");
+    }
+
+    #[test]
+    fn render_options_carry_cli_settings() {
+        let opts = RenderOptions {
+            size: RenderSize::TargetWidth(1600),
+            max_width: None,
+            max_height: None,
+            widths: Vec::new(),
+            supersample: None,
+            resample: ResampleFilter::Catmull,
+            no_upscale: false,
+            quality: 90,
+            box_type: BoxType::Bleed,
+            extract_images: true,
+            reencode_extracted: false,
+            encoder: JpegEncoderType::Vips,
+            progressive: false,
+            subsampling: None,
+            password: Some("secret".to_string()),
+            keep_order: false,
+            limit: None,
+            grayscale: false,
+            format: OutputFormat::Png,
+            lossless: false,
+            speed: None,
+            background: Background::TRANSPARENT,
+            rotation: Rotation::Degrees270,
+            rotate_auto: false,
+            region: None,
+            align: None,
+            autocrop: None,
+            deskew: false,
+            icc_profile: None,
+            watermark: None,
+            exif_orientation: false,
+            no_images: false,
+            pdfium_flags: PdfiumFlags::default(),
+            layers: Layers::default(),
+            reproducible: false,
+            name_template: NameTemplate::parse("{stem}_{n}").unwrap(),
+            label_names: false,
+            overwrite: false,
+            manifest: None,
+            list_outputs: false,
+            progress: ProgressMode::Human,
+            quiet: false,
+            page_timeout: None,
+            retries: 0,
+            strict: false,
+            repeat: 1,
+            worker_mem_limit: None,
+            max_inflight: None,
+            cache: None,
+        };
+
+        assert_eq!(opts.size, RenderSize::TargetWidth(1600));
+        assert_eq!(opts.resample, ResampleFilter::Catmull);
+        assert_eq!(opts.quality, 90);
+        assert!(matches!(opts.box_type, BoxType::Bleed));
+        assert!(opts.extract_images);
+        assert!(matches!(opts.encoder, JpegEncoderType::Vips));
+        assert_eq!(opts.password.as_deref(), Some("secret"));
+        assert!(!opts.keep_order);
+        assert!(!opts.grayscale);
+        assert_eq!(opts.format, OutputFormat::Png);
+        assert!(!opts.lossless);
+        assert!(opts.background.is_transparent());
+        assert_eq!(opts.rotation, Rotation::Degrees270);
+        assert_eq!(opts.name_template.as_str(), "{stem}_{n}");
+        assert!(!opts.overwrite);
+        assert_eq!(opts.progress, ProgressMode::Human);
+    }
+
+    #[test]
+    fn resample_filters_map_to_image_filters() {
+        assert_eq!(ResampleFilter::default(), ResampleFilter::Lanczos);
+        assert_eq!(ResampleFilter::Lanczos.filter_type(), FilterType::Lanczos3);
+        assert_eq!(
+            ResampleFilter::Catmull.filter_type(),
+            FilterType::CatmullRom
+        );
+        assert_eq!(ResampleFilter::Nearest.to_arg(), "nearest");
+        assert_eq!(ResampleFilter::Triangle.to_arg(), "triangle");
+    }
+
+    #[test]
+    fn quarter_turns_swap_output_dimensions() {
+        assert!(!Rotation::None.is_quarter_turn());
+        assert!(Rotation::Degrees90.is_quarter_turn());
+        assert!(!Rotation::Degrees180.is_quarter_turn());
+        assert!(Rotation::Degrees270.is_quarter_turn());
+        assert_eq!(Rotation::Degrees270.degrees(), 270);
+    }
+
+    #[test]
+    fn progress_events_are_single_json_lines() {
+        assert_eq!(
+            progress_json(7, PageStatus::Rendered),
+            r#"{"event":"page","page":7,"status":"rendered"}"#
+        );
+        assert_eq!(
+            progress_json(2, PageStatus::Extracted),
+            r#"{"event":"page","page":2,"status":"extracted"}"#
+        );
+        assert_eq!(
+            progress_json(3, PageStatus::Failed),
+            r#"{"event":"page","page":3,"status":"failed"}"#
+        );
+    }
+
+    #[test]
+    fn dpi_sizing_scales_page_width_in_points() {
+        // US Letter (612pt = 8.5in) at 150 DPI
+        assert_eq!(RenderSize::Dpi(150).pixel_width(612.0, 792.0), 1275);
+        assert_eq!(RenderSize::Dpi(72).pixel_width(612.0, 792.0), 612);
+        assert_eq!(
+            RenderSize::TargetWidth(2560).pixel_width(612.0, 792.0),
+            2560
+        );
+    }
+
+    #[test]
+    fn max_size_scales_down_to_the_tighter_cap() {
+        assert_eq!(max_size_scale(4000.0, 2000.0, Some(1000), None), 0.25);
+        assert_eq!(max_size_scale(4000.0, 2000.0, Some(2000), Some(200)), 0.1);
+        assert_eq!(max_size_scale(4000.0, 2000.0, None, Some(1000)), 0.5);
+    }
+
+    #[test]
+    fn supersample_factor_stays_within_the_bitmap_limit() {
+        assert_eq!(supersample_factor(1000.0, 1500.0, None), 1.0);
+        assert_eq!(supersample_factor(1000.0, 1500.0, Some(2.0)), 2.0);
+        let capped = supersample_factor(20_000.0, 20_000.0, Some(4.0));
+        assert!(capped < 4.0);
+        assert!(check_bitmap_size(20_000.0 * capped, 20_000.0 * capped).is_ok());
+        // Never below rendering at the output size itself.
+        assert_eq!(supersample_factor(40_000.0, 40_000.0, Some(2.0)), 1.0);
+    }
+
+    #[test]
+    fn supersample_must_be_between_1_and_the_maximum() {
+        let mut opts = RenderOptions {
+            supersample: Some(2.0),
+            ..RenderOptions::default()
+        };
+        assert!(opts.validate().is_ok());
+        for factor in [0.5, MAX_SUPERSAMPLE + 1.0, f32::NAN] {
+            opts.supersample = Some(factor);
+            assert!(matches!(opts.validate(), Err(Error::InvalidArgs(_))));
+        }
+    }
+
+    #[test]
+    fn empty_bitmaps_are_zero_size_pages() {
+        assert!(check_bitmap_size(1.0, 1.0).is_ok());
+        for (width, height) in [(0.0, 100.0), (100.0, 0.4), (f64::NAN, 100.0)] {
+            let err = check_bitmap_size(width, height).unwrap_err();
+            assert_eq!(err.to_string(), "zero-size page");
+        }
+    }
+
+    #[test]
+    fn oversized_bitmaps_are_rejected() {
+        assert!(check_bitmap_size(16_384.0, 32_767.0).is_ok());
+        let err = check_bitmap_size(1000.0, f64::INFINITY).unwrap_err();
+        assert!(matches!(err, Error::Render(_)));
+        let err = check_bitmap_size(100_000.0, 100_000.0).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "rendering error: page would render at 100000x100000 pixels, over the 536870911 pixel limit"
+        );
+    }
+
+    #[test]
+    fn max_size_never_scales_up() {
+        assert_eq!(max_size_scale(200.0, 100.0, Some(1000), Some(1000)), 1.0);
+        assert_eq!(max_size_scale(200.0, 100.0, None, None), 1.0);
+    }
+
+    #[test]
+    fn no_upscale_caps_at_native_size() {
+        assert_eq!(no_upscale_scale(400.0, 100.0, true), 0.25);
+        assert_eq!(no_upscale_scale(50.0, 100.0, true), 1.0);
+        assert_eq!(no_upscale_scale(400.0, 100.0, false), 1.0);
+    }
+
+    #[test]
+    fn target_height_sizes_width_from_aspect_ratio() {
+        assert_eq!(RenderSize::TargetHeight(396).pixel_width(612.0, 792.0), 306);
+        assert_eq!(RenderSize::TargetHeight(100).pixel_width(200.0, 100.0), 200);
+    }
+
+    #[test]
+    fn canvas_fits_the_page_inside_both_dimensions() {
+        let canvas = RenderSize::parse_canvas("800x600").unwrap();
+        assert_eq!(
+            canvas,
+            RenderSize::Canvas {
+                width: 800,
+                height: 600
+            }
+        );
+        // Letter portrait is height-bound, a wide page width-bound.
+        assert_eq!(canvas.pixel_width(612.0, 792.0), 463);
+        assert_eq!(canvas.pixel_width(400.0, 100.0), 800);
+        assert_eq!(canvas.pixel_width(1.0, 10000.0), 1);
+
+        for input in [
+            "800",
+            "800x",
+            "x600",
+            "800x600x2",
+            "800 by 600",
+            "0x600",
+            "800x0",
+        ] {
+            assert!(
+                matches!(RenderSize::parse_canvas(input), Err(Error::InvalidArgs(_))),
+                "{input}"
+            );
+        }
+    }
+
+    #[test]
+    fn render_size_args_are_mutually_exclusive() {
+        assert_eq!(
+            RenderSize::from_args(None, None, None).unwrap(),
+            RenderSize::NATIVE
+        );
+        assert_eq!(RenderSize::NATIVE.pixel_width(612.0, 792.0), 612);
+        assert_eq!(
+            RenderSize::from_args(Some(1600), None, None).unwrap(),
+            RenderSize::TargetWidth(1600)
+        );
+        assert_eq!(
+            RenderSize::from_args(None, Some(900), None).unwrap(),
+            RenderSize::TargetHeight(900)
+        );
+        assert_eq!(
+            RenderSize::from_args(None, None, Some(300)).unwrap(),
+            RenderSize::Dpi(300)
+        );
+        for (width, height, dpi) in [
+            (Some(1600), Some(900), None),
+            (Some(1600), None, Some(300)),
+            (None, Some(900), Some(300)),
+            (None, None, Some(0)),
+            (Some(0), None, None),
+            (None, Some(0), None),
+        ] {
+            assert!(
+                matches!(
+                    RenderSize::from_args(width, height, dpi),
+                    Err(Error::InvalidArgs(_))
+                ),
+                "{width:?} {height:?} {dpi:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn output_filename_prefixes_sequence_when_keeping_order() {
+        let template = NameTemplate::default();
+        assert_eq!(
+            output_filename(&template, "doc", 7, None, None, OutputFormat::Jpeg),
+            "page-0007.jpg"
+        );
+        assert_eq!(
+            output_filename(&template, "doc", 7, None, Some(2), OutputFormat::Jpeg),
+            "0002-page-0007.jpg"
+        );
+        assert_eq!(
+            output_filename(&template, "doc", 7, None, None, OutputFormat::Png),
+            "page-0007.png"
+        );
+    }
+
+    #[test]
+    fn output_filename_expands_name_template() {
+        let template = NameTemplate::parse("{stem}_{n:03}").unwrap();
+        assert_eq!(
+            output_filename(&template, "invoice", 7, None, None, OutputFormat::Jpeg),
+            "invoice_007.jpg"
+        );
+        assert_eq!(
+            output_filename(&template, "invoice", 7, None, Some(3), OutputFormat::Png),
+            "0003-invoice_007.png"
+        );
+    }
+
+    #[test]
+    fn output_filename_uses_sanitized_label() {
+        let template = NameTemplate::default();
+        assert_eq!(
+            output_filename(&template, "doc", 3, Some("iii"), None, OutputFormat::Jpeg),
+            "page-iii.jpg"
+        );
+        assert_eq!(
+            output_filename(&template, "doc", 3, Some("A/1"), Some(2), OutputFormat::Png),
+            "0002-page-A_1.png"
+        );
+        assert_eq!(
+            output_filename(&template, "doc", 3, Some(" "), None, OutputFormat::Jpeg),
+            "page-0003.jpg"
+        );
+    }
+
+    #[test]
+    fn grayscale_images_encode_single_component_jpeg() {
+        let gray = DynamicImage::ImageLuma8(image::GrayImage::from_pixel(8, 8, image::Luma([128])));
+        let rgb = DynamicImage::ImageRgb8(image::RgbImage::from_pixel(8, 8, image::Rgb([1, 2, 3])));
+
+        let mut gray_jpeg = Vec::new();
+        encode_jpeg_image(&gray, &mut gray_jpeg, 90).unwrap();
+        let mut rgb_jpeg = Vec::new();
+        encode_jpeg_image(&rgb, &mut rgb_jpeg, 90).unwrap();
+
+        assert_eq!(jpeg_component_count(&gray_jpeg), Some(1));
+        assert_eq!(jpeg_component_count(&rgb_jpeg), Some(3));
+    }
+
+    /// Luma sampling factors (`0xHV`) from a baseline JPEG's SOF0 segment.
+    fn luma_sampling(jpeg: &[u8]) -> Option<u8> {
+        let sof = jpeg.windows(2).position(|marker| marker == [0xFF, 0xC0])?;
+        jpeg.get(sof + 11).copied()
+    }
+
+    #[test]
+    fn turbo_encoder_applies_chroma_subsampling() {
+        let rgb = DynamicImage::ImageRgb8(image::RgbImage::from_pixel(
+            16,
+            16,
+            image::Rgb([9, 80, 200]),
+        ));
+        let encode = |subsampling| {
+            let mut jpeg = Vec::new();
+            encode_jpeg_turbo(&rgb, &mut jpeg, 90, subsampling).unwrap();
+            jpeg
+        };
+
+        assert_eq!(luma_sampling(&encode(JpegSubsampling::Quarter)), Some(0x22));
+        assert_eq!(luma_sampling(&encode(JpegSubsampling::Half)), Some(0x21));
+        assert_eq!(luma_sampling(&encode(JpegSubsampling::Full)), Some(0x11));
+    }
+
+    #[test]
+    fn turbo_encoder_keeps_grayscale_single_component() {
+        let gray = DynamicImage::ImageLuma8(image::GrayImage::from_pixel(8, 8, image::Luma([128])));
+        let mut jpeg = Vec::new();
+        encode_jpeg_turbo(&gray, &mut jpeg, 90, JpegSubsampling::Quarter).unwrap();
+
+        assert_eq!(jpeg_component_count(&jpeg), Some(1));
+    }
+
+    #[test]
+    fn progressive_encoder_writes_sof2_with_subsampling() {
+        let rgb = DynamicImage::ImageRgb8(image::RgbImage::from_pixel(
+            16,
+            16,
+            image::Rgb([9, 80, 200]),
+        ));
+        let encode = |subsampling| {
+            let mut jpeg = Vec::new();
+            encode_jpeg_progressive(&rgb, &mut jpeg, 90, subsampling).unwrap();
+            let sof = jpeg.windows(2).position(|marker| marker == [0xFF, 0xC2]);
+            sof.and_then(|sof| jpeg.get(sof + 11).copied())
+        };
+
+        assert_eq!(encode(JpegSubsampling::Quarter), Some(0x22));
+        assert_eq!(encode(JpegSubsampling::Half), Some(0x21));
+        assert_eq!(encode(JpegSubsampling::Full), Some(0x11));
+
+        let gray = DynamicImage::ImageLuma8(image::GrayImage::from_pixel(8, 8, image::Luma([128])));
+        let mut jpeg = Vec::new();
+        encode_jpeg_progressive(&gray, &mut jpeg, 90, JpegSubsampling::Quarter).unwrap();
+        assert_eq!(jpeg_component_count(&jpeg), Some(1));
+        let decoded = image::load_from_memory_with_format(&jpeg, image::ImageFormat::Jpeg).unwrap();
+        assert!(decoded.to_luma8().get_pixel(4, 4).0[0].abs_diff(128) <= 2);
+    }
+
+    #[test]
+    fn jpeg_options_are_validated() {
+        let mut opts = RenderOptions {
+            subsampling: Some(JpegSubsampling::Quarter),
+            ..RenderOptions::default()
+        };
+        assert!(opts.validate().is_ok());
+
+        opts.format = OutputFormat::Png;
+        assert!(matches!(opts.validate(), Err(Error::InvalidArgs(_))));
+
+        opts.format = OutputFormat::Jpeg;
+        opts.progressive = true;
+        assert!(opts.validate().is_ok());
+
+        opts.encoder = JpegEncoderType::Vips;
+        assert!(opts.validate().is_ok());
+
+        opts.subsampling = Some(JpegSubsampling::Half);
+        assert!(matches!(opts.validate(), Err(Error::InvalidArgs(_))));
+    }
+
+    #[test]
+    fn padding_rounds_dimensions_up_with_background() {
+        let image = DynamicImage::ImageRgb8(image::RgbImage::from_pixel(
+            30,
+            17,
+            image::Rgb([10, 20, 30]),
+        ));
+
+        let padded = pad_to_multiple(image, 16, Background::BLACK);
+
+        assert_eq!((padded.width(), padded.height()), (32, 32));
+        let rgba = padded.to_rgba8();
+        assert_eq!(rgba.get_pixel(29, 16).0, [10, 20, 30, 255]);
+        assert_eq!(rgba.get_pixel(31, 0).0, [0, 0, 0, 255]);
+        assert_eq!(rgba.get_pixel(0, 31).0, [0, 0, 0, 255]);
+    }
+
+    #[test]
+    fn pdfium_flags_forward_only_what_is_set() {
+        assert!(PdfiumFlags::default().to_args().is_empty());
+
+        let flags = PdfiumFlags {
+            lcd_text: true,
+            no_smooth_path: true,
+            annotations: Annotations::Off,
+            ..PdfiumFlags::default()
+        };
+        assert_eq!(
+            flags.to_args(),
+            ["--lcd-text", "--no-smooth-path", "--annotations=off"]
+        );
+    }
+
+    #[test]
+    fn autocrop_trims_background_margins() {
+        let mut page = image::RgbImage::from_pixel(40, 30, image::Rgb([255, 255, 255]));
+        for (x, y) in [(10, 5), (25, 20)] {
+            page.put_pixel(x, y, image::Rgb([0, 0, 0]));
+        }
+        // Within the threshold of white, so still margin.
+        page.put_pixel(35, 28, image::Rgb([250, 250, 250]));
+
+        let trimmed = autocrop(DynamicImage::ImageRgb8(page), Background::WHITE, 16);
+
+        assert_eq!((trimmed.width(), trimmed.height()), (16, 16));
+        assert_eq!(trimmed.to_rgb8().get_pixel(0, 0).0, [0, 0, 0]);
+    }
+
+    #[test]
+    fn autocrop_uses_alpha_on_transparent_backgrounds() {
+        let mut page = image::RgbaImage::new(20, 20);
+        page.put_pixel(4, 6, image::Rgba([255, 255, 255, 255]));
+
+        assert_eq!(
+            content_bounds(&page, Background::TRANSPARENT, 0),
+            Some((4, 6, 1, 1))
+        );
     }
 
     #[test]
-    fn jpeg_cmyk_detection_uses_sof_component_count() {
-        let rgb = [
-            0xFF, 0xD8, 0xFF, 0xC0, 0x00, 0x0B, 0x08, 0x00, 0x10, 0x00, 0x10, 0x03,
-        ];
-        let cmyk = [
-            0xFF, 0xD8, 0xFF, 0xC2, 0x00, 0x0B, 0x08, 0x00, 0x10, 0x00, 0x10, 0x04,
+    fn autocrop_keeps_blank_pages() {
+        let page =
+            DynamicImage::ImageRgb8(image::RgbImage::from_pixel(20, 10, image::Rgb([0, 0, 0])));
+
+        let trimmed = autocrop(page, Background::BLACK, 0);
+
+        assert_eq!((trimmed.width(), trimmed.height()), (20, 10));
+    }
+
+    #[test]
+    fn aligned_images_are_not_padded() {
+        let image = DynamicImage::ImageRgb8(image::RgbImage::new(32, 16));
+
+        let padded = pad_to_multiple(image, 16, Background::WHITE);
+
+        assert!(matches!(padded, DynamicImage::ImageRgb8(_)));
+        assert_eq!((padded.width(), padded.height()), (32, 16));
+    }
+
+    #[test]
+    fn canvas_padding_centers_the_page() {
+        let page =
+            DynamicImage::ImageRgb8(image::RgbImage::from_pixel(40, 100, image::Rgb([0, 0, 0])));
+
+        let padded = pad_to_canvas(page, 100, 100, Background::WHITE).to_rgb8();
+
+        assert_eq!(padded.dimensions(), (100, 100));
+        assert_eq!(padded.get_pixel(29, 50).0, [255, 255, 255]);
+        assert_eq!(padded.get_pixel(30, 50).0, [0, 0, 0]);
+        assert_eq!(padded.get_pixel(69, 50).0, [0, 0, 0]);
+        assert_eq!(padded.get_pixel(70, 50).0, [255, 255, 255]);
+    }
+
+    #[test]
+    fn canvas_rejects_size_changing_options() {
+        let canvas = || RenderOptions {
+            size: RenderSize::Canvas {
+                width: 800,
+                height: 600,
+            },
+            ..RenderOptions::default()
+        };
+        assert!(canvas().validate().is_ok());
+
+        let conflicts: [fn(&mut RenderOptions); 3] = [
+            |opts| opts.align = Some(16),
+            |opts| opts.extract_images = true,
+            |opts| opts.exif_orientation = true,
         ];
+        for conflict in conflicts {
+            let mut opts = canvas();
+            conflict(&mut opts);
+            assert!(matches!(opts.validate(), Err(Error::InvalidArgs(_))));
+        }
+    }
 
-        assert!(!jpeg_is_cmyk(&rgb));
-        assert!(jpeg_is_cmyk(&cmyk));
-        assert!(!jpeg_is_cmyk(&[0xFF, 0xD8, 0xFF]));
+    #[test]
+    fn background_parses_hex_and_names() {
+        assert_eq!(
+            Background::parse("#1e2a3B").unwrap(),
+            Background {
+                red: 0x1e,
+                green: 0x2a,
+                blue: 0x3b,
+                alpha: 255,
+            }
+        );
+        assert_eq!(Background::parse("white").unwrap(), Background::WHITE);
+        assert_eq!(Background::parse("Black").unwrap(), Background::BLACK);
+        assert_eq!(
+            Background::parse("transparent").unwrap(),
+            Background::TRANSPARENT
+        );
+        assert!(Background::parse("transparent").unwrap().is_transparent());
+        assert!(!Background::BLACK.is_transparent());
     }
 
     #[test]
-    fn render_options_types_have_expected_defaults() {
-        assert!(matches!(JpegEncoderType::default(), JpegEncoderType::Image));
-        assert!(matches!(BoxType::Crop, BoxType::Crop));
-        assert!(matches!(BoxType::Bleed, BoxType::Bleed));
+    fn background_rejects_malformed_colors() {
+        for input in [
+            "",
+            "red",
+            "1e2a3b",
+            "#1e2a3",
+            "#1e2a3b4c",
+            "#gg0000",
+            "#+1+2+3",
+        ] {
+            assert!(
+                matches!(Background::parse(input), Err(Error::InvalidArgs(_))),
+                "{input:?} should be rejected"
+            );
+        }
     }
 
     #[test]
-    fn worker_result_serializes_expected_fields() {
-        let result = WorkerResult {
+    fn background_round_trips_through_worker_arg() {
+        for background in [
+            Background::WHITE,
+            Background::TRANSPARENT,
+            Background::parse("#0a0B0c").unwrap(),
+        ] {
+            assert_eq!(Background::parse(&background.to_arg()).unwrap(), background);
+        }
+    }
+
+    #[test]
+    fn default_options_match_cli_defaults() {
+        let opts = RenderOptions::default();
+        assert_eq!(opts.size, RenderSize::NATIVE);
+        assert_eq!(opts.quality, 100);
+        assert_eq!(opts.format, OutputFormat::Jpeg);
+        assert_eq!(opts.background, Background::WHITE);
+        assert!(opts.validate().is_ok());
+    }
+
+    #[test]
+    fn timeout_errors_cover_the_stuck_page_and_the_rest() {
+        assert_eq!(
+            timeout_errors(&[3, 7, 8], 1, Duration::from_secs(2)),
+            vec![
+                PageError::new(7, "timeout after 2s"),
+                PageError::new(8, "not rendered, worker stopped after page 7 timed out"),
+            ]
+        );
+        assert_eq!(
+            timeout_errors(&[5], 0, Duration::from_secs(1)),
+            vec![PageError::new(5, "timeout after 1s")]
+        );
+    }
+
+    #[test]
+    fn widths_are_ordered_largest_first_without_repeats() {
+        assert_eq!(
+            parse_widths(&[480, 2560, 1024, 480]).unwrap(),
+            vec![2560, 1024, 480]
+        );
+        assert!(parse_widths(&[480, 0]).is_err());
+        assert_eq!(
+            RenderSize::from_widths(&[2560, 1024]),
+            RenderSize::TargetWidth(2560)
+        );
+    }
+
+    #[test]
+    fn sized_output_path_inserts_the_width_before_the_extension() {
+        assert_eq!(
+            sized_output_path(Path::new("out/page-0001.jpg"), 480),
+            Path::new("out/page-0001-480.jpg")
+        );
+        assert_eq!(
+            sized_output_path(Path::new("out/0002-scan.v2.png"), 1024),
+            Path::new("out/0002-scan.v2-1024.png")
+        );
+    }
+
+    #[test]
+    fn scaled_size_keeps_aspect_and_never_enlarges() {
+        assert_eq!(scaled_size(2560, 3620, 480), (480, 679));
+        assert_eq!(scaled_size(800, 600, 1024), (800, 600));
+        assert_eq!(scaled_size(1000, 1, 10), (10, 1));
+    }
+
+    #[test]
+    fn region_parses_points_and_round_trips() {
+        let region = Region::parse("10, 20.5,100,50").unwrap();
+        assert_eq!(
+            region,
+            Region {
+                x: 10.0,
+                y: 20.5,
+                width: 100.0,
+                height: 50.0
+            }
+        );
+        assert_eq!(Region::parse(&region.to_arg()).unwrap(), region);
+    }
+
+    #[test]
+    fn region_rejects_malformed_and_empty_rectangles() {
+        for input in [
+            "",
+            "1,2,3",
+            "1,2,3,4,5",
+            "a,0,10,10",
+            "-1,0,10,10",
+            "0,0,0,10",
+            "0,0,10,inf",
+        ] {
+            assert!(
+                matches!(Region::parse(input), Err(Error::InvalidArgs(_))),
+                "{input:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn region_must_fit_on_page() {
+        let region = Region::parse("306,396,306,396").unwrap();
+        assert!(region.check_within(612.0, 792.0).is_ok());
+        assert!(region.check_within(600.0, 792.0).is_err());
+        assert!(region.check_within(612.0, 700.0).is_err());
+    }
+
+    #[test]
+    fn region_pixel_rect_scales_and_clamps() {
+        let region = Region::parse("50,25,50,25").unwrap();
+        assert_eq!(region.pixel_rect(4.0, 400, 200), (200, 100, 200, 100));
+        // A region reaching past the bitmap edge is cut off there.
+        assert_eq!(region.pixel_rect(4.0, 390, 200), (200, 100, 190, 100));
+    }
+
+    #[test]
+    fn region_excludes_rotation_and_extraction() {
+        let mut opts = RenderOptions {
+            region: Some(Region::parse("0,0,10,10").unwrap()),
+            ..RenderOptions::default()
+        };
+        assert!(opts.validate().is_ok());
+
+        opts.rotation = Rotation::Degrees90;
+        assert!(matches!(opts.validate(), Err(Error::InvalidArgs(_))));
+
+        opts.rotation = Rotation::None;
+        opts.extract_images = true;
+        assert!(matches!(opts.validate(), Err(Error::InvalidArgs(_))));
+    }
+
+    #[test]
+    fn zero_page_timeout_is_rejected() {
+        let mut opts = RenderOptions {
+            page_timeout: Some(Duration::ZERO),
+            ..RenderOptions::default()
+        };
+        assert!(matches!(opts.validate(), Err(Error::InvalidArgs(_))));
+
+        opts.page_timeout = Some(Duration::from_secs(1));
+        assert!(opts.validate().is_ok());
+    }
+
+    #[test]
+    fn zero_worker_mem_limit_is_rejected() {
+        let mut opts = RenderOptions {
+            worker_mem_limit: Some(0),
+            ..RenderOptions::default()
+        };
+        assert!(matches!(opts.validate(), Err(Error::InvalidArgs(_))));
+
+        opts.worker_mem_limit = Some(512);
+        assert!(opts.validate().is_ok());
+    }
+
+    #[test]
+    fn zero_max_inflight_is_rejected() {
+        let mut opts = RenderOptions {
+            max_inflight: Some(0),
+            ..RenderOptions::default()
+        };
+        assert!(matches!(opts.validate(), Err(Error::InvalidArgs(_))));
+
+        opts.max_inflight = Some(2);
+        assert!(opts.validate().is_ok());
+    }
+
+    #[test]
+    fn strict_rejects_retries() {
+        let mut opts = RenderOptions {
+            strict: true,
+            retries: 2,
+            ..RenderOptions::default()
+        };
+        assert!(matches!(opts.validate(), Err(Error::InvalidArgs(_))));
+
+        opts.retries = 0;
+        assert!(opts.validate().is_ok());
+    }
+
+    #[test]
+    fn zero_limit_is_rejected() {
+        let mut opts = RenderOptions {
+            limit: Some(0),
+            ..RenderOptions::default()
+        };
+        assert!(matches!(opts.validate(), Err(Error::InvalidArgs(_))));
+
+        opts.limit = Some(1);
+        assert!(opts.validate().is_ok());
+    }
+
+    #[test]
+    fn zero_repeat_is_rejected() {
+        let mut opts = RenderOptions {
+            repeat: 0,
+            ..RenderOptions::default()
+        };
+        assert!(matches!(opts.validate(), Err(Error::InvalidArgs(_))));
+
+        opts.repeat = 3;
+        assert!(opts.validate().is_ok());
+    }
+
+    #[test]
+    fn worker_results_merge() {
+        let mut total = WorkerResult::default();
+        total.merge(WorkerResult {
             pages_rendered: 2,
             pages_extracted: 1,
-            errors: vec!["page 3 failed".to_string()],
+            pages_cached: 1,
+            errors: vec![PageError::new(4, "failed")],
+            outputs: vec![manifest_test_entry(1)],
+            iteration_secs: Vec::new(),
+            not_upscaled: vec![1],
+            box_fallbacks: vec![4],
+        });
+        total.merge(WorkerResult {
+            pages_rendered: 3,
+            pages_extracted: 0,
+            pages_cached: 0,
+            errors: Vec::new(),
+            outputs: vec![manifest_test_entry(5)],
+            iteration_secs: Vec::new(),
+            not_upscaled: vec![5],
+            box_fallbacks: Vec::new(),
+        });
+        assert_eq!(total.pages_rendered, 5);
+        assert_eq!(total.pages_extracted, 1);
+        assert_eq!(total.pages_cached, 1);
+        assert_eq!(total.errors, vec![PageError::new(4, "failed")]);
+        let pages: Vec<_> = total.outputs.iter().map(|entry| entry.page).collect();
+        assert_eq!(pages, vec![1, 5]);
+        assert_eq!(total.not_upscaled, vec![1, 5]);
+        assert_eq!(total.box_fallbacks, vec![4]);
+    }
+
+    fn manifest_test_entry(page: u32) -> ManifestEntry {
+        ManifestEntry {
+            page,
+            file: format!("out/page-{page:04}.jpg"),
+            method: PageStatus::Rendered,
+            width_pt: 72.0,
+            height_pt: 108.0,
+            width_px: 100,
+            height_px: 150,
+            bytes: 2048,
+            sha256: "0".repeat(64),
+        }
+    }
+
+    #[test]
+    fn manifest_entries_serialize_method_in_lowercase() {
+        let mut entry = manifest_test_entry(3);
+        entry.method = PageStatus::Extracted;
+
+        let json = serde_json::to_value(&entry).unwrap();
+
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "page": 3,
+                "file": "out/page-0003.jpg",
+                "method": "extracted",
+                "width_pt": 72.0,
+                "height_pt": 108.0,
+                "width_px": 100,
+                "height_px": 150,
+                "bytes": 2048,
+                "sha256": "0".repeat(64)
+            })
+        );
+        assert_eq!(
+            serde_json::from_value::<ManifestEntry>(json).unwrap(),
+            entry
+        );
+    }
+
+    #[test]
+    fn hashing_writer_hashes_what_it_passes_through() {
+        let mut writer = HashingWriter::new(Vec::new());
+        writer.write_all(b"a").unwrap();
+        writer.write_all(b"bc").unwrap();
+
+        assert_eq!(
+            writer.finish().unwrap(),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+    }
+
+    #[test]
+    fn transparent_output_requires_png() {
+        let mut opts = RenderOptions {
+            size: RenderSize::TargetWidth(100),
+            max_width: None,
+            max_height: None,
+            widths: Vec::new(),
+            supersample: None,
+            resample: ResampleFilter::Lanczos,
+            no_upscale: false,
+            quality: 90,
+            box_type: BoxType::Crop,
+            extract_images: false,
+            reencode_extracted: false,
+            encoder: JpegEncoderType::Image,
+            progressive: false,
+            subsampling: None,
+            password: None,
+            keep_order: false,
+            limit: None,
+            grayscale: false,
+            format: OutputFormat::Jpeg,
+            lossless: false,
+            speed: None,
+            background: Background::TRANSPARENT,
+            rotation: Rotation::None,
+            rotate_auto: false,
+            region: None,
+            align: None,
+            autocrop: None,
+            deskew: false,
+            icc_profile: None,
+            watermark: None,
+            exif_orientation: false,
+            no_images: false,
+            pdfium_flags: PdfiumFlags::default(),
+            layers: Layers::default(),
+            reproducible: false,
+            name_template: NameTemplate::default(),
+            label_names: false,
+            overwrite: true,
+            manifest: None,
+            list_outputs: false,
+            progress: ProgressMode::Human,
+            quiet: false,
+            page_timeout: None,
+            retries: 0,
+            strict: false,
+            repeat: 1,
+            worker_mem_limit: None,
+            max_inflight: None,
+            cache: None,
         };
+        assert!(matches!(opts.validate(), Err(Error::InvalidArgs(_))));
 
-        let json = serde_json::to_value(result).unwrap();
+        opts.format = OutputFormat::Png;
+        assert!(opts.validate().is_ok());
 
-        assert_eq!(json["pages_rendered"], 2);
-        assert_eq!(json["pages_extracted"], 1);
-        assert_eq!(json["errors"][0], "page 3 failed");
+        opts.extract_images = true;
+        assert!(matches!(opts.validate(), Err(Error::InvalidArgs(_))));
     }
 
     #[test]
-    fn render_options_carry_cli_settings() {
-        let opts = RenderOptions {
-            target_width: 1600,
+    fn rotation_excludes_image_extraction() {
+        let mut opts = RenderOptions {
+            size: RenderSize::TargetWidth(100),
+            max_width: None,
+            max_height: None,
+            widths: Vec::new(),
+            supersample: None,
+            resample: ResampleFilter::Lanczos,
+            no_upscale: false,
             quality: 90,
-            box_type: BoxType::Bleed,
+            box_type: BoxType::Crop,
             extract_images: true,
-            encoder: JpegEncoderType::Vips,
+            reencode_extracted: false,
+            encoder: JpegEncoderType::Image,
+            progressive: false,
+            subsampling: None,
+            password: None,
+            keep_order: false,
+            limit: None,
+            grayscale: false,
+            format: OutputFormat::Jpeg,
+            lossless: false,
+            speed: None,
+            background: Background::WHITE,
+            rotation: Rotation::Degrees90,
+            rotate_auto: false,
+            region: None,
+            align: None,
+            autocrop: None,
+            deskew: false,
+            icc_profile: None,
+            watermark: None,
+            exif_orientation: false,
+            no_images: false,
+            pdfium_flags: PdfiumFlags::default(),
+            layers: Layers::default(),
+            reproducible: false,
+            name_template: NameTemplate::default(),
+            label_names: false,
+            overwrite: true,
+            manifest: None,
+            list_outputs: false,
+            progress: ProgressMode::Human,
+            quiet: false,
+            page_timeout: None,
+            retries: 0,
+            strict: false,
+            repeat: 1,
+            worker_mem_limit: None,
+            max_inflight: None,
+            cache: None,
         };
+        assert!(matches!(opts.validate(), Err(Error::InvalidArgs(_))));
 
-        assert_eq!(opts.target_width, 1600);
-        assert_eq!(opts.quality, 90);
-        assert!(matches!(opts.box_type, BoxType::Bleed));
-        assert!(opts.extract_images);
-        assert!(matches!(opts.encoder, JpegEncoderType::Vips));
+        opts.extract_images = false;
+        assert!(opts.validate().is_ok());
+    }
+
+    #[test]
+    fn lossless_webp_has_riff_webp_magic() {
+        let rgb = DynamicImage::ImageRgb8(image::RgbImage::from_pixel(
+            8,
+            4,
+            image::Rgb([20, 120, 220]),
+        ));
+
+        let mut webp = Vec::new();
+        encode_webp_lossless(&rgb, &mut webp).unwrap();
+
+        assert_eq!(&webp[0..4], b"RIFF");
+        assert_eq!(&webp[8..12], b"WEBP");
+        let decoded = image::load_from_memory_with_format(&webp, image::ImageFormat::WebP).unwrap();
+        assert_eq!(decoded.to_rgb8().get_pixel(3, 2).0, [20, 120, 220]);
+    }
+
+    #[test]
+    fn speed_only_applies_to_avif() {
+        let mut opts = RenderOptions {
+            speed: Some(6),
+            ..RenderOptions::default()
+        };
+        assert!(matches!(opts.validate(), Err(Error::InvalidArgs(_))));
+
+        opts.format = OutputFormat::Avif;
+        assert_eq!(opts.validate().is_ok(), cfg!(feature = "avif"));
+        opts.speed = Some(11);
+        assert!(matches!(opts.validate(), Err(Error::InvalidArgs(_))));
+        assert_eq!(opts.format.extension(), "avif");
+    }
+
+    #[cfg(feature = "avif")]
+    #[test]
+    fn avif_has_ftyp_avif_box() {
+        let rgb = DynamicImage::new_rgb8(16, 16);
+        let mut avif = Vec::new();
+        encode_avif(&rgb, &mut avif, 80, 10).unwrap();
+
+        assert_eq!(&avif[4..8], b"ftyp");
+        assert_eq!(&avif[8..12], b"avif");
+    }
+
+    #[test]
+    fn lossless_only_applies_to_webp() {
+        let mut opts = RenderOptions {
+            lossless: true,
+            ..RenderOptions::default()
+        };
+        assert!(matches!(opts.validate(), Err(Error::InvalidArgs(_))));
+
+        opts.format = OutputFormat::Webp;
+        assert!(opts.validate().is_ok());
+
+        opts.background = Background::TRANSPARENT;
+        assert!(opts.validate().is_ok());
+        assert_eq!(opts.format.extension(), "webp");
+    }
+
+    #[test]
+    fn single_page_tiff_decodes_back() {
+        let rgb =
+            DynamicImage::ImageRgb8(image::RgbImage::from_pixel(6, 3, image::Rgb([200, 10, 40])));
+
+        let mut tiff = Vec::new();
+        encode_tiff(&rgb, &mut tiff).unwrap();
+
+        let decoded = image::load_from_memory_with_format(&tiff, image::ImageFormat::Tiff).unwrap();
+        assert_eq!(decoded.to_rgb8().get_pixel(5, 2).0, [200, 10, 40]);
+    }
+
+    #[test]
+    fn tiff_frames_are_appended_as_pages() {
+        let frames = [
+            DynamicImage::ImageRgb8(image::RgbImage::new(4, 2)),
+            DynamicImage::ImageLuma8(image::GrayImage::new(3, 3)),
+            DynamicImage::ImageLumaA8(image::GrayAlphaImage::new(2, 5)),
+        ];
+
+        let mut file = Cursor::new(Vec::new());
+        let mut encoder = tiff::encoder::TiffEncoder::new(&mut file).unwrap();
+        for frame in &frames {
+            write_tiff_frame(&mut encoder, frame).unwrap();
+        }
+
+        file.set_position(0);
+        let mut decoder = tiff::decoder::Decoder::new(file).unwrap();
+        let mut dimensions = vec![decoder.dimensions().unwrap()];
+        while decoder.more_images() {
+            decoder.next_image().unwrap();
+            dimensions.push(decoder.dimensions().unwrap());
+        }
+        assert_eq!(dimensions, vec![(4, 2), (3, 3), (2, 5)]);
+    }
+
+    #[test]
+    fn png_encoding_keeps_alpha_channel() {
+        let rgba = DynamicImage::ImageRgba8(image::RgbaImage::from_pixel(
+            4,
+            4,
+            image::Rgba([255, 255, 255, 0]),
+        ));
+
+        let mut png = Vec::new();
+        encode_png(&rgba, &mut png, None).unwrap();
+
+        let decoded = image::load_from_memory_with_format(&png, image::ImageFormat::Png).unwrap();
+        assert!(decoded.color().has_alpha());
+        assert_eq!(decoded.to_rgba8().get_pixel(0, 0)[3], 0);
+    }
+
+    fn jpeg_segment(marker: u8, payload: &[u8]) -> Vec<u8> {
+        let mut segment = vec![0xFF, marker];
+        segment.extend_from_slice(&(payload.len() as u16 + 2).to_be_bytes());
+        segment.extend_from_slice(payload);
+        segment
+    }
+
+    #[test]
+    fn strip_jpeg_metadata_drops_exif_and_comments() {
+        let jfif = jpeg_segment(0xE0, b"JFIF\0\x01\x01");
+        let icc = jpeg_segment(0xE2, b"ICC_PROFILE\0\x01\x01data");
+        let adobe = jpeg_segment(0xEE, b"Adobe");
+        let scan = [&jpeg_segment(0xDA, b"sos")[..], &[0x12, 0xFE, 0xD9]].concat();
+        let jpeg = [
+            &[0xFF, 0xD8][..],
+            &jfif,
+            &jpeg_segment(0xE1, b"Exif\0\0 2024:01:01 12:00:00"),
+            &icc,
+            &jpeg_segment(0xFE, b"created today"),
+            &jpeg_segment(0xED, b"Photoshop 3.0"),
+            &adobe,
+            &scan,
+        ]
+        .concat();
+
+        let stripped = strip_jpeg_metadata(&jpeg);
+
+        let expected = [&[0xFF, 0xD8][..], &jfif, &icc, &adobe, &scan].concat();
+        assert_eq!(stripped, expected);
+        assert_eq!(strip_jpeg_metadata(&stripped), expected);
+    }
+
+    #[test]
+    fn strip_jpeg_metadata_leaves_unparseable_data_alone() {
+        assert_eq!(strip_jpeg_metadata(b"not a jpeg"), b"not a jpeg");
+        let truncated = [0xFF, 0xD8, 0xFF, 0xE1, 0x00, 0x40, 0x01];
+        assert_eq!(strip_jpeg_metadata(&truncated), truncated);
+    }
+
+    #[test]
+    fn jfif_density_is_rewritten_in_place() {
+        let rgb = DynamicImage::ImageRgb8(image::RgbImage::new(4, 4));
+        let mut jpeg = Vec::new();
+        encode_jpeg_image(&rgb, &mut jpeg, 90).unwrap();
+
+        let dense = set_jfif_density(&jpeg, 300);
+
+        assert_eq!(dense.len(), jpeg.len());
+        assert_eq!(&dense[6..11], b"JFIF\0");
+        assert_eq!(dense[13], 1);
+        assert_eq!(&dense[14..18], &[0x01, 0x2C, 0x01, 0x2C]);
+        assert_eq!(image::load_from_memory(&dense).unwrap().width(), 4);
+    }
+
+    #[test]
+    fn jfif_density_adds_missing_header() {
+        let jpeg = [0xFF, 0xD8, 0xFF, 0xDB, 0x00, 0x02, 0xFF, 0xD9];
+
+        let dense = set_jfif_density(&jpeg, 150);
+
+        assert_eq!(&dense[2..6], &[0xFF, 0xE0, 0x00, 0x10]);
+        assert_eq!(&dense[6..11], b"JFIF\0");
+        assert_eq!(&dense[13..18], &[1, 0x00, 0x96, 0x00, 0x96]);
+        assert_eq!(&dense[20..], &jpeg[2..]);
+        assert_eq!(set_jfif_density(b"not a jpeg", 150), b"not a jpeg");
+    }
+
+    #[test]
+    fn exif_orientation_is_read_back_by_decoders() {
+        let rgb = DynamicImage::ImageRgb8(image::RgbImage::new(4, 2));
+        let mut jpeg = Vec::new();
+        encode_jpeg_image(&rgb, &mut jpeg, 90).unwrap();
+
+        let oriented = embed_exif_orientation(&jpeg, Rotation::Degrees90.exif_orientation());
+
+        assert_eq!(&oriented[..jfif_end(&jpeg)], &jpeg[..jfif_end(&jpeg)]);
+        let mut decoder =
+            image::codecs::jpeg::JpegDecoder::new(Cursor::new(oriented.as_slice())).unwrap();
+        assert_eq!(
+            image::ImageDecoder::orientation(&mut decoder).unwrap(),
+            image::metadata::Orientation::Rotate90
+        );
+    }
+
+    #[test]
+    fn no_images_excludes_extract_images() {
+        let mut opts = RenderOptions {
+            no_images: true,
+            ..RenderOptions::default()
+        };
+        assert!(opts.validate().is_ok());
+
+        opts.extract_images = true;
+        assert!(matches!(opts.validate(), Err(Error::InvalidArgs(_))));
+    }
+
+    #[test]
+    fn a_layer_cannot_be_both_hidden_and_shown() {
+        let mut opts = RenderOptions {
+            layers: Layers {
+                off: vec!["Dimensions".into()],
+                on: vec!["Notes".into()],
+            },
+            ..RenderOptions::default()
+        };
+        assert!(opts.validate().is_ok());
+
+        opts.layers.on.push("Dimensions".into());
+        assert!(matches!(opts.validate(), Err(Error::InvalidArgs(_))));
+    }
+
+    #[test]
+    fn level_rotation_undoes_the_lean_of_most_text() {
+        // Text running up the page, as on a portrait scan of a landscape page.
+        assert_eq!(
+            level_rotation([270.0, 270.5, 269.7], 0),
+            Rotation::Degrees90
+        );
+        assert_eq!(level_rotation([180.0, 0.0, 180.0], 0), Rotation::Degrees180);
+        assert_eq!(level_rotation([359.8, 0.1], 0), Rotation::None);
+        // /Rotate 90 already turns text drawn at 270 upright.
+        assert_eq!(level_rotation([270.0], 90), Rotation::None);
+        assert_eq!(level_rotation([0.0], 90), Rotation::Degrees270);
+        // No text, or a tie with upright text: leave the page alone.
+        assert_eq!(level_rotation([], 180), Rotation::None);
+        assert_eq!(level_rotation([0.0, 90.0], 0), Rotation::None);
+    }
+
+    #[test]
+    fn rotate_auto_excludes_fixed_and_per_pixel_orientation() {
+        let auto = || RenderOptions {
+            rotate_auto: true,
+            ..RenderOptions::default()
+        };
+        assert!(auto().validate().is_ok());
+
+        let conflicts: [fn(&mut RenderOptions); 4] = [
+            |opts| opts.rotation = Rotation::Degrees90,
+            |opts| opts.exif_orientation = true,
+            |opts| opts.region = Region::parse("0,0,10,10").ok(),
+            |opts| opts.extract_images = true,
+        ];
+        for conflict in conflicts {
+            let mut opts = auto();
+            conflict(&mut opts);
+            assert!(matches!(opts.validate(), Err(Error::InvalidArgs(_))));
+        }
+    }
+
+    #[test]
+    fn exif_orientation_requires_jpeg_without_align() {
+        let mut opts = RenderOptions {
+            exif_orientation: true,
+            rotation: Rotation::Degrees90,
+            ..RenderOptions::default()
+        };
+        assert!(opts.validate().is_ok());
+        assert_eq!(opts.baked_rotation(), Rotation::None);
+
+        opts.align = Some(16);
+        assert!(matches!(opts.validate(), Err(Error::InvalidArgs(_))));
+        opts.align = None;
+        opts.format = OutputFormat::Png;
+        assert!(matches!(opts.validate(), Err(Error::InvalidArgs(_))));
+    }
+
+    #[test]
+    fn png_encoding_embeds_icc_profile() {
+        let rgb = DynamicImage::ImageRgb8(image::RgbImage::new(2, 2));
+        let profile = IccProfile::srgb();
+
+        let mut png = Vec::new();
+        encode_png(&rgb, &mut png, Some(&profile)).unwrap();
+
+        let mut decoder = image::codecs::png::PngDecoder::new(Cursor::new(png.as_slice())).unwrap();
+        assert_eq!(
+            image::ImageDecoder::icc_profile(&mut decoder).unwrap(),
+            Some(profile.data)
+        );
+    }
+
+    #[test]
+    fn icc_profile_requires_jpeg_or_png() {
+        let mut opts = RenderOptions {
+            icc_profile: Some(IccProfile::srgb()),
+            ..RenderOptions::default()
+        };
+        assert!(opts.validate().is_ok());
+        opts.format = OutputFormat::Tiff;
+        assert!(matches!(opts.validate(), Err(Error::InvalidArgs(_))));
+        opts.format = OutputFormat::Jpeg;
+        opts.extract_images = true;
+        assert!(matches!(opts.validate(), Err(Error::InvalidArgs(_))));
     }
 }
@@ -0,0 +1,180 @@
+#[cfg(not(test))]
+use crate::error::Error;
+#[cfg(not(test))]
+use crate::json_output::print_json;
+#[cfg(not(test))]
+use crate::page_range::parse_page_range;
+#[cfg(not(test))]
+use crate::pdfium_init::{load_document, load_pdfium};
+#[cfg(not(test))]
+use pdfium_render::prelude::*;
+use serde::Serialize;
+#[cfg(not(test))]
+use std::path::Path;
+
+/// One occurrence of the query, bounded in page points (origin bottom-left).
+#[derive(Serialize, Debug, PartialEq)]
+pub struct SearchMatch {
+    pub text: String,
+    pub left: f32,
+    pub bottom: f32,
+    pub right: f32,
+    pub top: f32,
+}
+
+impl SearchMatch {
+    /// Combine the segments pdfium reports for one match (one per line when
+    /// a match wraps) into a single match covering all of them. Whitespace
+    /// around the match, which pdfium reads from the segment bounds along with
+    /// the matched characters, is trimmed.
+    pub fn from_segments(segments: impl IntoIterator<Item = (String, [f32; 4])>) -> Option<Self> {
+        let mut found = segments
+            .into_iter()
+            .map(|(text, [left, bottom, right, top])| SearchMatch {
+                text,
+                left,
+                bottom,
+                right,
+                top,
+            })
+            .reduce(|mut combined, segment| {
+                combined.text.push_str(&segment.text);
+                combined.left = combined.left.min(segment.left);
+                combined.bottom = combined.bottom.min(segment.bottom);
+                combined.right = combined.right.max(segment.right);
+                combined.top = combined.top.max(segment.top);
+                combined
+            })?;
+        found.text = found.text.trim().to_string();
+        Some(found)
+    }
+}
+
+#[cfg(not(test))]
+#[derive(Serialize)]
+pub struct PageMatches {
+    pub page: u32,
+    pub matches: Vec<SearchMatch>,
+}
+
+/// Search page text for `query` and print `[{page, matches}]` as JSON.
+///
+/// Pages without a match are left out, so no match at all prints `[]`.
+#[cfg(not(test))]
+#[cfg_attr(coverage_nightly, coverage(off))]
+pub fn run(
+    pdf_path: &Path,
+    query: &str,
+    pages: Option<&str>,
+    ignore_case: bool,
+    whole_word: bool,
+    password: Option<&str>,
+) -> Result<(), Error> {
+    if query.is_empty() {
+        return Err(Error::InvalidArgs("search query is empty".into()));
+    }
+    let pdfium = load_pdfium()?;
+    let document = load_document(&pdfium, pdf_path, password)?;
+    let total_pages = document.pages().len() as u32;
+
+    let page_list = match pages {
+        Some(range_str) => parse_page_range(range_str, total_pages)?,
+        None => (1..=total_pages).collect(),
+    };
+
+    let options = PdfSearchOptions::new()
+        .match_case(!ignore_case)
+        .match_whole_word(whole_word);
+    let mut results = Vec::new();
+    for page_num in page_list {
+        let matches = search_page(&document, page_num, query, &options)?;
+        if !matches.is_empty() {
+            results.push(PageMatches {
+                page: page_num,
+                matches,
+            });
+        }
+    }
+
+    print_json("search", &results);
+    Ok(())
+}
+
+#[cfg(not(test))]
+#[cfg_attr(coverage_nightly, coverage(off))]
+fn search_page(
+    document: &PdfDocument,
+    page_num: u32,
+    query: &str,
+    options: &PdfSearchOptions,
+) -> Result<Vec<SearchMatch>, Error> {
+    let page = document
+        .pages()
+        .get((page_num - 1) as u16)
+        .map_err(|e| Error::PdfInvalid(format!("page {page_num}: {e}")))?;
+    let text = page
+        .text()
+        .map_err(|e| Error::PdfInvalid(format!("page {page_num}: text extraction failed: {e}")))?;
+    let search = text
+        .search(query, options)
+        .map_err(|e| Error::PdfInvalid(format!("page {page_num}: search failed: {e}")))?;
+
+    Ok(search
+        .iter(PdfSearchDirection::SearchForward)
+        .filter_map(|segments| {
+            SearchMatch::from_segments(segments.iter().map(|segment| {
+                let bounds = segment.bounds();
+                (
+                    segment.text(),
+                    [
+                        bounds.left().value,
+                        bounds.bottom().value,
+                        bounds.right().value,
+                        bounds.top().value,
+                    ],
+                )
+            }))
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn single_segment_is_the_match() {
+        let found = SearchMatch::from_segments([("word".to_string(), [1.0, 2.0, 3.0, 4.0])]);
+
+        assert_eq!(
+            found,
+            Some(SearchMatch {
+                text: "word".into(),
+                left: 1.0,
+                bottom: 2.0,
+                right: 3.0,
+                top: 4.0,
+            })
+        );
+    }
+
+    #[test]
+    fn wrapped_match_covers_every_line() {
+        let found = SearchMatch::from_segments([
+            ("end of ".to_string(), [300.0, 700.0, 560.0, 712.0]),
+            ("line ".to_string(), [20.0, 686.0, 60.0, 698.0]),
+        ])
+        .unwrap();
+
+        assert_eq!(found.text, "end of line");
+        assert_eq!(
+            [found.left, found.bottom, found.right, found.top],
+            [20.0, 686.0, 560.0, 712.0]
+        );
+    }
+
+    #[test]
+    fn no_segments_is_no_match() {
+        assert_eq!(SearchMatch::from_segments([]), None);
+    }
+}
@@ -0,0 +1,108 @@
+#[cfg(not(test))]
+use crate::error::Error;
+#[cfg(not(test))]
+use crate::json_output::print_json;
+#[cfg(not(test))]
+use crate::output_path::{check_output_file, create_parent_dir};
+#[cfg(not(test))]
+use crate::page_range::parse_page_range;
+#[cfg(not(test))]
+use crate::pdfium_init::{load_document, load_pdfium};
+use crate::render_worker::Rotation;
+#[cfg(not(test))]
+use crate::render_worker::{render_rotation, rotation_degrees};
+#[cfg(not(test))]
+use pdfium_render::prelude::*;
+#[cfg(not(test))]
+use serde::Serialize;
+#[cfg(not(test))]
+use std::path::Path;
+
+#[cfg(not(test))]
+#[derive(Serialize)]
+struct SubsetSummary {
+    pages: u32,
+    output_file: String,
+}
+
+/// Copy the selected pages into a new PDF at `output_file`, optionally
+/// turning each one by `rotation` on top of its own `/Rotate`.
+///
+/// Pages are imported with pdfium, so their content stays vector; nothing is
+/// rasterized.
+#[cfg(not(test))]
+#[cfg_attr(coverage_nightly, coverage(off))]
+pub fn run(
+    pdf_path: &Path,
+    output_file: &Path,
+    pages: Option<&str>,
+    rotation: Rotation,
+    password: Option<&str>,
+    quiet: bool,
+) -> Result<(), Error> {
+    check_output_file(output_file)?;
+    let pdfium = load_pdfium()?;
+    let source = load_document(&pdfium, pdf_path, password)?;
+    let total_pages = source.pages().len() as u32;
+    let page_list = match pages {
+        Some(range_str) => parse_page_range(range_str, total_pages)?,
+        None => (1..=total_pages).collect(),
+    };
+    if page_list.is_empty() {
+        return Err(Error::PdfInvalid("PDF has no pages".into()));
+    }
+
+    let subset_error = |e: PdfiumError| Error::Render(format!("subset: {e}"));
+    let mut subset = pdfium.create_new_pdf().map_err(subset_error)?;
+    for (index, &page_num) in page_list.iter().enumerate() {
+        let destination = index as PdfPageIndex;
+        subset
+            .pages_mut()
+            .copy_page_from_document(&source, (page_num - 1) as PdfPageIndex, destination)
+            .map_err(subset_error)?;
+        if rotation != Rotation::None {
+            let mut page = subset.pages().get(destination).map_err(subset_error)?;
+            let current = page.rotation().map_or(0, rotation_degrees);
+            page.set_rotation(render_rotation(add_rotation(current, rotation)));
+        }
+    }
+
+    create_parent_dir(output_file)?;
+    subset
+        .save_to_file(output_file)
+        .map_err(|e| Error::Render(format!("{}: {e}", output_file.display())))?;
+
+    if !quiet {
+        let summary = SubsetSummary {
+            pages: page_list.len() as u32,
+            output_file: output_file.display().to_string(),
+        };
+        print_json("subset", &summary);
+    }
+    Ok(())
+}
+
+/// The page rotation after turning a page already at `current` degrees
+/// clockwise by `added`.
+fn add_rotation(current: u32, added: Rotation) -> Rotation {
+    match (current + added.degrees()) % 360 {
+        90 => Rotation::Degrees90,
+        180 => Rotation::Degrees180,
+        270 => Rotation::Degrees270,
+        _ => Rotation::None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rotation_adds_to_existing_page_rotation() {
+        assert_eq!(add_rotation(0, Rotation::Degrees90), Rotation::Degrees90);
+        assert_eq!(add_rotation(90, Rotation::Degrees90), Rotation::Degrees180);
+        assert_eq!(add_rotation(270, Rotation::Degrees180), Rotation::Degrees90);
+        assert_eq!(add_rotation(270, Rotation::Degrees90), Rotation::None);
+        assert_eq!(add_rotation(180, Rotation::None), Rotation::Degrees180);
+    }
+}
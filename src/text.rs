@@ -0,0 +1,283 @@
+#[cfg(not(test))]
+use crate::error::Error;
+#[cfg(not(test))]
+use crate::json_output::print_json;
+#[cfg(not(test))]
+use crate::page_range::parse_page_range;
+#[cfg(not(test))]
+use crate::pdfium_init::{load_document, load_pdfium};
+#[cfg(not(test))]
+use pdfium_render::prelude::*;
+#[cfg(not(test))]
+use serde::Serialize;
+#[cfg(not(test))]
+use std::path::Path;
+
+#[cfg(not(test))]
+#[derive(Serialize)]
+pub struct PageText {
+    pub page: u32,
+    pub text: String,
+}
+
+/// A page's text summarized for `--stats`.
+#[cfg(not(test))]
+#[derive(Serialize)]
+pub struct PageTextStats {
+    pub page: u32,
+    pub char_count: u32,
+    pub word_count: u32,
+    /// No extractable text, as on scanned pages that need OCR
+    pub is_empty: bool,
+}
+
+/// A character and its bounds in page points (origin bottom-left).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PositionedChar {
+    pub ch: char,
+    pub left: f32,
+    pub bottom: f32,
+    pub right: f32,
+    pub top: f32,
+}
+
+impl PositionedChar {
+    fn center_y(&self) -> f32 {
+        (self.bottom + self.top) / 2.0
+    }
+}
+
+/// Extract Unicode text per page, in the reading order pdfium reports, or
+/// laid out by position with `layout` (see `layout_text`). With `stats`, each
+/// page's text is replaced by its counts (see `count_text`).
+///
+/// Every requested page is emitted; pages without text get an empty string.
+#[cfg(not(test))]
+#[cfg_attr(coverage_nightly, coverage(off))]
+pub fn run(
+    pdf_path: &Path,
+    pages: Option<&str>,
+    password: Option<&str>,
+    layout: bool,
+    stats: bool,
+) -> Result<(), Error> {
+    let pdfium = load_pdfium()?;
+    let document = load_document(&pdfium, pdf_path, password)?;
+    let total_pages = document.pages().len() as u32;
+
+    let page_list = match pages {
+        Some(range_str) => parse_page_range(range_str, total_pages)?,
+        None => (1..=total_pages).collect(),
+    };
+
+    let texts = page_list
+        .iter()
+        .map(|&page_num| extract_page_text(&document, page_num, layout))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    if stats {
+        let stats: Vec<_> = texts
+            .into_iter()
+            .map(|text| {
+                let (char_count, word_count) = count_text(&text.text);
+                PageTextStats {
+                    page: text.page,
+                    char_count,
+                    word_count,
+                    is_empty: char_count == 0,
+                }
+            })
+            .collect();
+        print_json("text-stats", &stats);
+    } else {
+        print_json("text", &texts);
+    }
+
+    Ok(())
+}
+
+/// Non-whitespace characters and whitespace-separated words in `text`, so
+/// pages holding only spaces or line breaks count as empty.
+pub fn count_text(text: &str) -> (u32, u32) {
+    let chars = text.chars().filter(|ch| !ch.is_whitespace()).count();
+    let words = text.split_whitespace().count();
+    (chars as u32, words as u32)
+}
+
+#[cfg(not(test))]
+#[cfg_attr(coverage_nightly, coverage(off))]
+fn extract_page_text(
+    document: &PdfDocument,
+    page_num: u32,
+    layout: bool,
+) -> Result<PageText, Error> {
+    let page = document
+        .pages()
+        .get((page_num - 1) as u16)
+        .map_err(|e| Error::PdfInvalid(format!("page {page_num}: {e}")))?;
+    let text = page
+        .text()
+        .map_err(|e| Error::PdfInvalid(format!("page {page_num}: text extraction failed: {e}")))?;
+    let text = if layout {
+        layout_text(&positioned_chars(&text))
+    } else {
+        text.all()
+    };
+
+    Ok(PageText {
+        page: page_num,
+        text,
+    })
+}
+
+/// Every character pdfium reports for the page, with its loose bounds.
+#[cfg(not(test))]
+#[cfg_attr(coverage_nightly, coverage(off))]
+fn positioned_chars(text: &PdfPageText) -> Vec<PositionedChar> {
+    text.chars()
+        .iter()
+        .filter_map(|text_char| {
+            let ch = text_char.unicode_char()?;
+            let bounds = text_char.loose_bounds().ok()?;
+            Some(PositionedChar {
+                ch,
+                left: bounds.left().value,
+                bottom: bounds.bottom().value,
+                right: bounds.right().value,
+                top: bounds.top().value,
+            })
+        })
+        .collect()
+}
+
+/// Lay `chars` out on a grid of fixed-width cells, like `pdftotext -layout`,
+/// so columns and tables stay apart.
+///
+/// The cell is the median character width. Characters whose vertical centers
+/// are within half a line of each other form a line, and each goes in the
+/// column of its left edge; a gap of more than two lines leaves a blank line.
+/// Whitespace is dropped, since spacing comes from the positions; a gap of
+/// more than a third of a cell, about a proportional font's space, still
+/// separates two words that land in adjacent columns.
+pub fn layout_text(chars: &[PositionedChar]) -> String {
+    let mut glyphs: Vec<PositionedChar> = chars
+        .iter()
+        .filter(|glyph| !glyph.ch.is_whitespace())
+        .copied()
+        .collect();
+    if glyphs.is_empty() {
+        return String::new();
+    }
+    let cell = median(glyphs.iter().map(|glyph| glyph.right - glyph.left)).max(1.0);
+    let line_height = median(glyphs.iter().map(|glyph| glyph.top - glyph.bottom)).max(1.0);
+    let origin = glyphs
+        .iter()
+        .map(|glyph| glyph.left)
+        .fold(f32::INFINITY, f32::min);
+
+    // Top of the page first; the PDF y axis points up.
+    glyphs.sort_by(|a, b| b.center_y().total_cmp(&a.center_y()));
+    let mut lines: Vec<(f32, Vec<PositionedChar>)> = Vec::new();
+    for glyph in glyphs {
+        match lines.last_mut() {
+            Some((center, line)) if *center - glyph.center_y() <= line_height / 2.0 => {
+                line.push(glyph)
+            }
+            _ => lines.push((glyph.center_y(), vec![glyph])),
+        }
+    }
+
+    let mut out = String::new();
+    let mut previous_center = None;
+    for (center, mut line) in lines {
+        if let Some(previous) = previous_center {
+            out.push('\n');
+            if previous - center > 2.0 * line_height {
+                out.push('\n');
+            }
+        }
+        previous_center = Some(center);
+
+        line.sort_by(|a, b| a.left.total_cmp(&b.left));
+        let mut column = 0;
+        let mut previous_right: Option<f32> = None;
+        for glyph in line {
+            let target = ((glyph.left - origin) / cell).round() as usize;
+            if target > column {
+                out.extend(std::iter::repeat_n(' ', target - column));
+                column = target;
+            } else if previous_right.is_some_and(|right| glyph.left - right > cell / 3.0) {
+                out.push(' ');
+                column += 1;
+            }
+            out.push(glyph.ch);
+            column += 1;
+            previous_right = Some(glyph.right);
+        }
+    }
+    out
+}
+
+/// The median of `values`, or 0 for none.
+fn median(values: impl Iterator<Item = f32>) -> f32 {
+    let mut values: Vec<f32> = values.collect();
+    if values.is_empty() {
+        return 0.0;
+    }
+    values.sort_by(f32::total_cmp);
+    values[values.len() / 2]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts_skip_whitespace() {
+        assert_eq!(count_text("Hello from  page\none\n"), (16, 4));
+        assert_eq!(count_text(" \r\n\t"), (0, 0));
+        assert_eq!(count_text(""), (0, 0));
+    }
+
+    /// Characters of `text` in 10pt cells from (`left`, `bottom`), 12pt tall.
+    fn row(text: &str, left: f32, bottom: f32) -> Vec<PositionedChar> {
+        text.chars()
+            .enumerate()
+            .map(|(i, ch)| PositionedChar {
+                ch,
+                left: left + 10.0 * i as f32,
+                bottom,
+                right: left + 10.0 * (i + 1) as f32,
+                top: bottom + 12.0,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn layout_keeps_columns_apart() {
+        let mut chars = row("ab", 20.0, 100.0);
+        chars.extend(row("cd", 120.0, 101.0));
+        chars.extend(row("ef", 20.0, 86.0));
+        chars.extend(row("gh", 120.0, 86.0));
+
+        assert_eq!(layout_text(&chars), "ab        cd\nef        gh");
+    }
+
+    #[test]
+    fn layout_separates_words_and_paragraphs() {
+        // A gap under one cell still separates words, and pdfium's own
+        // spaces are ignored.
+        let mut chars = row("one", 0.0, 100.0);
+        chars.extend(row(" ", 30.0, 100.0));
+        chars.extend(row("two", 36.0, 100.0));
+        chars.extend(row("end", 0.0, 50.0));
+
+        assert_eq!(layout_text(&chars), "one two\n\nend");
+    }
+
+    #[test]
+    fn layout_of_nothing_is_empty() {
+        assert_eq!(layout_text(&[]), "");
+        assert_eq!(layout_text(&row("  ", 0.0, 0.0)), "");
+    }
+}
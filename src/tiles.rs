@@ -0,0 +1,233 @@
+#[cfg(not(test))]
+use crate::error::Error;
+#[cfg(not(test))]
+use crate::json_output::print_json;
+#[cfg(not(test))]
+use crate::output_path::{check_output_dir, create_output_dir};
+#[cfg(not(test))]
+use crate::page_range::parse_page_range;
+#[cfg(not(test))]
+use crate::pdfium_init::{load_document, load_pdfium};
+#[cfg(not(test))]
+use crate::render_worker::check_bitmap_size;
+use image::RgbImage;
+use image::imageops::crop_imm;
+#[cfg(not(test))]
+use pdfium_render::prelude::*;
+#[cfg(not(test))]
+use serde::Serialize;
+#[cfg(not(test))]
+use std::path::Path;
+
+#[cfg(not(test))]
+#[derive(Serialize)]
+struct TilesSummary {
+    pages: Vec<PageTiles>,
+    tiles: u32,
+    output_dir: String,
+}
+
+#[cfg(not(test))]
+#[derive(Serialize)]
+struct PageTiles {
+    page: u32,
+    level: u32,
+    width_px: u32,
+    height_px: u32,
+    columns: u32,
+    rows: u32,
+}
+
+/// Render each selected page `target_width` pixels wide and slice it into
+/// `tile` pixel squares, written as `page-NNNN/<level>/<x>_<y>.jpg` under
+/// `output_dir`.
+///
+/// Pages are rendered one row of tiles at a time, so memory stays at
+/// `target_width` x `tile` pixels however tall the page is.
+#[cfg(not(test))]
+#[cfg_attr(coverage_nightly, coverage(off))]
+pub fn run(
+    pdf_path: &Path,
+    output_dir: &Path,
+    pages: Option<&str>,
+    tile: u32,
+    target_width: u32,
+    password: Option<&str>,
+    quiet: bool,
+) -> Result<(), Error> {
+    if tile == 0 || target_width == 0 {
+        return Err(Error::InvalidArgs(
+            "--tile and --target-width must be greater than 0".into(),
+        ));
+    }
+
+    check_output_dir(output_dir)?;
+
+    let pdfium = load_pdfium()?;
+    let document = load_document(&pdfium, pdf_path, password)?;
+    let total_pages = document.pages().len() as u32;
+    let page_list = match pages {
+        Some(range_str) => parse_page_range(range_str, total_pages)?,
+        None => (1..=total_pages).collect(),
+    };
+    if page_list.is_empty() {
+        return Err(Error::PdfInvalid("PDF has no pages".into()));
+    }
+
+    create_output_dir(output_dir)?;
+    let mut summary = TilesSummary {
+        pages: Vec::with_capacity(page_list.len()),
+        tiles: 0,
+        output_dir: output_dir.display().to_string(),
+    };
+    for &page_num in &page_list {
+        let page = document
+            .pages()
+            .get((page_num - 1) as u16)
+            .map_err(|e| Error::PdfInvalid(format!("page {page_num}: {e}")))?;
+        let page_tiles = write_page_tiles(&page, page_num, output_dir, tile, target_width)?;
+        summary.tiles += page_tiles.columns * page_tiles.rows;
+        summary.pages.push(page_tiles);
+    }
+
+    if !quiet {
+        print_json("tiles", &summary);
+    }
+    Ok(())
+}
+
+#[cfg(not(test))]
+#[cfg_attr(coverage_nightly, coverage(off))]
+fn write_page_tiles(
+    page: &PdfPage,
+    page_num: u32,
+    output_dir: &Path,
+    tile: u32,
+    target_width: u32,
+) -> Result<PageTiles, Error> {
+    let (width_pt, height_pt) = (page.width().value, page.height().value);
+    if !(width_pt > 0.0 && height_pt > 0.0) {
+        return Err(Error::PdfInvalid(format!(
+            "page {page_num}: zero-size page"
+        )));
+    }
+    let pixels_per_point = target_width as f32 / width_pt;
+    let height_px = ((height_pt * pixels_per_point).round() as u32).max(1);
+    check_bitmap_size(target_width as f64, tile.min(height_px) as f64)?;
+
+    let level = deep_zoom_level(target_width, height_px);
+    let level_dir = output_dir
+        .join(format!("page-{page_num:04}"))
+        .join(level.to_string());
+    std::fs::create_dir_all(&level_dir)?;
+
+    let (columns, rows) = tile_grid(target_width, height_px, tile);
+    for y in 0..rows {
+        let top = y * tile;
+        let band = render_band(
+            page,
+            target_width,
+            pixels_per_point,
+            top,
+            tile.min(height_px - top),
+        )
+        .map_err(|e| Error::Render(format!("page {page_num}: {e}")))?;
+        for (x, tile_image) in (0u32..).zip(slice_band(&band, tile)) {
+            let path = level_dir.join(format!("{x}_{y}.jpg"));
+            tile_image
+                .save(&path)
+                .map_err(|e| Error::Render(format!("{}: {e}", path.display())))?;
+        }
+    }
+
+    Ok(PageTiles {
+        page: page_num,
+        level,
+        width_px: target_width,
+        height_px,
+        columns,
+        rows,
+    })
+}
+
+/// Render the `height` pixel rows of the page starting at `top` into a bitmap
+/// of just that size, by shifting the page up before pdfium scales it.
+#[cfg(not(test))]
+#[cfg_attr(coverage_nightly, coverage(off))]
+fn render_band(
+    page: &PdfPage,
+    width: u32,
+    pixels_per_point: f32,
+    top: u32,
+    height: u32,
+) -> Result<RgbImage, PdfiumError> {
+    let mut bitmap = PdfBitmap::empty(
+        width as i32,
+        height as i32,
+        PdfBitmapFormat::BGRA,
+        page.bindings(),
+    )?;
+    let config = PdfRenderConfig::new()
+        .set_target_width(width as i32)
+        .set_clear_color(PdfColor::WHITE)
+        .translate(
+            PdfPoints::ZERO,
+            PdfPoints::new(-(top as f32) / pixels_per_point),
+        )?;
+    page.render_into_bitmap_with_config(&mut bitmap, &config)?;
+    Ok(bitmap.as_image().into_rgb8())
+}
+
+/// Deep Zoom level at which a `width` x `height` image is shown at full size:
+/// the base-2 logarithm of its larger side, rounded up, so the tiles sit where
+/// a Deep Zoom (`.dzi`) viewer looks for that level.
+pub fn deep_zoom_level(width: u32, height: u32) -> u32 {
+    let largest = width.max(height).max(1);
+    u32::BITS - (largest - 1).leading_zeros()
+}
+
+/// Columns and rows of `tile` pixel tiles covering a `width` x `height` image;
+/// the last column and row are cut short rather than padded.
+pub fn tile_grid(width: u32, height: u32, tile: u32) -> (u32, u32) {
+    (width.div_ceil(tile), height.div_ceil(tile))
+}
+
+/// Cut a band one tile high into tiles, left to right.
+pub fn slice_band(band: &RgbImage, tile: u32) -> Vec<RgbImage> {
+    (0..band.width())
+        .step_by(tile as usize)
+        .map(|x| crop_imm(band, x, 0, tile.min(band.width() - x), band.height()).to_image())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn level_is_the_larger_side_rounded_up_to_a_power_of_two() {
+        assert_eq!(deep_zoom_level(1, 1), 0);
+        assert_eq!(deep_zoom_level(256, 100), 8);
+        assert_eq!(deep_zoom_level(200, 257), 9);
+        assert_eq!(deep_zoom_level(8192, 10600), 14);
+    }
+
+    #[test]
+    fn grid_covers_partial_tiles() {
+        assert_eq!(tile_grid(512, 512, 256), (2, 2));
+        assert_eq!(tile_grid(513, 300, 256), (3, 2));
+        assert_eq!(tile_grid(10, 10, 256), (1, 1));
+    }
+
+    #[test]
+    fn bands_slice_into_full_tiles_and_a_narrower_last_one() {
+        let band = RgbImage::from_fn(10, 4, |x, _| image::Rgb([x as u8; 3]));
+
+        let tiles = slice_band(&band, 4);
+
+        let sizes: Vec<_> = tiles.iter().map(RgbImage::dimensions).collect();
+        assert_eq!(sizes, [(4, 4), (4, 4), (2, 4)]);
+        assert_eq!(tiles[1].get_pixel(0, 0).0, [4; 3]);
+        assert_eq!(tiles[2].get_pixel(1, 3).0, [9; 3]);
+    }
+}
@@ -0,0 +1,227 @@
+use crate::error::Error;
+use ab_glyph::{FontArc, PxScale};
+use image::{DynamicImage, GrayImage, Luma, RgbaImage};
+use imageproc::drawing::{draw_text_mut, text_size};
+use imageproc::geometric_transformations::{Interpolation, rotate_about_center};
+use std::path::{Path, PathBuf};
+
+/// DejaVu Sans Bold, used unless `--watermark-font` is given
+/// (license in `assets/DejaVuSans-LICENSE.txt`).
+const BUNDLED_FONT: &[u8] = include_bytes!("../assets/DejaVuSans-Bold.ttf");
+
+pub const DEFAULT_OPACITY: f32 = 0.3;
+pub const DEFAULT_ANGLE: f32 = 45.0;
+
+const TEXT_COLOR: [u8; 3] = [128, 128, 128];
+/// Share of the page width and height the rotated text may span.
+const PAGE_FILL: f32 = 0.8;
+/// Scale the text is measured at before fitting it to the page.
+const MEASURE_SCALE: f32 = 100.0;
+
+/// Text drawn across every rendered page (`--watermark-text`).
+#[derive(Clone, Debug)]
+pub struct Watermark {
+    pub text: String,
+    /// 0.0 (invisible) to 1.0 (opaque)
+    pub opacity: f32,
+    /// Degrees counterclockwise from horizontal
+    pub angle: f32,
+    /// The `--watermark-font` file; `None` for the bundled font
+    pub font_path: Option<PathBuf>,
+    font: FontArc,
+}
+
+impl Watermark {
+    /// Check the settings and load the font, rejecting unreadable or
+    /// non-TrueType/OpenType files.
+    pub fn new(
+        text: &str,
+        opacity: f32,
+        angle: f32,
+        font_path: Option<&Path>,
+    ) -> Result<Self, Error> {
+        if text.trim().is_empty() {
+            return Err(Error::InvalidArgs("--watermark-text is empty".into()));
+        }
+        if !(0.0..=1.0).contains(&opacity) {
+            return Err(Error::InvalidArgs(
+                "--watermark-opacity must be between 0 and 1".into(),
+            ));
+        }
+        if !angle.is_finite() {
+            return Err(Error::InvalidArgs(
+                "--watermark-angle must be a number".into(),
+            ));
+        }
+        let font = match font_path {
+            Some(path) => {
+                let data = std::fs::read(path).map_err(|e| {
+                    Error::InvalidArgs(format!("--watermark-font {}: {e}", path.display()))
+                })?;
+                FontArc::try_from_vec(data).map_err(|_| {
+                    Error::InvalidArgs(format!(
+                        "--watermark-font {}: not a TrueType or OpenType font",
+                        path.display()
+                    ))
+                })?
+            }
+            None => FontArc::try_from_slice(BUNDLED_FONT).expect("bundled font is valid"),
+        };
+        Ok(Self {
+            text: text.to_string(),
+            opacity,
+            angle,
+            font_path: font_path.map(Path::to_path_buf),
+            font,
+        })
+    }
+
+    /// Draw the text centered on `image`, rotated by `angle` and sized to
+    /// fill most of the page. Transparent pages get the text's own alpha.
+    pub fn apply(&self, image: DynamicImage) -> DynamicImage {
+        let mut page = image.into_rgba8();
+        let mask = self.mask(page.width(), page.height());
+        composite(&mut page, &mask, self.opacity);
+        DynamicImage::ImageRgba8(page)
+    }
+
+    /// Text coverage, rotated, on a square canvas centered on the page.
+    fn mask(&self, page_width: u32, page_height: u32) -> GrayImage {
+        let (measured_width, measured_height) =
+            text_size(PxScale::from(MEASURE_SCALE), &self.font, &self.text);
+        let length = fitted_length(
+            page_width as f32,
+            page_height as f32,
+            measured_height as f32 / measured_width.max(1) as f32,
+            self.angle,
+        );
+        let scale = PxScale::from(MEASURE_SCALE * length / measured_width.max(1) as f32);
+        let (text_width, text_height) = text_size(scale, &self.font, &self.text);
+
+        // Large enough to hold the text at any angle.
+        let side = (text_width as f32).hypot(text_height as f32).ceil() as u32 + 2;
+        let mut mask = GrayImage::new(side, side);
+        draw_text_mut(
+            &mut mask,
+            Luma([255]),
+            ((side - text_width) / 2) as i32,
+            ((side - text_height) / 2) as i32,
+            scale,
+            &self.font,
+            &self.text,
+        );
+        // imageproc rotates clockwise.
+        rotate_about_center(
+            &mask,
+            -self.angle.to_radians(),
+            Interpolation::Bilinear,
+            Luma([0]),
+        )
+    }
+}
+
+/// Text length that keeps text of `aspect` (height / width) rotated by
+/// `angle` degrees within `PAGE_FILL` of the page.
+pub fn fitted_length(page_width: f32, page_height: f32, aspect: f32, angle: f32) -> f32 {
+    let (sin, cos) = angle.to_radians().sin_cos();
+    let (sin, cos) = (sin.abs(), cos.abs());
+    let by_width = PAGE_FILL * page_width / (cos + aspect * sin);
+    let by_height = PAGE_FILL * page_height / (sin + aspect * cos);
+    by_width.min(by_height)
+}
+
+/// Blend `TEXT_COLOR` over `page` where `mask` covers it, the mask's center
+/// on the page's center.
+fn composite(page: &mut RgbaImage, mask: &GrayImage, opacity: f32) {
+    let offset_x = (page.width() as i64 - mask.width() as i64) / 2;
+    let offset_y = (page.height() as i64 - mask.height() as i64) / 2;
+    for (x, y, coverage) in mask.enumerate_pixels() {
+        let (px, py) = (x as i64 + offset_x, y as i64 + offset_y);
+        if coverage[0] == 0
+            || px < 0
+            || py < 0
+            || px >= page.width() as i64
+            || py >= page.height() as i64
+        {
+            continue;
+        }
+        let alpha = coverage[0] as f32 / 255.0 * opacity;
+        let pixel = page.get_pixel_mut(px as u32, py as u32);
+        let below = pixel[3] as f32 / 255.0;
+        let out = alpha + below * (1.0 - alpha);
+        if out == 0.0 {
+            continue;
+        }
+        for channel in 0..3 {
+            let blended = (TEXT_COLOR[channel] as f32 * alpha
+                + pixel[channel] as f32 * below * (1.0 - alpha))
+                / out;
+            pixel[channel] = blended.round() as u8;
+        }
+        pixel[3] = (out * 255.0).round() as u8;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::Rgba;
+
+    fn white_page() -> DynamicImage {
+        DynamicImage::ImageRgba8(RgbaImage::from_pixel(200, 300, Rgba([255, 255, 255, 255])))
+    }
+
+    #[test]
+    fn watermark_darkens_the_page_center() {
+        let watermark = Watermark::new("DRAFT", 0.5, 45.0, None).unwrap();
+
+        let marked = watermark.apply(white_page()).into_rgba8();
+
+        let changed = marked.pixels().filter(|p| p[0] < 255).count();
+        assert!(changed > 1000, "{changed} pixels changed");
+        assert!(
+            marked
+                .pixels()
+                .all(|p| p[0] >= TEXT_COLOR[0] && p[3] == 255)
+        );
+        // The corners stay clear.
+        assert_eq!(marked.get_pixel(0, 0), &Rgba([255, 255, 255, 255]));
+        assert_eq!(marked.get_pixel(199, 299), &Rgba([255, 255, 255, 255]));
+    }
+
+    #[test]
+    fn zero_opacity_leaves_the_page_unchanged() {
+        let watermark = Watermark::new("DRAFT", 0.0, 45.0, None).unwrap();
+
+        assert_eq!(watermark.apply(white_page()), white_page());
+    }
+
+    #[test]
+    fn transparent_pages_get_opaque_text_pixels() {
+        let watermark = Watermark::new("DRAFT", 1.0, 0.0, None).unwrap();
+        let page = DynamicImage::ImageRgba8(RgbaImage::new(200, 300));
+
+        let marked = watermark.apply(page).into_rgba8();
+
+        let text_pixel = marked.pixels().find(|p| p[3] == 255).unwrap();
+        assert_eq!(&text_pixel.0[..3], &TEXT_COLOR);
+    }
+
+    #[test]
+    fn fitted_length_respects_both_page_dimensions() {
+        // Horizontal text on a wide page is limited by the width.
+        assert_eq!(fitted_length(1000.0, 800.0, 0.2, 0.0), 800.0);
+        // Vertical text is limited by the height.
+        assert_eq!(fitted_length(1000.0, 500.0, 0.2, 90.0).round(), 400.0);
+        let diagonal = fitted_length(300.0, 300.0, 0.0, 45.0);
+        assert!((diagonal - 240.0 * 2f32.sqrt()).abs() < 0.01);
+    }
+
+    #[test]
+    fn invalid_settings_are_rejected() {
+        assert!(Watermark::new(" ", 0.3, 45.0, None).is_err());
+        assert!(Watermark::new("DRAFT", 1.5, 45.0, None).is_err());
+        assert!(Watermark::new("DRAFT", 0.3, f32::NAN, None).is_err());
+        assert!(Watermark::new("DRAFT", 0.3, 45.0, Some(Path::new("/nonexistent.ttf"))).is_err());
+    }
+}
@@ -0,0 +1,32 @@
+mod common;
+
+use common::{assert_success, json_data, pdf_command};
+
+#[test]
+fn capabilities_list_jpeg_and_the_compiled_features() {
+    let output = pdf_command().arg("capabilities").output().unwrap();
+    assert_success(&output, "pdf capabilities");
+
+    let capabilities: serde_json::Value = json_data(&output.stdout);
+    let formats = capabilities["formats"].as_array().unwrap();
+    assert!(formats.contains(&"jpeg".into()), "{formats:?}");
+    assert_eq!(formats.contains(&"avif".into()), cfg!(feature = "avif"));
+    assert_eq!(capabilities["features"]["vips"], cfg!(feature = "vips"));
+    assert_eq!(capabilities["features"]["avif"], cfg!(feature = "avif"));
+    let encoders = capabilities["encoders"].as_array().unwrap();
+    assert!(encoders.contains(&"image".into()));
+    assert_eq!(encoders.contains(&"vips".into()), cfg!(feature = "vips"));
+    assert!(capabilities["pdfium_version"].is_string());
+}
+
+#[test]
+fn capabilities_do_not_need_pdfium() {
+    let output = pdf_command()
+        .arg("--pdfium-path")
+        .arg("/nonexistent/libpdfium.so")
+        .arg("capabilities")
+        .output()
+        .unwrap();
+
+    assert_success(&output, "pdf capabilities without pdfium");
+}
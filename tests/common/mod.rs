@@ -0,0 +1,181 @@
+#![allow(dead_code)]
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const CATALOG_ID: usize = 1;
+const PAGES_ID: usize = 2;
+
+pub fn create_temp_dir(prefix: &str) -> PathBuf {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_nanos();
+    let path = std::env::temp_dir().join(format!("{prefix}-{}-{nanos}", std::process::id()));
+    fs::create_dir(&path).unwrap();
+    path
+}
+
+pub fn pdfium_library() -> PathBuf {
+    std::env::var_os("PDFIUM_LIBRARY_PATH")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| Path::new(env!("CARGO_MANIFEST_DIR")).join("vendor/pdfium/libpdfium.so"))
+}
+
+/// `pdf` binary command with `PDFIUM_LIBRARY_PATH` pointing at a usable library.
+pub fn pdf_command() -> Command {
+    let library = pdfium_library();
+    assert!(
+        library.exists(),
+        "pdfium library missing at {}; set PDFIUM_LIBRARY_PATH",
+        library.display()
+    );
+    let mut command = Command::new(env!("CARGO_BIN_EXE_pdf"));
+    command.env("PDFIUM_LIBRARY_PATH", library);
+    command
+}
+
+/// `pdf render <pdf> -o <output_dir>`; callers add the flags under test.
+pub fn render_command(pdf: &Path, output_dir: &Path) -> Command {
+    let mut command = pdf_command();
+    command.arg("render").arg(pdf).arg("-o").arg(output_dir);
+    command
+}
+
+pub fn assert_success(output: &std::process::Output, what: &str) {
+    assert!(
+        output.status.success(),
+        "{what} failed\nstdout:\n{}\nstderr:\n{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
+
+/// Minimal PDF writer with a classic xref table.
+///
+/// Objects 1 and 2 are reserved for the catalog and page tree, which are
+/// written by `finish` once all pages are known.
+pub struct PdfBuilder {
+    bytes: Vec<u8>,
+    offsets: Vec<usize>,
+    page_ids: Vec<usize>,
+    catalog_entries: String,
+    trailer_entries: String,
+}
+
+impl PdfBuilder {
+    pub fn new() -> Self {
+        Self {
+            bytes: b"%PDF-1.7\n".to_vec(),
+            offsets: vec![0; PAGES_ID],
+            page_ids: Vec::new(),
+            catalog_entries: String::new(),
+            trailer_entries: String::new(),
+        }
+    }
+
+    pub fn add_object(&mut self, body: &str) -> usize {
+        self.offsets.push(self.bytes.len());
+        let id = self.offsets.len();
+        self.write_object(id, body.as_bytes());
+        id
+    }
+
+    /// Add a stream object; `dict` holds extra entries besides `/Length`.
+    pub fn add_stream(&mut self, dict: &str, data: &[u8]) -> usize {
+        self.offsets.push(self.bytes.len());
+        let id = self.offsets.len();
+        self.bytes.extend_from_slice(
+            format!("{id} 0 obj\n<< {dict} /Length {} >>\nstream\n", data.len()).as_bytes(),
+        );
+        self.bytes.extend_from_slice(data);
+        self.bytes.extend_from_slice(b"\nendstream\nendobj\n");
+        id
+    }
+
+    /// Add a page; `entries` holds page dictionary entries such as `/MediaBox`.
+    pub fn add_page(&mut self, entries: &str, content: &[u8]) -> usize {
+        let content_id = self.add_stream("", content);
+        let id = self.add_object(&format!(
+            "<< /Type /Page /Parent {PAGES_ID} 0 R {entries} /Contents {content_id} 0 R >>"
+        ));
+        self.page_ids.push(id);
+        id
+    }
+
+    /// Add entries such as `/PageLabels` to the document catalog.
+    pub fn add_catalog_entries(&mut self, entries: &str) {
+        self.catalog_entries.push(' ');
+        self.catalog_entries.push_str(entries);
+    }
+
+    /// Add entries such as `/Info` to the trailer.
+    pub fn add_trailer_entries(&mut self, entries: &str) {
+        self.trailer_entries.push(' ');
+        self.trailer_entries.push_str(entries);
+    }
+
+    pub fn finish(mut self) -> Vec<u8> {
+        let kids = self
+            .page_ids
+            .iter()
+            .map(|id| format!("{id} 0 R"))
+            .collect::<Vec<_>>()
+            .join(" ");
+        self.offsets[CATALOG_ID - 1] = self.bytes.len();
+        self.write_object(
+            CATALOG_ID,
+            format!(
+                "<< /Type /Catalog /Pages {PAGES_ID} 0 R{} >>",
+                self.catalog_entries
+            )
+            .as_bytes(),
+        );
+        self.offsets[PAGES_ID - 1] = self.bytes.len();
+        self.write_object(
+            PAGES_ID,
+            format!(
+                "<< /Type /Pages /Kids [{kids}] /Count {} >>",
+                self.page_ids.len()
+            )
+            .as_bytes(),
+        );
+
+        let xref_offset = self.bytes.len();
+        let size = self.offsets.len() + 1;
+        self.bytes
+            .extend_from_slice(format!("xref\n0 {size}\n0000000000 65535 f \n").as_bytes());
+        for offset in &self.offsets {
+            self.bytes
+                .extend_from_slice(format!("{offset:010} 00000 n \n").as_bytes());
+        }
+        self.bytes.extend_from_slice(
+            format!(
+                "trailer\n<< /Size {size} /Root {CATALOG_ID} 0 R{} >>\nstartxref\n{xref_offset}\n%%EOF\n",
+                self.trailer_entries
+            )
+            .as_bytes(),
+        );
+        self.bytes
+    }
+
+    fn write_object(&mut self, id: usize, body: &[u8]) {
+        self.bytes
+            .extend_from_slice(format!("{id} 0 obj\n").as_bytes());
+        self.bytes.extend_from_slice(body);
+        self.bytes.extend_from_slice(b"\nendobj\n");
+    }
+}
+
+/// The document inside `pdf`'s `{"schema": "pdf.<kind>.v1", "data": ...}` JSON output.
+pub fn json_data<T: serde::de::DeserializeOwned>(json: &[u8]) -> T {
+    let mut envelope: serde_json::Value = serde_json::from_slice(json).unwrap();
+    let schema = envelope["schema"].as_str().unwrap_or_default();
+    assert!(
+        schema.starts_with("pdf.") && schema.ends_with(".v1"),
+        "unexpected schema {schema:?} in {envelope}"
+    );
+    serde_json::from_value(envelope["data"].take()).unwrap()
+}
@@ -0,0 +1,69 @@
+mod common;
+
+use common::{PdfBuilder, assert_success, create_temp_dir, json_data, pdf_command};
+use std::fs;
+use std::path::PathBuf;
+
+#[test]
+fn four_pages_tile_into_a_two_by_two_sheet() {
+    let (pdf_path, temp_dir) = write_four_page_pdf("pdf-contact-sheet");
+    let sheet_path = temp_dir.join("sheet.png");
+
+    let output = pdf_command()
+        .arg("contact-sheet")
+        .arg(&pdf_path)
+        .arg("-o")
+        .arg(&sheet_path)
+        .arg("--columns")
+        .arg("2")
+        .arg("--thumb-width")
+        .arg("50")
+        .output()
+        .unwrap();
+    assert_success(&output, "pdf contact-sheet");
+
+    // 100x200pt pages at 50px wide are 100px tall.
+    let sheet = image::open(&sheet_path).unwrap();
+    assert_eq!((sheet.width(), sheet.height()), (100, 200));
+
+    let summary: serde_json::Value = json_data(&output.stdout);
+    assert_eq!(summary["pages"], 4);
+    assert_eq!(summary["columns"], 2);
+    assert_eq!(summary["rows"], 2);
+}
+
+#[test]
+fn contact_sheet_respects_page_range() {
+    let (pdf_path, temp_dir) = write_four_page_pdf("pdf-contact-sheet-pages");
+    let sheet_path = temp_dir.join("sheet.jpg");
+
+    let output = pdf_command()
+        .arg("contact-sheet")
+        .arg(&pdf_path)
+        .arg("-o")
+        .arg(&sheet_path)
+        .arg("--pages")
+        .arg("2-4")
+        .arg("--columns")
+        .arg("4")
+        .arg("--thumb-width")
+        .arg("50")
+        .output()
+        .unwrap();
+    assert_success(&output, "pdf contact-sheet --pages 2-4");
+
+    let sheet = image::open(&sheet_path).unwrap();
+    assert_eq!((sheet.width(), sheet.height()), (150, 100));
+}
+
+fn write_four_page_pdf(prefix: &str) -> (PathBuf, PathBuf) {
+    let temp_dir = create_temp_dir(prefix);
+    let pdf_path = temp_dir.join("four.pdf");
+    let mut pdf = PdfBuilder::new();
+    for shade in ["0", "0.3", "0.6", "0.9"] {
+        let content = format!("{shade} g 0 0 100 200 re f");
+        pdf.add_page("/MediaBox [0 0 100 200]", content.as_bytes());
+    }
+    fs::write(&pdf_path, pdf.finish()).unwrap();
+    (pdf_path, temp_dir)
+}
@@ -0,0 +1,80 @@
+mod common;
+
+use common::{PdfBuilder, assert_success, create_temp_dir, json_data, pdf_command};
+use image::codecs::jpeg::JpegEncoder;
+use image::{Rgb, RgbImage};
+use std::fs;
+
+const FLAT_COLOR: [u8; 3] = [200, 40, 10];
+
+#[test]
+fn extracts_every_image_on_a_page() {
+    let temp_dir = create_temp_dir("pdf-extract-images-command");
+    let pdf_path = temp_dir.join("stamped.pdf");
+    let output_dir = temp_dir.join("out");
+    let jpeg = background_jpeg();
+    fs::write(&pdf_path, build_stamped_pdf(&jpeg)).unwrap();
+
+    let output = pdf_command()
+        .arg("extract-images")
+        .arg(&pdf_path)
+        .arg("-o")
+        .arg(&output_dir)
+        .arg("--pages")
+        .arg("2")
+        .output()
+        .unwrap();
+    assert_success(&output, "pdf extract-images");
+
+    let summary: serde_json::Value = json_data(&output.stdout);
+    assert_eq!(summary["pages_scanned"], 1);
+    assert_eq!(summary["images_raw"], 1);
+    assert_eq!(summary["images_reencoded"], 1);
+
+    assert_eq!(
+        fs::read(output_dir.join("page-0002-img-01.jpg")).unwrap(),
+        jpeg,
+        "DCTDecode image should be copied raw"
+    );
+    let stamp = image::open(output_dir.join("page-0002-img-02.png"))
+        .unwrap()
+        .to_rgb8();
+    assert_eq!(stamp.dimensions(), (4, 2));
+    assert_eq!(stamp.get_pixel(0, 0).0, FLAT_COLOR);
+    assert!(!output_dir.join("page-0001-img-01.jpg").exists());
+}
+
+fn background_jpeg() -> Vec<u8> {
+    let image = RgbImage::from_fn(40, 60, |x, y| Rgb([(x * 5) as u8, (y * 4) as u8, 128]));
+    let mut bytes = Vec::new();
+    image
+        .write_with_encoder(JpegEncoder::new_with_quality(&mut bytes, 90))
+        .unwrap();
+    bytes
+}
+
+/// Page 1 is empty; page 2 draws a full-page JPEG plus a small uncompressed RGB stamp.
+fn build_stamped_pdf(jpeg: &[u8]) -> Vec<u8> {
+    let mut pdf = PdfBuilder::new();
+    pdf.add_page("/MediaBox [0 0 40 60]", b"");
+
+    let background_id = pdf.add_stream(
+        "/Type /XObject /Subtype /Image /Width 40 /Height 60 \
+         /ColorSpace /DeviceRGB /BitsPerComponent 8 /Filter /DCTDecode",
+        jpeg,
+    );
+    let stamp_pixels = FLAT_COLOR.repeat(4 * 2);
+    let stamp_id = pdf.add_stream(
+        "/Type /XObject /Subtype /Image /Width 4 /Height 2 \
+         /ColorSpace /DeviceRGB /BitsPerComponent 8",
+        &stamp_pixels,
+    );
+    pdf.add_page(
+        &format!(
+            "/MediaBox [0 0 40 60] \
+             /Resources << /XObject << /Bg {background_id} 0 R /Stamp {stamp_id} 0 R >> >>"
+        ),
+        b"q 40 0 0 60 0 0 cm /Bg Do Q\nq 8 0 0 4 4 4 cm /Stamp Do Q\n",
+    );
+    pdf.finish()
+}
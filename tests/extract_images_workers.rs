@@ -0,0 +1,180 @@
+mod common;
+
+use common::{PdfBuilder, assert_success, create_temp_dir, json_data, pdf_command, render_command};
+use image::codecs::jpeg::JpegEncoder;
+use image::{Rgb, RgbImage};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const PAGE_COUNT: u32 = 4;
+const IMAGE_WIDTH: u32 = 64;
+const IMAGE_HEIGHT: u32 = 96;
+
+#[test]
+fn extracted_output_is_identical_for_single_and_multi_process() {
+    let temp_dir = create_temp_dir("pdf-extract-workers");
+    let pdf_path = temp_dir.join("single-image-pages.pdf");
+    let jpegs: Vec<Vec<u8>> = (0..PAGE_COUNT).map(encode_test_jpeg).collect();
+    fs::write(&pdf_path, build_single_image_pdf(&jpegs)).unwrap();
+
+    let single_dir = render_extracting(&pdf_path, &temp_dir, 1);
+    let multi_dir = render_extracting(&pdf_path, &temp_dir, 4);
+
+    for (i, jpeg) in jpegs.iter().enumerate() {
+        let filename = format!("page-{:04}.jpg", i + 1);
+        let single = fs::read(single_dir.join(&filename)).unwrap();
+        let multi = fs::read(multi_dir.join(&filename)).unwrap();
+        assert_eq!(single, multi, "{filename} differs between 1 and 4 workers");
+        assert_eq!(
+            &single, jpeg,
+            "{filename} was re-encoded instead of extracted"
+        );
+    }
+}
+
+#[test]
+fn name_template_names_extracted_and_rendered_pages_alike() {
+    let temp_dir = create_temp_dir("pdf-extract-name-template");
+    let pdf_path = temp_dir.join("invoice.pdf");
+    let jpegs: Vec<Vec<u8>> = (0..PAGE_COUNT).map(encode_test_jpeg).collect();
+    fs::write(&pdf_path, build_single_image_pdf(&jpegs)).unwrap();
+
+    for (dir_name, extract) in [("extracted", true), ("rendered", false)] {
+        let output_dir = temp_dir.join(dir_name);
+        let mut command = pdf_command();
+        command
+            .arg("render")
+            .arg(&pdf_path)
+            .arg("-o")
+            .arg(&output_dir)
+            .arg("--workers")
+            .arg("2")
+            .arg("--name-template")
+            .arg("{stem}_{n:03}");
+        if extract {
+            command.arg("--extract-images");
+        }
+        let output = command.output().unwrap();
+        assert_success(&output, &format!("pdf render --name-template ({dir_name})"));
+
+        let mut names: Vec<String> = fs::read_dir(&output_dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+            .collect();
+        names.sort();
+        assert_eq!(
+            names,
+            [
+                "invoice_001.jpg",
+                "invoice_002.jpg",
+                "invoice_003.jpg",
+                "invoice_004.jpg"
+            ],
+            "{dir_name}"
+        );
+    }
+}
+
+#[test]
+fn name_template_rejects_unknown_placeholder() {
+    let temp_dir = create_temp_dir("pdf-name-template-invalid");
+    let pdf_path = temp_dir.join("doc.pdf");
+    fs::write(&pdf_path, build_single_image_pdf(&[encode_test_jpeg(0)])).unwrap();
+
+    let output = pdf_command()
+        .arg("render")
+        .arg(&pdf_path)
+        .arg("-o")
+        .arg(temp_dir.join("out"))
+        .arg("--name-template")
+        .arg("page-{page}")
+        .output()
+        .unwrap();
+
+    assert_eq!(output.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("--name-template"));
+}
+
+#[test]
+fn summary_counts_extracted_pages_and_throughput() {
+    let temp_dir = create_temp_dir("pdf-extract-throughput");
+    let pdf_path = temp_dir.join("mixed.pdf");
+    let jpegs: Vec<Vec<u8>> = (0..PAGE_COUNT).map(encode_test_jpeg).collect();
+    let mut pdf = single_image_pages(&jpegs);
+    pdf.add_page(
+        &format!("/MediaBox [0 0 {IMAGE_WIDTH} {IMAGE_HEIGHT}]"),
+        b"1 0 0 rg 0 0 10 10 re f",
+    );
+    fs::write(&pdf_path, pdf.finish()).unwrap();
+
+    let output = pdf_command()
+        .arg("render")
+        .arg(&pdf_path)
+        .arg("-o")
+        .arg(temp_dir.join("out"))
+        .arg("--workers")
+        .arg("2")
+        .arg("--extract-images")
+        .output()
+        .unwrap();
+    assert_success(&output, "pdf render --extract-images --workers 2");
+
+    let summary: serde_json::Value = json_data(&output.stdout);
+    assert_eq!(summary["pages_extracted"], PAGE_COUNT);
+    assert_eq!(summary["pages_rendered"], 1);
+    let throughput = summary["pages_per_second"].as_f64().unwrap();
+    assert!(throughput > 0.0, "{summary}");
+}
+
+fn render_extracting(pdf_path: &Path, temp_dir: &Path, workers: u32) -> PathBuf {
+    let output_dir = temp_dir.join(format!("out-{workers}"));
+    let output = render_command(pdf_path, &output_dir)
+        .arg("--workers")
+        .arg(workers.to_string())
+        .arg("--extract-images")
+        .output()
+        .unwrap();
+    assert_success(&output, &format!("pdf render --workers {workers}"));
+
+    let summary: serde_json::Value = json_data(&output.stdout);
+    assert_eq!(summary["pages_extracted"], PAGE_COUNT, "workers {workers}");
+
+    output_dir
+}
+
+fn encode_test_jpeg(seed: u32) -> Vec<u8> {
+    let image = RgbImage::from_fn(IMAGE_WIDTH, IMAGE_HEIGHT, |x, y| {
+        Rgb([(x * 4) as u8, (y * 2) as u8, (seed * 60) as u8])
+    });
+    let mut bytes = Vec::new();
+    image
+        .write_with_encoder(JpegEncoder::new_with_quality(&mut bytes, 90))
+        .unwrap();
+    bytes
+}
+
+/// Build a PDF where each page draws exactly one DCTDecode image filling the page.
+fn build_single_image_pdf(jpegs: &[Vec<u8>]) -> Vec<u8> {
+    single_image_pages(jpegs).finish()
+}
+
+fn single_image_pages(jpegs: &[Vec<u8>]) -> PdfBuilder {
+    let mut pdf = PdfBuilder::new();
+    for jpeg in jpegs {
+        let image_id = pdf.add_stream(
+            &format!(
+                "/Type /XObject /Subtype /Image /Width {IMAGE_WIDTH} /Height {IMAGE_HEIGHT} \
+                 /ColorSpace /DeviceRGB /BitsPerComponent 8 /Filter /DCTDecode"
+            ),
+            jpeg,
+        );
+        pdf.add_page(
+            &format!(
+                "/MediaBox [0 0 {IMAGE_WIDTH} {IMAGE_HEIGHT}] \
+                 /Resources << /XObject << /Im0 {image_id} 0 R >> >>"
+            ),
+            format!("q {IMAGE_WIDTH} 0 0 {IMAGE_HEIGHT} 0 0 cm /Im0 Do Q\n").as_bytes(),
+        );
+    }
+    pdf
+}
@@ -0,0 +1,128 @@
+mod common;
+
+use common::{PdfBuilder, assert_success, create_temp_dir, json_data, pdf_command};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+#[test]
+fn flatten_moves_form_values_into_the_page_content() {
+    let (pdf_path, temp_dir) = write_filled_form("pdf-flatten");
+    let flat_path = temp_dir.join("flat.pdf");
+    assert!(!page_text(&pdf_path, 0).contains("Jane Archive"));
+
+    let output = pdf_command()
+        .arg("flatten")
+        .arg(&pdf_path)
+        .arg("-o")
+        .arg(&flat_path)
+        .output()
+        .unwrap();
+    assert_success(&output, "pdf flatten");
+
+    let summary: serde_json::Value = json_data(&output.stdout);
+    assert_eq!(summary["pages"], 2);
+    assert_eq!(summary["pages_flattened"], 2);
+    assert_eq!(annotation_counts(&flat_path), [0, 0]);
+    assert!(page_text(&flat_path, 0).contains("Jane Archive"));
+    assert!(page_text(&flat_path, 1).contains("Jane Archive"));
+}
+
+#[test]
+fn flatten_pages_leaves_other_pages_interactive() {
+    let (pdf_path, temp_dir) = write_filled_form("pdf-flatten-pages");
+    let flat_path = temp_dir.join("flat.pdf");
+
+    let output = pdf_command()
+        .arg("flatten")
+        .arg(&pdf_path)
+        .arg("-o")
+        .arg(&flat_path)
+        .arg("--pages")
+        .arg("2")
+        .output()
+        .unwrap();
+    assert_success(&output, "pdf flatten --pages 2");
+
+    let summary: serde_json::Value = json_data(&output.stdout);
+    assert_eq!(summary["pages"], 2);
+    assert_eq!(summary["pages_flattened"], 1);
+    assert_eq!(annotation_counts(&flat_path), [1, 0]);
+    assert!(page_text(&flat_path, 1).contains("Jane Archive"));
+}
+
+#[test]
+fn flatten_rejects_pages_past_the_end() {
+    let (pdf_path, temp_dir) = write_filled_form("pdf-flatten-range");
+
+    let output = pdf_command()
+        .arg("flatten")
+        .arg(&pdf_path)
+        .arg("-o")
+        .arg(temp_dir.join("flat.pdf"))
+        .arg("--pages")
+        .arg("3")
+        .output()
+        .unwrap();
+
+    assert_eq!(output.status.code(), Some(1));
+    assert!(!temp_dir.join("flat.pdf").exists());
+}
+
+fn annotation_counts(pdf_path: &Path) -> Vec<u64> {
+    let output = pdf_command()
+        .arg("info")
+        .arg(pdf_path)
+        .arg("--all-pages")
+        .arg("--objects")
+        .output()
+        .unwrap();
+    assert_success(&output, "pdf info --objects");
+    let info: serde_json::Value = json_data(&output.stdout);
+    info["pages"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|page| page["objects"]["annotation_count"].as_u64().unwrap())
+        .collect()
+}
+
+fn page_text(pdf_path: &Path, index: usize) -> String {
+    let output = pdf_command().arg("text").arg(pdf_path).output().unwrap();
+    assert_success(&output, "pdf text");
+    let pages: serde_json::Value = json_data(&output.stdout);
+    pages[index]["text"].as_str().unwrap().to_string()
+}
+
+/// Two 200x200pt pages, each with a text field filled in as "Jane Archive";
+/// the value is only drawn by the field's appearance stream.
+fn write_filled_form(prefix: &str) -> (PathBuf, PathBuf) {
+    let temp_dir = create_temp_dir(prefix);
+    let pdf_path = temp_dir.join("form.pdf");
+    let mut pdf = PdfBuilder::new();
+    let font = pdf.add_object("<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica >>");
+    let appearance = pdf.add_stream(
+        &format!(
+            "/Type /XObject /Subtype /Form /BBox [0 0 180 30] \
+             /Resources << /Font << /Helv {font} 0 R >> >>"
+        ),
+        b"/Tx BMC BT /Helv 12 Tf 0 g 2 10 Td (Jane Archive) Tj ET EMC",
+    );
+    let mut fields = Vec::new();
+    for page in 1..=2 {
+        let field = pdf.add_object(&format!(
+            "<< /Type /Annot /Subtype /Widget /FT /Tx /T (name{page}) /V (Jane Archive) \
+             /DA (/Helv 12 Tf 0 g) /Rect [10 150 190 180] /F 4 /AP << /N {appearance} 0 R >> >>"
+        ));
+        pdf.add_page(
+            &format!("/MediaBox [0 0 200 200] /Annots [{field} 0 R]"),
+            b"",
+        );
+        fields.push(format!("{field} 0 R"));
+    }
+    pdf.add_catalog_entries(&format!(
+        "/AcroForm << /Fields [{}] /DA (/Helv 0 Tf 0 g) /DR << /Font << /Helv {font} 0 R >> >> >>",
+        fields.join(" ")
+    ));
+    fs::write(&pdf_path, pdf.finish()).unwrap();
+    (pdf_path, temp_dir)
+}
@@ -0,0 +1,54 @@
+mod common;
+
+use common::{PdfBuilder, assert_success, create_temp_dir, json_data, pdf_command};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+#[test]
+fn boxes_report_distinct_bleed_box() {
+    let pdf_path = write_bleed_pdf();
+
+    let info = run_info(&pdf_path, &["--boxes"]);
+    let boxes = &info["pages"][0]["boxes"];
+
+    assert_eq!(boxes["media"]["right"], 620.0);
+    assert_eq!(boxes["media"]["top"], 800.0);
+    assert_eq!(boxes["bleed"]["left"], 9.0);
+    assert_eq!(boxes["bleed"]["bottom"], 9.0);
+    assert_eq!(boxes["bleed"]["right"], 611.0);
+    assert_eq!(boxes["bleed"]["top"], 791.0);
+    assert_eq!(boxes["trim"]["left"], 18.0);
+    assert!(boxes["art"].is_null());
+}
+
+#[test]
+fn boxes_are_omitted_by_default() {
+    let pdf_path = write_bleed_pdf();
+
+    let info = run_info(&pdf_path, &[]);
+
+    assert!(info["pages"][0].get("boxes").is_none());
+}
+
+fn write_bleed_pdf() -> PathBuf {
+    let pdf_path = create_temp_dir("pdf-info-boxes").join("bleed.pdf");
+    let mut pdf = PdfBuilder::new();
+    pdf.add_page(
+        "/MediaBox [0 0 620 800] /BleedBox [9 9 611 791] /TrimBox [18 18 602 782]",
+        b"",
+    );
+    fs::write(&pdf_path, pdf.finish()).unwrap();
+    pdf_path
+}
+
+fn run_info(pdf_path: &Path, args: &[&str]) -> serde_json::Value {
+    let output = pdf_command()
+        .arg("info")
+        .arg(pdf_path)
+        .args(args)
+        .output()
+        .unwrap();
+    assert_success(&output, "pdf info");
+
+    json_data(&output.stdout)
+}
@@ -0,0 +1,66 @@
+mod common;
+
+use common::{PdfBuilder, assert_success, create_temp_dir, json_data, pdf_command};
+use serde_json::json;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const PAGE_COUNT: usize = 10_000;
+
+#[test]
+fn dimensions_only_prints_first_page_size() {
+    let pdf_path = write_long_pdf("pdf-info-dimensions");
+
+    let info = run_info(&pdf_path, &["--dimensions-only"]);
+
+    assert_eq!(info, json!({ "width_pt": 300.0, "height_pt": 400.0 }));
+}
+
+#[test]
+fn default_info_describes_only_the_first_page() {
+    let pdf_path = write_long_pdf("pdf-info-first-page");
+
+    let info = run_info(&pdf_path, &[]);
+
+    assert_eq!(info["page_count"], PAGE_COUNT);
+    assert_eq!(info["pages"].as_array().unwrap().len(), 1);
+    assert_eq!(info["pages"][0]["width_pt"], 300.0);
+}
+
+#[test]
+fn dimensions_only_conflicts_with_page_details() {
+    let pdf_path = write_long_pdf("pdf-info-dimensions-conflict");
+
+    let output = pdf_command()
+        .arg("info")
+        .arg(&pdf_path)
+        .arg("--dimensions-only")
+        .arg("--all-pages")
+        .output()
+        .unwrap();
+
+    assert!(!output.status.success());
+}
+
+fn run_info(pdf_path: &Path, args: &[&str]) -> serde_json::Value {
+    let output = pdf_command()
+        .arg("info")
+        .arg(pdf_path)
+        .args(args)
+        .output()
+        .unwrap();
+    assert_success(&output, "pdf info");
+    json_data(&output.stdout)
+}
+
+/// A 300x400pt first page followed by 9,999 letter-size pages.
+fn write_long_pdf(prefix: &str) -> PathBuf {
+    let pdf_path = create_temp_dir(prefix).join("long.pdf");
+    let mut pdf = PdfBuilder::new();
+    pdf.add_page("/MediaBox [0 0 300 400]", b"");
+    for _ in 1..PAGE_COUNT {
+        pdf.add_page("/MediaBox [0 0 612 792]", b"");
+    }
+    fs::write(&pdf_path, pdf.finish()).unwrap();
+    pdf_path
+}
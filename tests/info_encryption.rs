@@ -0,0 +1,229 @@
+mod common;
+
+use common::{PdfBuilder, assert_success, create_temp_dir, json_data, pdf_command, render_command};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Permission bits with printing (bit 3) cleared; bits 1-2 must be zero.
+const NO_PRINT: i32 = -8;
+const FILE_ID: [u8; 16] = *b"pdf-info-encrypt";
+
+#[test]
+fn restricted_printing_is_reported() {
+    let pdf_path = write_encrypted_pdf("no-print.pdf", b"", NO_PRINT);
+
+    let info = run_info(&pdf_path, &[]);
+    let encryption = &info["encryption"];
+
+    assert_eq!(encryption["encrypted"], true);
+    assert_eq!(encryption["opened_with_password"], false);
+    assert_eq!(encryption["permissions"]["print"], false);
+    assert_eq!(encryption["permissions"]["copy"], true);
+    assert_eq!(encryption["permissions"]["modify"], true);
+    assert_eq!(encryption["permissions"]["annotate"], true);
+}
+
+#[test]
+fn user_password_is_reported() {
+    let pdf_path = write_encrypted_pdf("user-password.pdf", b"secret", -4);
+
+    let info = run_info(&pdf_path, &["--password", "secret"]);
+
+    assert_eq!(info["encryption"]["encrypted"], true);
+    assert_eq!(info["encryption"]["opened_with_password"], true);
+    assert_eq!(info["encryption"]["permissions"]["print"], true);
+}
+
+#[test]
+fn unencrypted_document_has_null_permissions() {
+    let pdf_path = create_temp_dir("pdf-info-encryption").join("plain.pdf");
+    let mut pdf = PdfBuilder::new();
+    pdf.add_page("/MediaBox [0 0 100 100]", b"");
+    fs::write(&pdf_path, pdf.finish()).unwrap();
+
+    let info = run_info(&pdf_path, &["--password", "unused"]);
+
+    assert_eq!(info["encryption"]["encrypted"], false);
+    assert_eq!(info["encryption"]["opened_with_password"], false);
+    assert!(info["encryption"]["permissions"].is_null());
+}
+
+#[test]
+fn wrong_password_fails_info_cleanly() {
+    let pdf_path = write_encrypted_pdf("wrong-password-info.pdf", b"secret", -4);
+
+    let output = pdf_command()
+        .arg("info")
+        .arg(&pdf_path)
+        .args(["--password", "wrong"])
+        .output()
+        .unwrap();
+
+    assert_eq!(output.status.code(), Some(2));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("incorrect password"), "stderr: {stderr}");
+    assert!(output.stdout.is_empty());
+}
+
+#[test]
+fn wrong_password_fails_render_cleanly() {
+    let pdf_path = write_encrypted_pdf("wrong-password-render.pdf", b"secret", -4);
+    let output_dir = pdf_path.with_file_name("wrong-password-out");
+
+    let output = render(&pdf_path, &output_dir, "wrong");
+
+    assert_eq!(output.status.code(), Some(2));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("incorrect password"), "stderr: {stderr}");
+    assert!(!output_dir.join("page-0001.png").exists());
+}
+
+#[test]
+fn render_with_user_password_succeeds() {
+    let pdf_path = write_encrypted_pdf("render-password.pdf", b"secret", -4);
+    let output_dir = pdf_path.with_file_name("render-password-out");
+
+    let output = render(&pdf_path, &output_dir, "secret");
+
+    assert_success(&output, "pdf render --password");
+    let page = image::open(output_dir.join("page-0001.png")).unwrap();
+    assert_eq!((page.width(), page.height()), (100, 100));
+}
+
+fn render(pdf_path: &Path, output_dir: &Path, password: &str) -> std::process::Output {
+    render_command(pdf_path, output_dir)
+        .args(["--format", "png", "--dpi", "72", "--password", password])
+        .output()
+        .unwrap()
+}
+
+fn run_info(pdf_path: &Path, args: &[&str]) -> serde_json::Value {
+    let output = pdf_command()
+        .arg("info")
+        .arg(pdf_path)
+        .args(args)
+        .output()
+        .unwrap();
+    assert_success(&output, "pdf info");
+    json_data(&output.stdout)
+}
+
+/// Write a one-page PDF using the revision 2 standard security handler
+/// (40-bit RC4). The page content is empty, so nothing else needs encrypting.
+fn write_encrypted_pdf(name: &str, user_password: &[u8], permissions: i32) -> PathBuf {
+    let owner_key = &md5(&pad_password(b"owner"))[..5];
+    let owner_entry = rc4(owner_key, &pad_password(user_password));
+
+    let mut key_input = pad_password(user_password).to_vec();
+    key_input.extend_from_slice(&owner_entry);
+    key_input.extend_from_slice(&permissions.to_le_bytes());
+    key_input.extend_from_slice(&FILE_ID);
+    let key = &md5(&key_input)[..5];
+    let user_entry = rc4(key, &PASSWORD_PADDING);
+
+    let mut pdf = PdfBuilder::new();
+    pdf.add_page("/MediaBox [0 0 100 100]", b"");
+    let encrypt_id = pdf.add_object(&format!(
+        "<< /Filter /Standard /V 1 /R 2 /O <{}> /U <{}> /P {permissions} >>",
+        hex(&owner_entry),
+        hex(&user_entry)
+    ));
+    let bytes = String::from_utf8(pdf.finish()).unwrap();
+    let id = hex(&FILE_ID);
+    let bytes = bytes.replace(
+        "/Root 1 0 R >>",
+        &format!("/Root 1 0 R /Encrypt {encrypt_id} 0 R /ID [<{id}> <{id}>] >>"),
+    );
+
+    let pdf_path = create_temp_dir("pdf-info-encryption").join(name);
+    fs::write(&pdf_path, bytes).unwrap();
+    pdf_path
+}
+
+const PASSWORD_PADDING: [u8; 32] = [
+    0x28, 0xBF, 0x4E, 0x5E, 0x4E, 0x75, 0x8A, 0x41, 0x64, 0x00, 0x4E, 0x56, 0xFF, 0xFA, 0x01, 0x08,
+    0x2E, 0x2E, 0x00, 0xB6, 0xD0, 0x68, 0x3E, 0x80, 0x2F, 0x0C, 0xA9, 0xFE, 0x64, 0x53, 0x69, 0x7A,
+];
+
+fn pad_password(password: &[u8]) -> [u8; 32] {
+    let mut padded = PASSWORD_PADDING;
+    let len = password.len().min(32);
+    padded[..len].copy_from_slice(&password[..len]);
+    padded[len..].copy_from_slice(&PASSWORD_PADDING[..32 - len]);
+    padded
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02X}")).collect()
+}
+
+fn rc4(key: &[u8], data: &[u8]) -> Vec<u8> {
+    let mut state: Vec<u8> = (0..=255).collect();
+    let mut j = 0u8;
+    for i in 0..256 {
+        j = j.wrapping_add(state[i]).wrapping_add(key[i % key.len()]);
+        state.swap(i, j as usize);
+    }
+
+    let (mut i, mut j) = (0u8, 0u8);
+    data.iter()
+        .map(|byte| {
+            i = i.wrapping_add(1);
+            j = j.wrapping_add(state[i as usize]);
+            state.swap(i as usize, j as usize);
+            let k = state[state[i as usize].wrapping_add(state[j as usize]) as usize];
+            byte ^ k
+        })
+        .collect()
+}
+
+fn md5(data: &[u8]) -> [u8; 16] {
+    const SHIFTS: [u32; 64] = [
+        7, 12, 17, 22, 7, 12, 17, 22, 7, 12, 17, 22, 7, 12, 17, 22, 5, 9, 14, 20, 5, 9, 14, 20, 5,
+        9, 14, 20, 5, 9, 14, 20, 4, 11, 16, 23, 4, 11, 16, 23, 4, 11, 16, 23, 4, 11, 16, 23, 6, 10,
+        15, 21, 6, 10, 15, 21, 6, 10, 15, 21, 6, 10, 15, 21,
+    ];
+    let constants: Vec<u32> = (0..64)
+        .map(|i| ((i as f64 + 1.0).sin().abs() * 4_294_967_296.0) as u32)
+        .collect();
+
+    let mut message = data.to_vec();
+    message.push(0x80);
+    while message.len() % 64 != 56 {
+        message.push(0);
+    }
+    message.extend_from_slice(&((data.len() as u64) * 8).to_le_bytes());
+
+    let mut state = [0x67452301u32, 0xEFCDAB89, 0x98BADCFE, 0x10325476];
+    for chunk in message.chunks(64) {
+        let words: Vec<u32> = chunk
+            .chunks(4)
+            .map(|w| u32::from_le_bytes([w[0], w[1], w[2], w[3]]))
+            .collect();
+        let [mut a, mut b, mut c, mut d] = state;
+        for i in 0..64 {
+            let (f, g) = match i / 16 {
+                0 => ((b & c) | (!b & d), i),
+                1 => ((d & b) | (!d & c), (5 * i + 1) % 16),
+                2 => (b ^ c ^ d, (3 * i + 5) % 16),
+                _ => (c ^ (b | !d), (7 * i) % 16),
+            };
+            let rotated = a
+                .wrapping_add(f)
+                .wrapping_add(constants[i])
+                .wrapping_add(words[g])
+                .rotate_left(SHIFTS[i]);
+            (a, d, c) = (d, c, b);
+            b = b.wrapping_add(rotated);
+        }
+        for (word, value) in state.iter_mut().zip([a, b, c, d]) {
+            *word = word.wrapping_add(value);
+        }
+    }
+
+    let mut digest = [0u8; 16];
+    for (out, word) in digest.chunks_mut(4).zip(state) {
+        out.copy_from_slice(&word.to_le_bytes());
+    }
+    digest
+}
@@ -0,0 +1,88 @@
+mod common;
+
+use common::{PdfBuilder, assert_success, create_temp_dir, json_data, pdf_command};
+use serde_json::{Value, json};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const DEJAVU: &[u8] = include_bytes!("../assets/DejaVuSans-Bold.ttf");
+
+#[test]
+fn fonts_are_listed_once_with_their_pages() {
+    let pdf_path = write_pdf("pdf-info-fonts", |pdf| {
+        let helvetica = pdf.add_object("<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica >>");
+        let program = pdf.add_stream(&format!("/Length1 {}", DEJAVU.len()), DEJAVU);
+        let descriptor = pdf.add_object(&format!(
+            "<< /Type /FontDescriptor /FontName /DejaVuSans-Bold /Flags 32 \
+             /FontBBox [-1069 -415 1975 1174] /ItalicAngle 0 /Ascent 928 /Descent -236 \
+             /CapHeight 729 /StemV 120 /FontFile2 {program} 0 R >>"
+        ));
+        let dejavu = pdf.add_object(&format!(
+            "<< /Type /Font /Subtype /TrueType /BaseFont /DejaVuSans-Bold \
+             /Encoding /WinAnsiEncoding /FontDescriptor {descriptor} 0 R >>"
+        ));
+        let resources =
+            format!("/Resources << /Font << /F1 {helvetica} 0 R /F2 {dejavu} 0 R >> >>");
+        pdf.add_page(
+            &format!("/MediaBox [0 0 200 200] {resources}"),
+            b"BT /F1 12 Tf 10 150 Td (Plain) Tj /F2 12 Tf 0 -30 Td (Embedded) Tj ET",
+        );
+        pdf.add_page(
+            &format!("/MediaBox [0 0 200 200] {resources}"),
+            b"BT /F1 12 Tf 10 150 Td (Plain again) Tj ET",
+        );
+    });
+
+    let fonts = list_fonts(&pdf_path);
+
+    assert_eq!(
+        fonts,
+        json!([
+            {"name": "DejaVuSans-Bold", "embedded": true, "subtype": "TrueType", "pages": [1]},
+            {"name": "Helvetica", "embedded": false, "subtype": null, "pages": [1, 2]}
+        ])
+    );
+}
+
+#[test]
+fn documents_without_text_list_no_fonts() {
+    let pdf_path = write_pdf("pdf-info-fonts-none", |pdf| {
+        pdf.add_page("/MediaBox [0 0 100 100]", b"0 g 10 10 80 80 re f");
+    });
+
+    assert_eq!(list_fonts(&pdf_path), json!([]));
+}
+
+#[test]
+fn fonts_cannot_be_combined_with_page_details() {
+    let pdf_path = write_pdf("pdf-info-fonts-conflict", |pdf| {
+        pdf.add_page("/MediaBox [0 0 100 100]", b"");
+    });
+
+    let output = pdf_command()
+        .args(["info", "--fonts", "--all-pages"])
+        .arg(&pdf_path)
+        .output()
+        .unwrap();
+
+    assert!(!output.status.success());
+}
+
+fn list_fonts(pdf_path: &Path) -> Value {
+    let output = pdf_command()
+        .args(["info", "--fonts"])
+        .arg(pdf_path)
+        .output()
+        .unwrap();
+    assert_success(&output, "pdf info --fonts");
+    let report: Value = json_data(&output.stdout);
+    report["fonts"].clone()
+}
+
+fn write_pdf(prefix: &str, build: impl FnOnce(&mut PdfBuilder)) -> PathBuf {
+    let pdf_path = create_temp_dir(prefix).join("fonts.pdf");
+    let mut pdf = PdfBuilder::new();
+    build(&mut pdf);
+    fs::write(&pdf_path, pdf.finish()).unwrap();
+    pdf_path
+}
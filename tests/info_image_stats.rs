@@ -0,0 +1,58 @@
+mod common;
+
+use common::{PdfBuilder, assert_success, create_temp_dir, json_data, pdf_command};
+use image::codecs::jpeg::JpegEncoder;
+use image::{Rgb, RgbImage};
+use std::fs;
+
+#[test]
+fn image_stats_distinguish_photo_and_text_pages() {
+    let pdf_path = create_temp_dir("pdf-info-image-stats").join("mixed.pdf");
+    fs::write(&pdf_path, build_mixed_pdf()).unwrap();
+
+    let output = pdf_command()
+        .arg("info")
+        .arg(&pdf_path)
+        .arg("--all-pages")
+        .arg("--image-stats")
+        .output()
+        .unwrap();
+    assert_success(&output, "pdf info --image-stats");
+
+    let info: serde_json::Value = json_data(&output.stdout);
+    let photo = &info["pages"][0]["image_stats"];
+    assert_eq!(photo["objects"], 1);
+    assert_eq!(photo["image_count"], 1);
+    assert_eq!(photo["is_single_jpeg"], true);
+
+    let text = &info["pages"][1]["image_stats"];
+    assert_eq!(text["image_count"], 0);
+    assert_eq!(text["is_single_jpeg"], false);
+    assert!(text["objects"].as_u64().unwrap() >= 1);
+}
+
+/// Page 1 draws one full-page DCTDecode image; page 2 draws a line of text.
+fn build_mixed_pdf() -> Vec<u8> {
+    let image = RgbImage::from_fn(60, 80, |x, y| Rgb([(x * 4) as u8, (y * 3) as u8, 90]));
+    let mut jpeg = Vec::new();
+    image
+        .write_with_encoder(JpegEncoder::new_with_quality(&mut jpeg, 90))
+        .unwrap();
+
+    let mut pdf = PdfBuilder::new();
+    let image_id = pdf.add_stream(
+        "/Type /XObject /Subtype /Image /Width 60 /Height 80 \
+         /ColorSpace /DeviceRGB /BitsPerComponent 8 /Filter /DCTDecode",
+        &jpeg,
+    );
+    pdf.add_page(
+        &format!("/MediaBox [0 0 60 80] /Resources << /XObject << /Im0 {image_id} 0 R >> >>"),
+        b"q 60 0 0 80 0 0 cm /Im0 Do Q\n",
+    );
+    let font_id = pdf.add_object("<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica >>");
+    pdf.add_page(
+        &format!("/MediaBox [0 0 300 200] /Resources << /Font << /F1 {font_id} 0 R >> >>"),
+        b"BT /F1 12 Tf 20 100 Td (Plain text page) Tj ET\n",
+    );
+    pdf.finish()
+}
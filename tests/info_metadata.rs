@@ -0,0 +1,64 @@
+mod common;
+
+use common::{PdfBuilder, assert_success, create_temp_dir, json_data, pdf_command};
+use std::fs;
+use std::path::Path;
+
+#[test]
+fn info_dictionary_entries_are_reported() {
+    let mut pdf = PdfBuilder::new();
+    pdf.add_page("/MediaBox [0 0 100 100]", b"");
+    let info_id = pdf.add_object(
+        "<< /Title (Annual Report) /Author (Jane Doe) /Subject (Finances) \
+         /Keywords (budget, 2024) /Creator (Writer) /Producer (pdfTeX) \
+         /CreationDate (D:20240315093000+01'00') /ModDate (D:20240401Z) >>",
+    );
+    pdf.add_trailer_entries(&format!("/Info {info_id} 0 R"));
+
+    let metadata = run_info(&write_pdf("full.pdf", pdf.finish()));
+
+    assert_eq!(metadata["title"], "Annual Report");
+    assert_eq!(metadata["author"], "Jane Doe");
+    assert_eq!(metadata["subject"], "Finances");
+    assert_eq!(metadata["keywords"], "budget, 2024");
+    assert_eq!(metadata["creator"], "Writer");
+    assert_eq!(metadata["producer"], "pdfTeX");
+    assert_eq!(metadata["creation_date"], "2024-03-15T09:30:00+01:00");
+    assert_eq!(metadata["modification_date"], "2024-04-01T00:00:00Z");
+}
+
+#[test]
+fn missing_entries_are_null() {
+    let mut pdf = PdfBuilder::new();
+    pdf.add_page("/MediaBox [0 0 100 100]", b"");
+    let info_id = pdf.add_object("<< /Title (Only a title) >>");
+    pdf.add_trailer_entries(&format!("/Info {info_id} 0 R"));
+
+    let metadata = run_info(&write_pdf("partial.pdf", pdf.finish()));
+
+    assert_eq!(metadata["title"], "Only a title");
+    for key in [
+        "author",
+        "subject",
+        "keywords",
+        "creator",
+        "producer",
+        "creation_date",
+        "modification_date",
+    ] {
+        assert!(metadata[key].is_null(), "{key}: {}", metadata[key]);
+    }
+}
+
+fn write_pdf(name: &str, bytes: Vec<u8>) -> std::path::PathBuf {
+    let pdf_path = create_temp_dir("pdf-info-metadata").join(name);
+    fs::write(&pdf_path, bytes).unwrap();
+    pdf_path
+}
+
+fn run_info(pdf_path: &Path) -> serde_json::Value {
+    let output = pdf_command().arg("info").arg(pdf_path).output().unwrap();
+    assert_success(&output, "pdf info");
+    let info: serde_json::Value = json_data(&output.stdout);
+    info["metadata"].clone()
+}
@@ -0,0 +1,68 @@
+mod common;
+
+use common::{PdfBuilder, assert_success, create_temp_dir, json_data, pdf_command};
+use std::fs;
+
+#[test]
+fn objects_count_annotations_and_object_types() {
+    let pdf_path = create_temp_dir("pdf-info-objects").join("annotated.pdf");
+    fs::write(&pdf_path, build_annotated_pdf()).unwrap();
+
+    let output = pdf_command()
+        .arg("info")
+        .arg(&pdf_path)
+        .arg("--all-pages")
+        .arg("--objects")
+        .output()
+        .unwrap();
+    assert_success(&output, "pdf info --objects");
+
+    let info: serde_json::Value = json_data(&output.stdout);
+    let annotated = &info["pages"][0]["objects"];
+    assert_eq!(annotated["annotation_count"], 1);
+    assert_eq!(annotated["image_count"], 1);
+    assert_eq!(annotated["path_count"], 1);
+    assert_eq!(annotated["text_count"], 1);
+
+    let blank = &info["pages"][1]["objects"];
+    assert_eq!(blank["annotation_count"], 0);
+    assert_eq!(blank["image_count"], 0);
+}
+
+#[test]
+fn objects_are_omitted_by_default() {
+    let pdf_path = create_temp_dir("pdf-info-objects-default").join("annotated.pdf");
+    fs::write(&pdf_path, build_annotated_pdf()).unwrap();
+
+    let output = pdf_command().arg("info").arg(&pdf_path).output().unwrap();
+    assert_success(&output, "pdf info");
+
+    let info: serde_json::Value = json_data(&output.stdout);
+    assert!(info["pages"][0].get("objects").is_none());
+}
+
+/// Page 1 has a text annotation, one image, one filled rectangle and one
+/// line of text; page 2 is blank.
+fn build_annotated_pdf() -> Vec<u8> {
+    let mut pdf = PdfBuilder::new();
+    let image_id = pdf.add_stream(
+        "/Type /XObject /Subtype /Image /Width 2 /Height 2 \
+         /ColorSpace /DeviceRGB /BitsPerComponent 8",
+        &[255, 0, 0, 0, 255, 0, 0, 0, 255, 255, 255, 255],
+    );
+    let font_id = pdf.add_object("<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica >>");
+    let annot_id = pdf.add_object(
+        "<< /Type /Annot /Subtype /Text /Rect [150 150 170 170] /Contents (Check this) >>",
+    );
+    pdf.add_page(
+        &format!(
+            "/MediaBox [0 0 200 200] /Annots [{annot_id} 0 R] \
+             /Resources << /XObject << /Im0 {image_id} 0 R >> /Font << /F1 {font_id} 0 R >> >>"
+        ),
+        b"q 40 0 0 40 10 10 cm /Im0 Do Q\n\
+          0 0 1 rg 60 10 40 40 re f\n\
+          BT /F1 12 Tf 10 120 Td (Triage) Tj ET\n",
+    );
+    pdf.add_page("/MediaBox [0 0 200 200]", b"");
+    pdf.finish()
+}
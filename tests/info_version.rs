@@ -0,0 +1,47 @@
+mod common;
+
+use common::{PdfBuilder, assert_success, create_temp_dir, json_data, pdf_command};
+use std::fs;
+
+#[test]
+fn info_reports_header_version_and_file_size() {
+    let pdf_path = create_temp_dir("pdf-info-version").join("v14.pdf");
+    let mut pdf = PdfBuilder::new();
+    pdf.add_page("/MediaBox [0 0 100 100]", b"");
+    let mut bytes = pdf.finish();
+    assert!(bytes.starts_with(b"%PDF-1.7"));
+    bytes[..8].copy_from_slice(b"%PDF-1.4");
+    fs::write(&pdf_path, &bytes).unwrap();
+
+    let output = pdf_command().arg("info").arg(&pdf_path).output().unwrap();
+    assert_success(&output, "pdf info");
+
+    let info: serde_json::Value = json_data(&output.stdout);
+    assert_eq!(info["pdf_version"], "1.4");
+    assert_eq!(info["file_size_bytes"], bytes.len() as u64);
+}
+
+#[test]
+fn stdin_has_no_file_size() {
+    let mut pdf = PdfBuilder::new();
+    pdf.add_page("/MediaBox [0 0 100 100]", b"");
+    let bytes = pdf.finish();
+
+    let mut child = pdf_command()
+        .arg("info")
+        .arg("-")
+        .stdin(std::process::Stdio::piped())
+        .stdout(std::process::Stdio::piped())
+        .spawn()
+        .unwrap();
+    {
+        use std::io::Write;
+        child.stdin.take().unwrap().write_all(&bytes).unwrap();
+    }
+    let output = child.wait_with_output().unwrap();
+    assert_success(&output, "pdf info -");
+
+    let info: serde_json::Value = json_data(&output.stdout);
+    assert_eq!(info["pdf_version"], "1.7");
+    assert!(info["file_size_bytes"].is_null());
+}
@@ -0,0 +1,51 @@
+mod common;
+
+use common::{PdfBuilder, assert_success, create_temp_dir, json_data, pdf_command};
+use std::fs;
+use std::path::Path;
+
+const PAGE_COUNT: u32 = 7;
+
+#[test]
+fn multi_worker_info_matches_single_worker() {
+    let pdf_path = create_temp_dir("pdf-info-workers").join("sizes.pdf");
+    let mut pdf = PdfBuilder::new();
+    for i in 0..PAGE_COUNT {
+        // Distinct sizes per page so any reordering shows up in the output.
+        pdf.add_page(
+            &format!(
+                "/MediaBox [0 0 {} {}] /BleedBox [1 1 50 50]",
+                100 + i * 10,
+                200 + i
+            ),
+            b"",
+        );
+    }
+    fs::write(&pdf_path, pdf.finish()).unwrap();
+
+    let single = run_info(&pdf_path, 1);
+    let multi = run_info(&pdf_path, 3);
+
+    assert_eq!(single, multi);
+    assert_eq!(
+        multi["pages"].as_array().unwrap().len(),
+        PAGE_COUNT as usize
+    );
+    assert_eq!(multi["pages"][6]["page"], 7);
+    assert_eq!(multi["pages"][6]["width_pt"], 160.0);
+}
+
+fn run_info(pdf_path: &Path, workers: u32) -> serde_json::Value {
+    let output = pdf_command()
+        .arg("info")
+        .arg(pdf_path)
+        .arg("--all-pages")
+        .arg("--boxes")
+        .arg("--workers")
+        .arg(workers.to_string())
+        .output()
+        .unwrap();
+    assert_success(&output, &format!("pdf info --workers {workers}"));
+
+    json_data(&output.stdout)
+}
@@ -0,0 +1,64 @@
+mod common;
+
+use common::{PdfBuilder, create_temp_dir, pdf_command};
+use std::fs;
+
+#[test]
+fn missing_pdf_is_reported_as_json() {
+    let temp_dir = create_temp_dir("pdf-json-errors-missing");
+    let missing = temp_dir.join("missing.pdf");
+
+    let output = pdf_command()
+        .arg("--json-errors")
+        .arg("info")
+        .arg(&missing)
+        .output()
+        .unwrap();
+
+    assert_eq!(output.status.code(), Some(2));
+    assert!(output.stdout.is_empty());
+    let json: serde_json::Value = serde_json::from_slice(&output.stderr).unwrap();
+    assert_eq!(json["error"]["kind"], "pdf_invalid");
+    assert_eq!(json["error"]["exit_code"], 2);
+    let message = json["error"]["message"].as_str().unwrap();
+    assert!(message.contains("missing.pdf"), "{message}");
+}
+
+#[test]
+fn invalid_arguments_are_reported_as_json() {
+    let temp_dir = create_temp_dir("pdf-json-errors-args");
+    let pdf_path = temp_dir.join("blank.pdf");
+    let mut pdf = PdfBuilder::new();
+    pdf.add_page("/MediaBox [0 0 100 100]", b"");
+    fs::write(&pdf_path, pdf.finish()).unwrap();
+
+    let output = pdf_command()
+        .arg("render")
+        .arg(&pdf_path)
+        .arg("-o")
+        .arg(temp_dir.join("out"))
+        .arg("--limit")
+        .arg("0")
+        .arg("--json-errors")
+        .output()
+        .unwrap();
+
+    assert_eq!(output.status.code(), Some(1));
+    let json: serde_json::Value = serde_json::from_slice(&output.stderr).unwrap();
+    assert_eq!(json["error"]["kind"], "invalid_args");
+    assert_eq!(json["error"]["exit_code"], 1);
+}
+
+#[test]
+fn errors_are_text_by_default() {
+    let temp_dir = create_temp_dir("pdf-json-errors-default");
+
+    let output = pdf_command()
+        .arg("info")
+        .arg(temp_dir.join("missing.pdf"))
+        .output()
+        .unwrap();
+
+    assert_eq!(output.status.code(), Some(2));
+    assert!(String::from_utf8_lossy(&output.stderr).starts_with("error: "));
+}
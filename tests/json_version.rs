@@ -0,0 +1,123 @@
+mod common;
+
+use common::{PdfBuilder, assert_success, create_temp_dir, pdf_command, render_command};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+#[test]
+fn info_output_is_wrapped_in_a_versioned_envelope() {
+    let (pdf_path, _) = write_blank_pdf("pdf-json-version-info");
+
+    let output = pdf_command().arg("info").arg(&pdf_path).output().unwrap();
+    assert_success(&output, "pdf info");
+
+    let json = parse(&output.stdout);
+    assert_eq!(json["schema"], "pdf.info.v1");
+    assert_eq!(json["data"]["page_count"], 1);
+    assert_eq!(json.as_object().unwrap().len(), 2);
+}
+
+#[test]
+fn render_summary_is_wrapped_in_a_versioned_envelope() {
+    let (pdf_path, output_dir) = write_blank_pdf("pdf-json-version-render");
+
+    let output = render(&pdf_path, &output_dir, &[]);
+    assert_success(&output, "pdf render");
+
+    let json = parse(&output.stdout);
+    assert_eq!(json["schema"], "pdf.render.v1");
+    assert_eq!(json["data"]["pages_rendered"], 1);
+}
+
+#[test]
+fn json_version_1_matches_the_default() {
+    let (pdf_path, _) = write_blank_pdf("pdf-json-version-explicit");
+
+    let default = pdf_command().arg("info").arg(&pdf_path).output().unwrap();
+    let explicit = pdf_command()
+        .arg("--json-version")
+        .arg("1")
+        .arg("info")
+        .arg(&pdf_path)
+        .output()
+        .unwrap();
+    assert_success(&explicit, "pdf --json-version 1 info");
+
+    assert_eq!(explicit.stdout, default.stdout);
+}
+
+#[test]
+fn json_version_0_prints_bare_documents() {
+    let (pdf_path, output_dir) = write_blank_pdf("pdf-json-version-bare");
+
+    let info = pdf_command()
+        .arg("info")
+        .arg(&pdf_path)
+        .arg("--json-version")
+        .arg("0")
+        .output()
+        .unwrap();
+    assert_success(&info, "pdf info --json-version 0");
+    let info = parse(&info.stdout);
+    assert_eq!(info["page_count"], 1);
+    assert!(info.get("schema").is_none());
+
+    let render = render(&pdf_path, &output_dir, &["--json-version", "0"]);
+    assert_success(&render, "pdf render --json-version 0");
+    assert_eq!(parse(&render.stdout)["pages_rendered"], 1);
+}
+
+#[test]
+fn manifest_file_uses_the_envelope() {
+    let (pdf_path, output_dir) = write_blank_pdf("pdf-json-version-manifest");
+    let manifest_path = output_dir.with_file_name("manifest.json");
+
+    let output = render(
+        &pdf_path,
+        &output_dir,
+        &["--manifest", manifest_path.to_str().unwrap()],
+    );
+    assert_success(&output, "pdf render --manifest");
+
+    let manifest = parse(&fs::read(&manifest_path).unwrap());
+    assert_eq!(manifest["schema"], "pdf.manifest.v1");
+    assert_eq!(manifest["data"][0]["page"], 1);
+}
+
+#[test]
+fn unknown_json_version_is_rejected() {
+    let (pdf_path, _) = write_blank_pdf("pdf-json-version-unknown");
+
+    let output = pdf_command()
+        .arg("info")
+        .arg(&pdf_path)
+        .arg("--json-version")
+        .arg("2")
+        .output()
+        .unwrap();
+
+    assert!(!output.status.success());
+    assert!(output.stdout.is_empty());
+}
+
+fn render(pdf_path: &Path, output_dir: &Path, args: &[&str]) -> std::process::Output {
+    render_command(pdf_path, output_dir)
+        .arg("--target-width")
+        .arg("40")
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+fn parse(json: &[u8]) -> serde_json::Value {
+    serde_json::from_slice(json).unwrap()
+}
+
+fn write_blank_pdf(prefix: &str) -> (PathBuf, PathBuf) {
+    let temp_dir = create_temp_dir(prefix);
+    let pdf_path = temp_dir.join("blank.pdf");
+    let mut pdf = PdfBuilder::new();
+    pdf.add_page("/MediaBox [0 0 100 100]", b"");
+    fs::write(&pdf_path, pdf.finish()).unwrap();
+    (pdf_path, temp_dir.join("out"))
+}
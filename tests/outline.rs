@@ -0,0 +1,76 @@
+mod common;
+
+use common::{PdfBuilder, assert_success, create_temp_dir, json_data, pdf_command};
+use serde_json::json;
+use std::fs;
+use std::path::Path;
+
+#[test]
+fn two_level_outline_is_nested() {
+    let pdf_path = create_temp_dir("pdf-outline").join("outline.pdf");
+    let mut pdf = PdfBuilder::new();
+    let pages: Vec<usize> = (0..3)
+        .map(|_| pdf.add_page("/MediaBox [0 0 100 100]", b""))
+        .collect();
+    // Objects are numbered in order, so the tree can refer forward.
+    let root = pages[2] + 1;
+    let (chapter_one, section, website, chapter_two) = (root + 1, root + 2, root + 3, root + 4);
+    let id = pdf.add_object(&format!(
+        "<< /Type /Outlines /First {chapter_one} 0 R /Last {chapter_two} 0 R /Count 4 >>"
+    ));
+    assert_eq!(id, root);
+    pdf.add_object(&format!(
+        "<< /Title (Chapter One) /Parent {root} 0 R /Next {chapter_two} 0 R \
+         /First {section} 0 R /Last {website} 0 R /Count 2 /Dest [{} 0 R /Fit] >>",
+        pages[0]
+    ));
+    pdf.add_object(&format!(
+        "<< /Title (Background) /Parent {chapter_one} 0 R /Next {website} 0 R \
+         /Dest [{} 0 R /XYZ 0 100 0] >>",
+        pages[1]
+    ));
+    pdf.add_object(&format!(
+        "<< /Title (Website) /Parent {chapter_one} 0 R /Prev {section} 0 R \
+         /A << /S /URI /URI (https://example.com) >> >>"
+    ));
+    pdf.add_object(&format!(
+        "<< /Title (Chapter Two) /Parent {root} 0 R /Prev {chapter_one} 0 R \
+         /A << /S /GoTo /D [{} 0 R /Fit] >> >>",
+        pages[2]
+    ));
+    pdf.add_catalog_entries(&format!("/Outlines {root} 0 R"));
+    fs::write(&pdf_path, pdf.finish()).unwrap();
+
+    let outline = run_outline(&pdf_path);
+
+    assert_eq!(
+        outline,
+        json!([
+            {
+                "title": "Chapter One",
+                "page": 1,
+                "children": [
+                    { "title": "Background", "page": 2, "children": [] },
+                    { "title": "Website", "page": null, "children": [] }
+                ]
+            },
+            { "title": "Chapter Two", "page": 3, "children": [] }
+        ])
+    );
+}
+
+#[test]
+fn document_without_outline_prints_empty_array() {
+    let pdf_path = create_temp_dir("pdf-outline-empty").join("plain.pdf");
+    let mut pdf = PdfBuilder::new();
+    pdf.add_page("/MediaBox [0 0 100 100]", b"");
+    fs::write(&pdf_path, pdf.finish()).unwrap();
+
+    assert_eq!(run_outline(&pdf_path), json!([]));
+}
+
+fn run_outline(pdf_path: &Path) -> serde_json::Value {
+    let output = pdf_command().arg("outline").arg(pdf_path).output().unwrap();
+    assert_success(&output, "pdf outline");
+    json_data(&output.stdout)
+}
@@ -0,0 +1,106 @@
+#![cfg(unix)]
+
+mod common;
+
+use common::{PdfBuilder, create_temp_dir, pdf_command};
+use std::fs;
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
+
+#[test]
+fn read_only_output_dir_fails_once_before_rendering() {
+    let (temp_dir, pdf_path) = write_pdf("pdf-output-dir-read-only");
+    let output_dir = temp_dir.join("out");
+    fs::create_dir(&output_dir).unwrap();
+    let Some(_restore) = make_read_only(&output_dir) else {
+        return;
+    };
+
+    for workers in ["1", "3"] {
+        let output = pdf_command()
+            .arg("render")
+            .arg(&pdf_path)
+            .arg("-o")
+            .arg(&output_dir)
+            .arg("--workers")
+            .arg(workers)
+            .output()
+            .unwrap();
+
+        assert_eq!(output.status.code(), Some(5), "--workers {workers}");
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert_eq!(stderr.lines().count(), 1, "{stderr}");
+        assert!(
+            stderr.contains("output directory is not writable"),
+            "{stderr}"
+        );
+        assert!(
+            stderr.contains(&output_dir.display().to_string()),
+            "{stderr}"
+        );
+        assert!(output.stdout.is_empty());
+    }
+}
+
+#[test]
+fn output_dir_under_a_read_only_parent_names_the_directory() {
+    let (temp_dir, pdf_path) = write_pdf("pdf-output-dir-read-only-parent");
+    let parent = temp_dir.join("locked");
+    fs::create_dir(&parent).unwrap();
+    let Some(_restore) = make_read_only(&parent) else {
+        return;
+    };
+    let output_dir = parent.join("out");
+
+    let output = pdf_command()
+        .arg("extract-images")
+        .arg(&pdf_path)
+        .arg("-o")
+        .arg(&output_dir)
+        .output()
+        .unwrap();
+
+    assert_eq!(output.status.code(), Some(5));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("cannot create output directory"),
+        "{stderr}"
+    );
+    assert!(
+        stderr.contains(&output_dir.display().to_string()),
+        "{stderr}"
+    );
+}
+
+/// Restores write permission on drop so the temp dir can be cleaned up.
+struct Restore(PathBuf);
+
+impl Drop for Restore {
+    fn drop(&mut self) {
+        let _ = fs::set_permissions(&self.0, fs::Permissions::from_mode(0o755));
+    }
+}
+
+/// Make `dir` read-only; `None` when the permission is not enforced, as for
+/// root, so the test has nothing to check.
+fn make_read_only(dir: &Path) -> Option<Restore> {
+    fs::set_permissions(dir, fs::Permissions::from_mode(0o555)).unwrap();
+    let restore = Restore(dir.to_path_buf());
+    let probe = dir.join("probe");
+    if fs::write(&probe, b"").is_ok() {
+        let _ = fs::remove_file(probe);
+        return None;
+    }
+    Some(restore)
+}
+
+fn write_pdf(prefix: &str) -> (PathBuf, PathBuf) {
+    let temp_dir = create_temp_dir(prefix);
+    let pdf_path = temp_dir.join("pages.pdf");
+    let mut pdf = PdfBuilder::new();
+    for _ in 0..3 {
+        pdf.add_page("/MediaBox [0 0 100 100]", b"");
+    }
+    fs::write(&pdf_path, pdf.finish()).unwrap();
+    (temp_dir, pdf_path)
+}
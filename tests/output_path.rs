@@ -0,0 +1,108 @@
+mod common;
+
+use common::{PdfBuilder, assert_success, create_temp_dir, pdf_command};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+#[test]
+fn render_rejects_a_file_as_output_directory() {
+    let (temp_dir, pdf_path) = write_pdf("pdf-output-path-file");
+    let file = temp_dir.join("out");
+    fs::write(&file, b"not a directory").unwrap();
+
+    let output = render(&pdf_path, &["-o", file.to_str().unwrap()]);
+
+    assert_eq!(output.status.code(), Some(1));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("output path is a file, expected a directory"),
+        "{stderr}"
+    );
+    assert_eq!(fs::read(&file).unwrap(), b"not a directory");
+}
+
+#[test]
+fn render_rejects_an_output_directory_below_a_file() {
+    let (temp_dir, pdf_path) = write_pdf("pdf-output-path-below-file");
+    let file = temp_dir.join("out");
+    fs::write(&file, b"").unwrap();
+
+    let output = render(&pdf_path, &["-o", file.join("pages").to_str().unwrap()]);
+
+    assert_eq!(output.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("which is a file"));
+}
+
+#[test]
+fn dry_run_and_extract_images_check_the_output_directory() {
+    let (temp_dir, pdf_path) = write_pdf("pdf-output-path-other");
+    let file = temp_dir.join("out");
+    fs::write(&file, b"").unwrap();
+
+    let dry_run = render(&pdf_path, &["-o", file.to_str().unwrap(), "--dry-run"]);
+    assert_eq!(dry_run.status.code(), Some(1));
+
+    let extract = pdf_command()
+        .arg("extract-images")
+        .arg(&pdf_path)
+        .arg("-o")
+        .arg(&file)
+        .output()
+        .unwrap();
+    assert_eq!(extract.status.code(), Some(1));
+}
+
+#[test]
+fn single_file_accepts_a_new_file_path() {
+    let (temp_dir, pdf_path) = write_pdf("pdf-output-path-single-file");
+    let tiff = temp_dir.join("nested/pages.tiff");
+
+    let output = render(
+        &pdf_path,
+        &["--single-file", tiff.to_str().unwrap(), "--format", "tiff"],
+    );
+
+    assert_success(&output, "pdf render --single-file");
+    assert!(tiff.is_file());
+}
+
+#[test]
+fn single_file_rejects_a_directory() {
+    let (temp_dir, pdf_path) = write_pdf("pdf-output-path-single-file-dir");
+
+    let output = render(
+        &pdf_path,
+        &[
+            "--single-file",
+            temp_dir.to_str().unwrap(),
+            "--format",
+            "tiff",
+        ],
+    );
+
+    assert_eq!(output.status.code(), Some(1));
+    assert!(
+        String::from_utf8_lossy(&output.stderr)
+            .contains("output path is a directory, expected a file")
+    );
+}
+
+fn render(pdf_path: &Path, args: &[&str]) -> std::process::Output {
+    pdf_command()
+        .arg("render")
+        .arg(pdf_path)
+        .arg("--target-width")
+        .arg("20")
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+fn write_pdf(prefix: &str) -> (PathBuf, PathBuf) {
+    let temp_dir = create_temp_dir(prefix);
+    let pdf_path = temp_dir.join("doc.pdf");
+    let mut pdf = PdfBuilder::new();
+    pdf.add_page("/MediaBox [0 0 100 150]", b"");
+    fs::write(&pdf_path, pdf.finish()).unwrap();
+    (temp_dir, pdf_path)
+}
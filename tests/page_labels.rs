@@ -0,0 +1,96 @@
+mod common;
+
+use common::{PdfBuilder, assert_success, create_temp_dir, json_data, pdf_command};
+use std::fs;
+use std::path::PathBuf;
+
+#[test]
+fn info_reports_page_labels() {
+    let pdf_path = write_front_matter_pdf("pdf-info-page-labels");
+
+    let output = pdf_command()
+        .arg("info")
+        .arg(&pdf_path)
+        .arg("--all-pages")
+        .output()
+        .unwrap();
+    assert_success(&output, "pdf info");
+
+    let info: serde_json::Value = json_data(&output.stdout);
+    let labels: Vec<_> = info["pages"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|page| page["page_label"].as_str().unwrap().to_string())
+        .collect();
+    assert_eq!(labels, ["i", "ii", "iii", "1", "2"]);
+}
+
+#[test]
+fn label_names_name_files_by_label() {
+    let pdf_path = write_front_matter_pdf("pdf-render-label-names");
+    let output_dir = pdf_path.parent().unwrap().join("out");
+
+    let output = pdf_command()
+        .arg("render")
+        .arg(&pdf_path)
+        .arg("-o")
+        .arg(&output_dir)
+        .arg("--workers")
+        .arg("2")
+        .arg("--label-names")
+        .output()
+        .unwrap();
+    assert_success(&output, "pdf render --label-names");
+
+    let mut files: Vec<_> = fs::read_dir(&output_dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+        .collect();
+    files.sort();
+    assert_eq!(
+        files,
+        [
+            "page-0001.jpg",
+            "page-0002.jpg",
+            "page-i.jpg",
+            "page-ii.jpg",
+            "page-iii.jpg"
+        ]
+    );
+}
+
+#[test]
+fn label_names_fall_back_to_page_numbers() {
+    let temp_dir = create_temp_dir("pdf-render-label-names-none");
+    let pdf_path = temp_dir.join("plain.pdf");
+    let mut pdf = PdfBuilder::new();
+    pdf.add_page("/MediaBox [0 0 100 100]", b"");
+    pdf.add_page("/MediaBox [0 0 100 100]", b"");
+    fs::write(&pdf_path, pdf.finish()).unwrap();
+
+    let output = pdf_command()
+        .arg("render")
+        .arg(&pdf_path)
+        .arg("-o")
+        .arg(temp_dir.join("out"))
+        .arg("--label-names")
+        .output()
+        .unwrap();
+    assert_success(&output, "pdf render --label-names");
+
+    assert!(temp_dir.join("out/page-0001.jpg").exists());
+    assert!(temp_dir.join("out/page-0002.jpg").exists());
+}
+
+/// Three pages of lowercase roman front matter, then arabic numbering from 1.
+fn write_front_matter_pdf(prefix: &str) -> PathBuf {
+    let pdf_path = create_temp_dir(prefix).join("book.pdf");
+    let mut pdf = PdfBuilder::new();
+    for _ in 0..5 {
+        pdf.add_page("/MediaBox [0 0 100 100]", b"");
+    }
+    pdf.add_catalog_entries("/PageLabels << /Nums [0 << /S /r >> 3 << /S /D >>] >>");
+    fs::write(&pdf_path, pdf.finish()).unwrap();
+    pdf_path
+}
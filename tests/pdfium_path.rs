@@ -0,0 +1,156 @@
+mod common;
+
+use common::{PdfBuilder, assert_success, create_temp_dir, pdf_command, pdfium_library};
+use std::env::consts::{DLL_PREFIX, DLL_SUFFIX};
+use std::fs;
+use std::path::PathBuf;
+use std::process::Command;
+
+#[test]
+fn invalid_pdfium_path_names_the_path() {
+    let pdf_path = write_blank_pdf("pdf-pdfium-path-invalid");
+    let missing = pdf_path.with_file_name("missing-libpdfium.so");
+
+    let output = pdf_command()
+        .arg("--pdfium-path")
+        .arg(&missing)
+        .arg("info")
+        .arg(&pdf_path)
+        .output()
+        .unwrap();
+
+    assert_eq!(output.status.code(), Some(3));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains(&missing.display().to_string()),
+        "stderr: {stderr}"
+    );
+}
+
+#[test]
+fn pdfium_path_flag_overrides_env_var() {
+    let pdf_path = write_blank_pdf("pdf-pdfium-path-override");
+    let missing = pdf_path.with_file_name("missing-libpdfium.so");
+
+    let output = pdf_command()
+        .env("PDF_PDFIUM_PATH", pdfium_library())
+        .arg("info")
+        .arg(&pdf_path)
+        .arg("--pdfium-path")
+        .arg(&missing)
+        .output()
+        .unwrap();
+
+    assert_eq!(output.status.code(), Some(3));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains(&missing.display().to_string()),
+        "stderr: {stderr}"
+    );
+}
+
+#[test]
+fn pdfium_path_accepts_library_directory_for_workers() {
+    let pdf_path = write_blank_pdf("pdf-pdfium-path-dir");
+    let library_dir = pdfium_library().parent().unwrap().to_path_buf();
+
+    let output = pdf_command()
+        .env_remove("PDFIUM_LIBRARY_PATH")
+        .arg("render")
+        .arg(&pdf_path)
+        .arg("-o")
+        .arg(pdf_path.with_file_name("out"))
+        .arg("--target-width")
+        .arg("40")
+        .arg("--workers")
+        .arg("2")
+        .arg("--pdfium-path")
+        .arg(&library_dir)
+        .output()
+        .unwrap();
+    assert_success(&output, "pdf render --pdfium-path <dir>");
+}
+
+#[test]
+fn env_var_path_is_used_when_invalid() {
+    let pdf_path = write_blank_pdf("pdf-pdfium-path-env");
+    let missing = pdf_path.with_file_name("missing-libpdfium.so");
+
+    let output = pdf_command()
+        .env("PDF_PDFIUM_PATH", &missing)
+        .arg("info")
+        .arg(&pdf_path)
+        .output()
+        .unwrap();
+
+    assert_eq!(output.status.code(), Some(3));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains(&missing.display().to_string()),
+        "stderr: {stderr}"
+    );
+}
+
+#[test]
+fn failed_search_lists_the_locations_tried() {
+    let pdf_path = write_blank_pdf("pdf-pdfium-search");
+    // A copy of the binary in a directory without pdfium next to it.
+    let exe_dir = create_temp_dir("pdf-pdfium-search-exe");
+    let exe = exe_dir.join("pdf");
+    fs::copy(env!("CARGO_BIN_EXE_pdf"), &exe).unwrap();
+
+    let output = Command::new(&exe)
+        .env_remove("PDFIUM_LIBRARY_PATH")
+        .env_remove("PDF_PDFIUM_PATH")
+        .env_remove("LD_LIBRARY_PATH")
+        .arg("info")
+        .arg(&pdf_path)
+        .output()
+        .unwrap();
+    if output.status.success() {
+        eprintln!("skipped: a system pdfium library is installed");
+        return;
+    }
+
+    assert_eq!(output.status.code(), Some(3));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let exe_dir_library = exe_dir.join(format!("{DLL_PREFIX}pdfium{DLL_SUFFIX}"));
+    assert!(
+        stderr.contains(&format!("{} (not found)", exe_dir_library.display())),
+        "stderr: {stderr}"
+    );
+    assert!(
+        stderr.contains(&format!("system library {DLL_PREFIX}pdfium{DLL_SUFFIX}")),
+        "stderr: {stderr}"
+    );
+    assert!(stderr.contains("--pdfium-path"), "stderr: {stderr}");
+}
+
+#[test]
+fn verbose_prints_the_loaded_library() {
+    let pdf_path = write_blank_pdf("pdf-pdfium-verbose");
+
+    let output = pdf_command()
+        .arg("--verbose")
+        .arg("info")
+        .arg(&pdf_path)
+        .output()
+        .unwrap();
+    assert_success(&output, "pdf --verbose info");
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert_eq!(
+        stderr.trim_end(),
+        format!("pdfium: loaded {}", pdfium_library().display())
+    );
+}
+
+/// Two blank pages, so `--workers 2` really spawns two workers.
+fn write_blank_pdf(prefix: &str) -> PathBuf {
+    let pdf_path = create_temp_dir(prefix).join("blank.pdf");
+    let mut pdf = PdfBuilder::new();
+    pdf.add_page("/MediaBox [0 0 100 100]", b"");
+    pdf.add_page("/MediaBox [0 0 100 100]", b"");
+    fs::write(&pdf_path, pdf.finish()).unwrap();
+    pdf_path
+}
@@ -0,0 +1,61 @@
+mod common;
+
+use common::{PdfBuilder, assert_success, create_temp_dir, pdf_command};
+use std::fs;
+
+#[test]
+fn align_16_pads_both_dimensions_with_background() {
+    let temp_dir = create_temp_dir("pdf-render-align");
+    let pdf_path = temp_dir.join("odd.pdf");
+    let mut pdf = PdfBuilder::new();
+    pdf.add_page("/MediaBox [0 0 100 77]", b"1 1 1 rg 0 0 100 77 re f");
+    fs::write(&pdf_path, pdf.finish()).unwrap();
+    let output_dir = temp_dir.join("out");
+
+    let output = pdf_command()
+        .arg("render")
+        .arg(&pdf_path)
+        .arg("-o")
+        .arg(&output_dir)
+        .arg("--target-width")
+        .arg("100")
+        .arg("--format")
+        .arg("png")
+        .arg("--background")
+        .arg("black")
+        .arg("--align")
+        .arg("16")
+        .output()
+        .unwrap();
+    assert_success(&output, "pdf render --align 16");
+
+    let image = image::open(output_dir.join("page-0001.png"))
+        .unwrap()
+        .to_rgb8();
+    assert_eq!(image.width() % 16, 0);
+    assert_eq!(image.height() % 16, 0);
+    assert_eq!(image.dimensions(), (112, 80));
+    assert_eq!(image.get_pixel(50, 40).0, [255, 255, 255]);
+    assert_eq!(image.get_pixel(111, 79).0, [0, 0, 0]);
+}
+
+#[test]
+fn zero_align_is_rejected() {
+    let temp_dir = create_temp_dir("pdf-render-align-zero");
+    let pdf_path = temp_dir.join("blank.pdf");
+    let mut pdf = PdfBuilder::new();
+    pdf.add_page("/MediaBox [0 0 100 100]", b"");
+    fs::write(&pdf_path, pdf.finish()).unwrap();
+
+    let output = pdf_command()
+        .arg("render")
+        .arg(&pdf_path)
+        .arg("-o")
+        .arg(temp_dir.join("out"))
+        .arg("--align")
+        .arg("0")
+        .output()
+        .unwrap();
+
+    assert_eq!(output.status.code(), Some(1));
+}
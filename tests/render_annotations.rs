@@ -0,0 +1,86 @@
+mod common;
+
+use common::{PdfBuilder, assert_success, create_temp_dir, render_command};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+#[test]
+fn annotations_are_drawn_by_default() {
+    let (temp_dir, pdf_path) = write_annotated_pdf("pdf-render-annotations");
+
+    let default = render(&pdf_path, &temp_dir, "default", &[]);
+    let on = render(&pdf_path, &temp_dir, "on", &["--annotations", "on"]);
+
+    assert_eq!(on, default);
+    assert_eq!(center_pixel(&on), [0, 0, 255]);
+}
+
+#[test]
+fn annotations_off_renders_only_the_page_content() {
+    let (temp_dir, pdf_path) = write_annotated_pdf("pdf-render-annotations-off");
+
+    let on = render(&pdf_path, &temp_dir, "on", &["--annotations", "on"]);
+    let off = render(&pdf_path, &temp_dir, "off", &["--annotations", "off"]);
+
+    assert_ne!(off, on);
+    assert_eq!(center_pixel(&off), [255, 255, 255]);
+}
+
+#[test]
+fn annotations_off_reaches_worker_processes() {
+    let (temp_dir, pdf_path) = write_annotated_pdf("pdf-render-annotations-workers");
+
+    let single = render(&pdf_path, &temp_dir, "single", &["--annotations", "off"]);
+    let multi = render(
+        &pdf_path,
+        &temp_dir,
+        "multi",
+        &["--annotations", "off", "--workers", "2"],
+    );
+
+    assert_eq!(multi, single);
+}
+
+/// Render page 1 to PNG and return its bytes.
+fn render(pdf_path: &Path, temp_dir: &Path, name: &str, args: &[&str]) -> Vec<u8> {
+    let output_dir = temp_dir.join(name);
+    let output = render_command(pdf_path, &output_dir)
+        .arg("--format")
+        .arg("png")
+        .arg("--target-width")
+        .arg("100")
+        .args(args)
+        .output()
+        .unwrap();
+    assert_success(&output, &format!("pdf render {}", args.join(" ")));
+    fs::read(output_dir.join("page-0001.png")).unwrap()
+}
+
+fn center_pixel(png: &[u8]) -> [u8; 3] {
+    let image = image::load_from_memory(png).unwrap().to_rgb8();
+    image.get_pixel(50, 50).0
+}
+
+/// Two blank 100x100pt pages, each with a square annotation whose appearance
+/// fills the middle blue.
+fn write_annotated_pdf(prefix: &str) -> (PathBuf, PathBuf) {
+    let temp_dir = create_temp_dir(prefix);
+    let pdf_path = temp_dir.join("annotated.pdf");
+    let mut pdf = PdfBuilder::new();
+    let appearance = pdf.add_stream(
+        "/Type /XObject /Subtype /Form /BBox [0 0 60 60]",
+        b"0 0 1 rg 0 0 60 60 re f",
+    );
+    for _ in 0..2 {
+        let annotation = pdf.add_object(&format!(
+            "<< /Type /Annot /Subtype /Square /Rect [20 20 80 80] /F 4 \
+             /AP << /N {appearance} 0 R >> >>"
+        ));
+        pdf.add_page(
+            &format!("/MediaBox [0 0 100 100] /Annots [{annotation} 0 R]"),
+            b"",
+        );
+    }
+    fs::write(&pdf_path, pdf.finish()).unwrap();
+    (temp_dir, pdf_path)
+}
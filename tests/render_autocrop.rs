@@ -0,0 +1,60 @@
+mod common;
+
+use common::{PdfBuilder, assert_success, create_temp_dir, render_command};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+#[test]
+fn autocrop_trims_white_margins() {
+    let (temp_dir, pdf_path) = write_pdf("pdf-render-autocrop");
+    let full_dir = temp_dir.join("full");
+    let trimmed_dir = temp_dir.join("trimmed");
+
+    let full = render(&pdf_path, &full_dir, &[]);
+    assert_success(&full, "pdf render");
+    let trimmed = render(&pdf_path, &trimmed_dir, &["--autocrop"]);
+    assert_success(&trimmed, "pdf render --autocrop");
+
+    let page = |dir: &Path| image::open(dir.join("page-0001.png")).unwrap();
+    let full = page(&full_dir);
+    let trimmed = page(&trimmed_dir);
+    assert_eq!((full.width(), full.height()), (200, 160));
+    // The 100x60pt rectangle, give or take anti-aliased edges.
+    assert!(trimmed.width().abs_diff(100) <= 2, "{}", trimmed.width());
+    assert!(trimmed.height().abs_diff(60) <= 2, "{}", trimmed.height());
+    let center = trimmed.to_rgb8();
+    let center = center.get_pixel(trimmed.width() / 2, trimmed.height() / 2);
+    assert_eq!(center.0, [0, 0, 0]);
+}
+
+#[test]
+fn autocrop_threshold_requires_autocrop() {
+    let (temp_dir, pdf_path) = write_pdf("pdf-render-autocrop-threshold");
+
+    let output = render(
+        &pdf_path,
+        &temp_dir.join("out"),
+        &["--autocrop-threshold", "40"],
+    );
+
+    assert!(!output.status.success());
+}
+
+fn render(pdf_path: &Path, output_dir: &Path, args: &[&str]) -> std::process::Output {
+    render_command(pdf_path, output_dir)
+        .arg("--format")
+        .arg("png")
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+/// A 200x160pt page with a black 100x60pt rectangle and white margins.
+fn write_pdf(prefix: &str) -> (PathBuf, PathBuf) {
+    let temp_dir = create_temp_dir(prefix);
+    let pdf_path = temp_dir.join("margins.pdf");
+    let mut pdf = PdfBuilder::new();
+    pdf.add_page("/MediaBox [0 0 200 160]", b"0 0 0 rg 40 50 100 60 re f");
+    fs::write(&pdf_path, pdf.finish()).unwrap();
+    (temp_dir, pdf_path)
+}
@@ -0,0 +1,71 @@
+mod common;
+
+use common::{PdfBuilder, create_temp_dir, pdf_command, render_command};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+#[cfg(feature = "avif")]
+#[test]
+fn avif_output_has_ftyp_avif_box() {
+    let (pdf_path, output_dir) = write_pdf("pdf-render-avif");
+
+    let output = render_avif(
+        &pdf_path,
+        &output_dir,
+        &["--quality", "60", "--speed", "10"],
+    );
+    common::assert_success(&output, "pdf render --format avif");
+
+    let bytes = fs::read(output_dir.join("page-0001.avif")).unwrap();
+    assert_eq!(&bytes[4..8], b"ftyp");
+    assert_eq!(&bytes[8..12], b"avif");
+}
+
+#[cfg(not(feature = "avif"))]
+#[test]
+fn avif_without_feature_is_invalid_args() {
+    let (pdf_path, output_dir) = write_pdf("pdf-render-avif-disabled");
+
+    let output = render_avif(&pdf_path, &output_dir, &[]);
+
+    assert_eq!(output.status.code(), Some(1));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("--features avif"), "{stderr}");
+}
+
+#[test]
+fn speed_rejects_non_avif_output() {
+    let (pdf_path, output_dir) = write_pdf("pdf-render-speed-jpeg");
+
+    let output = pdf_command()
+        .arg("render")
+        .arg(&pdf_path)
+        .arg("-o")
+        .arg(&output_dir)
+        .arg("--speed")
+        .arg("6")
+        .output()
+        .unwrap();
+
+    assert_eq!(output.status.code(), Some(1));
+}
+
+fn render_avif(pdf_path: &Path, output_dir: &Path, args: &[&str]) -> std::process::Output {
+    render_command(pdf_path, output_dir)
+        .arg("--target-width")
+        .arg("40")
+        .arg("--format")
+        .arg("avif")
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+fn write_pdf(prefix: &str) -> (PathBuf, PathBuf) {
+    let temp_dir = create_temp_dir(prefix);
+    let pdf_path = temp_dir.join("doc.pdf");
+    let mut pdf = PdfBuilder::new();
+    pdf.add_page("/MediaBox [0 0 100 100]", b"0 0.5 1 rg 10 10 80 80 re f\n");
+    fs::write(&pdf_path, pdf.finish()).unwrap();
+    (pdf_path, temp_dir.join("out"))
+}
@@ -0,0 +1,68 @@
+mod common;
+
+use common::{PdfBuilder, assert_success, create_temp_dir, pdf_command};
+use std::fs;
+use std::path::PathBuf;
+
+#[test]
+fn blank_page_corners_match_background_color() {
+    let (pdf_path, output_dir) = write_blank_pdf("pdf-render-background");
+
+    let output = pdf_command()
+        .arg("render")
+        .arg(&pdf_path)
+        .arg("-o")
+        .arg(&output_dir)
+        .arg("--target-width")
+        .arg("40")
+        .arg("--format")
+        .arg("png")
+        .arg("--background")
+        .arg("#336699")
+        .output()
+        .unwrap();
+    assert_success(&output, "pdf render --background #336699");
+
+    let image = image::open(output_dir.join("page-0001.png"))
+        .unwrap()
+        .to_rgb8();
+    let (width, height) = image.dimensions();
+    for (x, y) in [
+        (0, 0),
+        (width - 1, 0),
+        (0, height - 1),
+        (width - 1, height - 1),
+    ] {
+        assert_eq!(
+            image.get_pixel(x, y).0,
+            [0x33, 0x66, 0x99],
+            "corner ({x}, {y})"
+        );
+    }
+}
+
+#[test]
+fn transparent_background_rejects_jpeg_output() {
+    let (pdf_path, output_dir) = write_blank_pdf("pdf-render-background-jpeg");
+
+    let output = pdf_command()
+        .arg("render")
+        .arg(&pdf_path)
+        .arg("-o")
+        .arg(&output_dir)
+        .arg("--background")
+        .arg("transparent")
+        .output()
+        .unwrap();
+
+    assert_eq!(output.status.code(), Some(1));
+}
+
+fn write_blank_pdf(prefix: &str) -> (PathBuf, PathBuf) {
+    let temp_dir = create_temp_dir(prefix);
+    let pdf_path = temp_dir.join("blank.pdf");
+    let mut pdf = PdfBuilder::new();
+    pdf.add_page("/MediaBox [0 0 100 100]", b"");
+    fs::write(&pdf_path, pdf.finish()).unwrap();
+    (pdf_path, temp_dir.join("out"))
+}
@@ -0,0 +1,57 @@
+mod common;
+
+use common::{PdfBuilder, assert_success, create_temp_dir, render_command};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+#[test]
+fn auto_box_prefers_trim_over_crop() {
+    let (pdf_path, temp_dir) = write_pdf("pdf-render-box-auto");
+    let output_dir = temp_dir.join("out");
+
+    let output = render(&pdf_path, &output_dir, "auto");
+    assert_success(&output, "pdf render --box auto");
+
+    // Page 1 renders its 100x60 TrimBox rather than the 160x120 CropBox;
+    // page 2 defines neither TrimBox nor BleedBox and keeps its MediaBox.
+    assert_eq!(page_size(&output_dir, 1), (100, 60));
+    assert_eq!(page_size(&output_dir, 2), (80, 40));
+}
+
+#[test]
+fn crop_box_ignores_trim_box() {
+    let (pdf_path, temp_dir) = write_pdf("pdf-render-box-crop");
+    let output_dir = temp_dir.join("out");
+
+    let output = render(&pdf_path, &output_dir, "crop");
+    assert_success(&output, "pdf render --box crop");
+
+    assert_eq!(page_size(&output_dir, 1), (160, 120));
+}
+
+fn page_size(output_dir: &Path, page: u32) -> (u32, u32) {
+    image::image_dimensions(output_dir.join(format!("page-{page:04}.jpg"))).unwrap()
+}
+
+fn render(pdf_path: &Path, output_dir: &Path, box_type: &str) -> std::process::Output {
+    render_command(pdf_path, output_dir)
+        .arg("--dpi")
+        .arg("72")
+        .arg("--box")
+        .arg(box_type)
+        .output()
+        .unwrap()
+}
+
+fn write_pdf(prefix: &str) -> (PathBuf, PathBuf) {
+    let temp_dir = create_temp_dir(prefix);
+    let pdf_path = temp_dir.join("boxes.pdf");
+    let mut pdf = PdfBuilder::new();
+    pdf.add_page(
+        "/MediaBox [0 0 200 200] /CropBox [20 20 180 140] /TrimBox [40 40 140 100]",
+        b"",
+    );
+    pdf.add_page("/MediaBox [0 0 80 40]", b"");
+    fs::write(&pdf_path, pdf.finish()).unwrap();
+    (pdf_path, temp_dir)
+}
@@ -0,0 +1,61 @@
+mod common;
+
+use common::{PdfBuilder, assert_success, create_temp_dir, json_data, pdf_command};
+use std::fs;
+
+#[test]
+fn missing_bleed_box_falls_back_with_a_warning() {
+    let temp_dir = create_temp_dir("pdf-render-box-fallback");
+    let pdf_path = temp_dir.join("boxes.pdf");
+    let mut pdf = PdfBuilder::new();
+    pdf.add_page("/MediaBox [0 0 200 200] /BleedBox [10 10 190 190]", b"");
+    pdf.add_page("/MediaBox [0 0 200 200] /CropBox [20 20 180 180]", b"");
+    fs::write(&pdf_path, pdf.finish()).unwrap();
+
+    let output = pdf_command()
+        .arg("render")
+        .arg(&pdf_path)
+        .arg("-o")
+        .arg(temp_dir.join("out"))
+        .arg("--box")
+        .arg("bleed")
+        .arg("--dpi")
+        .arg("72")
+        .output()
+        .unwrap();
+    assert_success(&output, "pdf render --box bleed");
+
+    let summary: serde_json::Value = json_data(&output.stdout);
+    assert_eq!(summary["pages_rendered"], 2);
+    assert_eq!(summary["box_fallbacks"], 1);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("no BleedBox on page 2; rendered the CropBox instead"),
+        "stderr: {stderr}"
+    );
+    // Page 2 still renders, from its CropBox.
+    let size = image::image_dimensions(temp_dir.join("out").join("page-0002.jpg")).unwrap();
+    assert_eq!(size, (160, 160));
+}
+
+#[test]
+fn crop_box_never_falls_back() {
+    let temp_dir = create_temp_dir("pdf-render-box-crop-no-fallback");
+    let pdf_path = temp_dir.join("plain.pdf");
+    let mut pdf = PdfBuilder::new();
+    pdf.add_page("/MediaBox [0 0 200 200]", b"");
+    fs::write(&pdf_path, pdf.finish()).unwrap();
+
+    let output = pdf_command()
+        .arg("render")
+        .arg(&pdf_path)
+        .arg("-o")
+        .arg(temp_dir.join("out"))
+        .output()
+        .unwrap();
+    assert_success(&output, "pdf render");
+
+    let summary: serde_json::Value = json_data(&output.stdout);
+    assert!(summary.get("box_fallbacks").is_none());
+    assert!(!String::from_utf8_lossy(&output.stderr).contains("BleedBox"));
+}
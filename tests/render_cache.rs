@@ -0,0 +1,110 @@
+mod common;
+
+use common::{PdfBuilder, assert_success, create_temp_dir, json_data, render_command};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const PAGE: &str = "/MediaBox [0 0 100 150]";
+const CONTENT: &[u8] = b"0 0 1 rg 10 10 60 90 re f\n";
+
+#[test]
+fn identical_page_is_served_from_cache() {
+    let temp_dir = create_temp_dir("pdf-render-cache-identical");
+    let pdf_path = temp_dir.join("twins.pdf");
+    let mut pdf = PdfBuilder::new();
+    pdf.add_page(PAGE, CONTENT);
+    pdf.add_page(PAGE, CONTENT);
+    fs::write(&pdf_path, pdf.finish()).unwrap();
+    let output_dir = temp_dir.join("out");
+    let cache_dir = temp_dir.join("cache");
+
+    let output = render(&pdf_path, &output_dir, &cache_dir, &["--workers", "1"]);
+    assert_success(&output, "pdf render --cache");
+
+    let summary: serde_json::Value = json_data(&output.stdout);
+    assert_eq!(summary["pages_rendered"], 2);
+    assert_eq!(summary["pages_cached"], 1);
+    assert_eq!(cache_files(&cache_dir).len(), 1);
+    assert_eq!(
+        fs::read(output_dir.join("page-0001.jpg")).unwrap(),
+        fs::read(output_dir.join("page-0002.jpg")).unwrap()
+    );
+}
+
+#[test]
+fn cache_hit_copies_the_stored_file_instead_of_encoding() {
+    let (temp_dir, pdf_path) = write_pdf("pdf-render-cache-hit");
+    let cache_dir = temp_dir.join("cache");
+
+    let first = render(&pdf_path, &temp_dir.join("first"), &cache_dir, &[]);
+    assert_success(&first, "pdf render --cache (cold)");
+    let first: serde_json::Value = json_data(&first.stdout);
+    assert!(first.get("pages_cached").is_none());
+
+    // Overwrite the entry: a re-render would produce a real JPEG instead.
+    let entries = cache_files(&cache_dir);
+    assert_eq!(entries.len(), 1);
+    fs::write(&entries[0], b"from the cache").unwrap();
+
+    let output_dir = temp_dir.join("second");
+    let second = render(&pdf_path, &output_dir, &cache_dir, &[]);
+    assert_success(&second, "pdf render --cache (warm)");
+
+    let summary: serde_json::Value = json_data(&second.stdout);
+    assert_eq!(summary["pages_rendered"], 1);
+    assert_eq!(summary["pages_cached"], 1);
+    assert_eq!(
+        fs::read(output_dir.join("page-0001.jpg")).unwrap(),
+        b"from the cache"
+    );
+}
+
+#[test]
+fn changed_settings_miss_the_cache() {
+    let (temp_dir, pdf_path) = write_pdf("pdf-render-cache-settings");
+    let cache_dir = temp_dir.join("cache");
+
+    let first = render(&pdf_path, &temp_dir.join("first"), &cache_dir, &[]);
+    assert_success(&first, "pdf render --cache");
+    let second = render(
+        &pdf_path,
+        &temp_dir.join("second"),
+        &cache_dir,
+        &["--quality", "50"],
+    );
+    assert_success(&second, "pdf render --cache --quality 50");
+
+    let summary: serde_json::Value = json_data(&second.stdout);
+    assert!(summary.get("pages_cached").is_none());
+    assert_eq!(cache_files(&cache_dir).len(), 2);
+}
+
+fn cache_files(cache_dir: &Path) -> Vec<PathBuf> {
+    fs::read_dir(cache_dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .collect()
+}
+
+fn render(
+    pdf_path: &Path,
+    output_dir: &Path,
+    cache_dir: &Path,
+    args: &[&str],
+) -> std::process::Output {
+    render_command(pdf_path, output_dir)
+        .arg("--cache")
+        .arg(cache_dir)
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+fn write_pdf(name: &str) -> (PathBuf, PathBuf) {
+    let temp_dir = create_temp_dir(name);
+    let pdf_path = temp_dir.join("doc.pdf");
+    let mut pdf = PdfBuilder::new();
+    pdf.add_page(PAGE, CONTENT);
+    fs::write(&pdf_path, pdf.finish()).unwrap();
+    (temp_dir, pdf_path)
+}
@@ -0,0 +1,104 @@
+mod common;
+
+use common::{PdfBuilder, assert_success, create_temp_dir, render_command};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+#[test]
+fn differently_shaped_pages_fill_the_same_canvas() {
+    let (pdf_path, temp_dir) = write_pdf("pdf-render-canvas");
+    let output_dir = temp_dir.join("out");
+
+    let output = render(&pdf_path, &output_dir, &["--canvas", "120x80"]);
+    assert_success(&output, "pdf render --canvas");
+
+    let portrait = image::open(output_dir.join("page-0001.png"))
+        .unwrap()
+        .to_rgb8();
+    let landscape = image::open(output_dir.join("page-0002.png"))
+        .unwrap()
+        .to_rgb8();
+    assert_eq!(portrait.dimensions(), (120, 80));
+    assert_eq!(landscape.dimensions(), (120, 80));
+
+    // The 1:2 page is 40px wide, centered between white bars; the 3:1 page
+    // spans the width with bars above and below.
+    assert_eq!(portrait.get_pixel(10, 40).0, [255, 255, 255]);
+    assert_eq!(portrait.get_pixel(60, 40).0, [0, 0, 0]);
+    assert_eq!(portrait.get_pixel(110, 40).0, [255, 255, 255]);
+    assert_eq!(landscape.get_pixel(60, 5).0, [255, 255, 255]);
+    assert_eq!(landscape.get_pixel(60, 40).0, [0, 0, 0]);
+    assert_eq!(landscape.get_pixel(60, 75).0, [255, 255, 255]);
+}
+
+#[test]
+fn canvas_padding_uses_the_background() {
+    let (pdf_path, temp_dir) = write_pdf("pdf-render-canvas-background");
+    let output_dir = temp_dir.join("out");
+
+    let output = render(
+        &pdf_path,
+        &output_dir,
+        &[
+            "--canvas",
+            "120x80",
+            "--background",
+            "#ff0000",
+            "--workers",
+            "2",
+        ],
+    );
+    assert_success(&output, "pdf render --canvas --background");
+
+    let portrait = image::open(output_dir.join("page-0001.png"))
+        .unwrap()
+        .to_rgb8();
+    assert_eq!(portrait.dimensions(), (120, 80));
+    assert_eq!(portrait.get_pixel(5, 40).0, [255, 0, 0]);
+}
+
+#[test]
+fn malformed_canvas_is_rejected() {
+    let (pdf_path, temp_dir) = write_pdf("pdf-render-canvas-invalid");
+
+    for canvas in ["120", "120x", "0x80", "wide"] {
+        let output = render(&pdf_path, &temp_dir.join("out"), &["--canvas", canvas]);
+
+        assert_eq!(output.status.code(), Some(1), "{canvas}");
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(stderr.contains("--canvas"), "{stderr}");
+    }
+}
+
+#[test]
+fn canvas_conflicts_with_target_width() {
+    let (pdf_path, temp_dir) = write_pdf("pdf-render-canvas-conflict");
+
+    let output = render(
+        &pdf_path,
+        &temp_dir.join("out"),
+        &["--canvas", "120x80", "--target-width", "100"],
+    );
+
+    assert!(!output.status.success());
+}
+
+fn render(pdf_path: &Path, output_dir: &Path, args: &[&str]) -> std::process::Output {
+    render_command(pdf_path, output_dir)
+        .arg("--format")
+        .arg("png")
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+/// A black 100x200pt portrait page and a black 300x100pt landscape page.
+fn write_pdf(prefix: &str) -> (PathBuf, PathBuf) {
+    let temp_dir = create_temp_dir(prefix);
+    let pdf_path = temp_dir.join("shapes.pdf");
+    let mut pdf = PdfBuilder::new();
+    pdf.add_page("/MediaBox [0 0 100 200]", b"0 g 0 0 100 200 re f");
+    pdf.add_page("/MediaBox [0 0 300 100]", b"0 g 0 0 300 100 re f");
+    fs::write(&pdf_path, pdf.finish()).unwrap();
+    (pdf_path, temp_dir)
+}
@@ -0,0 +1,86 @@
+mod common;
+
+use common::{PdfBuilder, assert_success, create_temp_dir, render_command};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Output;
+
+#[test]
+fn config_target_width_applies_when_flag_is_absent() {
+    let work_dir = write_config("pdf-render-config", "target_width = 50\n");
+
+    let output = render_in(&work_dir, &[]);
+
+    assert_success(&output, "pdf render with pdf.toml");
+    assert_eq!(page_width(&work_dir), 50);
+}
+
+#[test]
+fn command_line_flag_overrides_config() {
+    let work_dir = write_config("pdf-render-config-override", "target_width = 50\n");
+
+    let output = render_in(&work_dir, &["--target-width", "80"]);
+    assert_success(&output, "pdf render --target-width");
+    assert_eq!(page_width(&work_dir), 80);
+
+    // A different size flag replaces the configured width instead of conflicting.
+    let output = render_in(&work_dir, &["--dpi", "36"]);
+    assert_success(&output, "pdf render --dpi");
+    assert_eq!(page_width(&work_dir), 100);
+}
+
+#[test]
+fn unknown_config_keys_warn() {
+    let work_dir = write_config(
+        "pdf-render-config-unknown",
+        "target_width = 50\ncolour = \"red\"\n",
+    );
+
+    let output = render_in(&work_dir, &[]);
+
+    assert_success(&output, "pdf render with unknown key");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("unknown key `colour` ignored"), "{stderr}");
+    assert_eq!(page_width(&work_dir), 50);
+}
+
+#[test]
+fn invalid_config_is_an_argument_error() {
+    let work_dir = write_config("pdf-render-config-invalid", "target_width = \"wide\"\n");
+
+    let output = render_in(&work_dir, &[]);
+
+    assert_eq!(output.status.code(), Some(1));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("pdf.toml"), "{stderr}");
+}
+
+/// A work directory holding `pdf.toml` and a 200x100pt `doc.pdf`.
+fn write_config(prefix: &str, config: &str) -> PathBuf {
+    let work_dir = create_temp_dir(prefix);
+    fs::write(work_dir.join("pdf.toml"), config).unwrap();
+    let mut pdf = PdfBuilder::new();
+    pdf.add_page("/MediaBox [0 0 200 100]", b"");
+    fs::write(work_dir.join("doc.pdf"), pdf.finish()).unwrap();
+    work_dir
+}
+
+fn render_in(work_dir: &Path, args: &[&str]) -> Output {
+    // Keep a user-level pdf.toml out of the test.
+    let config_home = work_dir.join("config-home");
+    fs::create_dir_all(&config_home).unwrap();
+    render_command(Path::new("doc.pdf"), Path::new("out"))
+        .current_dir(work_dir)
+        .env("XDG_CONFIG_HOME", config_home)
+        .arg("--workers")
+        .arg("1")
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+fn page_width(work_dir: &Path) -> u32 {
+    image::open(work_dir.join("out/page-0001.jpg"))
+        .unwrap()
+        .width()
+}
@@ -0,0 +1,60 @@
+mod common;
+
+use common::{PdfBuilder, assert_success, create_temp_dir, render_command};
+use pdf::Background;
+use pdf::deskew::skew_angle;
+use std::fs;
+use std::path::Path;
+
+#[test]
+fn deskew_straightens_a_tilted_page() {
+    let temp_dir = create_temp_dir("pdf-render-deskew");
+    let pdf_path = temp_dir.join("tilted.pdf");
+    fs::write(&pdf_path, tilted_pdf()).unwrap();
+
+    let plain_dir = temp_dir.join("plain");
+    let output = render(&pdf_path, &plain_dir, &[]);
+    assert_success(&output, "pdf render");
+    let deskewed_dir = temp_dir.join("deskewed");
+    let output = render(&pdf_path, &deskewed_dir, &["--deskew"]);
+    assert_success(&output, "pdf render --deskew");
+
+    let plain = image::open(plain_dir.join("page-0001.png")).unwrap();
+    let deskewed = image::open(deskewed_dir.join("page-0001.png")).unwrap();
+    // Turned 3 degrees counterclockwise in PDF space, the bars rise to the
+    // right in the image.
+    let before = skew_angle(&plain, Background::WHITE);
+    let after = skew_angle(&deskewed, Background::WHITE);
+    assert!(
+        (before + 3.0).abs() < 0.3,
+        "measured {before} before --deskew"
+    );
+    assert!(after.abs() < 0.3, "measured {after} after --deskew");
+    assert_eq!(
+        (deskewed.width(), deskewed.height()),
+        (plain.width(), plain.height())
+    );
+}
+
+fn render(pdf_path: &Path, output_dir: &Path, args: &[&str]) -> std::process::Output {
+    render_command(pdf_path, output_dir)
+        .arg("--format")
+        .arg("png")
+        .arg("--target-width")
+        .arg("400")
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+/// Eleven bars, like lines of text, rotated 3 degrees about the page center.
+fn tilted_pdf() -> Vec<u8> {
+    let (sin, cos) = 3f32.to_radians().sin_cos();
+    let mut content = format!("1 0 0 1 200 150 cm {cos} {sin} {} {cos} 0 0 cm\n", -sin);
+    for line in -5..=5 {
+        content.push_str(&format!("-150 {} 300 6 re f\n", line * 20));
+    }
+    let mut pdf = PdfBuilder::new();
+    pdf.add_page("/MediaBox [0 0 400 300]", content.as_bytes());
+    pdf.finish()
+}
@@ -0,0 +1,72 @@
+mod common;
+
+use common::{PdfBuilder, assert_success, create_temp_dir, json_data, pdf_command};
+use std::fs;
+
+#[test]
+fn dry_run_reports_worker_ranges_and_outputs() {
+    let temp_dir = create_temp_dir("pdf-render-dry-run");
+    let pdf_path = temp_dir.join("ten.pdf");
+    let mut pdf = PdfBuilder::new();
+    for _ in 0..10 {
+        pdf.add_page("/MediaBox [0 0 100 100]", b"");
+    }
+    fs::write(&pdf_path, pdf.finish()).unwrap();
+    let output_dir = temp_dir.join("out");
+
+    let output = pdf_command()
+        .arg("render")
+        .arg(&pdf_path)
+        .arg("-o")
+        .arg(&output_dir)
+        .arg("--workers")
+        .arg("3")
+        .arg("--dry-run")
+        .output()
+        .unwrap();
+
+    assert_success(&output, "pdf render --dry-run");
+    let plan: serde_json::Value = json_data(&output.stdout);
+    assert_eq!(plan["page_count"], 10);
+    assert_eq!(plan["workers_used"], 3);
+    assert_eq!(
+        plan["worker_pages"],
+        serde_json::json!(["1-4", "5-7", "8-10"])
+    );
+    let files = plan["output_files"].as_array().unwrap();
+    assert_eq!(files.len(), 10);
+    assert_eq!(
+        files[9],
+        output_dir.join("page-0010.jpg").display().to_string()
+    );
+    assert!(!output_dir.exists());
+}
+
+#[test]
+fn dry_run_follows_page_selection() {
+    let temp_dir = create_temp_dir("pdf-render-dry-run-pages");
+    let pdf_path = temp_dir.join("ten.pdf");
+    let mut pdf = PdfBuilder::new();
+    for _ in 0..10 {
+        pdf.add_page("/MediaBox [0 0 100 100]", b"");
+    }
+    fs::write(&pdf_path, pdf.finish()).unwrap();
+
+    let output = pdf_command()
+        .arg("render")
+        .arg(&pdf_path)
+        .arg("-o")
+        .arg(temp_dir.join("out"))
+        .arg("--pages")
+        .arg("odd")
+        .arg("--workers")
+        .arg("2")
+        .arg("--dry-run")
+        .output()
+        .unwrap();
+
+    assert_success(&output, "pdf render --pages odd --dry-run");
+    let plan: serde_json::Value = json_data(&output.stdout);
+    assert_eq!(plan["page_count"], 5);
+    assert_eq!(plan["worker_pages"], serde_json::json!(["1,3,5", "7,9"]));
+}
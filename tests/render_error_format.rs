@@ -0,0 +1,56 @@
+mod common;
+
+use common::{PdfBuilder, assert_success, create_temp_dir, json_data, pdf_command};
+use std::fs;
+use std::path::PathBuf;
+
+#[test]
+fn json_error_format_lists_failed_pages_in_summary() {
+    let (pdf_path, output_dir) = write_blank_pdf("pdf-render-error-format");
+
+    let output = pdf_command()
+        .arg("render")
+        .arg(&pdf_path)
+        .arg("-o")
+        .arg(&output_dir)
+        .arg("--target-width")
+        .arg("40")
+        .arg("--error-format")
+        .arg("json")
+        .output()
+        .unwrap();
+    assert_success(&output, "pdf render --error-format json");
+
+    let summary: serde_json::Value = json_data(&output.stdout);
+    assert_eq!(summary["pages_rendered"], 1);
+    assert_eq!(summary["failed_pages"], serde_json::json!([]));
+    assert_eq!(summary["worker_errors"], serde_json::json!([]));
+}
+
+#[test]
+fn text_error_format_omits_failed_pages() {
+    let (pdf_path, output_dir) = write_blank_pdf("pdf-render-error-text");
+
+    let output = pdf_command()
+        .arg("render")
+        .arg(&pdf_path)
+        .arg("-o")
+        .arg(&output_dir)
+        .arg("--target-width")
+        .arg("40")
+        .output()
+        .unwrap();
+    assert_success(&output, "pdf render");
+
+    let summary: serde_json::Value = json_data(&output.stdout);
+    assert!(summary.get("failed_pages").is_none());
+}
+
+fn write_blank_pdf(prefix: &str) -> (PathBuf, PathBuf) {
+    let temp_dir = create_temp_dir(prefix);
+    let pdf_path = temp_dir.join("blank.pdf");
+    let mut pdf = PdfBuilder::new();
+    pdf.add_page("/MediaBox [0 0 100 100]", b"");
+    fs::write(&pdf_path, pdf.finish()).unwrap();
+    (pdf_path, temp_dir.join("out"))
+}
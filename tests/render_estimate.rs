@@ -0,0 +1,65 @@
+mod common;
+
+use common::{PdfBuilder, assert_success, create_temp_dir, json_data, pdf_command};
+use std::fs;
+
+#[test]
+fn estimate_renders_only_sample_pages() {
+    let temp_dir = create_temp_dir("pdf-render-estimate");
+    let pdf_path = temp_dir.join("ten.pdf");
+    let mut pdf = PdfBuilder::new();
+    for _ in 0..10 {
+        pdf.add_page("/MediaBox [0 0 100 150]", b"0 0 1 rg 10 10 50 80 re f\n");
+    }
+    fs::write(&pdf_path, pdf.finish()).unwrap();
+    let output_dir = temp_dir.join("out");
+
+    let output = pdf_command()
+        .arg("render")
+        .arg(&pdf_path)
+        .arg("-o")
+        .arg(&output_dir)
+        .arg("--target-width")
+        .arg("200")
+        .arg("--estimate")
+        .output()
+        .unwrap();
+    assert_success(&output, "pdf render --estimate");
+
+    let estimate: serde_json::Value = json_data(&output.stdout);
+    assert_eq!(estimate["page_count"], 10);
+    assert_eq!(estimate["sample_pages"], serde_json::json!([1, 5, 10]));
+    let sample_bytes: Vec<u64> = estimate["sample_bytes"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|bytes| bytes.as_u64().unwrap())
+        .collect();
+    assert_eq!(sample_bytes.len(), 3);
+    assert!(sample_bytes.iter().all(|&bytes| bytes > 0));
+    let total = estimate["estimated_total_bytes"].as_u64().unwrap();
+    let mean = sample_bytes.iter().sum::<u64>() as f64 / 3.0;
+    assert_eq!(total, (mean * 10.0).round() as u64);
+    assert!(!output_dir.exists(), "--estimate must not write to -o");
+}
+
+#[test]
+fn estimate_does_not_need_an_output_directory() {
+    let temp_dir = create_temp_dir("pdf-render-estimate-no-output");
+    let pdf_path = temp_dir.join("two.pdf");
+    let mut pdf = PdfBuilder::new();
+    pdf.add_page("/MediaBox [0 0 100 100]", b"");
+    pdf.add_page("/MediaBox [0 0 100 100]", b"");
+    fs::write(&pdf_path, pdf.finish()).unwrap();
+
+    let output = pdf_command()
+        .arg("render")
+        .arg(&pdf_path)
+        .arg("--estimate")
+        .output()
+        .unwrap();
+    assert_success(&output, "pdf render --estimate without -o");
+
+    let estimate: serde_json::Value = json_data(&output.stdout);
+    assert_eq!(estimate["sample_pages"], serde_json::json!([1, 2]));
+}
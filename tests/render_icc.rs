@@ -0,0 +1,80 @@
+mod common;
+
+use common::{PdfBuilder, assert_success, create_temp_dir, render_command};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Output;
+
+const PROFILE: &[u8] = b"not a real profile, embedded verbatim";
+
+#[test]
+fn icc_file_is_embedded_in_every_jpeg() {
+    let temp_dir = write_two_page_pdf("pdf-render-icc");
+    let profile_path = temp_dir.join("vendor.icc");
+    fs::write(&profile_path, PROFILE).unwrap();
+
+    // Two workers, so the profile also has to reach the worker processes.
+    let output = render(&temp_dir, &["--workers", "2", "--icc"], Some(&profile_path));
+    assert_success(&output, "pdf render --icc");
+
+    for page in 1..=2 {
+        let jpeg = fs::read(temp_dir.join(format!("out/page-{page:04}.jpg"))).unwrap();
+        let mut segment = vec![0xFF, 0xE2];
+        segment.extend_from_slice(&(2u16 + 14 + PROFILE.len() as u16).to_be_bytes());
+        segment.extend_from_slice(b"ICC_PROFILE\0\x01\x01");
+        segment.extend_from_slice(PROFILE);
+        assert!(
+            contains(&jpeg, &segment),
+            "page {page} lacks the APP2 profile"
+        );
+        image::load_from_memory(&jpeg).unwrap();
+    }
+}
+
+#[test]
+fn srgb_profile_is_embedded_in_png() {
+    let temp_dir = write_two_page_pdf("pdf-render-srgb");
+
+    let output = render(&temp_dir, &["--format", "png", "--srgb"], None);
+    assert_success(&output, "pdf render --srgb");
+
+    let png = fs::read(temp_dir.join("out/page-0001.png")).unwrap();
+    assert!(contains(&png, b"iCCP"));
+}
+
+#[test]
+fn missing_or_empty_icc_file_is_an_argument_error() {
+    let temp_dir = write_two_page_pdf("pdf-render-icc-invalid");
+    let empty = temp_dir.join("empty.icc");
+    fs::write(&empty, b"").unwrap();
+
+    for profile in [temp_dir.join("missing.icc"), empty] {
+        let output = render(&temp_dir, &["--icc"], Some(&profile));
+        assert_eq!(output.status.code(), Some(1), "{}", profile.display());
+        assert!(!temp_dir.join("out").exists());
+    }
+}
+
+fn render(temp_dir: &Path, args: &[&str], profile: Option<&Path>) -> Output {
+    let mut command = render_command(&temp_dir.join("doc.pdf"), &temp_dir.join("out"));
+    command.args(args);
+    if let Some(profile) = profile {
+        command.arg(profile);
+    }
+    command.output().unwrap()
+}
+
+fn contains(haystack: &[u8], needle: &[u8]) -> bool {
+    haystack
+        .windows(needle.len())
+        .any(|window| window == needle)
+}
+
+fn write_two_page_pdf(prefix: &str) -> PathBuf {
+    let temp_dir = create_temp_dir(prefix);
+    let mut pdf = PdfBuilder::new();
+    pdf.add_page("/MediaBox [0 0 40 30]", b"");
+    pdf.add_page("/MediaBox [0 0 40 30]", b"");
+    fs::write(temp_dir.join("doc.pdf"), pdf.finish()).unwrap();
+    temp_dir
+}
@@ -0,0 +1,79 @@
+mod common;
+
+use common::{PdfBuilder, assert_success, create_temp_dir, render_command};
+use image::ImageDecoder;
+use image::codecs::jpeg::JpegDecoder;
+use image::metadata::Orientation;
+use std::fs;
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
+
+#[test]
+fn dpi_is_written_to_jfif_density() {
+    let (temp_dir, pdf_path) = write_pdf("pdf-render-jfif-dpi");
+
+    let jpeg = render(&pdf_path, &temp_dir.join("out"), &["--dpi", "150"]);
+
+    assert_eq!(jfif_density(&jpeg), (1, 150, 150));
+}
+
+#[test]
+fn target_width_density_is_the_effective_dpi() {
+    let (temp_dir, pdf_path) = write_pdf("pdf-render-jfif-width");
+
+    // 100pt wide at 200px is 2px per point: 144 dpi.
+    let jpeg = render(&pdf_path, &temp_dir.join("out"), &["--target-width", "200"]);
+
+    assert_eq!(jfif_density(&jpeg), (1, 144, 144));
+}
+
+#[test]
+fn exif_orientation_stores_the_page_unrotated() {
+    let (temp_dir, pdf_path) = write_pdf("pdf-render-exif-orientation");
+
+    let jpeg = render(
+        &pdf_path,
+        &temp_dir.join("out"),
+        &[
+            "--target-width",
+            "300",
+            "--rotate",
+            "90",
+            "--exif-orientation",
+        ],
+    );
+
+    let mut decoder = JpegDecoder::new(Cursor::new(jpeg.as_slice())).unwrap();
+    // Displayed 300px wide once turned, so stored 200x300 as the page is.
+    assert_eq!(decoder.dimensions(), (200, 300));
+    assert_eq!(decoder.orientation().unwrap(), Orientation::Rotate90);
+}
+
+/// `(units, x density, y density)` from the JFIF APP0 segment.
+fn jfif_density(jpeg: &[u8]) -> (u8, u16, u16) {
+    assert_eq!(&jpeg[..4], &[0xFF, 0xD8, 0xFF, 0xE0]);
+    assert_eq!(&jpeg[6..11], b"JFIF\0");
+    (
+        jpeg[13],
+        u16::from_be_bytes([jpeg[14], jpeg[15]]),
+        u16::from_be_bytes([jpeg[16], jpeg[17]]),
+    )
+}
+
+fn render(pdf_path: &Path, output_dir: &Path, args: &[&str]) -> Vec<u8> {
+    let output = render_command(pdf_path, output_dir)
+        .args(args)
+        .output()
+        .unwrap();
+    assert_success(&output, "pdf render");
+    fs::read(output_dir.join("page-0001.jpg")).unwrap()
+}
+
+fn write_pdf(prefix: &str) -> (PathBuf, PathBuf) {
+    let temp_dir = create_temp_dir(prefix);
+    let pdf_path = temp_dir.join("page.pdf");
+    let mut pdf = PdfBuilder::new();
+    pdf.add_page("/MediaBox [0 0 100 150]", b"0 0 1 rg 10 10 30 60 re f\n");
+    fs::write(&pdf_path, pdf.finish()).unwrap();
+    (temp_dir, pdf_path)
+}
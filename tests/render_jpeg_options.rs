@@ -0,0 +1,93 @@
+mod common;
+
+use common::{PdfBuilder, assert_success, create_temp_dir, render_command};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+#[test]
+fn subsampling_420_halves_chroma() {
+    let (pdf_path, output_dir) = write_color_pdf("pdf-render-subsampling");
+
+    let output = render(&pdf_path, &output_dir, &["--subsampling", "420"]);
+    assert_success(&output, "pdf render --subsampling 420");
+
+    let jpeg = fs::read(output_dir.join("page-0001.jpg")).unwrap();
+    let sof = find_marker(&jpeg, 0xC0).expect("baseline SOF0 marker");
+    assert_eq!(jpeg[sof + 11], 0x22, "luma sampling factors");
+}
+
+#[test]
+fn default_output_is_baseline() {
+    let (pdf_path, output_dir) = write_color_pdf("pdf-render-baseline");
+
+    let output = render(&pdf_path, &output_dir, &[]);
+    assert_success(&output, "pdf render");
+
+    let jpeg = fs::read(output_dir.join("page-0001.jpg")).unwrap();
+    assert!(find_marker(&jpeg, 0xC0).is_some());
+    assert!(find_marker(&jpeg, 0xC2).is_none());
+}
+
+#[test]
+fn progressive_output_has_sof2_marker() {
+    let (pdf_path, output_dir) = write_color_pdf("pdf-render-progressive");
+
+    let output = render(
+        &pdf_path,
+        &output_dir,
+        &["--progressive", "--subsampling", "420"],
+    );
+    assert_success(&output, "pdf render --progressive");
+
+    let jpeg = fs::read(output_dir.join("page-0001.jpg")).unwrap();
+    let sof = find_marker(&jpeg, 0xC2).expect("progressive SOF2 marker");
+    assert_eq!(jpeg[sof + 11], 0x22, "luma sampling factors");
+    assert!(find_marker(&jpeg, 0xC0).is_none());
+}
+
+#[test]
+fn unknown_subsampling_is_rejected() {
+    let (pdf_path, output_dir) = write_color_pdf("pdf-render-subsampling-bad");
+
+    let output = render(&pdf_path, &output_dir, &["--subsampling", "411"]);
+
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("411"), "{stderr}");
+}
+
+fn render(pdf_path: &Path, output_dir: &Path, args: &[&str]) -> std::process::Output {
+    render_command(pdf_path, output_dir)
+        .arg("--workers")
+        .arg("1")
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+fn write_color_pdf(prefix: &str) -> (PathBuf, PathBuf) {
+    let temp_dir = create_temp_dir(prefix);
+    let pdf_path = temp_dir.join("color.pdf");
+    let mut pdf = PdfBuilder::new();
+    pdf.add_page(
+        "/MediaBox [0 0 64 64]",
+        b"0.9 0.2 0.1 rg 0 0 32 64 re f 0.1 0.3 0.9 rg 32 0 32 64 re f",
+    );
+    fs::write(&pdf_path, pdf.finish()).unwrap();
+    (pdf_path, temp_dir.join("out"))
+}
+
+/// Offset of the first `FF <marker>` segment before the scan data.
+fn find_marker(jpeg: &[u8], marker: u8) -> Option<usize> {
+    let mut i = 2;
+    while i + 3 < jpeg.len() && jpeg[i] == 0xFF {
+        if jpeg[i + 1] == marker {
+            return Some(i);
+        }
+        if jpeg[i + 1] == 0xDA {
+            return None;
+        }
+        i += 2 + u16::from_be_bytes([jpeg[i + 2], jpeg[i + 3]]) as usize;
+    }
+    None
+}
@@ -0,0 +1,148 @@
+mod common;
+
+use common::{PdfBuilder, assert_success, create_temp_dir, json_data, pdf_command, render_command};
+use image::RgbImage;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const RED: [u8; 3] = [255, 0, 0];
+const BLUE: [u8; 3] = [0, 0, 255];
+const WHITE: [u8; 3] = [255, 255, 255];
+
+#[test]
+fn info_lists_layer_names() {
+    let (_, pdf_path) = write_pdf("pdf-info-layers");
+
+    let output = pdf_command()
+        .arg("info")
+        .arg(&pdf_path)
+        .arg("--layers")
+        .output()
+        .unwrap();
+    assert_success(&output, "pdf info --layers");
+
+    let info: serde_json::Value = json_data(&output.stdout);
+    assert_eq!(
+        info,
+        serde_json::json!({ "layers": ["Dimensions", "Notes"] })
+    );
+}
+
+#[test]
+fn layers_follow_the_default_configuration() {
+    let (temp_dir, pdf_path) = write_pdf("pdf-render-layers-default");
+
+    let page = render(&pdf_path, &temp_dir.join("out"), &[]).0;
+
+    assert_eq!(page.get_pixel(25, 50).0, RED);
+    assert_eq!(page.get_pixel(75, 50).0, WHITE);
+}
+
+#[test]
+fn layers_off_hides_a_visible_layer() {
+    let (temp_dir, pdf_path) = write_pdf("pdf-render-layers-off");
+
+    let page = render(
+        &pdf_path,
+        &temp_dir.join("out"),
+        &["--layers-off", "Dimensions"],
+    )
+    .0;
+
+    assert_eq!(page.get_pixel(25, 50).0, WHITE);
+    assert_eq!(page.get_pixel(75, 50).0, WHITE);
+}
+
+#[test]
+fn layers_on_shows_a_hidden_layer_in_every_worker() {
+    let (temp_dir, pdf_path) = write_pdf("pdf-render-layers-on");
+    let output_dir = temp_dir.join("out");
+
+    render(
+        &pdf_path,
+        &output_dir,
+        &["--layers-on", "Notes", "--workers", "2"],
+    );
+
+    for page in ["page-0001.png", "page-0002.png"] {
+        let page = image::open(output_dir.join(page)).unwrap().to_rgb8();
+        assert_eq!(page.get_pixel(25, 50).0, RED);
+        assert_eq!(page.get_pixel(75, 50).0, BLUE);
+    }
+}
+
+#[test]
+fn unknown_layer_names_warn() {
+    let (temp_dir, pdf_path) = write_pdf("pdf-render-layers-unknown");
+
+    let (page, stderr) = render(
+        &pdf_path,
+        &temp_dir.join("out"),
+        &["--layers-off", "Dimensions,Ghost"],
+    );
+
+    assert!(stderr.contains("no layer named \"Ghost\""), "{stderr}");
+    assert!(!stderr.contains("\"Dimensions\""), "{stderr}");
+    assert_eq!(page.get_pixel(25, 50).0, WHITE);
+}
+
+#[test]
+fn a_layer_cannot_be_hidden_and_shown() {
+    let (temp_dir, pdf_path) = write_pdf("pdf-render-layers-conflict");
+
+    let output = pdf_command()
+        .arg("render")
+        .arg(&pdf_path)
+        .arg("-o")
+        .arg(temp_dir.join("out"))
+        .arg("--layers-off")
+        .arg("Notes")
+        .arg("--layers-on")
+        .arg("Notes")
+        .output()
+        .unwrap();
+
+    assert_eq!(output.status.code(), Some(1));
+}
+
+/// The first page, and stderr.
+fn render(pdf_path: &Path, output_dir: &Path, args: &[&str]) -> (RgbImage, String) {
+    let output = render_command(pdf_path, output_dir)
+        .arg("--format")
+        .arg("png")
+        .args(args)
+        .output()
+        .unwrap();
+    assert_success(&output, "pdf render");
+    let page = image::open(output_dir.join("page-0001.png"))
+        .unwrap()
+        .to_rgb8();
+    (page, String::from_utf8_lossy(&output.stderr).into_owned())
+}
+
+/// Two 100x100pt pages, each with a red rectangle over the left half in the
+/// "Dimensions" layer (on by default) and a blue one over the right half in
+/// the "Notes" layer (off by default).
+fn write_pdf(prefix: &str) -> (PathBuf, PathBuf) {
+    let temp_dir = create_temp_dir(prefix);
+    let pdf_path = temp_dir.join("layers.pdf");
+    let mut pdf = PdfBuilder::new();
+    let dimensions = pdf.add_object("<< /Type /OCG /Name (Dimensions) >>");
+    let notes = pdf.add_object("<< /Type /OCG /Name (Notes) >>");
+    let content = b"/OC /L1 BDC 1 0 0 rg 0 0 50 100 re f EMC\n\
+                    /OC /L2 BDC 0 0 1 rg 50 0 50 100 re f EMC";
+    for _ in 0..2 {
+        pdf.add_page(
+            &format!(
+                "/MediaBox [0 0 100 100] \
+                 /Resources << /Properties << /L1 {dimensions} 0 R /L2 {notes} 0 R >> >>"
+            ),
+            content,
+        );
+    }
+    pdf.add_catalog_entries(&format!(
+        "/OCProperties << /OCGs [{dimensions} 0 R {notes} 0 R] /D << /OFF [{notes} 0 R] >> >>"
+    ));
+    fs::write(&pdf_path, pdf.finish()).unwrap();
+    (temp_dir, pdf_path)
+}
@@ -0,0 +1,101 @@
+mod common;
+
+use common::{PdfBuilder, assert_success, create_temp_dir, json_data, render_command};
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+#[test]
+fn limit_caps_the_selected_pages() {
+    let (temp_dir, pdf_path) = write_pdf("pdf-render-limit");
+    let output_dir = temp_dir.join("out");
+
+    let output = render(
+        &pdf_path,
+        &output_dir,
+        &["--pages", "1-100", "--limit", "10"],
+    );
+    assert_success(&output, "pdf render --limit 10");
+
+    let summary: serde_json::Value = json_data(&output.stdout);
+    assert_eq!(summary["pages_rendered"], 10);
+    assert_eq!(summary["pages_truncated"], 90);
+    let expected: BTreeSet<_> = (1..=10).map(|page| format!("page-{page:04}.jpg")).collect();
+    assert_eq!(files(&output_dir), expected);
+}
+
+#[test]
+fn limit_with_keep_order_samples_in_request_order() {
+    let (temp_dir, pdf_path) = write_pdf("pdf-render-limit-keep-order");
+    let output_dir = temp_dir.join("out");
+
+    let output = render(
+        &pdf_path,
+        &output_dir,
+        &["--pages", "50,3,20,7", "--keep-order", "--limit", "2"],
+    );
+    assert_success(&output, "pdf render --keep-order --limit 2");
+
+    let summary: serde_json::Value = json_data(&output.stdout);
+    assert_eq!(summary["pages_rendered"], 2);
+    assert_eq!(summary["pages_truncated"], 2);
+    assert_eq!(
+        files(&output_dir),
+        BTreeSet::from([
+            "0001-page-0050.jpg".to_string(),
+            "0002-page-0003.jpg".to_string()
+        ])
+    );
+}
+
+#[test]
+fn limit_above_the_selection_renders_everything() {
+    let (temp_dir, pdf_path) = write_pdf("pdf-render-limit-above");
+
+    let output = render(
+        &pdf_path,
+        &temp_dir.join("out"),
+        &["--pages", "1-5", "--limit", "10"],
+    );
+    assert_success(&output, "pdf render --limit 10");
+
+    let summary: serde_json::Value = json_data(&output.stdout);
+    assert_eq!(summary["pages_rendered"], 5);
+    assert!(summary.get("pages_truncated").is_none());
+}
+
+#[test]
+fn zero_limit_is_rejected() {
+    let (temp_dir, pdf_path) = write_pdf("pdf-render-limit-zero");
+
+    let output = render(&pdf_path, &temp_dir.join("out"), &["--limit", "0"]);
+
+    assert_eq!(output.status.code(), Some(1));
+}
+
+fn render(pdf_path: &Path, output_dir: &Path, args: &[&str]) -> std::process::Output {
+    render_command(pdf_path, output_dir)
+        .arg("--target-width")
+        .arg("20")
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+fn files(output_dir: &Path) -> BTreeSet<String> {
+    fs::read_dir(output_dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+        .collect()
+}
+
+fn write_pdf(prefix: &str) -> (PathBuf, PathBuf) {
+    let temp_dir = create_temp_dir(prefix);
+    let pdf_path = temp_dir.join("hundred.pdf");
+    let mut pdf = PdfBuilder::new();
+    for _ in 0..100 {
+        pdf.add_page("/MediaBox [0 0 100 150]", b"");
+    }
+    fs::write(&pdf_path, pdf.finish()).unwrap();
+    (temp_dir, pdf_path)
+}
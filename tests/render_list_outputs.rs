@@ -0,0 +1,90 @@
+mod common;
+
+use common::{PdfBuilder, assert_success, create_temp_dir, json_data, render_command};
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+#[test]
+fn listed_outputs_match_the_files_written() {
+    let (temp_dir, pdf_path) = write_pdf("pdf-render-list-outputs");
+    let output_dir = temp_dir.join("out");
+    // A leftover from an earlier run is not part of this render.
+    fs::create_dir(&output_dir).unwrap();
+    fs::write(output_dir.join("stale.jpg"), b"old").unwrap();
+
+    let summary = render(
+        &pdf_path,
+        &output_dir,
+        &["--workers", "2", "--list-outputs"],
+    );
+
+    let listed: Vec<PathBuf> = summary["output_files"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|file| PathBuf::from(file.as_str().unwrap()))
+        .collect();
+    let expected: Vec<PathBuf> = (1..=5)
+        .map(|page| output_dir.join(format!("page-{page:04}.jpg")))
+        .collect();
+    assert_eq!(listed, expected);
+    let on_disk: BTreeSet<PathBuf> = fs::read_dir(&output_dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.file_name().unwrap() != "stale.jpg")
+        .collect();
+    assert_eq!(on_disk, listed.into_iter().collect());
+}
+
+#[test]
+fn listed_outputs_follow_keep_order() {
+    let (temp_dir, pdf_path) = write_pdf("pdf-render-list-outputs-keep-order");
+    let output_dir = temp_dir.join("out");
+
+    let summary = render(
+        &pdf_path,
+        &output_dir,
+        &["--pages", "4,2", "--keep-order", "--list-outputs"],
+    );
+
+    let output_dir = output_dir.display();
+    assert_eq!(
+        summary["output_files"],
+        serde_json::json!([
+            format!("{output_dir}/0001-page-0004.jpg"),
+            format!("{output_dir}/0002-page-0002.jpg")
+        ])
+    );
+}
+
+#[test]
+fn outputs_are_not_listed_by_default() {
+    let (temp_dir, pdf_path) = write_pdf("pdf-render-list-outputs-default");
+
+    let summary = render(&pdf_path, &temp_dir.join("out"), &[]);
+
+    assert!(summary.get("output_files").is_none());
+}
+
+fn render(pdf_path: &Path, output_dir: &Path, args: &[&str]) -> serde_json::Value {
+    let output = render_command(pdf_path, output_dir)
+        .arg("--target-width")
+        .arg("20")
+        .args(args)
+        .output()
+        .unwrap();
+    assert_success(&output, "pdf render");
+    json_data(&output.stdout)
+}
+
+fn write_pdf(prefix: &str) -> (PathBuf, PathBuf) {
+    let temp_dir = create_temp_dir(prefix);
+    let pdf_path = temp_dir.join("five.pdf");
+    let mut pdf = PdfBuilder::new();
+    for _ in 0..5 {
+        pdf.add_page("/MediaBox [0 0 100 150]", b"");
+    }
+    fs::write(&pdf_path, pdf.finish()).unwrap();
+    (temp_dir, pdf_path)
+}
@@ -0,0 +1,140 @@
+mod common;
+
+use common::{PdfBuilder, assert_success, create_temp_dir, json_data, pdf_command};
+use image::codecs::jpeg::JpegEncoder;
+use image::{Rgb, RgbImage};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::Path;
+
+const IMAGE_WIDTH: u32 = 64;
+const IMAGE_HEIGHT: u32 = 96;
+
+#[test]
+fn manifest_matches_produced_files() {
+    let temp_dir = create_temp_dir("pdf-render-manifest");
+    let pdf_path = temp_dir.join("mixed.pdf");
+    fs::write(&pdf_path, build_mixed_pdf()).unwrap();
+    let output_dir = temp_dir.join("out");
+    let manifest_path = temp_dir.join("manifest.json");
+
+    let output = pdf_command()
+        .arg("render")
+        .arg(&pdf_path)
+        .arg("-o")
+        .arg(&output_dir)
+        .arg("--workers")
+        .arg("2")
+        .arg("--target-width")
+        .arg("120")
+        .arg("--extract-images")
+        .arg("--manifest")
+        .arg(&manifest_path)
+        .output()
+        .unwrap();
+    assert_success(&output, "pdf render --manifest");
+
+    let manifest: Vec<serde_json::Value> = json_data(&fs::read(&manifest_path).unwrap());
+    let pages: Vec<_> = manifest.iter().map(|entry| entry["page"].clone()).collect();
+    assert_eq!(pages, [1, 2, 3]);
+    let methods: Vec<_> = manifest
+        .iter()
+        .map(|entry| entry["method"].clone())
+        .collect();
+    assert_eq!(methods, ["extracted", "rendered", "extracted"]);
+
+    for entry in &manifest {
+        let file = Path::new(entry["file"].as_str().unwrap());
+        assert_eq!(file.parent(), Some(output_dir.as_path()));
+        assert_eq!(entry["bytes"], fs::metadata(file).unwrap().len());
+        let sha256 = format!("{:x}", Sha256::digest(fs::read(file).unwrap()));
+        assert_eq!(entry["sha256"], sha256, "{}", file.display());
+        let (width, height) = image::image_dimensions(file).unwrap();
+        assert_eq!(entry["width_px"], width, "{}", file.display());
+        assert_eq!(entry["height_px"], height, "{}", file.display());
+    }
+    assert_eq!(manifest[0]["width_px"], IMAGE_WIDTH);
+    assert_eq!(manifest[1]["width_px"], 120);
+}
+
+#[test]
+fn manifest_records_point_and_pixel_sizes_per_page() {
+    let temp_dir = create_temp_dir("pdf-render-manifest-sizes");
+    let pdf_path = temp_dir.join("sizes.pdf");
+    let mut pdf = PdfBuilder::new();
+    pdf.add_page("/MediaBox [0 0 72 144]", b"");
+    pdf.add_page("/MediaBox [0 0 216 36]", b"");
+    fs::write(&pdf_path, pdf.finish()).unwrap();
+    let output_dir = temp_dir.join("out");
+    let manifest_path = temp_dir.join("manifest.json");
+
+    let output = pdf_command()
+        .arg("render")
+        .arg(&pdf_path)
+        .arg("-o")
+        .arg(&output_dir)
+        .arg("--dpi")
+        .arg("144")
+        .arg("--manifest")
+        .arg(&manifest_path)
+        .output()
+        .unwrap();
+    assert_success(&output, "pdf render --dpi --manifest");
+
+    let manifest: Vec<serde_json::Value> = json_data(&fs::read(&manifest_path).unwrap());
+    let sizes: Vec<_> = manifest
+        .iter()
+        .map(|entry| (entry["width_pt"].as_f64(), entry["height_pt"].as_f64()))
+        .collect();
+    assert_eq!(
+        sizes,
+        [(Some(72.0), Some(144.0)), (Some(216.0), Some(36.0))]
+    );
+    for entry in &manifest {
+        let file = Path::new(entry["file"].as_str().unwrap());
+        let (width, height) = image::image_dimensions(file).unwrap();
+        assert_eq!(entry["width_px"], width, "{}", file.display());
+        assert_eq!(entry["height_px"], height, "{}", file.display());
+    }
+    // 144 dpi is two pixels per point.
+    assert_eq!(manifest[0]["width_px"], 144);
+    assert_eq!(manifest[1]["height_px"], 72);
+}
+
+fn encode_test_jpeg() -> Vec<u8> {
+    let image = RgbImage::from_fn(IMAGE_WIDTH, IMAGE_HEIGHT, |x, y| {
+        Rgb([(x * 4) as u8, (y * 2) as u8, 90])
+    });
+    let mut bytes = Vec::new();
+    image
+        .write_with_encoder(JpegEncoder::new_with_quality(&mut bytes, 90))
+        .unwrap();
+    bytes
+}
+
+/// Pages 1 and 3 are a single DCTDecode image (extractable); page 2 is vector.
+fn build_mixed_pdf() -> Vec<u8> {
+    let jpeg = encode_test_jpeg();
+    let mut pdf = PdfBuilder::new();
+    for page in 1..=3 {
+        if page == 2 {
+            pdf.add_page("/MediaBox [0 0 100 150]", b"0 0 0 rg 10 10 50 50 re f\n");
+            continue;
+        }
+        let image_id = pdf.add_stream(
+            &format!(
+                "/Type /XObject /Subtype /Image /Width {IMAGE_WIDTH} /Height {IMAGE_HEIGHT} \
+                 /ColorSpace /DeviceRGB /BitsPerComponent 8 /Filter /DCTDecode"
+            ),
+            &jpeg,
+        );
+        pdf.add_page(
+            &format!(
+                "/MediaBox [0 0 {IMAGE_WIDTH} {IMAGE_HEIGHT}] \
+                 /Resources << /XObject << /Im0 {image_id} 0 R >> >>"
+            ),
+            format!("q {IMAGE_WIDTH} 0 0 {IMAGE_HEIGHT} 0 0 cm /Im0 Do Q\n").as_bytes(),
+        );
+    }
+    pdf.finish()
+}
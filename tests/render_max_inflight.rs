@@ -0,0 +1,93 @@
+#![cfg(target_os = "linux")]
+
+mod common;
+
+use common::{PdfBuilder, assert_success, create_temp_dir, pdf_command};
+use std::fs;
+use std::path::PathBuf;
+use std::process::Stdio;
+use std::time::Duration;
+
+#[test]
+fn max_inflight_caps_running_workers() {
+    let (temp_dir, pdf_path) = write_pdf("pdf-render-max-inflight", 12);
+    let output_dir = temp_dir.join("out");
+
+    let mut child = pdf_command()
+        .arg("render")
+        .arg(&pdf_path)
+        .arg("-o")
+        .arg(&output_dir)
+        .args(["--format", "png", "--target-width", "2000"])
+        .args(["--workers", "6", "--max-inflight", "2"])
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+
+    let mut peak = 0;
+    while child.try_wait().unwrap().is_none() {
+        peak = peak.max(child_processes(child.id()));
+        std::thread::sleep(Duration::from_millis(2));
+    }
+    let output = child.wait_with_output().unwrap();
+    assert_success(&output, "pdf render --max-inflight 2");
+
+    assert!(peak <= 2, "{peak} workers ran at once");
+    assert!(peak >= 1, "no worker was seen running");
+    for page in 1..=12 {
+        assert!(output_dir.join(format!("page-{page:04}.png")).exists());
+    }
+}
+
+#[test]
+fn max_inflight_rejects_thread_mode() {
+    let (temp_dir, pdf_path) = write_pdf("pdf-render-max-inflight-thread", 2);
+
+    let output = pdf_command()
+        .arg("render")
+        .arg(&pdf_path)
+        .arg("-o")
+        .arg(temp_dir.join("out"))
+        .args(["--max-inflight", "1", "--mode", "thread"])
+        .output()
+        .unwrap();
+
+    assert_eq!(output.status.code(), Some(1));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("--max-inflight requires --mode process"),
+        "{stderr}"
+    );
+}
+
+/// Processes whose parent is `pid`, read from `/proc/<pid>/stat`.
+fn child_processes(pid: u32) -> usize {
+    let Ok(entries) = fs::read_dir("/proc") else {
+        return 0;
+    };
+    entries
+        .filter_map(|entry| fs::read_to_string(entry.ok()?.path().join("stat")).ok())
+        .filter(|stat| {
+            // The command name is parenthesized and may contain spaces; the
+            // parent pid is the second field after it.
+            stat.rsplit_once(')')
+                .and_then(|(_, rest)| rest.split_whitespace().nth(1)?.parse::<u32>().ok())
+                == Some(pid)
+        })
+        .count()
+}
+
+fn write_pdf(name: &str, pages: usize) -> (PathBuf, PathBuf) {
+    let temp_dir = create_temp_dir(name);
+    let pdf_path = temp_dir.join("doc.pdf");
+    let mut pdf = PdfBuilder::new();
+    for _ in 0..pages {
+        pdf.add_page(
+            "/MediaBox [0 0 100 100]",
+            b"0 0 1 rg 10 10 80 80 re f\n1 0 0 rg 30 30 40 40 re f\n",
+        );
+    }
+    fs::write(&pdf_path, pdf.finish()).unwrap();
+    (temp_dir, pdf_path)
+}
@@ -0,0 +1,42 @@
+mod common;
+
+use common::{PdfBuilder, assert_success, create_temp_dir, pdf_command};
+use std::fs;
+
+#[test]
+fn large_page_is_clamped_and_small_page_is_not() {
+    let temp_dir = create_temp_dir("pdf-render-max-size");
+    let pdf_path = temp_dir.join("drawings.pdf");
+    let mut pdf = PdfBuilder::new();
+    pdf.add_page("/MediaBox [0 0 2000 1000]", b"");
+    pdf.add_page("/MediaBox [0 0 100 50]", b"");
+    fs::write(&pdf_path, pdf.finish()).unwrap();
+    let output_dir = temp_dir.join("out");
+
+    let output = pdf_command()
+        .arg("render")
+        .arg(&pdf_path)
+        .arg("-o")
+        .arg(&output_dir)
+        .arg("--workers")
+        .arg("2")
+        .arg("--dpi")
+        .arg("144")
+        .arg("--max-width")
+        .arg("1000")
+        .arg("--max-height")
+        .arg("800")
+        .output()
+        .unwrap();
+    assert_success(&output, "pdf render --max-width --max-height");
+
+    // 4000x2000 at 144 DPI, shrunk to the 1000px width cap.
+    assert_eq!(
+        image::image_dimensions(output_dir.join("page-0001.jpg")).unwrap(),
+        (1000, 500)
+    );
+    assert_eq!(
+        image::image_dimensions(output_dir.join("page-0002.jpg")).unwrap(),
+        (200, 100)
+    );
+}
@@ -0,0 +1,41 @@
+mod common;
+
+use common::{PdfBuilder, assert_success, create_temp_dir, render_command};
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::Path;
+
+#[test]
+fn thread_mode_writes_the_same_files_as_process_mode() {
+    let temp_dir = create_temp_dir("pdf-render-modes");
+    let pdf_path = temp_dir.join("blank.pdf");
+    let mut pdf = PdfBuilder::new();
+    for _ in 0..6 {
+        pdf.add_page("/MediaBox [0 0 100 150]", b"");
+    }
+    fs::write(&pdf_path, pdf.finish()).unwrap();
+
+    let process_files = render_with_mode(&pdf_path, &temp_dir.join("process"), "process");
+    let thread_files = render_with_mode(&pdf_path, &temp_dir.join("thread"), "thread");
+
+    assert_eq!(process_files.len(), 6);
+    assert_eq!(process_files, thread_files);
+}
+
+fn render_with_mode(pdf_path: &Path, output_dir: &Path, mode: &str) -> BTreeSet<String> {
+    let output = render_command(pdf_path, output_dir)
+        .arg("--workers")
+        .arg("3")
+        .arg("--mode")
+        .arg(mode)
+        .arg("--target-width")
+        .arg("50")
+        .output()
+        .unwrap();
+    assert_success(&output, &format!("pdf render --mode {mode}"));
+
+    fs::read_dir(output_dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+        .collect()
+}
@@ -0,0 +1,124 @@
+mod common;
+
+use common::{PdfBuilder, assert_success, create_temp_dir, json_data, pdf_command};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+#[test]
+fn each_pdf_renders_into_its_own_subdirectory() {
+    let temp_dir = create_temp_dir("pdf-render-multiple");
+    let first = write_pdf(&temp_dir, "first.pdf", 2);
+    let second = write_pdf(&temp_dir, "second.pdf", 1);
+    let output_dir = temp_dir.join("out");
+
+    let output = pdf_command()
+        .arg("render")
+        .arg(&first)
+        .arg(&second)
+        .arg("-o")
+        .arg(&output_dir)
+        .args(["--target-width", "40", "--workers", "2"])
+        .output()
+        .unwrap();
+    assert_success(&output, "pdf render first.pdf second.pdf");
+
+    assert!(output_dir.join("first/page-0001.jpg").exists());
+    assert!(output_dir.join("first/page-0002.jpg").exists());
+    assert!(output_dir.join("second/page-0001.jpg").exists());
+    assert!(!output_dir.join("second/page-0002.jpg").exists());
+
+    let summary: serde_json::Value = json_data(&output.stdout);
+    assert_eq!(summary["pages_rendered"], 3);
+    assert_eq!(summary["failed"], 0);
+    let files = summary["files"].as_array().unwrap();
+    assert_eq!(files.len(), 2);
+    assert_eq!(files[0]["pdf"], first.display().to_string());
+    assert_eq!(files[0]["pages_rendered"], 2);
+    assert_eq!(
+        files[1]["output_dir"],
+        output_dir.join("second").display().to_string()
+    );
+    assert_eq!(files[1]["pages_rendered"], 1);
+}
+
+#[test]
+fn an_unreadable_pdf_does_not_stop_the_others() {
+    let temp_dir = create_temp_dir("pdf-render-multiple-broken");
+    let broken = temp_dir.join("broken.pdf");
+    fs::write(&broken, b"not a pdf").unwrap();
+    let good = write_pdf(&temp_dir, "good.pdf", 1);
+    let output_dir = temp_dir.join("out");
+
+    let output = pdf_command()
+        .arg("render")
+        .arg(&broken)
+        .arg(&good)
+        .arg("-o")
+        .arg(&output_dir)
+        .output()
+        .unwrap();
+
+    assert_eq!(output.status.code(), Some(6));
+    assert!(output_dir.join("good/page-0001.jpg").exists());
+    let summary: serde_json::Value = json_data(&output.stdout);
+    assert_eq!(summary["pages_rendered"], 1);
+    assert_eq!(summary["failed"], 1);
+    assert!(summary["files"][0]["error"].is_string());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains(&format!("error: {}:", broken.display())),
+        "{stderr}"
+    );
+}
+
+#[test]
+fn pdfs_with_the_same_stem_are_rejected() {
+    let temp_dir = create_temp_dir("pdf-render-multiple-clash");
+    fs::create_dir(temp_dir.join("a")).unwrap();
+    fs::create_dir(temp_dir.join("b")).unwrap();
+    let first = write_pdf(&temp_dir.join("a"), "report.pdf", 1);
+    let second = write_pdf(&temp_dir.join("b"), "report.pdf", 1);
+
+    let output = render_two(&first, &second, &temp_dir.join("out"), &[]);
+
+    assert_eq!(output.status.code(), Some(1));
+    assert!(!temp_dir.join("out").exists());
+}
+
+#[test]
+fn single_pdf_options_need_a_single_pdf() {
+    let temp_dir = create_temp_dir("pdf-render-multiple-single");
+    let first = write_pdf(&temp_dir, "first.pdf", 1);
+    let second = write_pdf(&temp_dir, "second.pdf", 1);
+
+    let output = render_two(&first, &second, &temp_dir.join("out"), &["--dry-run"]);
+
+    assert_eq!(output.status.code(), Some(1));
+}
+
+fn render_two(
+    first: &Path,
+    second: &Path,
+    output_dir: &Path,
+    args: &[&str],
+) -> std::process::Output {
+    pdf_command()
+        .arg("render")
+        .arg(first)
+        .arg(second)
+        .arg("-o")
+        .arg(output_dir)
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+fn write_pdf(dir: &Path, name: &str, pages: usize) -> PathBuf {
+    let pdf_path = dir.join(name);
+    let mut pdf = PdfBuilder::new();
+    for _ in 0..pages {
+        pdf.add_page("/MediaBox [0 0 100 100]", b"0 0 1 rg 10 10 80 80 re f\n");
+    }
+    fs::write(&pdf_path, pdf.finish()).unwrap();
+    pdf_path
+}
@@ -0,0 +1,73 @@
+mod common;
+
+use common::{PdfBuilder, assert_success, create_temp_dir, pdf_command, render_command};
+use image::RgbImage;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const RED: [u8; 3] = [255, 0, 0];
+const BLUE: [u8; 3] = [0, 0, 255];
+const WHITE: [u8; 3] = [255, 255, 255];
+
+#[test]
+fn no_images_keeps_vectors_and_drops_the_image() {
+    let (temp_dir, pdf_path) = write_pdf("pdf-render-no-images");
+
+    let with_images = render(&pdf_path, &temp_dir.join("with"), &[]);
+    let without_images = render(&pdf_path, &temp_dir.join("without"), &["--no-images"]);
+
+    // The image fills the left half; a vector rectangle the right half.
+    assert_eq!(with_images.get_pixel(25, 50).0, RED);
+    assert_eq!(without_images.get_pixel(25, 50).0, WHITE);
+    assert_eq!(with_images.get_pixel(75, 50).0, BLUE);
+    assert_eq!(without_images.get_pixel(75, 50).0, BLUE);
+}
+
+#[test]
+fn no_images_rejects_extract_images() {
+    let (temp_dir, pdf_path) = write_pdf("pdf-render-no-images-extract");
+
+    let output = pdf_command()
+        .arg("render")
+        .arg(&pdf_path)
+        .arg("-o")
+        .arg(temp_dir.join("out"))
+        .arg("--no-images")
+        .arg("--extract-images")
+        .output()
+        .unwrap();
+
+    assert!(!output.status.success());
+}
+
+fn render(pdf_path: &Path, output_dir: &Path, args: &[&str]) -> RgbImage {
+    let output = render_command(pdf_path, output_dir)
+        .arg("--format")
+        .arg("png")
+        .args(args)
+        .output()
+        .unwrap();
+    assert_success(&output, "pdf render");
+    image::open(output_dir.join("page-0001.png"))
+        .unwrap()
+        .to_rgb8()
+}
+
+/// A 100x100pt page: a 2x2 red image over the left half, a blue rectangle
+/// over the right half.
+fn write_pdf(prefix: &str) -> (PathBuf, PathBuf) {
+    let temp_dir = create_temp_dir(prefix);
+    let pdf_path = temp_dir.join("mixed.pdf");
+    let mut pdf = PdfBuilder::new();
+    let image_id = pdf.add_stream(
+        "/Type /XObject /Subtype /Image /Width 2 /Height 2 /ColorSpace /DeviceRGB \
+         /BitsPerComponent 8",
+        &RED.repeat(4),
+    );
+    pdf.add_page(
+        &format!("/MediaBox [0 0 100 100] /Resources << /XObject << /Im0 {image_id} 0 R >> >>"),
+        b"q 50 0 0 100 0 0 cm /Im0 Do Q 0 0 1 rg 50 0 50 100 re f\n",
+    );
+    fs::write(&pdf_path, pdf.finish()).unwrap();
+    (temp_dir, pdf_path)
+}
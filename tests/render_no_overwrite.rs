@@ -0,0 +1,65 @@
+mod common;
+
+use common::{PdfBuilder, assert_success, create_temp_dir, pdf_command};
+use std::fs;
+use std::path::PathBuf;
+
+#[test]
+fn existing_output_aborts_before_rendering() {
+    let (pdf_path, output_dir) = write_two_page_pdf("pdf-render-no-overwrite");
+    fs::create_dir(&output_dir).unwrap();
+    let existing = output_dir.join("page-0002.jpg");
+    fs::write(&existing, b"keep me").unwrap();
+
+    let output = pdf_command()
+        .arg("render")
+        .arg(&pdf_path)
+        .arg("-o")
+        .arg(&output_dir)
+        .arg("--target-width")
+        .arg("40")
+        .arg("--no-overwrite")
+        .output()
+        .unwrap();
+
+    assert_eq!(output.status.code(), Some(5));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains(&existing.display().to_string()), "{stderr}");
+    assert!(
+        output.stdout.is_empty(),
+        "no summary after a pre-start abort"
+    );
+    assert_eq!(fs::read(&existing).unwrap(), b"keep me");
+    assert!(!output_dir.join("page-0001.jpg").exists());
+}
+
+#[test]
+fn overwrite_remains_the_default() {
+    let (pdf_path, output_dir) = write_two_page_pdf("pdf-render-overwrite");
+    fs::create_dir(&output_dir).unwrap();
+    let existing = output_dir.join("page-0002.jpg");
+    fs::write(&existing, b"replace me").unwrap();
+
+    let output = pdf_command()
+        .arg("render")
+        .arg(&pdf_path)
+        .arg("-o")
+        .arg(&output_dir)
+        .arg("--target-width")
+        .arg("40")
+        .output()
+        .unwrap();
+    assert_success(&output, "pdf render over existing files");
+
+    assert_ne!(fs::read(&existing).unwrap(), b"replace me");
+}
+
+fn write_two_page_pdf(prefix: &str) -> (PathBuf, PathBuf) {
+    let temp_dir = create_temp_dir(prefix);
+    let pdf_path = temp_dir.join("doc.pdf");
+    let mut pdf = PdfBuilder::new();
+    pdf.add_page("/MediaBox [0 0 100 100]", b"");
+    pdf.add_page("/MediaBox [0 0 100 100]", b"");
+    fs::write(&pdf_path, pdf.finish()).unwrap();
+    (pdf_path, temp_dir.join("out"))
+}
@@ -0,0 +1,61 @@
+mod common;
+
+use common::{PdfBuilder, assert_success, create_temp_dir, json_data, render_command};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+#[test]
+fn small_pages_stay_at_native_size() {
+    let (pdf_path, temp_dir) = write_pdf("pdf-render-no-upscale");
+    let output_dir = temp_dir.join("out");
+
+    let output = render(&pdf_path, &output_dir, &["--no-upscale", "--workers", "2"]);
+    assert_success(&output, "pdf render --no-upscale");
+
+    // The 50x40pt page would be blown up eightfold; the 800x600pt page still
+    // scales down to the target.
+    assert_eq!(
+        image::image_dimensions(output_dir.join("page-0001.png")).unwrap(),
+        (50, 40)
+    );
+    assert_eq!(
+        image::image_dimensions(output_dir.join("page-0002.png")).unwrap(),
+        (400, 300)
+    );
+    let summary: serde_json::Value = json_data(&output.stdout);
+    assert_eq!(summary["pages_not_upscaled"], serde_json::json!([1]));
+}
+
+#[test]
+fn pages_are_upscaled_by_default() {
+    let (pdf_path, temp_dir) = write_pdf("pdf-render-upscale-default");
+    let output_dir = temp_dir.join("out");
+
+    let output = render(&pdf_path, &output_dir, &[]);
+    assert_success(&output, "pdf render");
+
+    assert_eq!(
+        image::image_dimensions(output_dir.join("page-0001.png")).unwrap(),
+        (400, 320)
+    );
+    let summary: serde_json::Value = json_data(&output.stdout);
+    assert!(summary.get("pages_not_upscaled").is_none());
+}
+
+fn render(pdf_path: &Path, output_dir: &Path, args: &[&str]) -> std::process::Output {
+    render_command(pdf_path, output_dir)
+        .args(["--format", "png", "--target-width", "400"])
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+fn write_pdf(prefix: &str) -> (PathBuf, PathBuf) {
+    let temp_dir = create_temp_dir(prefix);
+    let pdf_path = temp_dir.join("sizes.pdf");
+    let mut pdf = PdfBuilder::new();
+    pdf.add_page("/MediaBox [0 0 50 40]", b"0 g 10 10 30 20 re f");
+    pdf.add_page("/MediaBox [0 0 800 600]", b"0 g 100 100 600 400 re f");
+    fs::write(&pdf_path, pdf.finish()).unwrap();
+    (pdf_path, temp_dir)
+}
@@ -0,0 +1,40 @@
+mod common;
+
+use common::{PdfBuilder, create_temp_dir, pdfium_library};
+use pdf::{PageError, RenderOptions, render_pages};
+use std::fs;
+use std::path::PathBuf;
+
+/// Library callers pass page lists directly, so a page past the end reaches
+/// the renderer and must come back as a `PageError` rather than a string.
+#[test]
+fn out_of_range_page_is_a_structured_error() {
+    let (pdf_path, output_dir) = write_blank_pdf("pdf-render-error-page");
+    fs::create_dir(&output_dir).unwrap();
+    // SAFETY: this binary has a single test, so nothing reads the environment concurrently.
+    unsafe { std::env::set_var("PDFIUM_LIBRARY_PATH", pdfium_library()) };
+
+    let result = render_pages(
+        &pdf_path,
+        &output_dir,
+        &[1, 5],
+        1,
+        &RenderOptions::default(),
+    )
+    .unwrap();
+
+    assert_eq!(result.pages_rendered, 1);
+    assert_eq!(result.errors.len(), 1);
+    let PageError { page, message } = &result.errors[0];
+    assert_eq!(*page, 5);
+    assert!(!message.is_empty());
+}
+
+fn write_blank_pdf(prefix: &str) -> (PathBuf, PathBuf) {
+    let temp_dir = create_temp_dir(prefix);
+    let pdf_path = temp_dir.join("blank.pdf");
+    let mut pdf = PdfBuilder::new();
+    pdf.add_page("/MediaBox [0 0 100 100]", b"");
+    fs::write(&pdf_path, pdf.finish()).unwrap();
+    (pdf_path, temp_dir.join("out"))
+}
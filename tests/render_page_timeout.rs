@@ -0,0 +1,92 @@
+mod common;
+
+use common::{PdfBuilder, create_temp_dir, pdf_command};
+use std::fs;
+use std::time::{Duration, Instant};
+
+/// Nesting depth and fan-out of the form XObject tree: 12^8 leaf fills.
+const FORM_DEPTH: usize = 8;
+const FORM_FAN_OUT: usize = 12;
+
+#[test]
+fn slow_page_times_out_and_remaining_pages_are_reported() {
+    let temp_dir = create_temp_dir("pdf-render-page-timeout");
+    let pdf_path = temp_dir.join("slow.pdf");
+    fs::write(&pdf_path, build_slow_pdf()).unwrap();
+
+    let start = Instant::now();
+    let output = pdf_command()
+        .arg("render")
+        .arg(&pdf_path)
+        .arg("-o")
+        .arg(temp_dir.join("out"))
+        .arg("--workers")
+        .arg("1")
+        .arg("--target-width")
+        .arg("200")
+        .arg("--page-timeout")
+        .arg("1")
+        .output()
+        .unwrap();
+
+    assert!(
+        start.elapsed() < Duration::from_secs(30),
+        "watchdog did not stop the worker"
+    );
+    assert_eq!(output.status.code(), Some(4));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("page 1: timeout after 1s"), "{stderr}");
+    assert!(
+        stderr.contains("page 2: not rendered, worker stopped after page 1 timed out"),
+        "{stderr}"
+    );
+}
+
+#[test]
+fn page_timeout_rejects_thread_mode() {
+    let temp_dir = create_temp_dir("pdf-render-page-timeout-thread");
+    let pdf_path = temp_dir.join("blank.pdf");
+    let mut pdf = PdfBuilder::new();
+    pdf.add_page("/MediaBox [0 0 100 100]", b"");
+    fs::write(&pdf_path, pdf.finish()).unwrap();
+
+    let output = pdf_command()
+        .arg("render")
+        .arg(&pdf_path)
+        .arg("-o")
+        .arg(temp_dir.join("out"))
+        .arg("--mode")
+        .arg("thread")
+        .arg("--page-timeout")
+        .arg("1")
+        .output()
+        .unwrap();
+
+    assert_eq!(output.status.code(), Some(1));
+}
+
+/// Page 1 draws a deep tree of form XObjects that takes pdfium far longer than
+/// a second to rasterize; page 2 is blank.
+fn build_slow_pdf() -> Vec<u8> {
+    let mut pdf = PdfBuilder::new();
+    let mut child_id = pdf.add_stream(
+        "/Type /XObject /Subtype /Form /BBox [0 0 100 100]",
+        b"0 0 0 rg 0 0 100 100 re f\n",
+    );
+    for _ in 0..FORM_DEPTH {
+        let content = "q /X Do Q\n".repeat(FORM_FAN_OUT);
+        child_id = pdf.add_stream(
+            &format!(
+                "/Type /XObject /Subtype /Form /BBox [0 0 100 100] \
+                 /Resources << /XObject << /X {child_id} 0 R >> >>"
+            ),
+            content.as_bytes(),
+        );
+    }
+    pdf.add_page(
+        &format!("/MediaBox [0 0 100 100] /Resources << /XObject << /X {child_id} 0 R >> >>"),
+        b"/X Do\n",
+    );
+    pdf.add_page("/MediaBox [0 0 100 100]", b"");
+    pdf.finish()
+}
@@ -0,0 +1,89 @@
+mod common;
+
+use common::{PdfBuilder, assert_success, create_temp_dir, render_command};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+#[test]
+fn pages_from_file_renders_the_listed_pages() {
+    let (pdf_path, temp_dir) = write_pdf("pdf-render-pages-from");
+    let list = temp_dir.join("pages.txt");
+    fs::write(&list, "# front matter\n1\n\n  3-4  \n# appendix\n~1\n").unwrap();
+    let output_dir = temp_dir.join("out");
+
+    let output = render(&pdf_path, &output_dir, &list, &[]);
+    assert_success(&output, "pdf render --pages-from");
+
+    assert_eq!(
+        rendered_files(&output_dir),
+        [
+            "page-0001.jpg",
+            "page-0003.jpg",
+            "page-0004.jpg",
+            "page-0006.jpg"
+        ]
+    );
+}
+
+#[test]
+fn pages_from_conflicts_with_pages() {
+    let (pdf_path, temp_dir) = write_pdf("pdf-render-pages-from-conflict");
+    let list = temp_dir.join("pages.txt");
+    fs::write(&list, "1\n").unwrap();
+
+    let output = render(&pdf_path, &temp_dir.join("out"), &list, &["--pages", "2"]);
+
+    assert!(!output.status.success());
+    assert!(!temp_dir.join("out").exists());
+}
+
+#[test]
+fn pages_from_rejects_empty_and_missing_files() {
+    let (pdf_path, temp_dir) = write_pdf("pdf-render-pages-from-empty");
+    let list = temp_dir.join("pages.txt");
+    fs::write(&list, "# nothing yet\n\n").unwrap();
+
+    let empty = render(&pdf_path, &temp_dir.join("out"), &list, &[]);
+    assert_eq!(empty.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&empty.stderr).contains("no pages listed"));
+
+    let missing = render(
+        &pdf_path,
+        &temp_dir.join("out"),
+        &temp_dir.join("missing.txt"),
+        &[],
+    );
+    assert_eq!(missing.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&missing.stderr).contains("missing.txt"));
+}
+
+fn render(pdf_path: &Path, output_dir: &Path, list: &Path, args: &[&str]) -> std::process::Output {
+    render_command(pdf_path, output_dir)
+        .arg("--target-width")
+        .arg("20")
+        .arg("--pages-from")
+        .arg(list)
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+fn rendered_files(output_dir: &Path) -> Vec<String> {
+    let mut names: Vec<String> = fs::read_dir(output_dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+        .collect();
+    names.sort();
+    names
+}
+
+fn write_pdf(prefix: &str) -> (PathBuf, PathBuf) {
+    let temp_dir = create_temp_dir(prefix);
+    let pdf_path = temp_dir.join("six.pdf");
+    let mut pdf = PdfBuilder::new();
+    for _ in 0..6 {
+        pdf.add_page("/MediaBox [0 0 100 100]", b"");
+    }
+    fs::write(&pdf_path, pdf.finish()).unwrap();
+    (pdf_path, temp_dir)
+}
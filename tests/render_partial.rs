@@ -0,0 +1,66 @@
+mod common;
+
+use common::{PdfBuilder, create_temp_dir, json_data, render_command};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+#[test]
+fn some_failed_pages_exit_with_partial_code() {
+    let (pdf_path, output_dir) = write_mixed_size_pdf("pdf-render-partial");
+
+    // The region fits the 200x100pt page but not the 100x50pt one.
+    let output = render_region(&pdf_path, &output_dir, "120,0,40,40");
+
+    assert_eq!(output.status.code(), Some(6));
+    let summary: serde_json::Value = json_data(&output.stdout);
+    assert_eq!(summary["pages_rendered"], 1);
+    assert_eq!(summary["failed"], 1);
+    assert!(output_dir.join("page-0001.jpg").exists());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("1 pages succeeded"), "{stderr}");
+}
+
+#[test]
+fn no_rendered_pages_exit_with_render_code() {
+    let (pdf_path, output_dir) = write_mixed_size_pdf("pdf-render-total-failure");
+
+    let output = render_region(&pdf_path, &output_dir, "300,0,40,40");
+
+    assert_eq!(output.status.code(), Some(4));
+    let summary: serde_json::Value = json_data(&output.stdout);
+    assert_eq!(summary["pages_rendered"], 0);
+    assert_eq!(summary["failed"], 2);
+}
+
+#[test]
+fn successful_render_reports_zero_failed() {
+    let (pdf_path, output_dir) = write_mixed_size_pdf("pdf-render-no-failure");
+
+    let output = render_region(&pdf_path, &output_dir, "0,0,40,40");
+
+    assert_eq!(output.status.code(), Some(0));
+    let summary: serde_json::Value = json_data(&output.stdout);
+    assert_eq!(summary["pages_rendered"], 2);
+    assert_eq!(summary["failed"], 0);
+}
+
+fn render_region(pdf_path: &Path, output_dir: &Path, region: &str) -> std::process::Output {
+    render_command(pdf_path, output_dir)
+        .arg("--workers")
+        .arg("1")
+        .arg("--region")
+        .arg(region)
+        .output()
+        .unwrap()
+}
+
+/// A 200x100pt page followed by a 100x50pt page.
+fn write_mixed_size_pdf(prefix: &str) -> (PathBuf, PathBuf) {
+    let temp_dir = create_temp_dir(prefix);
+    let pdf_path = temp_dir.join("mixed.pdf");
+    let mut pdf = PdfBuilder::new();
+    pdf.add_page("/MediaBox [0 0 200 100]", b"");
+    pdf.add_page("/MediaBox [0 0 100 50]", b"");
+    fs::write(&pdf_path, pdf.finish()).unwrap();
+    (pdf_path, temp_dir.join("out"))
+}
@@ -0,0 +1,81 @@
+mod common;
+
+use common::{PdfBuilder, assert_success, create_temp_dir, render_command};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+#[test]
+fn text_rendering_flags_change_text_pages() {
+    let (temp_dir, pdf_path) = write_text_pdf("pdf-render-pdfium-flags");
+
+    let default = render(&pdf_path, &temp_dir, "default", &[]);
+    for flag in ["--lcd-text", "--no-smooth-text", "--grayscale-aa"] {
+        assert_ne!(
+            render(&pdf_path, &temp_dir, flag, &[flag]),
+            default,
+            "{flag}"
+        );
+    }
+}
+
+#[test]
+fn no_native_text_renders_in_workers_and_threads() {
+    let (temp_dir, pdf_path) = write_text_pdf("pdf-render-no-native-text");
+
+    // Only Windows and macOS builds of pdfium have native text output; on the
+    // others the flag is accepted and the glyphs are rasterized as usual.
+    let single = render(&pdf_path, &temp_dir, "single", &["--no-native-text"]);
+    for mode in ["process", "thread"] {
+        let multi = render(
+            &pdf_path,
+            &temp_dir,
+            mode,
+            &["--no-native-text", "--workers", "2", "--mode", mode],
+        );
+        assert_eq!(multi, single, "{mode}");
+    }
+}
+
+#[test]
+fn flags_reach_worker_processes() {
+    let (temp_dir, pdf_path) = write_text_pdf("pdf-render-pdfium-flags-workers");
+
+    let single = render(&pdf_path, &temp_dir, "single", &["--lcd-text"]);
+    let multi = render(
+        &pdf_path,
+        &temp_dir,
+        "multi",
+        &["--lcd-text", "--workers", "2"],
+    );
+
+    assert_eq!(multi, single);
+}
+
+/// Render page 1 to PNG and return its bytes.
+fn render(pdf_path: &Path, temp_dir: &Path, name: &str, args: &[&str]) -> Vec<u8> {
+    let output_dir = temp_dir.join(name.trim_start_matches('-'));
+    let output = render_command(pdf_path, &output_dir)
+        .arg("--format")
+        .arg("png")
+        .args(args)
+        .output()
+        .unwrap();
+    assert_success(&output, &format!("pdf render {}", args.join(" ")));
+    fs::read(output_dir.join("page-0001.png")).unwrap()
+}
+
+/// Two pages of red Helvetica text, so two workers each get one.
+fn write_text_pdf(prefix: &str) -> (PathBuf, PathBuf) {
+    let temp_dir = create_temp_dir(prefix);
+    let pdf_path = temp_dir.join("text.pdf");
+    let mut pdf = PdfBuilder::new();
+    let font_id = pdf.add_object("<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica >>");
+    for _ in 0..2 {
+        pdf.add_page(
+            &format!("/MediaBox [0 0 300 100] /Resources << /Font << /F1 {font_id} 0 R >> >>"),
+            b"1 0 0 rg BT /F1 24 Tf 20 40 Td (Reference text) Tj ET\n",
+        );
+    }
+    fs::write(&pdf_path, pdf.finish()).unwrap();
+    (temp_dir, pdf_path)
+}
@@ -0,0 +1,65 @@
+mod common;
+
+use common::{PdfBuilder, assert_success, create_temp_dir, pdf_command};
+use std::fs;
+use std::path::PathBuf;
+
+#[test]
+fn transparent_png_keeps_unpainted_regions_clear() {
+    let (pdf_path, output_dir) = write_half_painted_pdf("pdf-render-transparent");
+
+    let output = pdf_command()
+        .arg("render")
+        .arg(&pdf_path)
+        .arg("-o")
+        .arg(&output_dir)
+        .arg("--workers")
+        .arg("1")
+        .arg("--target-width")
+        .arg("100")
+        .arg("--format")
+        .arg("png")
+        .arg("--transparent")
+        .output()
+        .unwrap();
+    assert_success(&output, "pdf render --format png --transparent");
+
+    let image = image::open(output_dir.join("page-0001.png"))
+        .unwrap()
+        .to_rgba8();
+    assert!(
+        image.pixels().any(|pixel| pixel[3] < 255),
+        "expected non-opaque pixels in the unpainted half"
+    );
+    assert_eq!(
+        image.get_pixel(10, 10)[3],
+        255,
+        "painted half should be opaque"
+    );
+}
+
+#[test]
+fn transparent_rejects_jpeg_output() {
+    let (pdf_path, output_dir) = write_half_painted_pdf("pdf-render-transparent-jpeg");
+
+    let output = pdf_command()
+        .arg("render")
+        .arg(&pdf_path)
+        .arg("-o")
+        .arg(&output_dir)
+        .arg("--transparent")
+        .output()
+        .unwrap();
+
+    assert_eq!(output.status.code(), Some(1));
+}
+
+/// One 100x100pt page whose top half is painted black; the bottom half is empty.
+fn write_half_painted_pdf(prefix: &str) -> (PathBuf, PathBuf) {
+    let temp_dir = create_temp_dir(prefix);
+    let pdf_path = temp_dir.join("half.pdf");
+    let mut pdf = PdfBuilder::new();
+    pdf.add_page("/MediaBox [0 0 100 100]", b"0 0 0 rg\n0 50 100 50 re\nf\n");
+    fs::write(&pdf_path, pdf.finish()).unwrap();
+    (pdf_path, temp_dir.join("out"))
+}
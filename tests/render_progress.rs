@@ -0,0 +1,79 @@
+mod common;
+
+use common::{PdfBuilder, assert_success, create_temp_dir, pdf_command};
+use std::fs;
+use std::path::PathBuf;
+
+const PAGE_COUNT: u32 = 3;
+
+#[test]
+fn worker_emits_one_json_line_per_page() {
+    let (pdf_path, output_dir) = write_pdf("pdf-progress-worker");
+    fs::create_dir_all(&output_dir).unwrap();
+
+    let output = pdf_command()
+        .arg("render-worker")
+        .arg(&pdf_path)
+        .arg("-o")
+        .arg(&output_dir)
+        .arg("--pages")
+        .arg("1-3")
+        .arg("--target-width")
+        .arg("50")
+        .arg("--progress")
+        .arg("json")
+        .output()
+        .unwrap();
+    assert_success(&output, "pdf render-worker --progress json");
+
+    assert_eq!(rendered_pages(&output.stderr), vec![1, 2, 3]);
+}
+
+#[test]
+fn parent_passes_worker_progress_through() {
+    let (pdf_path, output_dir) = write_pdf("pdf-progress-parent");
+
+    let output = pdf_command()
+        .arg("render")
+        .arg(&pdf_path)
+        .arg("-o")
+        .arg(&output_dir)
+        .arg("--workers")
+        .arg("2")
+        .arg("--target-width")
+        .arg("50")
+        .arg("--progress")
+        .arg("json")
+        .output()
+        .unwrap();
+    assert_success(&output, "pdf render --progress json");
+
+    let mut pages = rendered_pages(&output.stderr);
+    pages.sort();
+    assert_eq!(pages, vec![1, 2, 3]);
+}
+
+/// Parse every stderr line as a page event and return the rendered page numbers.
+fn rendered_pages(stderr: &[u8]) -> Vec<u64> {
+    String::from_utf8_lossy(stderr)
+        .lines()
+        .map(|line| {
+            let event: serde_json::Value = serde_json::from_str(line)
+                .unwrap_or_else(|e| panic!("stderr line is not JSON ({e}): {line:?}"));
+            assert_eq!(event["event"], "page");
+            assert_eq!(event["status"], "rendered");
+            event["page"].as_u64().unwrap()
+        })
+        .collect()
+}
+
+fn write_pdf(prefix: &str) -> (PathBuf, PathBuf) {
+    let temp_dir = create_temp_dir(prefix);
+    let pdf_path = temp_dir.join("pages.pdf");
+    let mut pdf = PdfBuilder::new();
+    for _ in 0..PAGE_COUNT {
+        pdf.add_page("/MediaBox [0 0 100 100]", b"0 0 0 rg\n0 0 50 50 re\nf\n");
+    }
+    fs::write(&pdf_path, pdf.finish()).unwrap();
+    (pdf_path, temp_dir.join("out"))
+}
@@ -0,0 +1,58 @@
+mod common;
+
+use common::{PdfBuilder, assert_success, create_temp_dir, render_command};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Output;
+
+#[test]
+fn high_preset_matches_jpeg_quality_90() {
+    let pdf_path = write_pdf("pdf-render-quality-high");
+    let preset_dir = pdf_path.with_file_name("high");
+    let numeric_dir = pdf_path.with_file_name("numeric");
+    let max_dir = pdf_path.with_file_name("max");
+
+    let preset = render(&pdf_path, &preset_dir, "high");
+    assert_success(&preset, "pdf render --quality high");
+    let numeric = render(&pdf_path, &numeric_dir, "90");
+    assert_success(&numeric, "pdf render --quality 90");
+
+    let max = render(&pdf_path, &max_dir, "max");
+    assert_success(&max, "pdf render --quality max");
+
+    let page = |dir: &Path| fs::read(dir.join("page-0001.jpg")).unwrap();
+    assert_eq!(page(&preset_dir), page(&numeric_dir));
+    assert_ne!(page(&preset_dir), page(&max_dir));
+}
+
+#[test]
+fn unknown_quality_name_is_rejected() {
+    let pdf_path = write_pdf("pdf-render-quality-unknown");
+    let output_dir = pdf_path.with_file_name("out");
+
+    let output = render(&pdf_path, &output_dir, "best");
+
+    assert_eq!(output.status.code(), Some(1));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("--quality"), "{stderr}");
+    assert!(!output_dir.exists());
+}
+
+fn render(pdf_path: &Path, output_dir: &Path, quality: &str) -> Output {
+    render_command(pdf_path, output_dir)
+        .arg("--quality")
+        .arg(quality)
+        .output()
+        .unwrap()
+}
+
+fn write_pdf(prefix: &str) -> PathBuf {
+    let pdf_path = create_temp_dir(prefix).join("shapes.pdf");
+    let mut pdf = PdfBuilder::new();
+    pdf.add_page(
+        "/MediaBox [0 0 120 120]",
+        b"1 0 0 rg 10 10 60 60 re f 0 0 1 rg 40 40 70 70 re f",
+    );
+    fs::write(&pdf_path, pdf.finish()).unwrap();
+    pdf_path
+}
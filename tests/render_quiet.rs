@@ -0,0 +1,84 @@
+mod common;
+
+use common::{PdfBuilder, assert_success, create_temp_dir, json_data, pdf_command};
+use std::fs;
+use std::path::PathBuf;
+
+#[test]
+fn quiet_leaves_stdout_and_stderr_empty() {
+    let (pdf_path, temp_dir) = write_two_page_pdf("pdf-render-quiet");
+    let output_dir = temp_dir.join("out");
+
+    let output = pdf_command()
+        .arg("--quiet")
+        .arg("render")
+        .arg(&pdf_path)
+        .arg("-o")
+        .arg(&output_dir)
+        .arg("--workers")
+        .arg("2")
+        .output()
+        .unwrap();
+    assert_success(&output, "pdf --quiet render");
+
+    assert!(output.stdout.is_empty(), "{:?}", output.stdout);
+    assert!(
+        output.stderr.is_empty(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(output_dir.join("page-0001.jpg").exists());
+    assert!(output_dir.join("page-0002.jpg").exists());
+}
+
+#[test]
+fn summary_only_keeps_summary_without_progress() {
+    let (pdf_path, temp_dir) = write_two_page_pdf("pdf-render-summary-only");
+
+    let output = pdf_command()
+        .arg("render")
+        .arg(&pdf_path)
+        .arg("-o")
+        .arg(temp_dir.join("out"))
+        .arg("--summary-only")
+        .output()
+        .unwrap();
+    assert_success(&output, "pdf render --summary-only");
+
+    assert!(
+        output.stderr.is_empty(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let summary: serde_json::Value = json_data(&output.stdout);
+    assert_eq!(summary["pages_rendered"], 2);
+}
+
+#[test]
+fn quiet_stdout_output_is_only_image_bytes() {
+    let (pdf_path, _) = write_two_page_pdf("pdf-render-quiet-stdout");
+
+    let output = pdf_command()
+        .arg("render")
+        .arg(&pdf_path)
+        .arg("--stdout")
+        .arg("--pages")
+        .arg("1")
+        .arg("--quiet")
+        .output()
+        .unwrap();
+    assert_success(&output, "pdf render --stdout --quiet");
+
+    assert!(output.stdout.starts_with(&[0xFF, 0xD8]));
+    assert!(output.stdout.ends_with(&[0xFF, 0xD9]));
+}
+
+fn write_two_page_pdf(prefix: &str) -> (PathBuf, PathBuf) {
+    let temp_dir = create_temp_dir(prefix);
+    let pdf_path = temp_dir.join("two.pdf");
+    let mut pdf = PdfBuilder::new();
+    pdf.add_page("/MediaBox [0 0 100 100]", b"");
+    pdf.add_page("/MediaBox [0 0 100 100]", b"");
+    fs::write(&pdf_path, pdf.finish()).unwrap();
+    (pdf_path, temp_dir)
+}
@@ -0,0 +1,149 @@
+mod common;
+
+use common::{PdfBuilder, assert_success, create_temp_dir, json_data, pdf_command, render_command};
+use image::codecs::jpeg::JpegEncoder;
+use image::{Rgb, RgbImage};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const IMAGE_WIDTH: u32 = 64;
+const IMAGE_HEIGHT: u32 = 96;
+
+#[test]
+fn extracted_jpegs_are_copied_by_default() {
+    let (pdf_path, temp_dir) = write_pdf("pdf-render-extract-copy");
+    let output_dir = temp_dir.join("out");
+
+    render(&pdf_path, &output_dir, &["--quality", "50"]);
+
+    let jpeg = fs::read(output_dir.join("page-0001.jpg")).unwrap();
+    assert_eq!(jpeg, source_jpeg());
+}
+
+#[test]
+fn reencode_extracted_applies_quality_and_subsampling() {
+    let (pdf_path, temp_dir) = write_pdf("pdf-render-reencode-extracted");
+    let output_dir = temp_dir.join("out");
+
+    let summary = render(
+        &pdf_path,
+        &output_dir,
+        &[
+            "--reencode-extracted",
+            "--quality",
+            "50",
+            "--subsampling",
+            "420",
+            "--workers",
+            "2",
+        ],
+    );
+    assert_eq!(summary["pages_extracted"], 2);
+
+    let source = source_jpeg();
+    for name in ["page-0001.jpg", "page-0002.jpg"] {
+        let jpeg = fs::read(output_dir.join(name)).unwrap();
+        assert_ne!(jpeg, source, "{name}");
+        assert_eq!(
+            image::image_dimensions(output_dir.join(name)).unwrap(),
+            (IMAGE_WIDTH, IMAGE_HEIGHT)
+        );
+        let sof = find_marker(&jpeg, 0xC0).expect("baseline SOF0 marker");
+        assert_eq!(jpeg[sof + 11], 0x22, "{name}: luma sampling factors");
+        // The same luminance table as a quality 50 encode, not the source's 95.
+        assert_eq!(
+            quantization_table(&jpeg),
+            quantization_table(&encode_jpeg(50))
+        );
+        assert_ne!(quantization_table(&jpeg), quantization_table(&source));
+    }
+}
+
+#[test]
+fn reencode_extracted_requires_extract_images() {
+    let (pdf_path, temp_dir) = write_pdf("pdf-render-reencode-alone");
+
+    let output = pdf_command()
+        .arg("render")
+        .arg(&pdf_path)
+        .arg("-o")
+        .arg(temp_dir.join("out"))
+        .arg("--reencode-extracted")
+        .output()
+        .unwrap();
+
+    assert!(!output.status.success());
+}
+
+fn render(pdf_path: &Path, output_dir: &Path, args: &[&str]) -> serde_json::Value {
+    let output = render_command(pdf_path, output_dir)
+        .arg("--extract-images")
+        .args(args)
+        .output()
+        .unwrap();
+    assert_success(&output, "pdf render --extract-images");
+    json_data(&output.stdout)
+}
+
+fn source_jpeg() -> Vec<u8> {
+    encode_jpeg(95)
+}
+
+fn encode_jpeg(quality: u8) -> Vec<u8> {
+    let image = RgbImage::from_fn(IMAGE_WIDTH, IMAGE_HEIGHT, |x, y| {
+        Rgb([(x * 4) as u8, (y * 2) as u8, 90])
+    });
+    let mut bytes = Vec::new();
+    image
+        .write_with_encoder(JpegEncoder::new_with_quality(&mut bytes, quality))
+        .unwrap();
+    bytes
+}
+
+/// The luminance table: the first 8-bit table of the first DQT segment,
+/// after its marker, length and precision/id byte.
+fn quantization_table(jpeg: &[u8]) -> Vec<u8> {
+    let dqt = find_marker(jpeg, 0xDB).expect("DQT marker");
+    jpeg[dqt + 5..dqt + 5 + 64].to_vec()
+}
+
+/// Offset of the first `FF <marker>` segment before the scan data.
+fn find_marker(jpeg: &[u8], marker: u8) -> Option<usize> {
+    let mut i = 2;
+    while i + 3 < jpeg.len() && jpeg[i] == 0xFF {
+        if jpeg[i + 1] == marker {
+            return Some(i);
+        }
+        if jpeg[i + 1] == 0xDA {
+            return None;
+        }
+        i += 2 + u16::from_be_bytes([jpeg[i + 2], jpeg[i + 3]]) as usize;
+    }
+    None
+}
+
+/// Two pages that are each a single DCTDecode image filling the page.
+fn write_pdf(prefix: &str) -> (PathBuf, PathBuf) {
+    let temp_dir = create_temp_dir(prefix);
+    let pdf_path = temp_dir.join("scans.pdf");
+    let jpeg = source_jpeg();
+    let mut pdf = PdfBuilder::new();
+    for _ in 0..2 {
+        let image_id = pdf.add_stream(
+            &format!(
+                "/Type /XObject /Subtype /Image /Width {IMAGE_WIDTH} /Height {IMAGE_HEIGHT} \
+                 /ColorSpace /DeviceRGB /BitsPerComponent 8 /Filter /DCTDecode"
+            ),
+            &jpeg,
+        );
+        pdf.add_page(
+            &format!(
+                "/MediaBox [0 0 {IMAGE_WIDTH} {IMAGE_HEIGHT}] \
+                 /Resources << /XObject << /Im0 {image_id} 0 R >> >>"
+            ),
+            format!("q {IMAGE_WIDTH} 0 0 {IMAGE_HEIGHT} 0 0 cm /Im0 Do Q\n").as_bytes(),
+        );
+    }
+    fs::write(&pdf_path, pdf.finish()).unwrap();
+    (pdf_path, temp_dir)
+}
@@ -0,0 +1,95 @@
+mod common;
+
+use common::{PdfBuilder, assert_success, create_temp_dir, pdf_command};
+use std::fs;
+use std::path::PathBuf;
+
+#[test]
+fn quarter_region_keeps_its_aspect_ratio_and_content() {
+    let (pdf_path, output_dir) = write_quarter_painted_pdf("pdf-render-region");
+
+    let output = pdf_command()
+        .arg("render")
+        .arg(&pdf_path)
+        .arg("-o")
+        .arg(&output_dir)
+        .arg("--workers")
+        .arg("1")
+        .arg("--target-width")
+        .arg("400")
+        .arg("--format")
+        .arg("png")
+        .arg("--region")
+        .arg("0,0,100,50")
+        .output()
+        .unwrap();
+    assert_success(&output, "pdf render --region 0,0,100,50");
+
+    let image = image::open(output_dir.join("page-0001.png"))
+        .unwrap()
+        .to_rgb8();
+    assert_eq!(image.dimensions(), (400, 200));
+    // The top-left quarter is the painted one.
+    assert_eq!(image.get_pixel(200, 100).0, [0, 0, 0]);
+}
+
+#[test]
+fn region_is_forwarded_to_workers() {
+    let (pdf_path, output_dir) = write_quarter_painted_pdf("pdf-render-region-workers");
+
+    let output = pdf_command()
+        .arg("render")
+        .arg(&pdf_path)
+        .arg("-o")
+        .arg(&output_dir)
+        .arg("--workers")
+        .arg("2")
+        .arg("--target-width")
+        .arg("100")
+        .arg("--region")
+        .arg("100,50,100,50")
+        .output()
+        .unwrap();
+    assert_success(&output, "pdf render --region with workers");
+
+    for page in 1..=2 {
+        let image = image::open(output_dir.join(format!("page-{page:04}.jpg")))
+            .unwrap()
+            .to_rgb8();
+        assert_eq!(image.dimensions(), (100, 50));
+        assert!(image.get_pixel(50, 25).0.iter().all(|&c| c > 200));
+    }
+}
+
+#[test]
+fn region_outside_page_is_an_error() {
+    let (pdf_path, output_dir) = write_quarter_painted_pdf("pdf-render-region-outside");
+
+    let output = pdf_command()
+        .arg("render")
+        .arg(&pdf_path)
+        .arg("-o")
+        .arg(&output_dir)
+        .arg("--workers")
+        .arg("1")
+        .arg("--region")
+        .arg("150,0,100,50")
+        .output()
+        .unwrap();
+
+    assert_eq!(output.status.code(), Some(4));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("extends past"), "{stderr}");
+}
+
+/// Two 200x100pt pages with the top-left 100x50pt quarter painted black.
+fn write_quarter_painted_pdf(prefix: &str) -> (PathBuf, PathBuf) {
+    let temp_dir = create_temp_dir(prefix);
+    let pdf_path = temp_dir.join("quarter.pdf");
+    let mut pdf = PdfBuilder::new();
+    for _ in 0..2 {
+        pdf.add_page("/MediaBox [0 0 200 100]", b"0 0 0 rg 0 50 100 50 re f\n");
+    }
+    fs::write(&pdf_path, pdf.finish()).unwrap();
+    (pdf_path, temp_dir.join("out"))
+}
@@ -0,0 +1,78 @@
+mod common;
+
+use common::{PdfBuilder, assert_success, create_temp_dir, json_data, render_command};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+#[test]
+fn repeat_reports_each_iteration() {
+    let (temp_dir, pdf_path) = write_pdf("pdf-render-repeat");
+    let output_dir = temp_dir.join("out");
+
+    let output = render(&pdf_path, &output_dir, &["--workers", "1", "--repeat", "2"]);
+    assert_success(&output, "pdf render --repeat 2");
+
+    let summary: serde_json::Value = json_data(&output.stdout);
+    let iterations = summary["iteration_secs"].as_array().unwrap();
+    assert_eq!(iterations.len(), 2);
+    assert!(iterations.iter().all(|secs| secs.as_f64().unwrap() >= 0.0));
+    // Counts describe one pass; the second overwrote the first's files.
+    assert_eq!(summary["pages_rendered"], 3);
+    for page in 1..=3 {
+        assert!(output_dir.join(format!("page-{page:04}.jpg")).exists());
+    }
+}
+
+#[test]
+fn iterations_are_not_reported_without_repeat() {
+    let (temp_dir, pdf_path) = write_pdf("pdf-render-repeat-default");
+
+    let output = render(&pdf_path, &temp_dir.join("out"), &["--workers", "1"]);
+    assert_success(&output, "pdf render");
+
+    let summary: serde_json::Value = json_data(&output.stdout);
+    assert!(summary.get("iteration_secs").is_none());
+}
+
+#[test]
+fn repeat_requires_a_single_process() {
+    let (temp_dir, pdf_path) = write_pdf("pdf-render-repeat-workers");
+
+    let output = render(
+        &pdf_path,
+        &temp_dir.join("out"),
+        &["--workers", "2", "--repeat", "2"],
+    );
+
+    assert_eq!(output.status.code(), Some(1));
+    assert!(!temp_dir.join("out").exists());
+}
+
+#[test]
+fn zero_repeat_is_rejected() {
+    let (temp_dir, pdf_path) = write_pdf("pdf-render-repeat-zero");
+
+    let output = render(&pdf_path, &temp_dir.join("out"), &["--repeat", "0"]);
+
+    assert_eq!(output.status.code(), Some(1));
+}
+
+fn render(pdf_path: &Path, output_dir: &Path, args: &[&str]) -> std::process::Output {
+    render_command(pdf_path, output_dir)
+        .arg("--target-width")
+        .arg("20")
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+fn write_pdf(prefix: &str) -> (PathBuf, PathBuf) {
+    let temp_dir = create_temp_dir(prefix);
+    let pdf_path = temp_dir.join("three.pdf");
+    let mut pdf = PdfBuilder::new();
+    for _ in 0..3 {
+        pdf.add_page("/MediaBox [0 0 100 150]", b"");
+    }
+    fs::write(&pdf_path, pdf.finish()).unwrap();
+    (temp_dir, pdf_path)
+}
@@ -0,0 +1,183 @@
+mod common;
+
+use common::{PdfBuilder, assert_success, create_temp_dir, render_command};
+use image::codecs::jpeg::JpegEncoder;
+use image::{Rgb, RgbImage};
+use std::fs;
+use std::path::Path;
+
+const IMAGE_WIDTH: u32 = 64;
+const IMAGE_HEIGHT: u32 = 96;
+const EXIF_DATE: &[u8] = b"2024:05:06 07:08:09";
+
+#[test]
+fn repeated_renders_have_identical_sha256() {
+    let temp_dir = create_temp_dir("pdf-render-reproducible");
+    let pdf_path = temp_dir.join("doc.pdf");
+    fs::write(&pdf_path, build_pdf()).unwrap();
+
+    // Different worker counts must not change a single byte.
+    let first = temp_dir.join("first");
+    let second = temp_dir.join("second");
+    render(&pdf_path, &first, &["--workers", "1"]);
+    render(&pdf_path, &second, &["--workers", "2"]);
+
+    for name in ["page-0001.jpg", "page-0002.jpg"] {
+        let first_bytes = fs::read(first.join(name)).unwrap();
+        let second_bytes = fs::read(second.join(name)).unwrap();
+        assert_eq!(
+            hex(&sha256(&first_bytes)),
+            hex(&sha256(&second_bytes)),
+            "{name}"
+        );
+    }
+}
+
+#[test]
+fn reproducible_strips_metadata_from_extracted_jpegs() {
+    let temp_dir = create_temp_dir("pdf-render-reproducible-exif");
+    let pdf_path = temp_dir.join("doc.pdf");
+    fs::write(&pdf_path, build_pdf()).unwrap();
+
+    let plain = temp_dir.join("plain");
+    render_with(&pdf_path, &plain, &["--extract-images"]);
+    let stripped = temp_dir.join("stripped");
+    render(&pdf_path, &stripped, &["--extract-images"]);
+
+    let plain_jpeg = fs::read(plain.join("page-0001.jpg")).unwrap();
+    let stripped_jpeg = fs::read(stripped.join("page-0001.jpg")).unwrap();
+    assert!(contains(&plain_jpeg, EXIF_DATE));
+    assert!(!contains(&stripped_jpeg, EXIF_DATE));
+    assert_eq!(
+        image::load_from_memory(&plain_jpeg).unwrap().to_rgb8(),
+        image::load_from_memory(&stripped_jpeg).unwrap().to_rgb8()
+    );
+}
+
+fn render(pdf_path: &Path, output_dir: &Path, args: &[&str]) {
+    render_with(pdf_path, output_dir, &[args, &["--reproducible"]].concat());
+}
+
+fn render_with(pdf_path: &Path, output_dir: &Path, args: &[&str]) {
+    let output = render_command(pdf_path, output_dir)
+        .args(args)
+        .output()
+        .unwrap();
+    assert_success(&output, "pdf render");
+}
+
+fn contains(haystack: &[u8], needle: &[u8]) -> bool {
+    haystack
+        .windows(needle.len())
+        .any(|window| window == needle)
+}
+
+/// A JPEG whose APP1 EXIF segment carries a timestamp, as cameras write it.
+fn jpeg_with_exif() -> Vec<u8> {
+    let image = RgbImage::from_fn(IMAGE_WIDTH, IMAGE_HEIGHT, |x, y| {
+        Rgb([(x * 4) as u8, (y * 2) as u8, 90])
+    });
+    let mut jpeg = Vec::new();
+    image
+        .write_with_encoder(JpegEncoder::new_with_quality(&mut jpeg, 90))
+        .unwrap();
+
+    let payload = [&b"Exif\0\0"[..], EXIF_DATE].concat();
+    let mut exif = vec![0xFF, 0xE1];
+    exif.extend_from_slice(&(payload.len() as u16 + 2).to_be_bytes());
+    exif.extend_from_slice(&payload);
+    [&jpeg[..2], &exif, &jpeg[2..]].concat()
+}
+
+/// Page 1 is a single extractable JPEG with EXIF; page 2 is vector art.
+fn build_pdf() -> Vec<u8> {
+    let mut pdf = PdfBuilder::new();
+    let image_id = pdf.add_stream(
+        &format!(
+            "/Type /XObject /Subtype /Image /Width {IMAGE_WIDTH} /Height {IMAGE_HEIGHT} \
+             /ColorSpace /DeviceRGB /BitsPerComponent 8 /Filter /DCTDecode"
+        ),
+        &jpeg_with_exif(),
+    );
+    pdf.add_page(
+        &format!(
+            "/MediaBox [0 0 {IMAGE_WIDTH} {IMAGE_HEIGHT}] \
+             /Resources << /XObject << /Im0 {image_id} 0 R >> >>"
+        ),
+        format!("q {IMAGE_WIDTH} 0 0 {IMAGE_HEIGHT} 0 0 cm /Im0 Do Q\n").as_bytes(),
+    );
+    pdf.add_page(
+        "/MediaBox [0 0 100 150]",
+        b"0.2 0.4 0.8 rg 10 10 50 80 re f 0 0 0 RG 2 w 5 5 m 95 145 l S\n",
+    );
+    pdf.finish()
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+fn sha256(data: &[u8]) -> [u8; 32] {
+    const K: [u32; 64] = [
+        0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4,
+        0xab1c5ed5, 0xd807aa98, 0x12835b01, 0x243185be, 0x550c7dc3, 0x72be5d74, 0x80deb1fe,
+        0x9bdc06a7, 0xc19bf174, 0xe49b69c1, 0xefbe4786, 0x0fc19dc6, 0x240ca1cc, 0x2de92c6f,
+        0x4a7484aa, 0x5cb0a9dc, 0x76f988da, 0x983e5152, 0xa831c66d, 0xb00327c8, 0xbf597fc7,
+        0xc6e00bf3, 0xd5a79147, 0x06ca6351, 0x14292967, 0x27b70a85, 0x2e1b2138, 0x4d2c6dfc,
+        0x53380d13, 0x650a7354, 0x766a0abb, 0x81c2c92e, 0x92722c85, 0xa2bfe8a1, 0xa81a664b,
+        0xc24b8b70, 0xc76c51a3, 0xd192e819, 0xd6990624, 0xf40e3585, 0x106aa070, 0x19a4c116,
+        0x1e376c08, 0x2748774c, 0x34b0bcb5, 0x391c0cb3, 0x4ed8aa4a, 0x5b9cca4f, 0x682e6ff3,
+        0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208, 0x90befffa, 0xa4506ceb, 0xbef9a3f7,
+        0xc67178f2,
+    ];
+
+    let mut message = data.to_vec();
+    message.push(0x80);
+    while message.len() % 64 != 56 {
+        message.push(0);
+    }
+    message.extend_from_slice(&((data.len() as u64) * 8).to_be_bytes());
+
+    let mut state: [u32; 8] = [
+        0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab,
+        0x5be0cd19,
+    ];
+    for chunk in message.chunks(64) {
+        let mut w = [0u32; 64];
+        for (i, word) in chunk.chunks(4).enumerate() {
+            w[i] = u32::from_be_bytes([word[0], word[1], word[2], word[3]]);
+        }
+        for i in 16..64 {
+            let s0 = w[i - 15].rotate_right(7) ^ w[i - 15].rotate_right(18) ^ (w[i - 15] >> 3);
+            let s1 = w[i - 2].rotate_right(17) ^ w[i - 2].rotate_right(19) ^ (w[i - 2] >> 10);
+            w[i] = w[i - 16]
+                .wrapping_add(s0)
+                .wrapping_add(w[i - 7])
+                .wrapping_add(s1);
+        }
+
+        let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut h] = state;
+        for i in 0..64 {
+            let s1 = e.rotate_right(6) ^ e.rotate_right(11) ^ e.rotate_right(25);
+            let choice = (e & f) ^ (!e & g);
+            let t1 = h
+                .wrapping_add(s1)
+                .wrapping_add(choice)
+                .wrapping_add(K[i])
+                .wrapping_add(w[i]);
+            let s0 = a.rotate_right(2) ^ a.rotate_right(13) ^ a.rotate_right(22);
+            let majority = (a & b) ^ (a & c) ^ (b & c);
+            let t2 = s0.wrapping_add(majority);
+            (h, g, f, e, d, c, b, a) = (g, f, e, d.wrapping_add(t1), c, b, a, t1.wrapping_add(t2));
+        }
+        for (word, value) in state.iter_mut().zip([a, b, c, d, e, f, g, h]) {
+            *word = word.wrapping_add(value);
+        }
+    }
+
+    let mut digest = [0u8; 32];
+    for (out, word) in digest.chunks_mut(4).zip(state) {
+        out.copy_from_slice(&word.to_be_bytes());
+    }
+    digest
+}
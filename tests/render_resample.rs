@@ -0,0 +1,83 @@
+mod common;
+
+use common::{PdfBuilder, assert_success, create_temp_dir, render_command};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+#[test]
+fn widths_downscale_with_the_chosen_filter() {
+    let (temp_dir, pdf_path) = write_pdf("pdf-render-resample-widths");
+    let lanczos = temp_dir.join("lanczos");
+    let nearest = temp_dir.join("nearest");
+
+    render(&pdf_path, &lanczos, &["--widths", "400,130"]);
+    render(
+        &pdf_path,
+        &nearest,
+        &["--widths", "400,130", "--resample", "nearest"],
+    );
+
+    // The largest width is rendered directly, so the filter never touches it.
+    assert_eq!(
+        fs::read(lanczos.join("page-0001-400.png")).unwrap(),
+        fs::read(nearest.join("page-0001-400.png")).unwrap()
+    );
+    assert_ne!(
+        file_size(&lanczos.join("page-0001-130.png")),
+        file_size(&nearest.join("page-0001-130.png"))
+    );
+}
+
+#[test]
+fn supersample_downscales_with_the_chosen_filter() {
+    let (temp_dir, pdf_path) = write_pdf("pdf-render-resample-supersample");
+    let args = ["--target-width", "130", "--supersample", "3"];
+    let lanczos = temp_dir.join("lanczos");
+    let triangle = temp_dir.join("triangle");
+
+    render(&pdf_path, &lanczos, &args);
+    render(
+        &pdf_path,
+        &triangle,
+        &[&args[..], &["--resample", "triangle"]].concat(),
+    );
+
+    assert_eq!(
+        image::image_dimensions(triangle.join("page-0001.png")).unwrap(),
+        (130, 195)
+    );
+    assert_ne!(
+        file_size(&lanczos.join("page-0001.png")),
+        file_size(&triangle.join("page-0001.png"))
+    );
+}
+
+fn render(pdf_path: &Path, output_dir: &Path, args: &[&str]) {
+    let output = render_command(pdf_path, output_dir)
+        .arg("--format")
+        .arg("png")
+        .args(args)
+        .output()
+        .unwrap();
+    assert_success(&output, "pdf render");
+}
+
+fn file_size(path: &Path) -> u64 {
+    fs::metadata(path).unwrap().len()
+}
+
+/// A 100x150pt page of small text and thin lines, which every filter
+/// scales differently.
+fn write_pdf(prefix: &str) -> (PathBuf, PathBuf) {
+    let temp_dir = create_temp_dir(prefix);
+    let pdf_path = temp_dir.join("text.pdf");
+    let mut pdf = PdfBuilder::new();
+    let font = pdf.add_object("<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica >>");
+    pdf.add_page(
+        &format!("/MediaBox [0 0 100 150] /Resources << /Font << /F1 {font} 0 R >> >>"),
+        b"0.3 w 10 10 m 90 140 l S 10 140 m 90 10 l S \
+          BT /F1 6 Tf 10 100 Td (Small print, resampled) Tj 0 -10 Td (in two ways) Tj ET",
+    );
+    fs::write(&pdf_path, pdf.finish()).unwrap();
+    (temp_dir, pdf_path)
+}
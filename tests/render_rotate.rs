@@ -0,0 +1,33 @@
+mod common;
+
+use common::{PdfBuilder, assert_success, create_temp_dir, pdf_command};
+use std::fs;
+
+#[test]
+fn rotate_quarter_turn_makes_landscape_page_portrait() {
+    let temp_dir = create_temp_dir("pdf-render-rotate");
+    let pdf_path = temp_dir.join("landscape.pdf");
+    let output_dir = temp_dir.join("out");
+    let mut pdf = PdfBuilder::new();
+    pdf.add_page("/MediaBox [0 0 400 200]", b"0 0 0 rg\n0 0 200 200 re\nf\n");
+    fs::write(&pdf_path, pdf.finish()).unwrap();
+
+    let output = pdf_command()
+        .arg("render")
+        .arg(&pdf_path)
+        .arg("-o")
+        .arg(&output_dir)
+        .arg("--workers")
+        .arg("1")
+        .arg("--target-width")
+        .arg("100")
+        .arg("--rotate")
+        .arg("90")
+        .output()
+        .unwrap();
+    assert_success(&output, "pdf render --rotate 90");
+
+    let image = image::open(output_dir.join("page-0001.jpg")).unwrap();
+    assert_eq!(image.width(), 100, "target width applies after rotation");
+    assert_eq!(image.height(), 200);
+}
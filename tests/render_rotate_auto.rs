@@ -0,0 +1,121 @@
+mod common;
+
+use common::{PdfBuilder, assert_success, create_temp_dir, json_data, pdf_command, render_command};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+#[test]
+fn info_reports_the_declared_rotation() {
+    let (pdf_path, _) = write_pdf("pdf-info-rotation", |pdf| {
+        pdf.add_page("/MediaBox [0 0 100 200] /Rotate 90", b"");
+        pdf.add_page("/MediaBox [0 0 100 200]", b"");
+    });
+
+    let output = pdf_command()
+        .arg("info")
+        .arg(&pdf_path)
+        .arg("--all-pages")
+        .output()
+        .unwrap();
+    assert_success(&output, "pdf info --all-pages");
+
+    let info: serde_json::Value = json_data(&output.stdout);
+    assert_eq!(info["pages"][0]["rotation_deg"], 90);
+    assert_eq!(info["pages"][0]["width_pt"], 200.0);
+    assert_eq!(info["pages"][1]["rotation_deg"], 0);
+}
+
+#[test]
+fn rotated_pages_render_upright() {
+    // A black square in the bottom-left corner of the unrotated page, which
+    // /Rotate 90 turns to the top-left.
+    let (pdf_path, temp_dir) = write_pdf("pdf-render-rotate-auto-declared", |pdf| {
+        pdf.add_page("/MediaBox [0 0 100 200] /Rotate 90", b"0 g 0 0 50 50 re f");
+    });
+    let output_dir = temp_dir.join("out");
+
+    render(&pdf_path, &output_dir, &["--rotate-auto"]);
+
+    let image = image::open(output_dir.join("page-0001.png"))
+        .unwrap()
+        .to_rgb8();
+    assert_eq!(image.dimensions(), (100, 50));
+    assert_eq!(image.get_pixel(5, 5).0, [0, 0, 0]);
+    assert_eq!(image.get_pixel(95, 45).0, [255, 255, 255]);
+}
+
+#[test]
+fn sideways_text_is_turned_upright() {
+    let (pdf_path, temp_dir) = write_sideways_text_pdf("pdf-render-rotate-auto-text");
+
+    let plain = temp_dir.join("plain");
+    render(&pdf_path, &plain, &[]);
+    let auto = temp_dir.join("auto");
+    render(&pdf_path, &auto, &["--rotate-auto", "--workers", "2"]);
+
+    for page in ["page-0001.png", "page-0002.png"] {
+        assert_eq!(
+            image::image_dimensions(plain.join(page)).unwrap(),
+            (100, 50)
+        );
+        // Text running up the landscape page reads left to right once the
+        // page is turned a quarter clockwise.
+        assert_eq!(
+            image::image_dimensions(auto.join(page)).unwrap(),
+            (100, 200)
+        );
+    }
+}
+
+#[test]
+fn rotate_auto_conflicts_with_rotate() {
+    let (pdf_path, temp_dir) = write_pdf("pdf-render-rotate-auto-conflict", |pdf| {
+        pdf.add_page("/MediaBox [0 0 100 100]", b"");
+    });
+
+    let output = pdf_command()
+        .arg("render")
+        .arg(&pdf_path)
+        .arg("-o")
+        .arg(temp_dir.join("out"))
+        .args(["--rotate-auto", "--rotate", "90"])
+        .output()
+        .unwrap();
+
+    assert_eq!(output.status.code(), Some(1));
+}
+
+fn render(pdf_path: &Path, output_dir: &Path, args: &[&str]) {
+    let output = render_command(pdf_path, output_dir)
+        .arg("--format")
+        .arg("png")
+        .arg("--target-width")
+        .arg("100")
+        .args(args)
+        .output()
+        .unwrap();
+    assert_success(&output, &format!("pdf render {}", args.join(" ")));
+}
+
+/// Two 200x100pt pages without /Rotate whose text runs bottom to top.
+fn write_sideways_text_pdf(prefix: &str) -> (PathBuf, PathBuf) {
+    write_pdf(prefix, |pdf| {
+        let font = pdf.add_object("<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica >>");
+        for _ in 0..2 {
+            pdf.add_page(
+                &format!("/MediaBox [0 0 200 100] /Resources << /Font << /F1 {font} 0 R >> >>"),
+                b"BT /F1 10 Tf 0 1 -1 0 60 10 Tm (Scanned sideways) Tj \
+                  0 1 -1 0 80 10 Tm (with an OCR layer) Tj ET",
+            );
+        }
+    })
+}
+
+fn write_pdf(prefix: &str, build: impl FnOnce(&mut PdfBuilder)) -> (PathBuf, PathBuf) {
+    let temp_dir = create_temp_dir(prefix);
+    let pdf_path = temp_dir.join("rotated.pdf");
+    let mut pdf = PdfBuilder::new();
+    build(&mut pdf);
+    fs::write(&pdf_path, pdf.finish()).unwrap();
+    (pdf_path, temp_dir)
+}
@@ -0,0 +1,93 @@
+mod common;
+
+use common::{PdfBuilder, assert_success, create_temp_dir, json_data, render_command};
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+#[test]
+fn sparse_pages_split_across_workers_without_gaps_or_duplicates() {
+    let (temp_dir, pdf_path) = write_pdf("pdf-render-sparse-pages");
+
+    for mode in ["process", "thread"] {
+        let output_dir = temp_dir.join(mode);
+        let (summary, files) = render(&pdf_path, &output_dir, mode, &["--pages", "1,5,9,20"]);
+
+        assert_eq!(summary["pages_rendered"], 4, "{mode}");
+        assert_eq!(summary["workers_used"], 2, "{mode}");
+        assert_eq!(
+            files,
+            expected(&[
+                "page-0001.jpg",
+                "page-0005.jpg",
+                "page-0009.jpg",
+                "page-0020.jpg"
+            ]),
+            "{mode}"
+        );
+    }
+}
+
+#[test]
+fn unsorted_sparse_pages_keep_their_sequence_across_workers() {
+    let (temp_dir, pdf_path) = write_pdf("pdf-render-sparse-keep-order");
+
+    let (summary, files) = render(
+        &pdf_path,
+        &temp_dir.join("out"),
+        "process",
+        &["--pages", "20,1,9,5", "--keep-order"],
+    );
+
+    assert_eq!(summary["pages_rendered"], 4);
+    assert_eq!(
+        files,
+        expected(&[
+            "0001-page-0020.jpg",
+            "0002-page-0001.jpg",
+            "0003-page-0009.jpg",
+            "0004-page-0005.jpg"
+        ])
+    );
+}
+
+fn expected(names: &[&str]) -> BTreeSet<String> {
+    names.iter().map(|name| name.to_string()).collect()
+}
+
+fn render(
+    pdf_path: &Path,
+    output_dir: &Path,
+    mode: &str,
+    args: &[&str],
+) -> (serde_json::Value, BTreeSet<String>) {
+    let output = render_command(pdf_path, output_dir)
+        .arg("--workers")
+        .arg("2")
+        .arg("--mode")
+        .arg(mode)
+        .arg("--target-width")
+        .arg("20")
+        .args(args)
+        .output()
+        .unwrap();
+    assert_success(&output, &format!("pdf render --mode {mode}"));
+
+    let summary = json_data(&output.stdout);
+    let files = fs::read_dir(output_dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+        .collect();
+    (summary, files)
+}
+
+fn write_pdf(prefix: &str) -> (PathBuf, PathBuf) {
+    let temp_dir = create_temp_dir(prefix);
+    let pdf_path = temp_dir.join("twenty.pdf");
+    let mut pdf = PdfBuilder::new();
+    for _ in 0..20 {
+        pdf.add_page("/MediaBox [0 0 100 150]", b"");
+    }
+    fs::write(&pdf_path, pdf.finish()).unwrap();
+    (temp_dir, pdf_path)
+}
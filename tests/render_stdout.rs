@@ -0,0 +1,56 @@
+mod common;
+
+use common::{PdfBuilder, assert_success, create_temp_dir, pdf_command};
+use std::fs;
+use std::path::PathBuf;
+
+#[test]
+fn stdout_carries_a_single_valid_jpeg() {
+    let pdf_path = write_blank_pdf("pdf-render-stdout", 2);
+
+    let output = pdf_command()
+        .arg("render")
+        .arg(&pdf_path)
+        .arg("--pages")
+        .arg("2")
+        .arg("--target-width")
+        .arg("80")
+        .arg("--stdout")
+        .output()
+        .unwrap();
+    assert_success(&output, "pdf render --stdout");
+
+    assert_eq!(
+        &output.stdout[..2],
+        &[0xFF, 0xD8],
+        "missing JPEG SOI marker"
+    );
+    let image = image::load_from_memory_with_format(&output.stdout, image::ImageFormat::Jpeg)
+        .expect("stdout is not a decodable JPEG");
+    assert_eq!(image.width(), 80);
+}
+
+#[test]
+fn stdout_rejects_multiple_pages() {
+    let pdf_path = write_blank_pdf("pdf-render-stdout-multi", 2);
+
+    let output = pdf_command()
+        .arg("render")
+        .arg(&pdf_path)
+        .arg("--stdout")
+        .output()
+        .unwrap();
+
+    assert_eq!(output.status.code(), Some(1));
+    assert!(output.stdout.is_empty());
+}
+
+fn write_blank_pdf(prefix: &str, pages: usize) -> PathBuf {
+    let pdf_path = create_temp_dir(prefix).join("blank.pdf");
+    let mut pdf = PdfBuilder::new();
+    for _ in 0..pages {
+        pdf.add_page("/MediaBox [0 0 200 300]", b"");
+    }
+    fs::write(&pdf_path, pdf.finish()).unwrap();
+    pdf_path
+}
@@ -0,0 +1,85 @@
+mod common;
+
+use common::{PdfBuilder, create_temp_dir, render_command};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// The region fits the 200x100pt pages but not the 100x50pt page 2.
+const REGION: &str = "120,0,40,40";
+
+#[test]
+fn strict_stops_at_the_first_bad_page() {
+    let (pdf_path, output_dir) = write_pdf("pdf-render-strict");
+
+    let output = render(&pdf_path, &output_dir, &["--workers", "1", "--strict"]);
+
+    assert_eq!(output.status.code(), Some(4));
+    assert!(output_dir.join("page-0001.jpg").exists());
+    for page in 2..=6 {
+        assert!(
+            !output_dir.join(format!("page-{page:04}.jpg")).exists(),
+            "page {page} was rendered"
+        );
+    }
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("page 2"), "{stderr}");
+}
+
+#[test]
+fn strict_stops_worker_processes_after_the_first_failure() {
+    let (pdf_path, output_dir) = write_pdf("pdf-render-strict-workers");
+
+    let output = render(&pdf_path, &output_dir, &["--workers", "2", "--strict"]);
+
+    assert_eq!(output.status.code(), Some(4));
+    // Worker 0 has pages 1-3 and stops at page 2.
+    assert!(output_dir.join("page-0001.jpg").exists());
+    assert!(!output_dir.join("page-0003.jpg").exists());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("--strict"), "{stderr}");
+}
+
+#[test]
+fn lenient_default_renders_past_the_bad_page() {
+    let (pdf_path, output_dir) = write_pdf("pdf-render-lenient");
+
+    let output = render(&pdf_path, &output_dir, &["--workers", "1"]);
+
+    assert_eq!(output.status.code(), Some(6));
+    assert!(output_dir.join("page-0006.jpg").exists());
+}
+
+#[test]
+fn strict_rejects_retries() {
+    let (pdf_path, output_dir) = write_pdf("pdf-render-strict-retries");
+
+    let output = render(&pdf_path, &output_dir, &["--strict", "--retries", "1"]);
+
+    assert_eq!(output.status.code(), Some(1));
+}
+
+fn render(pdf_path: &Path, output_dir: &Path, args: &[&str]) -> std::process::Output {
+    render_command(pdf_path, output_dir)
+        .arg("--region")
+        .arg(REGION)
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+/// Six 200x100pt pages, except page 2 at 100x50pt.
+fn write_pdf(prefix: &str) -> (PathBuf, PathBuf) {
+    let temp_dir = create_temp_dir(prefix);
+    let pdf_path = temp_dir.join("pages.pdf");
+    let mut pdf = PdfBuilder::new();
+    for page in 1..=6 {
+        let media_box = if page == 2 {
+            "0 0 100 50"
+        } else {
+            "0 0 200 100"
+        };
+        pdf.add_page(&format!("/MediaBox [{media_box}]"), b"");
+    }
+    fs::write(&pdf_path, pdf.finish()).unwrap();
+    (pdf_path, temp_dir.join("out"))
+}
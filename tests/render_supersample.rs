@@ -0,0 +1,120 @@
+mod common;
+
+use common::{PdfBuilder, assert_success, create_temp_dir, render_command};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+#[test]
+fn supersampled_pages_have_the_requested_size() {
+    let (temp_dir, pdf_path) = write_pdf("pdf-render-supersample");
+    let output_dir = temp_dir.join("out");
+
+    let output = render(
+        &pdf_path,
+        &output_dir,
+        &[
+            "--target-width",
+            "150",
+            "--supersample",
+            "2",
+            "--workers",
+            "2",
+        ],
+    );
+    assert_success(&output, "pdf render --supersample 2");
+
+    for page in ["page-0001.png", "page-0002.png"] {
+        assert_eq!(
+            image::image_dimensions(output_dir.join(page)).unwrap(),
+            (150, 225)
+        );
+    }
+}
+
+#[test]
+fn supersample_respects_max_width() {
+    let (temp_dir, pdf_path) = write_pdf("pdf-render-supersample-max");
+    let output_dir = temp_dir.join("out");
+
+    let output = render(
+        &pdf_path,
+        &output_dir,
+        &[
+            "--target-width",
+            "400",
+            "--max-width",
+            "100",
+            "--supersample",
+            "3",
+        ],
+    );
+    assert_success(&output, "pdf render --supersample --max-width");
+
+    assert_eq!(
+        image::image_dimensions(output_dir.join("page-0001.png")).unwrap(),
+        (100, 150)
+    );
+}
+
+#[test]
+fn supersampled_text_stays_close_to_a_direct_render() {
+    let (temp_dir, pdf_path) = write_pdf("pdf-render-supersample-text");
+
+    let direct = render(
+        &pdf_path,
+        &temp_dir.join("direct"),
+        &["--target-width", "150"],
+    );
+    assert_success(&direct, "pdf render");
+    let supersampled = render(
+        &pdf_path,
+        &temp_dir.join("supersampled"),
+        &["--target-width", "150", "--supersample", "2"],
+    );
+    assert_success(&supersampled, "pdf render --supersample 2");
+
+    let direct = image::open(temp_dir.join("direct/page-0001.png"))
+        .unwrap()
+        .to_rgb8();
+    let supersampled = image::open(temp_dir.join("supersampled/page-0001.png"))
+        .unwrap()
+        .to_rgb8();
+    assert_ne!(direct, supersampled);
+    // The dark text block is in the same place in both.
+    assert!(supersampled.get_pixel(75, 40)[0] < 128);
+    assert!(supersampled.get_pixel(75, 200)[0] > 200);
+}
+
+#[test]
+fn supersample_out_of_range_is_rejected() {
+    let (temp_dir, pdf_path) = write_pdf("pdf-render-supersample-range");
+
+    let output = render(&pdf_path, &temp_dir.join("out"), &["--supersample", "0.5"]);
+
+    assert_eq!(output.status.code(), Some(1));
+}
+
+fn render(pdf_path: &Path, output_dir: &Path, args: &[&str]) -> std::process::Output {
+    render_command(pdf_path, output_dir)
+        .arg("--format")
+        .arg("png")
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+/// Two 100x150pt pages with a black bar across the top and a line of text.
+fn write_pdf(prefix: &str) -> (PathBuf, PathBuf) {
+    let temp_dir = create_temp_dir(prefix);
+    let pdf_path = temp_dir.join("text.pdf");
+    let mut pdf = PdfBuilder::new();
+    let font = pdf.add_object("<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica >>");
+    for _ in 0..2 {
+        pdf.add_page(
+            &format!("/MediaBox [0 0 100 150] /Resources << /Font << /F1 {font} 0 R >> >>"),
+            b"0 g 10 115 80 20 re f BT /F1 6 Tf 10 60 Td (Small print) Tj ET",
+        );
+    }
+    fs::write(&pdf_path, pdf.finish()).unwrap();
+    (temp_dir, pdf_path)
+}
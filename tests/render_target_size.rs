@@ -0,0 +1,77 @@
+mod common;
+
+use common::{PdfBuilder, assert_success, create_temp_dir, pdf_command, render_command};
+use std::fs;
+use std::path::PathBuf;
+
+#[test]
+fn target_width_only_sets_width() {
+    assert_eq!(
+        render_dimensions("pdf-size-width", &["--target-width", "100"]),
+        (100, 150)
+    );
+}
+
+#[test]
+fn target_height_only_sets_height() {
+    assert_eq!(
+        render_dimensions("pdf-size-height", &["--target-height", "300"]),
+        (200, 300)
+    );
+}
+
+#[test]
+fn no_size_renders_at_point_size() {
+    assert_eq!(render_dimensions("pdf-size-native", &[]), (200, 300));
+}
+
+#[test]
+fn target_width_and_height_conflict() {
+    let (pdf_path, output_dir) = write_portrait_pdf("pdf-size-conflict");
+
+    let output = pdf_command()
+        .arg("render")
+        .arg(&pdf_path)
+        .arg("-o")
+        .arg(&output_dir)
+        .arg("--target-width")
+        .arg("100")
+        .arg("--target-height")
+        .arg("300")
+        .output()
+        .unwrap();
+
+    assert_eq!(output.status.code(), Some(1));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("mutually exclusive"), "{stderr}");
+    assert!(!output_dir.exists());
+}
+
+fn render_dimensions(prefix: &str, size_args: &[&str]) -> (u32, u32) {
+    let (pdf_path, output_dir) = write_portrait_pdf(prefix);
+
+    let output = render_command(&pdf_path, &output_dir)
+        .arg("--workers")
+        .arg("2")
+        .args(size_args)
+        .output()
+        .unwrap();
+    assert_success(&output, &format!("pdf render {}", size_args.join(" ")));
+
+    let first = image::image_dimensions(output_dir.join("page-0001.jpg")).unwrap();
+    let second = image::image_dimensions(output_dir.join("page-0002.jpg")).unwrap();
+    assert_eq!(first, second, "workers disagree on page size");
+    first
+}
+
+/// Two 200x300pt pages, so `--workers 2` forwards the size to subprocesses.
+fn write_portrait_pdf(prefix: &str) -> (PathBuf, PathBuf) {
+    let temp_dir = create_temp_dir(prefix);
+    let pdf_path = temp_dir.join("portrait.pdf");
+    let mut pdf = PdfBuilder::new();
+    for _ in 0..2 {
+        pdf.add_page("/MediaBox [0 0 200 300]", b"");
+    }
+    fs::write(&pdf_path, pdf.finish()).unwrap();
+    (pdf_path, temp_dir.join("out"))
+}
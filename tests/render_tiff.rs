@@ -0,0 +1,89 @@
+mod common;
+
+use common::{PdfBuilder, assert_success, create_temp_dir, json_data, pdf_command};
+use std::fs::{self, File};
+use std::path::PathBuf;
+
+#[test]
+fn single_file_writes_one_frame_per_page() {
+    let (pdf_path, temp_dir) = write_three_page_pdf("pdf-render-tiff-single");
+    let tiff_path = temp_dir.join("archive").join("book.tiff");
+
+    let output = pdf_command()
+        .arg("render")
+        .arg(&pdf_path)
+        .arg("--format")
+        .arg("tiff")
+        .arg("--single-file")
+        .arg(&tiff_path)
+        .arg("--target-width")
+        .arg("50")
+        .output()
+        .unwrap();
+    assert_success(&output, "pdf render --format tiff --single-file");
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("ignoring --workers 4"), "{stderr}");
+    let summary: serde_json::Value = json_data(&output.stdout);
+    assert_eq!(summary["pages_rendered"], 3);
+    assert_eq!(summary["output_file"], tiff_path.display().to_string());
+
+    let mut decoder = tiff::decoder::Decoder::new(File::open(&tiff_path).unwrap()).unwrap();
+    let mut frames = 1;
+    assert_eq!(decoder.dimensions().unwrap().0, 50);
+    while decoder.more_images() {
+        decoder.next_image().unwrap();
+        frames += 1;
+    }
+    assert_eq!(frames, 3);
+}
+
+#[test]
+fn tiff_format_writes_a_file_per_page() {
+    let (pdf_path, temp_dir) = write_three_page_pdf("pdf-render-tiff-pages");
+    let output_dir = temp_dir.join("out");
+
+    let output = pdf_command()
+        .arg("render")
+        .arg(&pdf_path)
+        .arg("-o")
+        .arg(&output_dir)
+        .arg("--format")
+        .arg("tiff")
+        .arg("--target-width")
+        .arg("50")
+        .output()
+        .unwrap();
+    assert_success(&output, "pdf render --format tiff");
+
+    for page in 1..=3 {
+        let image = image::open(output_dir.join(format!("page-{page:04}.tiff"))).unwrap();
+        assert_eq!(image.width(), 50);
+    }
+}
+
+#[test]
+fn single_file_requires_tiff_format() {
+    let (pdf_path, temp_dir) = write_three_page_pdf("pdf-render-tiff-format");
+
+    let output = pdf_command()
+        .arg("render")
+        .arg(&pdf_path)
+        .arg("--single-file")
+        .arg(temp_dir.join("book.tiff"))
+        .output()
+        .unwrap();
+
+    assert_eq!(output.status.code(), Some(1));
+}
+
+fn write_three_page_pdf(prefix: &str) -> (PathBuf, PathBuf) {
+    let temp_dir = create_temp_dir(prefix);
+    let pdf_path = temp_dir.join("book.pdf");
+    let mut pdf = PdfBuilder::new();
+    for _ in 0..3 {
+        pdf.add_page("/MediaBox [0 0 100 150]", b"0 0 0 rg 10 10 20 20 re f\n");
+    }
+    fs::write(&pdf_path, pdf.finish()).unwrap();
+    (pdf_path, temp_dir)
+}
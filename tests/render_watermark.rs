@@ -0,0 +1,97 @@
+mod common;
+
+use common::{PdfBuilder, assert_success, create_temp_dir, pdf_command, render_command};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+#[test]
+fn watermarked_page_differs_from_plain_render() {
+    let (temp_dir, pdf_path) = write_blank_pdf("pdf-render-watermark");
+    let plain_dir = temp_dir.join("plain");
+    let marked_dir = temp_dir.join("marked");
+
+    assert_success(&render(&pdf_path, &plain_dir, &[]), "pdf render");
+    let output = render(
+        &pdf_path,
+        &marked_dir,
+        &["--watermark-text", "DRAFT", "--watermark-angle", "-30"],
+    );
+    assert_success(&output, "pdf render --watermark-text");
+
+    let plain = image::open(plain_dir.join("page-0001.png"))
+        .unwrap()
+        .to_rgb8();
+    let marked = image::open(marked_dir.join("page-0001.png"))
+        .unwrap()
+        .to_rgb8();
+    assert_eq!(plain.dimensions(), marked.dimensions());
+    let (width, height) = marked.dimensions();
+    // Text lands around the center; the corners keep the page background.
+    let changed = plain
+        .pixels()
+        .zip(marked.pixels())
+        .filter(|(a, b)| a != b)
+        .count();
+    assert!(changed > 100, "only {changed} pixels changed");
+    assert_eq!(marked.get_pixel(0, 0), plain.get_pixel(0, 0));
+    assert_eq!(
+        marked.get_pixel(width - 1, height - 1),
+        plain.get_pixel(width - 1, height - 1)
+    );
+}
+
+#[test]
+fn watermark_warns_with_extract_images() {
+    let (temp_dir, pdf_path) = write_blank_pdf("pdf-render-watermark-extract");
+
+    let output = pdf_command()
+        .arg("render")
+        .arg(&pdf_path)
+        .arg("-o")
+        .arg(temp_dir.join("out"))
+        .arg("--extract-images")
+        .arg("--watermark-text")
+        .arg("DRAFT")
+        .output()
+        .unwrap();
+
+    assert_success(&output, "pdf render --extract-images --watermark-text");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("--watermark-text is not applied to pages copied by --extract-images"),
+        "{stderr}"
+    );
+}
+
+#[test]
+fn watermark_options_require_text() {
+    let (temp_dir, pdf_path) = write_blank_pdf("pdf-render-watermark-no-text");
+
+    let output = render(
+        &pdf_path,
+        &temp_dir.join("out"),
+        &["--watermark-opacity", "0.5"],
+    );
+
+    assert!(!output.status.success());
+}
+
+fn render(pdf_path: &Path, output_dir: &Path, args: &[&str]) -> std::process::Output {
+    render_command(pdf_path, output_dir)
+        .arg("--target-width")
+        .arg("300")
+        .arg("--format")
+        .arg("png")
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+fn write_blank_pdf(prefix: &str) -> (PathBuf, PathBuf) {
+    let temp_dir = create_temp_dir(prefix);
+    let pdf_path = temp_dir.join("blank.pdf");
+    let mut pdf = PdfBuilder::new();
+    pdf.add_page("/MediaBox [0 0 200 300]", b"");
+    fs::write(&pdf_path, pdf.finish()).unwrap();
+    (temp_dir, pdf_path)
+}
@@ -0,0 +1,82 @@
+mod common;
+
+use common::{PdfBuilder, assert_success, create_temp_dir, pdf_command};
+use std::fs;
+use std::path::PathBuf;
+
+#[test]
+fn lossless_webp_output_has_riff_webp_magic() {
+    let (pdf_path, output_dir) = write_blank_pdf("pdf-render-webp");
+
+    let output = pdf_command()
+        .arg("render")
+        .arg(&pdf_path)
+        .arg("-o")
+        .arg(&output_dir)
+        .arg("--target-width")
+        .arg("40")
+        .arg("--format")
+        .arg("webp")
+        .arg("--lossless")
+        .output()
+        .unwrap();
+    assert_success(&output, "pdf render --format webp --lossless");
+
+    let bytes = fs::read(output_dir.join("page-0001.webp")).unwrap();
+    assert_eq!(&bytes[0..4], b"RIFF");
+    assert_eq!(&bytes[8..12], b"WEBP");
+    let image = image::load_from_memory(&bytes).unwrap();
+    assert_eq!(image.width(), 40);
+}
+
+#[test]
+fn webp_ignores_extract_images_with_warning() {
+    let (pdf_path, output_dir) = write_blank_pdf("pdf-render-webp-extract");
+
+    let output = pdf_command()
+        .arg("render")
+        .arg(&pdf_path)
+        .arg("-o")
+        .arg(&output_dir)
+        .arg("--target-width")
+        .arg("40")
+        .arg("--format")
+        .arg("webp")
+        .arg("--lossless")
+        .arg("--extract-images")
+        .output()
+        .unwrap();
+    assert_success(&output, "pdf render --format webp --extract-images");
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("--extract-images ignored"),
+        "stderr: {stderr}"
+    );
+    assert!(output_dir.join("page-0001.webp").exists());
+}
+
+#[test]
+fn lossless_rejects_non_webp_output() {
+    let (pdf_path, output_dir) = write_blank_pdf("pdf-render-lossless-jpeg");
+
+    let output = pdf_command()
+        .arg("render")
+        .arg(&pdf_path)
+        .arg("-o")
+        .arg(&output_dir)
+        .arg("--lossless")
+        .output()
+        .unwrap();
+
+    assert_eq!(output.status.code(), Some(1));
+}
+
+fn write_blank_pdf(prefix: &str) -> (PathBuf, PathBuf) {
+    let temp_dir = create_temp_dir(prefix);
+    let pdf_path = temp_dir.join("blank.pdf");
+    let mut pdf = PdfBuilder::new();
+    pdf.add_page("/MediaBox [0 0 100 100]", b"");
+    fs::write(&pdf_path, pdf.finish()).unwrap();
+    (pdf_path, temp_dir.join("out"))
+}
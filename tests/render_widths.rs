@@ -0,0 +1,69 @@
+mod common;
+
+use common::{PdfBuilder, assert_success, create_temp_dir, json_data, pdf_command};
+use std::fs;
+use std::path::Path;
+
+#[test]
+fn each_width_gets_its_own_file_per_page() {
+    let temp_dir = create_temp_dir("pdf-render-widths");
+    let pdf_path = temp_dir.join("doc.pdf");
+    let mut pdf = PdfBuilder::new();
+    pdf.add_page("/MediaBox [0 0 100 150]", b"0 0 1 rg 10 10 80 130 re f\n");
+    pdf.add_page("/MediaBox [0 0 100 150]", b"");
+    fs::write(&pdf_path, pdf.finish()).unwrap();
+    let output_dir = temp_dir.join("out");
+
+    let output = pdf_command()
+        .arg("render")
+        .arg(&pdf_path)
+        .arg("-o")
+        .arg(&output_dir)
+        .arg("--widths")
+        .arg("50,200,100")
+        .arg("--workers")
+        .arg("2")
+        .arg("--list-outputs")
+        .output()
+        .unwrap();
+    assert_success(&output, "pdf render --widths");
+
+    for page in ["page-0001", "page-0002"] {
+        assert_eq!(size(&output_dir, &format!("{page}-200.jpg")), (200, 300));
+        assert_eq!(size(&output_dir, &format!("{page}-100.jpg")), (100, 150));
+        assert_eq!(size(&output_dir, &format!("{page}-50.jpg")), (50, 75));
+        assert!(!output_dir.join(format!("{page}.jpg")).exists());
+    }
+    assert_eq!(fs::read_dir(&output_dir).unwrap().count(), 6);
+
+    let summary: serde_json::Value = json_data(&output.stdout);
+    assert_eq!(summary["pages_rendered"], 2);
+    assert_eq!(summary["output_files"].as_array().unwrap().len(), 6);
+}
+
+#[test]
+fn widths_conflict_with_target_width() {
+    let temp_dir = create_temp_dir("pdf-render-widths-conflict");
+    let pdf_path = temp_dir.join("doc.pdf");
+    let mut pdf = PdfBuilder::new();
+    pdf.add_page("/MediaBox [0 0 100 150]", b"");
+    fs::write(&pdf_path, pdf.finish()).unwrap();
+
+    let output = pdf_command()
+        .arg("render")
+        .arg(&pdf_path)
+        .arg("-o")
+        .arg(temp_dir.join("out"))
+        .arg("--widths")
+        .arg("50,100")
+        .arg("--target-width")
+        .arg("80")
+        .output()
+        .unwrap();
+
+    assert!(!output.status.success());
+}
+
+fn size(output_dir: &Path, name: &str) -> (u32, u32) {
+    image::image_dimensions(output_dir.join(name)).unwrap()
+}
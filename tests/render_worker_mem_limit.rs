@@ -0,0 +1,78 @@
+#![cfg(unix)]
+
+mod common;
+
+use common::{PdfBuilder, assert_success, create_temp_dir, json_data, render_command};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+#[test]
+fn tiny_limit_fails_the_worker_not_the_host() {
+    let (temp_dir, pdf_path) = write_pdf("pdf-render-mem-limit-tiny");
+
+    // 1 MB cannot even hold the worker's executable; it must fail on its own.
+    let output = render(
+        &pdf_path,
+        &temp_dir.join("out"),
+        &["--worker-mem-limit", "1", "--retries", "1"],
+    );
+
+    assert_eq!(output.status.code(), Some(4));
+    let summary: serde_json::Value = json_data(&output.stdout);
+    assert_eq!(summary["pages_rendered"], 0);
+    assert_eq!(summary["failed"], 1);
+    assert_eq!(summary["retried_pages"], serde_json::json!([1, 2]));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("error: worker 0:"), "{stderr}");
+}
+
+#[test]
+fn generous_limit_renders_normally() {
+    let (temp_dir, pdf_path) = write_pdf("pdf-render-mem-limit-generous");
+    let output_dir = temp_dir.join("out");
+
+    // pdfium's PartitionAlloc reserves tens of GB of address space up front,
+    // so a generous RLIMIT_AS is far above the memory actually used.
+    let output = render(&pdf_path, &output_dir, &["--worker-mem-limit", "262144"]);
+
+    assert_success(&output, "pdf render --worker-mem-limit");
+    assert!(output_dir.join("page-0001.jpg").exists());
+    assert!(output_dir.join("page-0002.jpg").exists());
+}
+
+#[test]
+fn worker_mem_limit_rejects_thread_mode() {
+    let (temp_dir, pdf_path) = write_pdf("pdf-render-mem-limit-thread");
+
+    let output = render(
+        &pdf_path,
+        &temp_dir.join("out"),
+        &["--worker-mem-limit", "512", "--mode", "thread"],
+    );
+
+    assert_eq!(output.status.code(), Some(1));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("--worker-mem-limit requires --mode process"),
+        "{stderr}"
+    );
+}
+
+fn render(pdf_path: &Path, output_dir: &Path, args: &[&str]) -> std::process::Output {
+    render_command(pdf_path, output_dir)
+        .arg("--workers")
+        .arg("1")
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+fn write_pdf(name: &str) -> (PathBuf, PathBuf) {
+    let temp_dir = create_temp_dir(name);
+    let pdf_path = temp_dir.join("doc.pdf");
+    let mut pdf = PdfBuilder::new();
+    pdf.add_page("/MediaBox [0 0 100 100]", b"0 0 1 rg 10 10 80 80 re f\n");
+    pdf.add_page("/MediaBox [0 0 100 100]", b"1 0 0 rg 10 10 80 80 re f\n");
+    fs::write(&pdf_path, pdf.finish()).unwrap();
+    (temp_dir, pdf_path)
+}
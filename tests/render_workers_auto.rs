@@ -0,0 +1,54 @@
+mod common;
+
+use common::{PdfBuilder, assert_success, create_temp_dir, json_data, render_command};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const PAGE_COUNT: u64 = 2;
+
+#[test]
+fn workers_auto_is_positive_and_capped_by_page_count() {
+    let temp_dir = create_temp_dir("pdf-render-workers-auto");
+    let pdf_path = write_pdf(&temp_dir);
+    let output_dir = temp_dir.join("out");
+
+    for workers in ["auto", "0"] {
+        let output = render(&pdf_path, &output_dir, workers);
+
+        assert_success(&output, "pdf render --workers auto");
+        let summary: serde_json::Value = json_data(&output.stdout);
+        let workers_used = summary["workers_used"].as_u64().unwrap();
+        assert!((1..=PAGE_COUNT).contains(&workers_used), "{summary}");
+        assert_eq!(summary["pages_rendered"], PAGE_COUNT);
+    }
+}
+
+#[test]
+fn workers_rejects_other_words() {
+    let temp_dir = create_temp_dir("pdf-render-workers-invalid");
+    let pdf_path = write_pdf(&temp_dir);
+
+    let output = render(&pdf_path, &temp_dir.join("out"), "all");
+
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("expected a number or `auto`"), "{stderr}");
+}
+
+fn render(pdf_path: &Path, output_dir: &Path, workers: &str) -> std::process::Output {
+    render_command(pdf_path, output_dir)
+        .arg("--workers")
+        .arg(workers)
+        .output()
+        .unwrap()
+}
+
+fn write_pdf(temp_dir: &Path) -> PathBuf {
+    let pdf_path = temp_dir.join("doc.pdf");
+    let mut pdf = PdfBuilder::new();
+    for _ in 0..PAGE_COUNT {
+        pdf.add_page("/MediaBox [0 0 100 100]", b"");
+    }
+    fs::write(&pdf_path, pdf.finish()).unwrap();
+    pdf_path
+}
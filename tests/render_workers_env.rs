@@ -0,0 +1,78 @@
+mod common;
+
+use common::{PdfBuilder, assert_success, create_temp_dir, json_data, render_command};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+#[test]
+fn env_forces_single_process_despite_workers_flag() {
+    let (temp_dir, pdf_path) = write_pdf("pdf-render-workers-env");
+    let output_dir = temp_dir.join("out");
+
+    // --repeat is only accepted when everything renders in this process.
+    let output = render(&pdf_path, &output_dir, Some("1"), &["--repeat", "2"]);
+    assert_success(&output, "PDF_WORKERS=1 pdf render --workers 4 --repeat 2");
+
+    let summary: serde_json::Value = json_data(&output.stdout);
+    assert_eq!(summary["workers_used"], 1);
+    assert_eq!(summary["pages_rendered"], 4);
+    assert_eq!(summary["iteration_secs"].as_array().unwrap().len(), 2);
+    for page in 1..=4 {
+        assert!(output_dir.join(format!("page-{page:04}.jpg")).exists());
+    }
+}
+
+#[test]
+fn workers_flag_applies_without_env() {
+    let (temp_dir, pdf_path) = write_pdf("pdf-render-workers-no-env");
+
+    let output = render(&pdf_path, &temp_dir.join("out"), None, &["--repeat", "2"]);
+
+    assert_eq!(output.status.code(), Some(1));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("--repeat times a single process"),
+        "{stderr}"
+    );
+}
+
+#[test]
+fn invalid_env_value_is_rejected() {
+    let (temp_dir, pdf_path) = write_pdf("pdf-render-workers-env-invalid");
+
+    let output = render(&pdf_path, &temp_dir.join("out"), Some("lots"), &[]);
+
+    assert_eq!(output.status.code(), Some(1));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("PDF_WORKERS"), "{stderr}");
+}
+
+fn render(
+    pdf_path: &Path,
+    output_dir: &Path,
+    workers_env: Option<&str>,
+    args: &[&str],
+) -> std::process::Output {
+    let mut command = render_command(pdf_path, output_dir);
+    command.env_remove("PDF_WORKERS");
+    if let Some(workers) = workers_env {
+        command.env("PDF_WORKERS", workers);
+    }
+    command
+        .arg("--workers")
+        .arg("4")
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+fn write_pdf(name: &str) -> (PathBuf, PathBuf) {
+    let temp_dir = create_temp_dir(name);
+    let pdf_path = temp_dir.join("doc.pdf");
+    let mut pdf = PdfBuilder::new();
+    for _ in 0..4 {
+        pdf.add_page("/MediaBox [0 0 100 100]", b"0 0 1 rg 10 10 80 80 re f\n");
+    }
+    fs::write(&pdf_path, pdf.finish()).unwrap();
+    (temp_dir, pdf_path)
+}
@@ -0,0 +1,38 @@
+mod common;
+
+use common::{PdfBuilder, create_temp_dir, json_data, pdf_command};
+use std::fs;
+
+#[test]
+fn zero_size_page_is_reported_and_others_render() {
+    let temp_dir = create_temp_dir("pdf-render-zero-size");
+    let pdf_path = temp_dir.join("damaged.pdf");
+    let mut pdf = PdfBuilder::new();
+    pdf.add_page("/MediaBox [0 0 100 100]", b"");
+    // A CropBox outside the MediaBox clips the page to nothing.
+    pdf.add_page("/MediaBox [0 0 100 100] /CropBox [200 200 300 300]", b"");
+    pdf.add_page("/MediaBox [0 0 100 100]", b"");
+    fs::write(&pdf_path, pdf.finish()).unwrap();
+    let output_dir = temp_dir.join("out");
+
+    let output = pdf_command()
+        .arg("render")
+        .arg(&pdf_path)
+        .arg("-o")
+        .arg(&output_dir)
+        .arg("--workers")
+        .arg("1")
+        .arg("--target-width")
+        .arg("50")
+        .output()
+        .unwrap();
+
+    assert_eq!(output.status.code(), Some(6));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("page 2: zero-size page"), "{stderr}");
+    let summary: serde_json::Value = json_data(&output.stdout);
+    assert_eq!(summary["pages_rendered"], 2);
+    assert!(output_dir.join("page-0001.jpg").exists());
+    assert!(!output_dir.join("page-0002.jpg").exists());
+    assert!(output_dir.join("page-0003.jpg").exists());
+}
@@ -0,0 +1,96 @@
+mod common;
+
+use common::{PdfBuilder, assert_success, create_temp_dir, json_data, pdf_command};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Helvetica 12pt, drawn from (20, 100) on a 300x200pt page.
+const FONT_SIZE: f64 = 12.0;
+const TEXT_X: f64 = 20.0;
+const TEXT_Y: f64 = 100.0;
+
+#[test]
+fn finds_phrase_with_page_and_box() {
+    let pdf_path = write_pdf("pdf-search", &["Nothing here", "The quick brown fox"]);
+
+    let results = run_search(&pdf_path, "quick brown", &[]);
+
+    assert_eq!(results.len(), 1, "{results:?}");
+    assert_eq!(results[0]["page"], 2);
+    let matches = results[0]["matches"].as_array().unwrap();
+    assert_eq!(matches.len(), 1);
+    let found = &matches[0];
+    assert_eq!(found["text"], "quick brown");
+    let [left, bottom, right, top] =
+        ["left", "bottom", "right", "top"].map(|key| found[key].as_f64().unwrap());
+    // "The " is about 22pt wide; "quick brown" about 65pt.
+    assert!(left > TEXT_X + 10.0 && left < TEXT_X + 35.0, "{found}");
+    assert!(right > left + 40.0 && right < left + 90.0, "{found}");
+    assert!(
+        (TEXT_Y - FONT_SIZE..TEXT_Y + 1.0).contains(&bottom),
+        "{found}"
+    );
+    assert!(top > TEXT_Y && top <= TEXT_Y + FONT_SIZE + 1.0, "{found}");
+}
+
+#[test]
+fn case_and_whole_word_options() {
+    let pdf_path = write_pdf("pdf-search-options", &["Foxes and a fox"]);
+
+    assert_eq!(count_matches(&run_search(&pdf_path, "FOX", &[])), 0);
+    assert_eq!(
+        count_matches(&run_search(&pdf_path, "FOX", &["--ignore-case"])),
+        2
+    );
+    assert_eq!(
+        count_matches(&run_search(
+            &pdf_path,
+            "fox",
+            &["--ignore-case", "--whole-word"]
+        )),
+        1
+    );
+}
+
+#[test]
+fn no_match_prints_empty_array() {
+    let pdf_path = write_pdf("pdf-search-none", &["Nothing here"]);
+
+    assert!(run_search(&pdf_path, "absent", &[]).is_empty());
+}
+
+fn count_matches(results: &[serde_json::Value]) -> usize {
+    results
+        .iter()
+        .map(|page| page["matches"].as_array().unwrap().len())
+        .sum()
+}
+
+fn run_search(pdf_path: &Path, query: &str, args: &[&str]) -> Vec<serde_json::Value> {
+    let output = pdf_command()
+        .arg("search")
+        .arg(pdf_path)
+        .arg(query)
+        .args(args)
+        .output()
+        .unwrap();
+    assert_success(&output, "pdf search");
+
+    json_data(&output.stdout)
+}
+
+/// One page per entry, each drawing its text in Helvetica.
+fn write_pdf(prefix: &str, page_texts: &[&str]) -> PathBuf {
+    let temp_dir = create_temp_dir(prefix);
+    let pdf_path = temp_dir.join("text.pdf");
+    let mut pdf = PdfBuilder::new();
+    let font_id = pdf.add_object("<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica >>");
+    for text in page_texts {
+        pdf.add_page(
+            &format!("/MediaBox [0 0 300 200] /Resources << /Font << /F1 {font_id} 0 R >> >>"),
+            format!("BT /F1 {FONT_SIZE} Tf {TEXT_X} {TEXT_Y} Td ({text}) Tj ET\n").as_bytes(),
+        );
+    }
+    fs::write(&pdf_path, pdf.finish()).unwrap();
+    pdf_path
+}
@@ -0,0 +1,68 @@
+mod common;
+
+use common::{PdfBuilder, assert_success, create_temp_dir, json_data, pdf_command};
+use std::io::Write;
+use std::process::{Output, Stdio};
+
+#[test]
+fn info_reads_pdf_from_stdin() {
+    let output = run_with_stdin(&["info", "-", "--all-pages"], &two_page_pdf());
+    assert_success(&output, "pdf info -");
+
+    let info: serde_json::Value = json_data(&output.stdout);
+    assert_eq!(info["page_count"], 2);
+    assert_eq!(info["pages"][1]["width_pt"], 300.0);
+}
+
+#[test]
+fn info_workers_spool_stdin() {
+    let output = run_with_stdin(
+        &["info", "-", "--all-pages", "--workers", "2"],
+        &two_page_pdf(),
+    );
+    assert_success(&output, "pdf info - --workers 2");
+
+    let info: serde_json::Value = json_data(&output.stdout);
+    assert_eq!(info["pages"].as_array().unwrap().len(), 2);
+}
+
+#[test]
+fn multi_process_render_reads_stdin() {
+    let output_dir = create_temp_dir("pdf-stdin-render").join("out");
+    let output = run_with_stdin(
+        &[
+            "render",
+            "-",
+            "-o",
+            output_dir.to_str().unwrap(),
+            "--workers",
+            "2",
+            "--target-width",
+            "50",
+        ],
+        &two_page_pdf(),
+    );
+    assert_success(&output, "pdf render - --workers 2");
+
+    assert!(output_dir.join("page-0001.jpg").exists());
+    assert!(output_dir.join("page-0002.jpg").exists());
+}
+
+fn run_with_stdin(args: &[&str], input: &[u8]) -> Output {
+    let mut child = pdf_command()
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child.stdin.take().unwrap().write_all(input).unwrap();
+    child.wait_with_output().unwrap()
+}
+
+fn two_page_pdf() -> Vec<u8> {
+    let mut pdf = PdfBuilder::new();
+    pdf.add_page("/MediaBox [0 0 200 100]", b"");
+    pdf.add_page("/MediaBox [0 0 300 100]", b"");
+    pdf.finish()
+}
@@ -0,0 +1,108 @@
+mod common;
+
+use common::{PdfBuilder, assert_success, create_temp_dir, json_data, pdf_command};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+#[test]
+fn subset_copies_selected_pages_with_rotation() {
+    let (pdf_path, temp_dir) = write_three_page_pdf("pdf-subset");
+    let subset_path = temp_dir.join("subset.pdf");
+
+    let output = pdf_command()
+        .arg("subset")
+        .arg(&pdf_path)
+        .arg("-o")
+        .arg(&subset_path)
+        .arg("--pages")
+        .arg("1,3")
+        .arg("--rotate")
+        .arg("90")
+        .output()
+        .unwrap();
+    assert_success(&output, "pdf subset");
+
+    let info = run_info(&subset_path);
+    assert_eq!(info["page_count"], 2);
+    assert_eq!(info["pages"][0]["width_pt"], 200.0);
+    assert_eq!(info["pages"][0]["height_pt"], 100.0);
+    // Page 3 of the source is the wider one.
+    assert_eq!(info["pages"][1]["width_pt"], 200.0);
+    assert_eq!(info["pages"][1]["height_pt"], 300.0);
+}
+
+#[test]
+fn subset_keeps_vector_content_without_rotation() {
+    let (pdf_path, temp_dir) = write_three_page_pdf("pdf-subset-plain");
+    let subset_path = temp_dir.join("subset.pdf");
+
+    let output = pdf_command()
+        .arg("subset")
+        .arg(&pdf_path)
+        .arg("-o")
+        .arg(&subset_path)
+        .arg("--pages")
+        .arg("2")
+        .output()
+        .unwrap();
+    assert_success(&output, "pdf subset --pages 2");
+
+    let info = run_info(&subset_path);
+    assert_eq!(info["page_count"], 1);
+    assert_eq!(info["pages"][0]["width_pt"], 100.0);
+    assert_eq!(info["pages"][0]["height_pt"], 200.0);
+
+    let text = pdf_command()
+        .arg("text")
+        .arg(&subset_path)
+        .output()
+        .unwrap();
+    assert_success(&text, "pdf text");
+    let pages: serde_json::Value = json_data(&text.stdout);
+    assert!(pages[0]["text"].as_str().unwrap().contains("Page two"));
+}
+
+#[test]
+fn subset_rejects_pages_past_the_end() {
+    let (pdf_path, temp_dir) = write_three_page_pdf("pdf-subset-range");
+
+    let output = pdf_command()
+        .arg("subset")
+        .arg(&pdf_path)
+        .arg("-o")
+        .arg(temp_dir.join("subset.pdf"))
+        .arg("--pages")
+        .arg("2-5")
+        .output()
+        .unwrap();
+
+    assert!(!output.status.success());
+    assert!(!temp_dir.join("subset.pdf").exists());
+}
+
+fn run_info(pdf_path: &Path) -> serde_json::Value {
+    let output = pdf_command()
+        .arg("info")
+        .arg(pdf_path)
+        .arg("--all-pages")
+        .output()
+        .unwrap();
+    assert_success(&output, "pdf info");
+    json_data(&output.stdout)
+}
+
+/// Two 100x200pt pages, the second with text, then a 300x200pt page.
+fn write_three_page_pdf(prefix: &str) -> (PathBuf, PathBuf) {
+    let temp_dir = create_temp_dir(prefix);
+    let pdf_path = temp_dir.join("source.pdf");
+    let mut pdf = PdfBuilder::new();
+    let font_id = pdf.add_object("<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica >>");
+    pdf.add_page("/MediaBox [0 0 100 200]", b"");
+    pdf.add_page(
+        &format!("/MediaBox [0 0 100 200] /Resources << /Font << /F1 {font_id} 0 R >> >>"),
+        b"BT /F1 12 Tf 10 100 Td (Page two) Tj ET\n",
+    );
+    pdf.add_page("/MediaBox [0 0 300 200]", b"");
+    fs::write(&pdf_path, pdf.finish()).unwrap();
+    (pdf_path, temp_dir)
+}
@@ -0,0 +1,135 @@
+mod common;
+
+use common::{PdfBuilder, assert_success, create_temp_dir, json_data, pdf_command};
+use std::fs;
+use std::path::Path;
+
+const KNOWN_TEXT: &str = "Hello from page one";
+
+#[test]
+fn extracts_known_text_and_keeps_empty_pages() {
+    let temp_dir = create_temp_dir("pdf-text");
+    let pdf_path = temp_dir.join("text.pdf");
+    fs::write(&pdf_path, build_text_pdf(&[Some(KNOWN_TEXT), None])).unwrap();
+
+    let pages = run_text(&pdf_path, &[]);
+
+    assert_eq!(pages.len(), 2);
+    assert_eq!(pages[0]["page"], 1);
+    assert!(
+        pages[0]["text"].as_str().unwrap().contains(KNOWN_TEXT),
+        "unexpected text: {}",
+        pages[0]["text"]
+    );
+    assert_eq!(pages[1]["page"], 2);
+    assert_eq!(pages[1]["text"], "");
+}
+
+#[test]
+fn page_range_limits_extraction() {
+    let temp_dir = create_temp_dir("pdf-text-range");
+    let pdf_path = temp_dir.join("text.pdf");
+    fs::write(&pdf_path, build_text_pdf(&[None, Some(KNOWN_TEXT)])).unwrap();
+
+    let pages = run_text(&pdf_path, &["--pages", "2"]);
+
+    assert_eq!(pages.len(), 1);
+    assert_eq!(pages[0]["page"], 2);
+    assert!(pages[0]["text"].as_str().unwrap().contains(KNOWN_TEXT));
+}
+
+#[test]
+fn layout_keeps_columns_further_apart_than_plain_text() {
+    let temp_dir = create_temp_dir("pdf-text-layout");
+    let pdf_path = temp_dir.join("columns.pdf");
+    fs::write(&pdf_path, build_two_column_pdf()).unwrap();
+
+    let plain = run_text(&pdf_path, &[]);
+    let layout = run_text(&pdf_path, &["--layout"]);
+
+    let layout_text = layout[0]["text"].as_str().unwrap();
+    let rows: Vec<&str> = layout_text.lines().collect();
+    assert_eq!(rows.len(), 2, "unexpected layout: {layout_text:?}");
+    assert!(rows[0].starts_with("Left one") && rows[0].ends_with("Right one"));
+    assert!(rows[1].starts_with("Left two") && rows[1].ends_with("Right two"));
+
+    let plain_gap = column_gap(plain[0]["text"].as_str().unwrap());
+    let layout_gap = column_gap(layout_text);
+    assert!(
+        layout_gap > plain_gap && layout_gap >= 10,
+        "layout gap {layout_gap}, plain gap {plain_gap}"
+    );
+}
+
+/// Spaces between the end of "Left one" and "Right one" on the same line, or
+/// 0 when they are on different lines.
+fn column_gap(text: &str) -> usize {
+    text.lines()
+        .find_map(|line| {
+            let after_left = &line[line.find("Left one")? + "Left one".len()..];
+            let gap = after_left.find("Right one")?;
+            after_left[..gap].chars().all(|ch| ch == ' ').then_some(gap)
+        })
+        .unwrap_or(0)
+}
+
+fn run_text(pdf_path: &Path, args: &[&str]) -> Vec<serde_json::Value> {
+    let output = pdf_command()
+        .arg("text")
+        .arg(pdf_path)
+        .args(args)
+        .output()
+        .unwrap();
+    assert_success(&output, "pdf text");
+
+    json_data(&output.stdout)
+}
+
+#[test]
+fn stats_count_text_and_flag_empty_pages() {
+    let temp_dir = create_temp_dir("pdf-text-stats");
+    let pdf_path = temp_dir.join("text.pdf");
+    fs::write(&pdf_path, build_text_pdf(&[Some(KNOWN_TEXT), None])).unwrap();
+
+    let pages = run_text(&pdf_path, &["--stats"]);
+
+    assert_eq!(pages.len(), 2);
+    assert_eq!(pages[0]["page"], 1);
+    assert_eq!(pages[0]["char_count"], 16);
+    assert_eq!(pages[0]["word_count"], 4);
+    assert_eq!(pages[0]["is_empty"], false);
+    assert!(pages[0].get("text").is_none());
+    assert_eq!(pages[1]["page"], 2);
+    assert_eq!(pages[1]["char_count"], 0);
+    assert_eq!(pages[1]["word_count"], 0);
+    assert_eq!(pages[1]["is_empty"], true);
+}
+
+/// One page with two columns of two lines each, in separate text objects.
+fn build_two_column_pdf() -> Vec<u8> {
+    let mut pdf = PdfBuilder::new();
+    let font_id = pdf.add_object("<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica >>");
+    let content = "BT /F1 12 Tf 20 160 Td (Left one) Tj 0 -16 Td (Left two) Tj ET\n\
+                   BT /F1 12 Tf 320 160 Td (Right one) Tj 0 -16 Td (Right two) Tj ET\n";
+    pdf.add_page(
+        &format!("/MediaBox [0 0 500 200] /Resources << /Font << /F1 {font_id} 0 R >> >>"),
+        content.as_bytes(),
+    );
+    pdf.finish()
+}
+
+/// Build a PDF with one page per entry; `Some` pages draw the text in Helvetica.
+fn build_text_pdf(page_texts: &[Option<&str>]) -> Vec<u8> {
+    let mut pdf = PdfBuilder::new();
+    let font_id = pdf.add_object("<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica >>");
+    for text in page_texts {
+        let content = text
+            .map(|text| format!("BT /F1 12 Tf 20 100 Td ({text}) Tj ET\n"))
+            .unwrap_or_default();
+        pdf.add_page(
+            &format!("/MediaBox [0 0 300 200] /Resources << /Font << /F1 {font_id} 0 R >> >>"),
+            content.as_bytes(),
+        );
+    }
+    pdf.finish()
+}
@@ -0,0 +1,54 @@
+mod common;
+
+use common::{PdfBuilder, assert_success, create_temp_dir, json_data, pdf_command};
+use std::fs;
+use std::path::Path;
+
+#[test]
+fn page_is_sliced_into_a_tile_grid() {
+    let temp_dir = create_temp_dir("pdf-tiles");
+    let pdf_path = temp_dir.join("tall.pdf");
+    let mut pdf = PdfBuilder::new();
+    pdf.add_page("/MediaBox [0 0 100 150]", b"0 0 1 rg 0 0 100 150 re f\n");
+    pdf.add_page("/MediaBox [0 0 100 100]", b"");
+    fs::write(&pdf_path, pdf.finish()).unwrap();
+    let output_dir = temp_dir.join("tiles");
+
+    let output = pdf_command()
+        .arg("tiles")
+        .arg(&pdf_path)
+        .arg("-o")
+        .arg(&output_dir)
+        .arg("--tile")
+        .arg("64")
+        .arg("--target-width")
+        .arg("200")
+        .arg("--pages")
+        .arg("1")
+        .output()
+        .unwrap();
+    assert_success(&output, "pdf tiles");
+
+    // 200x300px is 4 columns by 5 rows of 64px tiles, at level 9 (2^9 >= 300).
+    let level_dir = output_dir.join("page-0001").join("9");
+    assert_eq!(fs::read_dir(&level_dir).unwrap().count(), 20);
+    assert_eq!(tile_size(&level_dir, "0_0"), (64, 64));
+    assert_eq!(tile_size(&level_dir, "3_4"), (8, 44));
+    assert!(!output_dir.join("page-0002").exists());
+
+    // The page is filled blue, so every tile, the bottom row included, is too.
+    let corner = image::open(level_dir.join("3_4.jpg")).unwrap().into_rgb8();
+    let [red, _, blue] = corner.get_pixel(4, 40).0;
+    assert!(red < 40 && blue > 200, "bottom-right tile is not blue");
+
+    let summary: serde_json::Value = json_data(&output.stdout);
+    assert_eq!(summary["tiles"], 20);
+    assert_eq!(summary["pages"][0]["page"], 1);
+    assert_eq!(summary["pages"][0]["level"], 9);
+    assert_eq!(summary["pages"][0]["columns"], 4);
+    assert_eq!(summary["pages"][0]["rows"], 5);
+}
+
+fn tile_size(level_dir: &Path, name: &str) -> (u32, u32) {
+    image::image_dimensions(level_dir.join(format!("{name}.jpg"))).unwrap()
+}